@@ -25,12 +25,15 @@ pub enum Subcommand {
 	/// Export blocks.
 	ExportBlocks(sc_cli::ExportBlocksCmd),
 
-	/// Export the state of a given block into a chain spec.
+	/// Export the state of a given block into a chain spec or a snapshot.
 	ExportState(sc_cli::ExportStateCmd),
 
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Import a snapshot of the state of a finalized block.
+	ImportState(sc_cli::ImportStateCmd),
+
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
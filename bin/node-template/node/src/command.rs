@@ -2,11 +2,12 @@ use crate::{
 	benchmarking::{inherent_benchmark_data, RemarkBuilder, TransferKeepAliveBuilder},
 	chain_spec,
 	cli::{Cli, Subcommand},
-	service,
+	service::{self, ExecutorDispatch},
 };
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
 use node_template_runtime::{Block, EXISTENTIAL_DEPOSIT};
 use sc_cli::SubstrateCli;
+use sc_executor::NativeExecutionDispatch;
 use sc_service::PartialComponents;
 use sp_keyring::Sr25519Keyring;
 
@@ -76,8 +77,15 @@ pub fn run() -> sc_cli::Result<()> {
 		Some(Subcommand::ExportState(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
-				let PartialComponents { client, task_manager, .. } = service::new_partial(&config)?;
-				Ok((cmd.run(client, config.chain_spec), task_manager))
+				let PartialComponents {
+					client,
+					backend,
+					task_manager,
+					other: (_, grandpa_link, _),
+					..
+				} = service::new_partial(&config)?;
+				let warp_sync = service::warp_sync_provider(backend, &grandpa_link);
+				Ok((cmd.run(client, config.chain_spec, Some(warp_sync)), task_manager))
 			})
 		},
 		Some(Subcommand::ImportBlocks(cmd)) => {
@@ -88,6 +96,21 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		},
+		Some(Subcommand::ImportState(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents {
+					client,
+					backend,
+					task_manager,
+					import_queue,
+					other: (_, grandpa_link, _),
+					..
+				} = service::new_partial(&config)?;
+				let warp_sync = service::warp_sync_provider(backend, &grandpa_link);
+				Ok((cmd.run(client, import_queue, warp_sync), task_manager))
+			})
+		},
 		Some(Subcommand::PurgeChain(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.database))
@@ -124,7 +147,9 @@ pub fn run() -> sc_cli::Result<()> {
 					},
 					BenchmarkCmd::Block(cmd) => {
 						let PartialComponents { client, .. } = service::new_partial(&config)?;
-						cmd.run(client)
+						type ExtraHostFunctions =
+							<ExecutorDispatch as NativeExecutionDispatch>::ExtendHostFunctions;
+						cmd.run::<Block, _, _, ExtraHostFunctions>(client)
 					},
 					#[cfg(not(feature = "runtime-benchmarks"))]
 					BenchmarkCmd::Storage(_) => Err(
@@ -205,7 +230,7 @@ pub fn run() -> sc_cli::Result<()> {
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config).await.map_err(sc_cli::Error::Service)
 			})
 		},
 	}
@@ -6,11 +6,13 @@ use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 use sc_consensus_grandpa::SharedVoterState;
 pub use sc_executor::NativeElseWasmExecutor;
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncParams};
+use sc_service::{
+	error::Error as ServiceError, Configuration, TaskManager, WarpSyncParams, WarpSyncProvider,
+};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -102,29 +104,39 @@ pub fn new_partial(
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
 
-	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
+	let slot_schedule_client = client.clone();
 
 	let import_queue =
 		sc_consensus_aura::import_queue::<AuraPair, _, _, _, _, _>(ImportQueueParams {
 			block_import: grandpa_block_import.clone(),
 			justification_import: Some(Box::new(grandpa_block_import.clone())),
 			client: client.clone(),
-			create_inherent_data_providers: move |_, ()| async move {
-				let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+			create_inherent_data_providers: move |parent, ()| {
+				let client = slot_schedule_client.clone();
+				async move {
+					let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
 
-				let slot =
-					sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
-						*timestamp,
-						slot_duration,
-					);
+					let slot_schedule = sc_consensus_aura::slot_schedule_at(&*client, parent)?;
+					let slot =
+						sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_schedule(
+							*timestamp,
+							slot_schedule,
+						);
 
-				Ok((slot, timestamp))
+					Ok::<_, Box<dyn std::error::Error + Send + Sync>>((slot, timestamp))
+				}
 			},
 			spawner: &task_manager.spawn_essential_handle(),
 			registry: config.prometheus_registry(),
 			check_for_equivocation: Default::default(),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			compatibility_mode: Default::default(),
+			fork_choice_rule: None,
+			pipeline: Some(sc_consensus::PipelineConfig {
+				spawner: Box::new(task_manager.spawn_handle()),
+				max_parallel_verifications: std::thread::available_parallelism()
+					.unwrap_or_else(|_| NonZeroUsize::new(1).expect("1 is not zero; qed")),
+			}),
 		})?;
 
 	Ok(sc_service::PartialComponents {
@@ -139,13 +151,25 @@ pub fn new_partial(
 	})
 }
 
+/// The warp sync provider proving the finality of blocks with GRANDPA justifications.
+pub fn warp_sync_provider(
+	backend: Arc<FullBackend>,
+	grandpa_link: &sc_consensus_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
+) -> Arc<dyn WarpSyncProvider<Block>> {
+	Arc::new(sc_consensus_grandpa::warp_proof::NetworkProvider::new(
+		backend,
+		grandpa_link.shared_authority_set().clone(),
+		Vec::default(),
+	))
+}
+
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+pub async fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
 		mut task_manager,
-		import_queue,
+		mut import_queue,
 		keystore_container,
 		select_chain,
 		transaction_pool,
@@ -162,11 +186,15 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		grandpa_protocol_name.clone(),
 	));
 
-	let warp_sync = Arc::new(sc_consensus_grandpa::warp_proof::NetworkProvider::new(
-		backend.clone(),
-		grandpa_link.shared_authority_set().clone(),
-		Vec::default(),
-	));
+	let warp_sync_params =
+		Some(WarpSyncParams::WithProvider(warp_sync_provider(backend.clone(), &grandpa_link)));
+	sc_service::import_configured_snapshot(
+		&config,
+		client.clone(),
+		&mut import_queue,
+		warp_sync_params.as_ref(),
+	)
+	.await?;
 
 	let (network, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
@@ -177,7 +205,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 			spawn_handle: task_manager.spawn_handle(),
 			import_queue,
 			block_announce_validator_builder: None,
-			warp_sync_params: Some(WarpSyncParams::WithProvider(warp_sync)),
+			warp_sync_params,
 		})?;
 
 	if config.offchain_worker.enabled {
@@ -194,6 +222,12 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 				)),
 				network_provider: network.clone(),
 				enable_http_requests: true,
+				http_config: sc_offchain::HttpConfig {
+					max_concurrent_requests: config.offchain_worker.http_max_concurrent_requests,
+					request_timeout: config.offchain_worker.http_request_timeout,
+					allowed_domains: config.offchain_worker.http_allowed_domains.clone(),
+				},
+				prometheus_registry: config.prometheus_registry().cloned(),
 				custom_extensions: |_| vec![],
 			})
 			.run(client.clone(), task_manager.spawn_handle())
@@ -243,25 +277,28 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 			telemetry.as_ref().map(|x| x.handle()),
 		);
 
-		let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
+		let slot_schedule_client = client.clone();
 
 		let aura = sc_consensus_aura::start_aura::<AuraPair, _, _, _, _, _, _, _, _, _, _>(
 			StartAuraParams {
-				slot_duration,
 				client,
 				select_chain,
 				block_import,
 				proposer_factory,
-				create_inherent_data_providers: move |_, ()| async move {
-					let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
-
-					let slot =
-						sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
-							*timestamp,
-							slot_duration,
-						);
-
-					Ok((slot, timestamp))
+				create_inherent_data_providers: move |parent, ()| {
+					let client = slot_schedule_client.clone();
+					async move {
+						let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+						let slot_schedule = sc_consensus_aura::slot_schedule_at(&*client, parent)?;
+						let slot =
+							sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_schedule(
+								*timestamp,
+								slot_schedule,
+							);
+
+						Ok::<_, Box<dyn std::error::Error + Send + Sync>>((slot, timestamp))
+					}
 				},
 				force_authoring,
 				backoff_authoring_blocks,
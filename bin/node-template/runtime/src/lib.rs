@@ -396,12 +396,22 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[api_version(3)]
 	impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
 		fn offchain_worker(header: &<Block as BlockT>::Header) {
 			Executive::offchain_worker(header)
 		}
+
+		fn offchain_worker_schedules() -> Vec<sp_offchain::PalletOffchainWorkerSchedule> {
+			Executive::offchain_worker_schedules()
+		}
+
+		fn offchain_worker_of(header: &<Block as BlockT>::Header, pallets: Vec<Vec<u8>>) {
+			Executive::offchain_worker_of(header, &pallets)
+		}
 	}
 
+	#[api_version(2)]
 	impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
 		fn slot_duration() -> sp_consensus_aura::SlotDuration {
 			sp_consensus_aura::SlotDuration::from_millis(Aura::slot_duration())
@@ -410,6 +420,10 @@ impl_runtime_apis! {
 		fn authorities() -> Vec<AuraId> {
 			Aura::authorities().into_inner()
 		}
+
+		fn slot_schedule() -> sp_consensus_aura::SlotSchedule {
+			Aura::slot_schedule()
+		}
 	}
 
 	impl sp_session::SessionKeys<Block> for Runtime {
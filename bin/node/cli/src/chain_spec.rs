@@ -440,7 +440,12 @@ pub(crate) mod tests {
 
 		sc_service_test::connectivity(integration_test_config_with_two_authorities(), |config| {
 			let NewFullBase { task_manager, client, network, sync, transaction_pool, .. } =
-				new_full_base(config, false, |_, _| ())?;
+				futures::executor::block_on(new_full_base(
+					config,
+					false,
+					Default::default(),
+					|_, _| (),
+				))?;
 			Ok(sc_service_test::TestNetComponents::new(
 				task_manager,
 				client,
@@ -38,6 +38,12 @@ pub struct Cli {
 	#[arg(long)]
 	pub no_hardware_benchmarks: bool,
 
+	/// Path to a TOML file describing the GRANDPA voting rules to apply.
+	///
+	/// The rules can be replaced at runtime through the unsafe `grandpa_setVotingRules` RPC.
+	#[arg(long, value_name = "PATH")]
+	pub grandpa_voting_rules: Option<std::path::PathBuf>,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub storage_monitor: sc_storage_monitor::StorageMonitorParams,
@@ -88,12 +94,15 @@ pub enum Subcommand {
 	/// Export blocks.
 	ExportBlocks(sc_cli::ExportBlocksCmd),
 
-	/// Export the state of a given block into a chain spec.
+	/// Export the state of a given block into a chain spec or a snapshot.
 	ExportState(sc_cli::ExportStateCmd),
 
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Import a snapshot of the state of a finalized block.
+	ImportState(sc_cli::ImportStateCmd),
+
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
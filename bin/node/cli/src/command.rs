@@ -28,6 +28,7 @@ use kitchensink_runtime::{ExistentialDeposit, RuntimeApi};
 use node_executor::ExecutorDispatch;
 use node_primitives::Block;
 use sc_cli::{Result, SubstrateCli};
+use sc_executor::NativeExecutionDispatch;
 use sc_service::PartialComponents;
 use sp_keyring::Sr25519Keyring;
 
@@ -90,7 +91,7 @@ pub fn run() -> Result<()> {
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config, cli).map_err(sc_cli::Error::Service)
+				service::new_full(config, cli).await.map_err(sc_cli::Error::Service)
 			})
 		},
 		Some(Subcommand::Inspect(cmd)) => {
@@ -119,7 +120,9 @@ pub fn run() -> Result<()> {
 					BenchmarkCmd::Block(cmd) => {
 						// ensure that we keep the task manager alive
 						let partial = new_partial(&config)?;
-						cmd.run(partial.client)
+						type ExtraHostFunctions =
+							<ExecutorDispatch as NativeExecutionDispatch>::ExtendHostFunctions;
+						cmd.run::<Block, _, _, ExtraHostFunctions>(partial.client)
 					},
 					#[cfg(not(feature = "runtime-benchmarks"))]
 					BenchmarkCmd::Storage(_) => Err(
@@ -199,8 +202,15 @@ pub fn run() -> Result<()> {
 		Some(Subcommand::ExportState(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
-				let PartialComponents { client, task_manager, .. } = new_partial(&config)?;
-				Ok((cmd.run(client, config.chain_spec), task_manager))
+				let PartialComponents {
+					client,
+					backend,
+					task_manager,
+					other: (_, (_, grandpa_link, _), _, _),
+					..
+				} = new_partial(&config)?;
+				let warp_sync = service::warp_sync_provider(backend, &grandpa_link);
+				Ok((cmd.run(client, config.chain_spec, Some(warp_sync)), task_manager))
 			})
 		},
 		Some(Subcommand::ImportBlocks(cmd)) => {
@@ -211,6 +221,21 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		},
+		Some(Subcommand::ImportState(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents {
+					client,
+					backend,
+					task_manager,
+					import_queue,
+					other: (_, (_, grandpa_link, _), _, _),
+					..
+				} = new_partial(&config)?;
+				let warp_sync = service::warp_sync_provider(backend, &grandpa_link);
+				Ok((cmd.run(client, import_queue, warp_sync), task_manager))
+			})
+		},
 		Some(Subcommand::PurgeChain(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.database))
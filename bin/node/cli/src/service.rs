@@ -33,7 +33,7 @@ use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_babe::{self, SlotProportion};
 use sc_executor::NativeElseWasmExecutor;
 use sc_network::{event::Event, NetworkEventStream, NetworkService};
-use sc_network_common::sync::warp::WarpSyncParams;
+use sc_network_common::sync::warp::{WarpSyncParams, WarpSyncProvider};
 use sc_network_sync::SyncingService;
 use sc_service::{config::Configuration, error::Error as ServiceError, RpcHandlers, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
@@ -41,7 +41,7 @@ use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_api::ProvideRuntimeApi;
 use sp_core::crypto::Pair;
 use sp_runtime::{generic, traits::Block as BlockT, SaturatedConversion};
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc};
 
 /// The full client type definition.
 pub type FullClient =
@@ -148,7 +148,7 @@ pub fn new_partial(
 				grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
 				sc_consensus_babe::BabeLink<Block>,
 			),
-			grandpa::SharedVoterState,
+			(grandpa::SharedVoterState, grandpa::SharedVotingRulesConfig<<Block as BlockT>::Hash>),
 			Option<Telemetry>,
 		),
 	>,
@@ -227,6 +227,11 @@ pub fn new_partial(
 			registry: config.prometheus_registry(),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(transaction_pool.clone()),
+			pipeline: Some(sc_consensus::PipelineConfig {
+				spawner: Box::new(task_manager.spawn_handle()),
+				max_parallel_verifications: std::thread::available_parallelism()
+					.unwrap_or_else(|_| NonZeroUsize::new(1).expect("1 is not zero; qed")),
+			}),
 		})?;
 
 	let import_setup = (block_import, grandpa_link, babe_link);
@@ -238,6 +243,8 @@ pub fn new_partial(
 		let shared_authority_set = grandpa_link.shared_authority_set().clone();
 		let shared_voter_state = grandpa::SharedVoterState::empty();
 		let shared_voter_state2 = shared_voter_state.clone();
+		let voting_rules = grandpa::SharedVotingRulesConfig::default();
+		let voting_rules2 = voting_rules.clone();
 
 		let finality_proof_provider = grandpa::FinalityProofProvider::new_for_service(
 			backend.clone(),
@@ -268,6 +275,7 @@ pub fn new_partial(
 					justification_stream: justification_stream.clone(),
 					subscription_executor,
 					finality_provider: finality_proof_provider.clone(),
+					voting_rules: voting_rules.clone(),
 				},
 				backend: rpc_backend.clone(),
 			};
@@ -275,7 +283,7 @@ pub fn new_partial(
 			node_rpc::create_full(deps).map_err(Into::into)
 		};
 
-		(rpc_extensions_builder, shared_voter_state2)
+		(rpc_extensions_builder, (shared_voter_state2, voting_rules2))
 	};
 
 	Ok(sc_service::PartialComponents {
@@ -304,12 +312,30 @@ pub struct NewFullBase {
 	pub transaction_pool: Arc<TransactionPool>,
 	/// The rpc handlers of the node.
 	pub rpc_handlers: RpcHandlers,
+	/// The reloadable GRANDPA voting rules of the node.
+	pub voting_rules: grandpa::SharedVotingRulesConfig<<Block as BlockT>::Hash>,
+}
+
+/// The warp sync provider proving the finality of blocks with GRANDPA justifications.
+pub fn warp_sync_provider(
+	backend: Arc<FullBackend>,
+	grandpa_link: &grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
+) -> Arc<dyn WarpSyncProvider<Block>> {
+	Arc::new(grandpa::warp_proof::NetworkProvider::new(
+		backend,
+		grandpa_link.shared_authority_set().clone(),
+		Vec::default(),
+	))
 }
 
 /// Creates a full service from the configuration.
-pub fn new_full_base(
+///
+/// The GRANDPA voter starts with the given voting rules, which can then be replaced through
+/// [`NewFullBase::voting_rules`].
+pub async fn new_full_base(
 	config: Configuration,
 	disable_hardware_benchmarks: bool,
+	voting_rules_config: grandpa::VotingRulesConfig<<Block as BlockT>::Hash>,
 	with_startup_data: impl FnOnce(
 		&sc_consensus_babe::BabeBlockImport<Block, FullClient, FullGrandpaBlockImport>,
 		&sc_consensus_babe::BabeLink<Block>,
@@ -326,14 +352,15 @@ pub fn new_full_base(
 		client,
 		backend,
 		mut task_manager,
-		import_queue,
+		mut import_queue,
 		keystore_container,
 		select_chain,
 		transaction_pool,
 		other: (rpc_builder, import_setup, rpc_setup, mut telemetry),
 	} = new_partial(&config)?;
 
-	let shared_voter_state = rpc_setup;
+	let (shared_voter_state, voting_rules) = rpc_setup;
+	voting_rules.set(voting_rules_config);
 	let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
 	let mut net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
 
@@ -345,11 +372,15 @@ pub fn new_full_base(
 		grandpa_protocol_name.clone(),
 	));
 
-	let warp_sync = Arc::new(grandpa::warp_proof::NetworkProvider::new(
-		backend.clone(),
-		import_setup.1.shared_authority_set().clone(),
-		Vec::default(),
-	));
+	let warp_sync_params =
+		Some(WarpSyncParams::WithProvider(warp_sync_provider(backend.clone(), &import_setup.1)));
+	sc_service::import_configured_snapshot(
+		&config,
+		client.clone(),
+		&mut import_queue,
+		warp_sync_params.as_ref(),
+	)
+	.await?;
 
 	let (network, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
@@ -360,7 +391,7 @@ pub fn new_full_base(
 			spawn_handle: task_manager.spawn_handle(),
 			import_queue,
 			block_announce_validator_builder: None,
-			warp_sync_params: Some(WarpSyncParams::WithProvider(warp_sync)),
+			warp_sync_params,
 		})?;
 
 	let role = config.role.clone();
@@ -371,6 +402,11 @@ pub fn new_full_base(
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let enable_offchain_worker = config.offchain_worker.enabled;
+	let offchain_http_config = sc_offchain::HttpConfig {
+		max_concurrent_requests: config.offchain_worker.http_max_concurrent_requests,
+		request_timeout: config.offchain_worker.http_request_timeout,
+		allowed_domains: config.offchain_worker.http_allowed_domains.clone(),
+	};
 
 	let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		config,
@@ -507,14 +543,15 @@ pub fn new_full_base(
 		// this point the full voter should provide better guarantees of block
 		// and vote data availability than the observer. The observer has not
 		// been tested extensively yet and having most nodes in a network run it
-		// could lead to finality stalls.
+		// could lead to finality stalls. Non-authority voters still report the
+		// equivocations they detect in the votes of each round.
 		let grandpa_config = grandpa::GrandpaParams {
 			config: grandpa_config,
 			link: grandpa_link,
 			network: network.clone(),
 			sync: Arc::new(sync_service.clone()),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
-			voting_rule: grandpa::VotingRulesBuilder::default().build(),
+			voting_rule: grandpa::VotingRulesBuilder::from_config(voting_rules.clone()).build(),
 			prometheus_registry: prometheus_registry.clone(),
 			shared_voter_state,
 			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(transaction_pool.clone()),
@@ -543,6 +580,8 @@ pub fn new_full_base(
 				network_provider: network.clone(),
 				is_validator: role.is_authority(),
 				enable_http_requests: true,
+				http_config: offchain_http_config,
+				prometheus_registry: prometheus_registry.clone(),
 				custom_extensions: |_| vec![],
 			})
 			.run(client.clone(), task_manager.spawn_handle())
@@ -558,13 +597,23 @@ pub fn new_full_base(
 		sync: sync_service,
 		transaction_pool,
 		rpc_handlers,
+		voting_rules,
 	})
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceError> {
+pub async fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceError> {
 	let database_source = config.database.clone();
-	let task_manager = new_full_base(config, cli.no_hardware_benchmarks, |_, _| ())
+	let voting_rules = cli
+		.grandpa_voting_rules
+		.as_deref()
+		.map(grandpa::VotingRulesConfig::from_toml_file)
+		.transpose()
+		.map_err(ServiceError::Other)?
+		.unwrap_or_default();
+
+	let task_manager = new_full_base(config, cli.no_hardware_benchmarks, voting_rules, |_, _| ())
+		.await
 		.map(|NewFullBase { task_manager, .. }| task_manager)?;
 
 	sc_storage_monitor::StorageMonitorService::try_spawn(
@@ -640,14 +689,15 @@ mod tests {
 			|config| {
 				let mut setup_handles = None;
 				let NewFullBase { task_manager, client, network, sync, transaction_pool, .. } =
-					new_full_base(
+					futures::executor::block_on(new_full_base(
 						config,
 						false,
+						Default::default(),
 						|block_import: &sc_consensus_babe::BabeBlockImport<Block, _, _>,
 						 babe_link: &sc_consensus_babe::BabeLink<Block>| {
 							setup_handles = Some((block_import.clone(), babe_link.clone()));
 						},
-					)?;
+					))?;
 
 				let node = sc_service_test::TestNetComponents::new(
 					task_manager,
@@ -816,7 +866,12 @@ mod tests {
 			crate::chain_spec::tests::integration_test_config_with_two_authorities(),
 			|config| {
 				let NewFullBase { task_manager, client, network, sync, transaction_pool, .. } =
-					new_full_base(config, false, |_, _| ())?;
+					futures::executor::block_on(new_full_base(
+						config,
+						false,
+						Default::default(),
+						|_, _| (),
+					))?;
 				Ok(sc_service_test::TestNetComponents::new(
 					task_manager,
 					client,
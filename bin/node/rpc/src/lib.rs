@@ -40,6 +40,7 @@ use sc_client_api::AuxStore;
 use sc_consensus_babe::BabeWorkerHandle;
 use sc_consensus_grandpa::{
 	FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState,
+	SharedVotingRulesConfig,
 };
 use sc_rpc::SubscriptionTaskExecutor;
 pub use sc_rpc_api::DenyUnsafe;
@@ -71,6 +72,8 @@ pub struct GrandpaDeps<B> {
 	pub subscription_executor: SubscriptionTaskExecutor,
 	/// Finality proof provider.
 	pub finality_provider: Arc<FinalityProofProvider<B, Block>>,
+	/// Reloadable voting rules of the local voter.
+	pub voting_rules: SharedVotingRulesConfig<Hash>,
 }
 
 /// Full client dependencies.
@@ -142,6 +145,7 @@ where
 		justification_stream,
 		subscription_executor,
 		finality_provider,
+		voting_rules,
 	} = grandpa;
 
 	let chain_name = chain_spec.name().to_string();
@@ -163,6 +167,7 @@ where
 			justification_stream,
 			finality_provider,
 		)
+		.with_voting_rules(voting_rules, deny_unsafe)
 		.into_rpc(),
 	)?;
 
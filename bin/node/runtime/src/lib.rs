@@ -622,6 +622,7 @@ impl pallet_election_provider_multi_phase::Config for Runtime {
 	type MaxElectableTargets = MaxElectableTargets;
 	type MaxWinners = MaxActiveValidators;
 	type MaxElectingVoters = MaxElectingVoters;
+	type Pages = ConstU32<1>;
 	type BenchmarkingConfig = ElectionProviderBenchmarkConfig;
 	type WeightInfo = pallet_election_provider_multi_phase::weights::SubstrateWeight<Self>;
 }
@@ -1240,10 +1241,19 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[api_version(3)]
 	impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
 		fn offchain_worker(header: &<Block as BlockT>::Header) {
 			Executive::offchain_worker(header)
 		}
+
+		fn offchain_worker_schedules() -> Vec<sp_offchain::PalletOffchainWorkerSchedule> {
+			Executive::offchain_worker_schedules()
+		}
+
+		fn offchain_worker_of(header: &<Block as BlockT>::Header, pallets: Vec<Vec<u8>>) {
+			Executive::offchain_worker_of(header, &pallets)
+		}
 	}
 
 	impl sp_consensus_grandpa::GrandpaApi<Block> for Runtime {
@@ -41,6 +41,22 @@ pub trait ProofProvider<Block: BlockT> {
 		keys: &mut dyn Iterator<Item = &[u8]>,
 	) -> sp_blockchain::Result<StorageProof>;
 
+	/// Reads at most `count` storage entries with the given `prefix`, following `start_key`
+	/// (exclusive), in the main trie or in the given child trie, returning a read proof.
+	///
+	/// Fewer entries are read once the proof reaches `size_limit`, but at least one. Also returns
+	/// the keys of the read entries and whether the end of the range was reached, so the absence
+	/// of any further entry is proven as well.
+	fn read_range_proof(
+		&self,
+		hash: Block::Hash,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		start_key: Option<&[u8]>,
+		count: u32,
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, Vec<Vec<u8>>, bool)>;
+
 	/// Execute a call to a contract on top of state in a block of given hash
 	/// AND returning execution proof.
 	///
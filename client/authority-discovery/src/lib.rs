@@ -34,7 +34,11 @@ pub use crate::{
 	worker::{AuthorityDiscovery, NetworkProvider, Role, Worker},
 };
 
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+	time::Duration,
+};
 
 use futures::{
 	channel::{mpsc, oneshot},
@@ -85,6 +89,12 @@ pub struct WorkerConfig {
 	///
 	/// Defaults to `false` to provide compatibility with old versions
 	pub strict_record_validation: bool,
+
+	/// Addresses of nodes acting in a given role on behalf of this node, keyed by the name of the
+	/// role, e.g. `sentry`. They are published alongside the node's own addresses.
+	///
+	/// Defaults to no addresses.
+	pub role_addresses: HashMap<String, Vec<Multiaddr>>,
 }
 
 impl Default for WorkerConfig {
@@ -106,6 +116,7 @@ impl Default for WorkerConfig {
 			max_query_interval: Duration::from_secs(10 * 60),
 			publish_non_global_ips: true,
 			strict_record_validation: false,
+			role_addresses: HashMap::new(),
 		}
 	}
 }
@@ -168,4 +179,8 @@ pub(crate) enum ServicetoWorkerMsg {
 	GetAddressesByAuthorityId(AuthorityId, oneshot::Sender<Option<HashSet<Multiaddr>>>),
 	/// See [`Service::get_authority_ids_by_peer_id`].
 	GetAuthorityIdsByPeerId(PeerId, oneshot::Sender<Option<HashSet<AuthorityId>>>),
+	/// See [`Service::get_role_addresses_by_authority_id`].
+	GetRoleAddressesByAuthorityId(AuthorityId, String, oneshot::Sender<Option<HashSet<Multiaddr>>>),
+	/// See [`Service::withdraw_record`].
+	WithdrawRecord,
 }
@@ -92,4 +92,35 @@ impl Service {
 
 		rx.await.ok().flatten()
 	}
+
+	/// Get the addresses of the nodes acting in the given role, e.g. `sentry`, on behalf of the
+	/// given [`AuthorityId`] from the local address cache.
+	///
+	/// Returns `None` if no entry was present or connection to the
+	/// [`crate::Worker`] failed.
+	pub async fn get_role_addresses_by_authority_id(
+		&mut self,
+		authority: AuthorityId,
+		role: String,
+	) -> Option<HashSet<Multiaddr>> {
+		let (tx, rx) = oneshot::channel();
+
+		self.to_worker
+			.send(ServicetoWorkerMsg::GetRoleAddressesByAuthorityId(authority, role, tx))
+			.await
+			.ok()?;
+
+		rx.await.ok().flatten()
+	}
+
+	/// Withdraw the local node's record from the DHT.
+	///
+	/// The [`crate::Worker`] publishes a record without any addresses, replacing the previously
+	/// published ones, and keeps doing so until it is restarted. Use this before moving an
+	/// authority to another host so that peers stop dialling the old addresses.
+	///
+	/// Returns `false` if the connection to the [`crate::Worker`] failed.
+	pub async fn withdraw_record(&mut self) -> bool {
+		self.to_worker.send(ServicetoWorkerMsg::WithdrawRecord).await.is_ok()
+	}
 }
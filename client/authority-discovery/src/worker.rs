@@ -27,7 +27,7 @@ use std::{
 	collections::{HashMap, HashSet},
 	marker::PhantomData,
 	sync::Arc,
-	time::Duration,
+	time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use futures::{channel::mpsc, future, stream::Fuse, FutureExt, Stream, StreamExt};
@@ -35,7 +35,7 @@ use futures::{channel::mpsc, future, stream::Fuse, FutureExt, Stream, StreamExt}
 use addr_cache::AddrCache;
 use codec::{Decode, Encode};
 use ip_network::IpNetwork;
-use libp2p::{core::multiaddr, identity::PublicKey, Multiaddr};
+use libp2p::{core::multiaddr, identity::PublicKey, Multiaddr, PeerId};
 use multihash_codetable::{Code, MultihashDigest};
 
 use log::{debug, error, log_enabled};
@@ -131,6 +131,11 @@ pub struct Worker<Client, Network, Block, DhtEventStream> {
 	publish_non_global_ips: bool,
 	/// Same value as in the configuration.
 	strict_record_validation: bool,
+	/// Same value as in the configuration.
+	role_addresses: HashMap<String, Vec<Multiaddr>>,
+	/// Whether the record was withdrawn through [`crate::Service::withdraw_record`], in which case
+	/// only withdrawn records are published.
+	withdrawn: bool,
 
 	/// Interval at which to request addresses of authorities, refilling the pending lookups queue.
 	query_interval: ExpIncInterval,
@@ -235,6 +240,8 @@ where
 			latest_published_keys: HashSet::new(),
 			publish_non_global_ips: config.publish_non_global_ips,
 			strict_record_validation: config.strict_record_validation,
+			role_addresses: config.role_addresses,
+			withdrawn: false,
 			query_interval,
 			pending_lookups: Vec::new(),
 			in_flight_lookups: HashMap::new(),
@@ -263,7 +270,7 @@ where
 				},
 				// Handle messages from [`Service`]. Ignore if sender side is closed.
 				msg = self.from_service.select_next_some() => {
-					self.process_message_from_service(msg);
+					self.process_message_from_service(msg).await;
 				},
 				// Publish own addresses.
 				only_if_changed = future::select(
@@ -290,7 +297,7 @@ where
 		}
 	}
 
-	fn process_message_from_service(&self, msg: ServicetoWorkerMsg) {
+	async fn process_message_from_service(&mut self, msg: ServicetoWorkerMsg) {
 		match msg {
 			ServicetoWorkerMsg::GetAddressesByAuthorityId(authority, sender) => {
 				let _ = sender.send(
@@ -301,6 +308,19 @@ where
 				let _ = sender
 					.send(self.addr_cache.get_authority_ids_by_peer_id(&peer_id).map(Clone::clone));
 			},
+			ServicetoWorkerMsg::GetRoleAddressesByAuthorityId(authority, role, sender) => {
+				let _ = sender.send(
+					self.addr_cache
+						.get_role_addresses_by_authority_id(&authority, &role)
+						.map(Clone::clone),
+				);
+			},
+			ServicetoWorkerMsg::WithdrawRecord => {
+				self.withdrawn = true;
+				if let Err(e) = self.publish_ext_addresses(false).await {
+					error!(target: LOG_TARGET, "Failed to publish withdrawn record: {}", e);
+				}
+			},
 		}
 	}
 
@@ -351,16 +371,36 @@ where
 			return Ok(())
 		}
 
-		let addresses = serialize_addresses(self.addresses_to_publish());
+		let record = if self.withdrawn {
+			schema::AuthorityRecord {
+				creation_time: creation_time(),
+				withdrawn: true,
+				..Default::default()
+			}
+		} else {
+			schema::AuthorityRecord {
+				addresses: serialize_addresses(self.addresses_to_publish()),
+				creation_time: creation_time(),
+				role_addresses: self
+					.role_addresses
+					.iter()
+					.map(|(role, addresses)| schema::RoleAddresses {
+						role: role.clone(),
+						addresses: serialize_addresses(addresses.iter().cloned()),
+					})
+					.collect(),
+				withdrawn: false,
+			}
+		};
 
 		if let Some(metrics) = &self.metrics {
 			metrics.publish.inc();
 			metrics
 				.amount_addresses_last_published
-				.set(addresses.len().try_into().unwrap_or(std::u64::MAX));
+				.set(record.addresses.len().try_into().unwrap_or(std::u64::MAX));
 		}
 
-		let serialized_record = serialize_authority_record(addresses)?;
+		let serialized_record = serialize_authority_record(record)?;
 		let peer_signature = sign_record_with_peer_id(&serialized_record, self.network.as_ref())?;
 
 		let keys_vec = keys.iter().cloned().collect::<Vec<_>>();
@@ -507,7 +547,7 @@ where
 
 		let local_peer_id = self.network.local_peer_id();
 
-		let remote_addresses: Vec<Multiaddr> = values
+		let records = values
 			.into_iter()
 			.map(|(_k, v)| {
 				let schema::SignedAuthorityRecord { record, auth_signature, peer_signature } =
@@ -521,26 +561,24 @@ where
 					return Err(Error::VerifyingDhtPayload)
 				}
 
-				let addresses: Vec<Multiaddr> = schema::AuthorityRecord::decode(record.as_slice())
-					.map(|a| a.addresses)
-					.map_err(Error::DecodingProto)?
-					.into_iter()
-					.map(|a| a.try_into())
-					.collect::<std::result::Result<_, _>>()
-					.map_err(Error::ParsingMultiaddress)?;
+				let schema::AuthorityRecord { addresses, creation_time, role_addresses, withdrawn } =
+					schema::AuthorityRecord::decode(record.as_slice())
+						.map_err(Error::DecodingProto)?;
 
-				let get_peer_id = |a: &Multiaddr| match a.iter().last() {
-					Some(multiaddr::Protocol::P2p(peer_id)) => Some(peer_id),
-					_ => None,
-				};
+				// A withdrawal only needs to be authorized by the authority, there are no
+				// addresses left for the peer id signature to vouch for.
+				if withdrawn {
+					return Ok(VerifiedRecord {
+						creation_time,
+						withdrawn,
+						addresses: Vec::new(),
+						role_addresses: HashMap::new(),
+					})
+				}
 
-				// Ignore [`Multiaddr`]s without [`PeerId`] or with own addresses.
-				let addresses: Vec<Multiaddr> = addresses
-					.into_iter()
-					.filter(|a| get_peer_id(a).filter(|p| *p != local_peer_id).is_some())
-					.collect();
+				let addresses = parse_addresses(addresses, &local_peer_id)?;
 
-				let remote_peer_id = single(addresses.iter().map(get_peer_id))
+				let remote_peer_id = single(addresses.iter().map(peer_id_of))
 					.map_err(|_| Error::ReceivingDhtValueFoundEventWithDifferentPeerIds)? // different peer_id in records
 					.flatten()
 					.ok_or(Error::ReceivingDhtValueFoundEventWithNoPeerIds)?; // no records with peer_id in them
@@ -565,22 +603,64 @@ where
 						"Received unsigned authority discovery record from {}", authority_id
 					);
 				}
-				Ok(addresses)
+
+				let role_addresses = role_addresses
+					.into_iter()
+					.map(|schema::RoleAddresses { role, addresses }| {
+						Ok((role, parse_addresses(addresses, &local_peer_id)?))
+					})
+					.collect::<Result<HashMap<_, _>>>()?;
+
+				Ok(VerifiedRecord { creation_time, withdrawn, addresses, role_addresses })
 			})
-			.collect::<Result<Vec<Vec<Multiaddr>>>>()?
-			.into_iter()
-			.flatten()
-			.take(MAX_ADDRESSES_PER_AUTHORITY)
-			.collect();
+			.collect::<Result<Vec<VerifiedRecord>>>()?;
+
+		// Only the most recent records count, different nodes of the DHT might still hold older
+		// ones.
+		let newest = records.iter().map(|r| r.creation_time).max().unwrap_or_default();
+		if newest < self.addr_cache.record_creation_time(&authority_id).unwrap_or_default() {
+			debug!(
+				target: LOG_TARGET,
+				"Ignoring outdated authority discovery record from {}", authority_id
+			);
+			return Ok(())
+		}
+		let records = records.into_iter().filter(|r| r.creation_time == newest).collect::<Vec<_>>();
+
+		self.addr_cache.note_record_creation_time(authority_id.clone(), newest);
+
+		if records.iter().any(|r| r.withdrawn) {
+			debug!(target: LOG_TARGET, "Authority {} withdrew its addresses", authority_id);
+			self.addr_cache.remove(&authority_id);
+		} else {
+			let mut remote_role_addresses = HashMap::<String, HashSet<Multiaddr>>::new();
+			for (role, addresses) in records.iter().flat_map(|r| r.role_addresses.iter()) {
+				let cached = remote_role_addresses.entry(role.clone()).or_default();
+				cached.extend(
+					addresses
+						.iter()
+						.take(MAX_ADDRESSES_PER_AUTHORITY.saturating_sub(cached.len()))
+						.cloned(),
+				);
+			}
 
-		if !remote_addresses.is_empty() {
-			self.addr_cache.insert(authority_id, remote_addresses);
-			if let Some(metrics) = &self.metrics {
-				metrics
-					.known_authorities_count
-					.set(self.addr_cache.num_authority_ids().try_into().unwrap_or(std::u64::MAX));
+			let remote_addresses: Vec<Multiaddr> = records
+				.into_iter()
+				.flat_map(|r| r.addresses)
+				.take(MAX_ADDRESSES_PER_AUTHORITY)
+				.collect();
+
+			if !remote_addresses.is_empty() {
+				self.addr_cache.insert(authority_id.clone(), remote_addresses);
+				self.addr_cache.insert_role_addresses(authority_id, remote_role_addresses);
 			}
 		}
+
+		if let Some(metrics) = &self.metrics {
+			metrics
+				.known_authorities_count
+				.set(self.addr_cache.num_authority_ids().try_into().unwrap_or(std::u64::MAX));
+		}
 		Ok(())
 	}
 
@@ -640,18 +720,53 @@ where
 	})
 }
 
+/// Authority record which passed signature verification.
+struct VerifiedRecord {
+	creation_time: u64,
+	withdrawn: bool,
+	addresses: Vec<Multiaddr>,
+	role_addresses: HashMap<String, Vec<Multiaddr>>,
+}
+
+fn peer_id_of(address: &Multiaddr) -> Option<PeerId> {
+	match address.iter().last() {
+		Some(multiaddr::Protocol::P2p(peer_id)) => Some(peer_id),
+		_ => None,
+	}
+}
+
+/// Parses serialized addresses, ignoring [`Multiaddr`]s without [`PeerId`] or with own addresses.
+fn parse_addresses(addresses: Vec<Vec<u8>>, local_peer_id: &PeerId) -> Result<Vec<Multiaddr>> {
+	let addresses = addresses
+		.into_iter()
+		.map(|a| a.try_into())
+		.collect::<std::result::Result<Vec<Multiaddr>, _>>()
+		.map_err(Error::ParsingMultiaddress)?;
+
+	Ok(addresses
+		.into_iter()
+		.filter(|a| peer_id_of(a).filter(|p| p != local_peer_id).is_some())
+		.collect())
+}
+
 fn serialize_addresses(addresses: impl Iterator<Item = Multiaddr>) -> Vec<Vec<u8>> {
 	addresses.map(|a| a.to_vec()).collect()
 }
 
-fn serialize_authority_record(addresses: Vec<Vec<u8>>) -> Result<Vec<u8>> {
+fn serialize_authority_record(record: schema::AuthorityRecord) -> Result<Vec<u8>> {
 	let mut serialized_record = vec![];
-	schema::AuthorityRecord { addresses }
-		.encode(&mut serialized_record)
-		.map_err(Error::EncodingProto)?;
+	record.encode(&mut serialized_record).map_err(Error::EncodingProto)?;
 	Ok(serialized_record)
 }
 
+/// Current time in nanoseconds since the UNIX epoch, used as the creation time of records.
+fn creation_time() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_nanos().try_into().unwrap_or(u64::MAX))
+		.unwrap_or_default()
+}
+
 fn sign_record_with_peer_id(
 	serialized_record: &[u8],
 	network: &impl NetworkSigner,
@@ -36,6 +36,11 @@ pub(super) struct AddrCache {
 	/// it's not expected that a single `AuthorityId` can have multiple `PeerId`s.
 	authority_id_to_addresses: HashMap<AuthorityId, HashSet<Multiaddr>>,
 	peer_id_to_authority_ids: HashMap<PeerId, HashSet<AuthorityId>>,
+	/// Addresses of nodes acting in a given role on behalf of an authority, e.g. sentries.
+	authority_id_to_role_addresses: HashMap<AuthorityId, HashMap<String, HashSet<Multiaddr>>>,
+	/// Creation time of the most recent record seen for an authority. Kept when the record is
+	/// withdrawn, so that older records of the authority are not accepted again.
+	authority_id_to_record_creation_time: HashMap<AuthorityId, u64>,
 }
 
 impl AddrCache {
@@ -43,6 +48,8 @@ impl AddrCache {
 		AddrCache {
 			authority_id_to_addresses: HashMap::new(),
 			peer_id_to_authority_ids: HashMap::new(),
+			authority_id_to_role_addresses: HashMap::new(),
+			authority_id_to_record_creation_time: HashMap::new(),
 		}
 	}
 
@@ -90,6 +97,42 @@ impl AddrCache {
 		self.remove_authority_id_from_peer_ids(&authority_id, old_peer_ids.difference(&peer_ids));
 	}
 
+	/// Replaces the role addresses of the given [`AuthorityId`].
+	pub fn insert_role_addresses(
+		&mut self,
+		authority_id: AuthorityId,
+		role_addresses: HashMap<String, HashSet<Multiaddr>>,
+	) {
+		if role_addresses.is_empty() {
+			self.authority_id_to_role_addresses.remove(&authority_id);
+		} else {
+			self.authority_id_to_role_addresses.insert(authority_id, role_addresses);
+		}
+	}
+
+	/// Notes the creation time of the most recent record seen for the given [`AuthorityId`].
+	pub fn note_record_creation_time(&mut self, authority_id: AuthorityId, creation_time: u64) {
+		self.authority_id_to_record_creation_time.insert(authority_id, creation_time);
+	}
+
+	/// Returns the creation time of the most recent record seen for the given [`AuthorityId`].
+	pub fn record_creation_time(&self, authority_id: &AuthorityId) -> Option<u64> {
+		self.authority_id_to_record_creation_time.get(authority_id).copied()
+	}
+
+	/// Removes all addresses of the given [`AuthorityId`], keeping the creation time of its
+	/// latest record.
+	pub fn remove(&mut self, authority_id: &AuthorityId) {
+		self.authority_id_to_role_addresses.remove(authority_id);
+
+		if let Some(addresses) = self.authority_id_to_addresses.remove(authority_id) {
+			self.remove_authority_id_from_peer_ids(
+				authority_id,
+				addresses_to_peer_ids(&addresses).iter(),
+			);
+		}
+	}
+
 	/// Remove the given `authority_id` from the `peer_id` to `authority_ids` mapping.
 	///
 	/// If a `peer_id` doesn't have any `authority_id` assigned anymore, it is removed.
@@ -123,6 +166,15 @@ impl AddrCache {
 		self.authority_id_to_addresses.get(authority_id)
 	}
 
+	/// Returns the addresses of the nodes acting in the given role for the given [`AuthorityId`].
+	pub fn get_role_addresses_by_authority_id(
+		&self,
+		authority_id: &AuthorityId,
+		role: &str,
+	) -> Option<&HashSet<Multiaddr>> {
+		self.authority_id_to_role_addresses.get(authority_id)?.get(role)
+	}
+
 	/// Returns the [`AuthorityId`]s for the given [`PeerId`].
 	///
 	/// As the authority id can change between sessions, one [`PeerId`] can be mapped to
@@ -137,26 +189,15 @@ impl AddrCache {
 		// The below logic could be replaced by `BtreeMap::drain_filter` once it stabilized.
 		let authority_ids_to_remove = self
 			.authority_id_to_addresses
-			.iter()
-			.filter(|(id, _addresses)| !authority_ids.contains(id))
-			.map(|entry| entry.0)
+			.keys()
+			.chain(self.authority_id_to_record_creation_time.keys())
+			.filter(|id| !authority_ids.contains(id))
 			.cloned()
-			.collect::<Vec<AuthorityId>>();
+			.collect::<HashSet<AuthorityId>>();
 
 		for authority_id_to_remove in authority_ids_to_remove {
-			// Remove other entries from `self.authority_id_to_addresses`.
-			let addresses = if let Some(addresses) =
-				self.authority_id_to_addresses.remove(&authority_id_to_remove)
-			{
-				addresses
-			} else {
-				continue
-			};
-
-			self.remove_authority_id_from_peer_ids(
-				&authority_id_to_remove,
-				addresses_to_peer_ids(&addresses).iter(),
-			);
+			self.remove(&authority_id_to_remove);
+			self.authority_id_to_record_creation_time.remove(&authority_id_to_remove);
 		}
 	}
 }
@@ -385,4 +426,35 @@ mod tests {
 			addr_cache.get_addresses_by_authority_id(&authority_id1).unwrap()
 		);
 	}
+
+	#[test]
+	fn removing_an_authority_keeps_its_record_creation_time() {
+		let mut addr_cache = AddrCache::new();
+
+		let peer_id = PeerId::random();
+		let addr = Multiaddr::empty().with(Protocol::P2p(peer_id));
+		let sentry_addr = Multiaddr::empty().with(Protocol::P2p(PeerId::random()));
+		let authority_id = AuthorityPair::generate().0.public();
+
+		addr_cache.insert(authority_id.clone(), vec![addr]);
+		addr_cache.insert_role_addresses(
+			authority_id.clone(),
+			HashMap::from([("sentry".to_string(), HashSet::from([sentry_addr.clone()]))]),
+		);
+		addr_cache.note_record_creation_time(authority_id.clone(), 42);
+		assert_eq!(
+			Some(&HashSet::from([sentry_addr])),
+			addr_cache.get_role_addresses_by_authority_id(&authority_id, "sentry"),
+		);
+
+		addr_cache.remove(&authority_id);
+
+		assert_eq!(None, addr_cache.get_addresses_by_authority_id(&authority_id));
+		assert_eq!(None, addr_cache.get_role_addresses_by_authority_id(&authority_id, "sentry"));
+		assert_eq!(None, addr_cache.get_authority_ids_by_peer_id(&peer_id));
+		assert_eq!(Some(42), addr_cache.record_creation_time(&authority_id));
+
+		addr_cache.retain_ids(&[]);
+		assert_eq!(None, addr_cache.record_creation_time(&authority_id));
+	}
 }
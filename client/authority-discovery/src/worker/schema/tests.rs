@@ -65,7 +65,15 @@ fn v1_decodes_v2() {
 	let vec_auth_signature = b"Totally valid signature, I promise!".to_vec();
 	let vec_peer_signature = b"Surprisingly hard to crack crypto".to_vec();
 
-	let record_v2 = AuthorityRecord { addresses: vec_addresses.clone() };
+	let record_v2 = AuthorityRecord {
+		addresses: vec_addresses.clone(),
+		creation_time: 1,
+		role_addresses: vec![RoleAddresses {
+			role: "sentry".to_string(),
+			addresses: vec_addresses.clone(),
+		}],
+		withdrawn: false,
+	};
 	let mut vec_record_v2 = vec![];
 	record_v2.encode(&mut vec_record_v2).unwrap();
 	let vec_peer_public = peer_public.encode_protobuf();
@@ -180,6 +180,14 @@ impl NetworkDHTProvider for TestNetwork {
 			.unbounded_send(TestNetworkEvent::GetCalled(key.clone()))
 			.unwrap();
 	}
+
+	fn start_providing(&self, _: KademliaKey) {
+		unimplemented!()
+	}
+
+	fn stop_providing(&self, _: KademliaKey) {
+		unimplemented!()
+	}
 }
 
 impl NetworkStateInfo for TestNetwork {
@@ -215,8 +223,21 @@ fn build_dht_event<Signer: NetworkSigner>(
 	key_store: &MemoryKeystore,
 	network: Option<&Signer>,
 ) -> Vec<(KademliaKey, Vec<u8>)> {
-	let serialized_record =
-		serialize_authority_record(serialize_addresses(addresses.into_iter())).unwrap();
+	let record = schema::AuthorityRecord {
+		addresses: serialize_addresses(addresses.into_iter()),
+		creation_time: creation_time(),
+		..Default::default()
+	};
+	build_dht_event_with_record(record, public_key, key_store, network)
+}
+
+fn build_dht_event_with_record<Signer: NetworkSigner>(
+	record: schema::AuthorityRecord,
+	public_key: AuthorityId,
+	key_store: &MemoryKeystore,
+	network: Option<&Signer>,
+) -> Vec<(KademliaKey, Vec<u8>)> {
+	let serialized_record = serialize_authority_record(record).unwrap();
 
 	let peer_signature = network.map(|n| sign_record_with_peer_id(&serialized_record, n).unwrap());
 	let kv_pairs = sign_record_with_authority_ids(
@@ -566,6 +587,124 @@ impl DhtValueFoundTester {
 			})
 			.unwrap()
 	}
+
+	/// Like [`Self::process_value_found`], but reuses the worker of the previous call.
+	fn process_next_value_found(
+		&mut self,
+		values: Vec<(KademliaKey, Vec<u8>)>,
+	) -> Option<&HashSet<Multiaddr>> {
+		let local_worker = self.local_worker.as_mut().unwrap();
+
+		block_on(local_worker.refill_pending_lookups_queue()).unwrap();
+		local_worker.start_new_lookups();
+
+		drop(local_worker.handle_dht_value_found_event(values));
+
+		local_worker
+			.addr_cache
+			.get_addresses_by_authority_id(&self.remote_authority_public.into())
+	}
+
+	fn record_event(
+		&self,
+		addresses: Vec<Multiaddr>,
+		creation_time: u64,
+		withdrawn: bool,
+	) -> Vec<(KademliaKey, Vec<u8>)> {
+		build_dht_event_with_record(
+			schema::AuthorityRecord {
+				addresses: serialize_addresses(addresses.into_iter()),
+				creation_time,
+				withdrawn,
+				..Default::default()
+			},
+			self.remote_authority_public.into(),
+			&self.remote_key_store,
+			Some(&TestSigner { keypair: &self.remote_node_key }),
+		)
+	}
+}
+
+#[test]
+fn newest_record_wins_within_a_lookup() {
+	let mut tester = DhtValueFoundTester::new();
+	let old_addr = tester.multiaddr_with_peer_id(1);
+	let new_addr = tester.multiaddr_with_peer_id(2);
+	let kv_pairs = tester
+		.record_event(vec![new_addr.clone()], 2, false)
+		.into_iter()
+		.chain(tester.record_event(vec![old_addr], 1, false))
+		.collect();
+
+	let cached_remote_addresses = tester.process_value_found(true, kv_pairs);
+
+	assert_eq!(Some(&HashSet::from([new_addr])), cached_remote_addresses);
+}
+
+#[test]
+fn outdated_record_is_ignored() {
+	let mut tester = DhtValueFoundTester::new();
+	let old_addr = tester.multiaddr_with_peer_id(1);
+	let new_addr = tester.multiaddr_with_peer_id(2);
+
+	let kv_pairs = tester.record_event(vec![new_addr.clone()], 2, false);
+	tester.process_value_found(true, kv_pairs);
+
+	let kv_pairs = tester.record_event(vec![old_addr], 1, false);
+	let cached_remote_addresses = tester.process_next_value_found(kv_pairs);
+
+	assert_eq!(Some(&HashSet::from([new_addr])), cached_remote_addresses);
+}
+
+#[test]
+fn withdrawn_record_removes_addresses() {
+	let mut tester = DhtValueFoundTester::new();
+	let addr = tester.multiaddr_with_peer_id(1);
+
+	let kv_pairs = tester.record_event(vec![addr.clone()], 1, false);
+	assert_eq!(Some(&HashSet::from([addr.clone()])), tester.process_value_found(true, kv_pairs));
+
+	let kv_pairs = tester.record_event(vec![], 2, true);
+	assert!(tester.process_next_value_found(kv_pairs).is_none());
+
+	// The withdrawal can't be undone by an older record still lingering in the DHT.
+	let kv_pairs = tester.record_event(vec![addr], 1, false);
+	assert!(tester.process_next_value_found(kv_pairs).is_none());
+}
+
+#[test]
+fn role_addresses_are_cached() {
+	let mut tester = DhtValueFoundTester::new();
+	let addr = tester.multiaddr_with_peer_id(1);
+	let sentry_addr: Multiaddr = "/ip6/2001:db8:0:0:0:0:0:3/tcp/30333"
+		.parse::<Multiaddr>()
+		.unwrap()
+		.with(multiaddr::Protocol::P2p(PeerId::random()));
+	let kv_pairs = build_dht_event_with_record(
+		schema::AuthorityRecord {
+			addresses: serialize_addresses(vec![addr.clone()].into_iter()),
+			creation_time: 1,
+			role_addresses: vec![schema::RoleAddresses {
+				role: "sentry".to_string(),
+				addresses: serialize_addresses(vec![sentry_addr.clone()].into_iter()),
+			}],
+			withdrawn: false,
+		},
+		tester.remote_authority_public.into(),
+		&tester.remote_key_store,
+		Some(&TestSigner { keypair: &tester.remote_node_key }),
+	);
+
+	assert_eq!(Some(&HashSet::from([addr])), tester.process_value_found(true, kv_pairs));
+	assert_eq!(
+		Some(&HashSet::from([sentry_addr])),
+		tester
+			.local_worker
+			.as_ref()
+			.unwrap()
+			.addr_cache
+			.get_role_addresses_by_authority_id(&tester.remote_authority_public.into(), "sentry"),
+	);
 }
 
 #[test]
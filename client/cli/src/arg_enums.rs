@@ -240,6 +240,8 @@ pub enum SyncMode {
 	FastUnsafe,
 	/// Prove finality and download the latest state.
 	Warp,
+	/// Import the snapshot given with `--snapshot`, then continue with a full sync.
+	Snapshot,
 }
 
 impl Into<sc_network::config::SyncMode> for SyncMode {
@@ -255,6 +257,7 @@ impl Into<sc_network::config::SyncMode> for SyncMode {
 				storage_chain_mode: false,
 			},
 			SyncMode::Warp => sc_network::config::SyncMode::Warp,
+			SyncMode::Snapshot => sc_network::config::SyncMode::Full,
 		}
 	}
 }
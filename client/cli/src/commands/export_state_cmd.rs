@@ -24,18 +24,32 @@ use crate::{
 };
 use clap::Parser;
 use log::info;
-use sc_client_api::{HeaderBackend, StorageProvider, UsageProvider};
+use sc_client_api::{BlockBackend, HeaderBackend, StorageProvider, UsageProvider};
+use sc_service::WarpSyncProvider;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
-use std::{fmt::Debug, io::Write, str::FromStr, sync::Arc};
+use std::{
+	fmt::Debug,
+	fs,
+	io::{BufWriter, Write},
+	path::PathBuf,
+	str::FromStr,
+	sync::Arc,
+};
 
 /// The `export-state` command used to export the state of a given block into
-/// a chain spec.
+/// a chain spec, or into a snapshot which can be imported with `import-state`.
 #[derive(Debug, Clone, Parser)]
 pub struct ExportStateCmd {
 	/// Block hash or number.
 	#[arg(value_name = "HASH or NUMBER")]
 	pub input: Option<BlockNumberOrHash>,
 
+	/// Write a snapshot of the block to this file instead of a chain spec to stdout.
+	///
+	/// Only the last block with a finality proof can be written to a snapshot, it's the default.
+	#[arg(long, value_name = "PATH")]
+	pub snapshot: Option<PathBuf>,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub shared_params: SharedParams,
@@ -51,20 +65,34 @@ pub struct ExportStateCmd {
 
 impl ExportStateCmd {
 	/// Run the `export-state` command
+	///
+	/// The `warp_sync_provider` proves the finality of the block of a snapshot.
 	pub async fn run<B, BA, C>(
 		&self,
 		client: Arc<C>,
 		mut input_spec: Box<dyn sc_service::ChainSpec>,
+		warp_sync_provider: Option<Arc<dyn WarpSyncProvider<B>>>,
 	) -> error::Result<()>
 	where
 		B: BlockT,
-		C: UsageProvider<B> + StorageProvider<B, BA> + HeaderBackend<B>,
+		C: UsageProvider<B> + StorageProvider<B, BA> + HeaderBackend<B> + BlockBackend<B>,
 		BA: sc_client_api::backend::Backend<B>,
 		<B::Hash as FromStr>::Err: Debug,
 		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
 	{
-		info!("Exporting raw state...");
 		let block_id = self.input.as_ref().map(|b| b.parse()).transpose()?;
+
+		if let Some(path) = &self.snapshot {
+			let warp_sync_provider = warp_sync_provider
+				.ok_or("Snapshots require a warp sync provider to prove their finality")?;
+			let hash = block_id.map(|id| client.expect_block_hash_from_id(&id)).transpose()?;
+			let output = BufWriter::new(fs::File::create(path)?);
+			sc_service::chain_ops::export_snapshot(client, &*warp_sync_provider, hash, output)?;
+			info!("Snapshot written to {}", path.display());
+			return Ok(())
+		}
+
+		info!("Exporting raw state...");
 		let hash = match block_id {
 			Some(id) => client.expect_block_hash_from_id(&id)?,
 			None => client.usage_info().chain.best_hash,
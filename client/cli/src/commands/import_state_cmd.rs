@@ -0,0 +1,86 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	error,
+	params::{ImportParams, SharedParams},
+	CliConfiguration,
+};
+use clap::Parser;
+use sc_client_api::HeaderBackend;
+use sc_service::{chain_ops::import_snapshot, WarpSyncProvider};
+use sp_runtime::traits::Block as BlockT;
+use std::{
+	fs,
+	io::{self, BufReader, Read},
+	path::PathBuf,
+	sync::Arc,
+};
+
+/// The `import-state` command used to import a snapshot written by `export-state --snapshot`.
+#[derive(Debug, Parser)]
+pub struct ImportStateCmd {
+	/// Input file or stdin if unspecified.
+	#[arg()]
+	pub input: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl ImportStateCmd {
+	/// Run the import-state command
+	///
+	/// The `warp_sync_provider` verifies the finality of the block of the snapshot.
+	pub async fn run<B, C, IQ>(
+		&self,
+		client: Arc<C>,
+		mut import_queue: IQ,
+		warp_sync_provider: Arc<dyn WarpSyncProvider<B>>,
+	) -> error::Result<()>
+	where
+		C: HeaderBackend<B> + Send + Sync + 'static,
+		B: BlockT,
+		IQ: sc_service::ImportQueue<B> + 'static,
+	{
+		let file: Box<dyn Read + Send> = match &self.input {
+			Some(filename) => Box::new(BufReader::new(fs::File::open(filename)?)),
+			None => Box::new(io::stdin()),
+		};
+
+		import_snapshot(client, &mut import_queue, &*warp_sync_provider, file)
+			.await
+			.map_err(Into::into)
+	}
+}
+
+impl CliConfiguration for ImportStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
@@ -242,6 +242,14 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(self.import_params().map(|x| x.trie_cache_maximum_size()).unwrap_or_default())
 	}
 
+	/// Get the maximum size of the persisted trie cache.
+	///
+	/// By default this is retrieved from `ImportParams` if it is available.
+	/// If `None` is returned the trie cache isn't persisted across restarts.
+	fn trie_cache_persistence_size(&self) -> Result<Option<usize>> {
+		Ok(self.import_params().and_then(|x| x.trie_cache_persistence_size()))
+	}
+
 	/// Get the state pruning mode.
 	///
 	/// By default this is retrieved from `PruningMode` if it is available. Otherwise its
@@ -489,6 +497,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			database: self.database_config(&config_dir, database_cache_size, database)?,
 			data_path: config_dir,
 			trie_cache_maximum_size: self.trie_cache_maximum_size()?,
+			trie_cache_persistence_size: self.trie_cache_persistence_size()?,
 			state_pruning: self.state_pruning()?,
 			blocks_pruning: self.blocks_pruning()?,
 			wasm_method: self.wasm_method()?,
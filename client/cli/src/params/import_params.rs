@@ -79,6 +79,16 @@ pub struct ImportParams {
 	#[arg(long, value_name = "Bytes", default_value_t = 67108864)]
 	pub trie_cache_size: usize,
 
+	/// Persist the hottest trie cache entries on shutdown and load them on startup.
+	/// Cached values are only loaded back if the best block didn't change in the meantime.
+	/// Has no effect if the trie cache is disabled.
+	#[arg(long)]
+	pub trie_cache_persist: bool,
+
+	/// Specify the maximum size of the persisted trie cache.
+	#[arg(long, value_name = "Bytes", default_value_t = 16777216)]
+	pub trie_cache_persist_size: usize,
+
 	/// DEPRECATED
 	/// Switch to `--trie-cache-size`.
 	#[arg(long)]
@@ -99,6 +109,17 @@ impl ImportParams {
 		}
 	}
 
+	/// Specify the maximum size of the persisted trie cache.
+	///
+	/// Returns `None` if the trie cache shouldn't be persisted.
+	pub fn trie_cache_persistence_size(&self) -> Option<usize> {
+		if self.trie_cache_persist && self.trie_cache_size != 0 {
+			Some(self.trie_cache_persist_size)
+		} else {
+			None
+		}
+	}
+
 	/// Get the WASM execution method from the parameters
 	pub fn wasm_method(&self) -> sc_service::config::WasmExecutionMethod {
 		self.execution_strategies.check_usage_and_print_deprecation_warning();
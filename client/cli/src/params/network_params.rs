@@ -21,7 +21,8 @@ use crate::{arg_enums::SyncMode, params::node_key_params::NodeKeyParams};
 use clap::Args;
 use sc_network::{
 	config::{
-		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, SetConfig, TransportConfig,
+		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, RateLimit, SetConfig,
+		TransportConfig,
 	},
 	multiaddr::Protocol,
 };
@@ -129,10 +130,25 @@ pub struct NetworkParams {
 	#[arg(long, default_value = "20")]
 	pub kademlia_replication_factor: NonZeroUsize,
 
+	/// Maximum number of block and state requests each peer can make per minute.
+	///
+	/// Requests exceeding the limit are refused and lower the reputation of the peer. By default,
+	/// or with `0`, requests aren't limited.
+	#[arg(long, value_name = "COUNT", default_value_t = 0)]
+	pub max_sync_requests_per_minute: u32,
+
 	/// Join the IPFS network and serve transactions over bitswap protocol.
 	#[arg(long)]
 	pub ipfs_server: bool,
 
+	/// Publish the state of every finalized block whose number is a multiple of this interval
+	/// over bitswap.
+	///
+	/// The state is kept in memory until the next one is published, so this is only suitable for
+	/// small states. Requires `--ipfs-server`.
+	#[arg(long, value_name = "BLOCKS", requires = "ipfs_server")]
+	pub ipfs_state_interval: Option<u32>,
+
 	/// Blockchain syncing mode.
 	#[arg(
 		long,
@@ -144,6 +160,10 @@ pub struct NetworkParams {
 	)]
 	pub sync: SyncMode,
 
+	/// Snapshot written by `export-state --snapshot` to import with `--sync snapshot`.
+	#[arg(long, value_name = "PATH", required_if_eq("sync", "snapshot"))]
+	pub snapshot: Option<PathBuf>,
+
 	/// Maximum number of blocks per request.
 	///
 	/// Try reducing this number from the default value if you have a slow network connection
@@ -243,8 +263,14 @@ impl NetworkParams {
 			kademlia_disjoint_query_paths: self.kademlia_disjoint_query_paths,
 			kademlia_replication_factor: self.kademlia_replication_factor,
 			yamux_window_size: None,
+			request_rate_limit: (self.max_sync_requests_per_minute != 0).then(|| RateLimit {
+				max_requests: self.max_sync_requests_per_minute,
+				..RateLimit::default()
+			}),
 			ipfs_server: self.ipfs_server,
+			ipfs_state_interval: self.ipfs_state_interval,
 			sync_mode: self.sync.into(),
+			snapshot: (self.sync == SyncMode::Snapshot).then(|| self.snapshot.clone()).flatten(),
 		}
 	}
 }
@@ -299,4 +325,15 @@ mod tests {
 
 		assert_eq!(SyncMode::Warp, params.network_params.sync);
 	}
+
+	#[test]
+	fn snapshot_sync_requires_a_snapshot() {
+		assert!(Cli::try_parse_from(["", "--sync", "snapshot"]).is_err());
+
+		let params = Cli::try_parse_from(["", "--sync", "snapshot", "--snapshot", "state.snap"])
+			.expect("Parses network params");
+
+		assert_eq!(SyncMode::Snapshot, params.network_params.sync);
+		assert_eq!(Some(PathBuf::from("state.snap")), params.network_params.snapshot);
+	}
 }
@@ -27,6 +27,7 @@
 use clap::{ArgAction, Args};
 use sc_network::config::Role;
 use sc_service::config::OffchainWorkerConfig;
+use std::time::Duration;
 
 use crate::{error, OffchainWorkerEnabled};
 
@@ -48,6 +49,21 @@ pub struct OffchainWorkerParams {
 	/// Enables a runtime to write directly to a offchain workers DB during block import.
 	#[arg(long = "enable-offchain-indexing", value_name = "ENABLE_OFFCHAIN_INDEXING", default_value_t = false, action = ArgAction::Set)]
 	pub indexing_enabled: bool,
+
+	/// Maximum number of concurrent HTTP requests a single offchain worker run may make.
+	#[arg(long = "offchain-http-max-requests", value_name = "COUNT", default_value_t = 32)]
+	pub http_max_concurrent_requests: usize,
+
+	/// Timeout in seconds after which an HTTP request of an offchain worker is aborted.
+	#[arg(long = "offchain-http-timeout", value_name = "SECONDS", default_value_t = 30)]
+	pub http_request_timeout: u64,
+
+	/// Domain offchain workers may send HTTP requests to, including its subdomains.
+	///
+	/// Can be passed multiple times. Requests to domains which weren't passed are refused, so
+	/// offchain workers can't make any HTTP requests without it. `*` allows any domain.
+	#[arg(long = "offchain-http-allow", value_name = "DOMAIN")]
+	pub http_allowed_domains: Vec<String>,
 }
 
 impl OffchainWorkerParams {
@@ -61,6 +77,12 @@ impl OffchainWorkerParams {
 		};
 
 		let indexing_enabled = self.indexing_enabled;
-		Ok(OffchainWorkerConfig { enabled, indexing_enabled })
+		Ok(OffchainWorkerConfig {
+			enabled,
+			indexing_enabled,
+			http_max_concurrent_requests: self.http_max_concurrent_requests,
+			http_request_timeout: Duration::from_secs(self.http_request_timeout),
+			http_allowed_domains: self.http_allowed_domains.clone(),
+		})
 	}
 }
@@ -29,13 +29,17 @@ use prometheus_endpoint::Registry;
 use sc_client_api::{backend::AuxStore, BlockOf, UsageProvider};
 use sc_consensus::{
 	block_import::{BlockImport, BlockImportParams, ForkChoiceStrategy},
-	import_queue::{BasicQueue, BoxJustificationImport, DefaultImportQueue, Verifier},
+	fork_choice::{ForkChoiceBlockImport, SharedForkChoiceRule},
+	import_queue::{
+		BasicQueue, BoxBlockImport, BoxJustificationImport, DefaultImportQueue, PipelineConfig,
+		PipelinedVerifier, Verifier,
+	},
 };
 use sc_consensus_slots::{check_equivocation, CheckedHeader, InherentDataProviderExt};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_TRACE};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
-use sp_blockchain::HeaderBackend;
+use sp_blockchain::{HeaderBackend, HeaderMetadata};
 use sp_consensus::Error as ConsensusError;
 use sp_consensus_aura::{inherents::AuraInherentData, AuraApi};
 use sp_consensus_slots::Slot;
@@ -52,43 +56,21 @@ use std::{fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc};
 /// containing the seal.
 ///
 /// This digest item will always return `Some` when used with `as_aura_seal`.
-fn check_header<C, B: BlockT, P: Pair>(
-	client: &C,
+fn check_header<B: BlockT, P: Pair>(
 	slot_now: Slot,
 	header: B::Header,
 	hash: B::Hash,
 	authorities: &[AuthorityId<P>],
-	check_for_equivocation: CheckForEquivocation,
 ) -> Result<CheckedHeader<B::Header, (Slot, DigestItem)>, Error<B>>
 where
 	P::Signature: Codec,
-	C: sc_client_api::backend::AuxStore,
 	P::Public: Encode + Decode + PartialEq + Clone,
 {
 	let check_result =
 		crate::standalone::check_header_slot_and_seal::<B, P>(slot_now, header, authorities);
 
 	match check_result {
-		Ok((header, slot, seal)) => {
-			let expected_author = crate::standalone::slot_author::<P>(slot, &authorities);
-			let should_equiv_check = check_for_equivocation.check_for_equivocation();
-			if let (true, Some(expected)) = (should_equiv_check, expected_author) {
-				if let Some(equivocation_proof) =
-					check_equivocation(client, slot_now, slot, &header, expected)
-						.map_err(Error::Client)?
-				{
-					info!(
-						target: LOG_TARGET,
-						"Slot author is equivocating at slot {} with headers {:?} and {:?}",
-						slot,
-						equivocation_proof.first_header.hash(),
-						equivocation_proof.second_header.hash(),
-					);
-				}
-			}
-
-			Ok(CheckedHeader::Checked(header, (slot, seal)))
-		},
+		Ok((header, slot, seal)) => Ok(CheckedHeader::Checked(header, (slot, seal))),
 		Err(SealVerificationError::Deferred(header, slot)) =>
 			Ok(CheckedHeader::Deferred(header, slot)),
 		Err(SealVerificationError::Unsealed) => Err(Error::HeaderUnsealed(hash)),
@@ -99,6 +81,51 @@ where
 	}
 }
 
+/// Check whether the author of the given (unsealed) header already authored another block in the
+/// same slot.
+fn check_header_equivocation<C, B: BlockT, P: Pair>(
+	client: &C,
+	slot_now: Slot,
+	slot: Slot,
+	header: &B::Header,
+	authorities: &[AuthorityId<P>],
+	check_for_equivocation: CheckForEquivocation,
+) -> Result<(), Error<B>>
+where
+	C: sc_client_api::backend::AuxStore,
+	P::Public: Encode + Decode + PartialEq + Clone,
+{
+	let expected_author = crate::standalone::slot_author::<P>(slot, authorities);
+	let should_equiv_check = check_for_equivocation.check_for_equivocation();
+	if let (true, Some(expected)) = (should_equiv_check, expected_author) {
+		if let Some(equivocation_proof) =
+			check_equivocation(client, slot_now, slot, header, expected).map_err(Error::Client)?
+		{
+			info!(
+				target: LOG_TARGET,
+				"Slot author is equivocating at slot {} with headers {:?} and {:?}",
+				slot,
+				equivocation_proof.first_header.hash(),
+				equivocation_proof.second_header.hash(),
+			);
+		}
+	}
+
+	Ok(())
+}
+
+/// The seal of an Aura header, checked ahead of the import of its parent.
+pub struct AuraCheckedSeal<B: BlockT, P: Pair> {
+	/// The authorities the seal was checked against.
+	authorities: Vec<AuthorityId<P>>,
+	/// The header without the seal.
+	pre_header: B::Header,
+	/// The slot of the header.
+	slot: Slot,
+	/// The seal.
+	seal: DigestItem,
+}
+
 /// A verifier for Aura blocks.
 pub struct AuraVerifier<C, P, CIDP, N> {
 	client: Arc<C>,
@@ -164,8 +191,7 @@ where
 	}
 }
 
-#[async_trait::async_trait]
-impl<B: BlockT, C, P, CIDP> Verifier<B> for AuraVerifier<C, P, CIDP, NumberFor<B>>
+impl<B: BlockT, C, P, CIDP> AuraVerifier<C, P, CIDP, NumberFor<B>>
 where
 	C: ProvideRuntimeApi<B> + Send + Sync + sc_client_api::backend::AuxStore,
 	C::Api: BlockBuilderApi<B> + AuraApi<B, AuthorityId<P>> + ApiExt<B>,
@@ -175,9 +201,12 @@ where
 	CIDP: CreateInherentDataProviders<B, ()> + Send + Sync,
 	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
 {
-	async fn verify(
-		&mut self,
+	/// Verify the given block, reusing the seal check if it was done against the right
+	/// authorities.
+	async fn verify_with(
+		&self,
 		mut block: BlockImportParams<B, ()>,
+		checked: Option<AuraCheckedSeal<B, P>>,
 	) -> Result<BlockImportParams<B, ()>, String> {
 		// Skip checks that include execution, if being told so or when importing only state.
 		//
@@ -217,17 +246,25 @@ where
 		// we add one to allow for some small drift.
 		// FIXME #1019 in the future, alter this queue to allow deferring of
 		// headers
-		let checked_header = check_header::<C, B, P>(
-			&self.client,
-			slot_now + 1,
-			block.header,
-			hash,
-			&authorities[..],
-			self.check_for_equivocation,
-		)
-		.map_err(|e| e.to_string())?;
+		let checked_header = match checked {
+			// The seal was checked against the authorities of the parent.
+			Some(checked) if checked.authorities == authorities && checked.slot <= slot_now + 1 =>
+				CheckedHeader::Checked(checked.pre_header, (checked.slot, checked.seal)),
+			_ => check_header::<B, P>(slot_now + 1, block.header, hash, &authorities[..])
+				.map_err(|e| e.to_string())?,
+		};
 		match checked_header {
 			CheckedHeader::Checked(pre_header, (slot, seal)) => {
+				check_header_equivocation::<C, B, P>(
+					&self.client,
+					slot_now + 1,
+					slot,
+					&pre_header,
+					&authorities[..],
+					self.check_for_equivocation,
+				)
+				.map_err(|e| e.to_string())?;
+
 				// if the body is passed through, we need to use the runtime
 				// to check that the internally-set timestamp in the inherents
 				// actually matches the slot set in the seal.
@@ -289,6 +326,83 @@ where
 	}
 }
 
+#[async_trait::async_trait]
+impl<B: BlockT, C, P, CIDP> Verifier<B> for AuraVerifier<C, P, CIDP, NumberFor<B>>
+where
+	C: ProvideRuntimeApi<B> + Send + Sync + sc_client_api::backend::AuxStore,
+	C::Api: BlockBuilderApi<B> + AuraApi<B, AuthorityId<P>> + ApiExt<B>,
+	P: Pair + Send + Sync + 'static,
+	P::Public: Send + Sync + Hash + Eq + Clone + Decode + Encode + Debug + 'static,
+	P::Signature: Encode + Decode,
+	CIDP: CreateInherentDataProviders<B, ()> + Send + Sync,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
+{
+	async fn verify(
+		&mut self,
+		block: BlockImportParams<B, ()>,
+	) -> Result<BlockImportParams<B, ()>, String> {
+		self.verify_with(block, None).await
+	}
+}
+
+#[async_trait::async_trait]
+impl<B: BlockT, C, P, CIDP> PipelinedVerifier<B> for AuraVerifier<C, P, CIDP, NumberFor<B>>
+where
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + sc_client_api::backend::AuxStore,
+	C::Api: BlockBuilderApi<B> + AuraApi<B, AuthorityId<P>> + ApiExt<B>,
+	P: Pair + Send + Sync + 'static,
+	P::Public: Send + Sync + Hash + Eq + Clone + Decode + Encode + Debug + 'static,
+	P::Signature: Encode + Decode,
+	CIDP: CreateInherentDataProviders<B, ()> + Send + Sync,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
+{
+	type CheckedSeal = AuraCheckedSeal<B, P>;
+
+	fn check_seal_ahead(&self, header: &B::Header) -> Option<AuraCheckedSeal<B, P>> {
+		// The parent is usually still being imported, in which case the authorities of the best
+		// block are used. If they turn out to be the wrong ones, the seal is checked again once the
+		// parent was imported.
+		let parent_hash = *header.parent_hash();
+		let authorities = authorities(
+			self.client.as_ref(),
+			parent_hash,
+			*header.number(),
+			&self.compatibility_mode,
+		)
+		.or_else(|_| {
+			authorities(
+				self.client.as_ref(),
+				self.client.info().best_hash,
+				*header.number(),
+				&self.compatibility_mode,
+			)
+		})
+		.ok()?;
+
+		// Whether the slot is too far in the future is checked once the block is verified.
+		match check_header::<B, P>(
+			Slot::from(u64::MAX),
+			header.clone(),
+			header.hash(),
+			&authorities,
+		)
+		.ok()?
+		{
+			CheckedHeader::Checked(pre_header, (slot, seal)) =>
+				Some(AuraCheckedSeal { authorities, pre_header, slot, seal }),
+			CheckedHeader::Deferred(..) => None,
+		}
+	}
+
+	async fn verify_checked(
+		&self,
+		block: BlockImportParams<B, ()>,
+		checked: Option<AuraCheckedSeal<B, P>>,
+	) -> Result<BlockImportParams<B, ()>, String> {
+		self.verify_with(block, checked).await
+	}
+}
+
 /// Should we check for equivocation of a block author?
 #[derive(Debug, Clone, Copy)]
 pub enum CheckForEquivocation {
@@ -335,6 +449,15 @@ pub struct ImportQueueParams<'a, Block: BlockT, I, C, S, CIDP> {
 	///
 	/// If in doubt, use `Default::default()`.
 	pub compatibility_mode: CompatibilityMode<NumberFor<Block>>,
+	/// Rule choosing the best block, replacing the longest chain rule.
+	///
+	/// The block import used for authoring should apply the same rule, see
+	/// [`ForkChoiceBlockImport`].
+	pub fork_choice_rule: Option<SharedForkChoiceRule<Block>>,
+	/// If set, the seals of queued blocks are checked ahead of their import.
+	///
+	/// See [`BasicQueue::new_pipelined`].
+	pub pipeline: Option<PipelineConfig>,
 }
 
 /// Start an import queue for the Aura consensus algorithm.
@@ -349,6 +472,8 @@ pub fn import_queue<P, Block, I, C, S, CIDP>(
 		check_for_equivocation,
 		telemetry,
 		compatibility_mode,
+		fork_choice_rule,
+		pipeline,
 	}: ImportQueueParams<Block, I, C, S, CIDP>,
 ) -> Result<DefaultImportQueue<Block, C>, sp_consensus::Error>
 where
@@ -361,7 +486,8 @@ where
 		+ Sync
 		+ AuxStore
 		+ UsageProvider<Block>
-		+ HeaderBackend<Block>,
+		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>,
 	I: BlockImport<Block, Error = ConsensusError, Transaction = sp_api::TransactionFor<C, Block>>
 		+ Send
 		+ Sync
@@ -373,6 +499,12 @@ where
 	CIDP: CreateInherentDataProviders<Block, ()> + Sync + Send + 'static,
 	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
 {
+	let block_import: BoxBlockImport<Block, sp_api::TransactionFor<C, Block>> =
+		match fork_choice_rule {
+			Some(rule) => Box::new(ForkChoiceBlockImport::new(block_import, client.clone(), rule)),
+			None => Box::new(block_import),
+		};
+
 	let verifier = build_verifier::<P, _, _, _>(BuildVerifierParams {
 		client,
 		create_inherent_data_providers,
@@ -381,7 +513,17 @@ where
 		compatibility_mode,
 	});
 
-	Ok(BasicQueue::new(verifier, Box::new(block_import), justification_import, spawner, registry))
+	Ok(match pipeline {
+		Some(config) => BasicQueue::new_pipelined(
+			verifier,
+			block_import,
+			justification_import,
+			spawner,
+			registry,
+			config,
+		),
+		None => BasicQueue::new(verifier, block_import, justification_import, spawner, registry),
+	})
 }
 
 /// Parameters of [`build_verifier`].
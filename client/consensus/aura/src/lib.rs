@@ -23,6 +23,10 @@
 //! agree on the current time. Time is divided up into discrete slots of t
 //! seconds each. For each slot s, the author of that slot is A[s % |A|].
 //!
+//! The slot duration t is read from the runtime at every block, so it can be changed by a runtime
+//! upgrade. The new duration takes effect at the slot following the last block authored with the
+//! old one, see [`AuraApi::slot_schedule`].
+//!
 //! The author is allowed to issue one block but not more during that slot,
 //! and it will be built upon the longest valid chain that has been seen.
 //!
@@ -41,7 +45,7 @@ use sc_client_api::{backend::AuxStore, BlockOf};
 use sc_consensus::{BlockImport, BlockImportParams, ForkChoiceStrategy, StateAction};
 use sc_consensus_slots::{
 	BackoffAuthoringBlocksStrategy, InherentDataProviderExt, SimpleSlotWorkerToSlotWorker,
-	SlotInfo, StorageChanges,
+	SlotInfo, SlotScheduleProvider, StorageChanges,
 };
 use sc_telemetry::TelemetryHandle;
 use sp_api::{Core, ProvideRuntimeApi};
@@ -57,7 +61,7 @@ use sp_runtime::traits::{Block as BlockT, Header, Member, NumberFor};
 mod import_queue;
 pub mod standalone;
 
-pub use crate::standalone::{find_pre_digest, slot_duration};
+pub use crate::standalone::{find_pre_digest, slot_duration, slot_schedule_at};
 pub use import_queue::{
 	build_verifier, import_queue, AuraVerifier, BuildVerifierParams, CheckForEquivocation,
 	ImportQueueParams,
@@ -67,7 +71,7 @@ pub use sp_consensus::SyncOracle;
 pub use sp_consensus_aura::{
 	digests::CompatibleDigestItem,
 	inherents::{InherentDataProvider, InherentType as AuraInherent, INHERENT_IDENTIFIER},
-	AuraApi, ConsensusLog, SlotDuration, AURA_ENGINE_ID,
+	AuraApi, ConsensusLog, SlotDuration, SlotSchedule, AURA_ENGINE_ID,
 };
 
 const LOG_TARGET: &str = "aura";
@@ -112,14 +116,18 @@ impl<N> Default for CompatibilityMode<N> {
 }
 
 /// Parameters of [`start_aura`].
+///
+/// The slot schedule is read from the runtime at every block, see [`slot_schedule_at`].
+/// `create_inherent_data_providers` should compute the slot with the same schedule.
 pub struct StartAuraParams<C, SC, I, PF, SO, L, CIDP, BS, N> {
-	/// The duration of a slot.
-	pub slot_duration: SlotDuration,
 	/// The client to interact with the chain.
 	pub client: Arc<C>,
 	/// A select chain implementation to select the best block.
 	pub select_chain: SC,
 	/// The block import.
+	///
+	/// Wrap it in a [`ForkChoiceBlockImport`](sc_consensus::ForkChoiceBlockImport) to choose the
+	/// best block with a custom fork choice rule.
 	pub block_import: I,
 	/// The proposer factory to build proposer instances.
 	pub proposer_factory: PF,
@@ -155,7 +163,6 @@ pub struct StartAuraParams<C, SC, I, PF, SO, L, CIDP, BS, N> {
 /// Start the aura worker. The returned future should be run in a futures executor.
 pub fn start_aura<P, B, C, SC, I, PF, SO, L, CIDP, BS, Error>(
 	StartAuraParams {
-		slot_duration,
 		client,
 		select_chain,
 		block_import,
@@ -190,6 +197,9 @@ where
 	BS: BackoffAuthoringBlocksStrategy<NumberFor<B>> + Send + Sync + 'static,
 	Error: std::error::Error + Send + From<ConsensusError> + 'static,
 {
+	let slot_schedule =
+		RuntimeSlotSchedule::<_, AuthorityId<P>> { client: client.clone(), _phantom: PhantomData };
+
 	let worker = build_aura_worker::<P, _, _, _, _, _, _, _, _>(BuildAuraWorkerParams {
 		client,
 		block_import,
@@ -206,7 +216,7 @@ where
 	});
 
 	Ok(sc_consensus_slots::start_slot_worker(
-		slot_duration,
+		slot_schedule,
 		select_chain,
 		SimpleSlotWorkerToSlotWorker(worker),
 		sync_oracle,
@@ -214,11 +224,33 @@ where
 	))
 }
 
+/// Reads the slot schedule from the runtime at every block.
+struct RuntimeSlotSchedule<C, A> {
+	client: Arc<C>,
+	_phantom: PhantomData<fn() -> A>,
+}
+
+impl<B, C, A> SlotScheduleProvider<B> for RuntimeSlotSchedule<C, A>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + AuxStore + Send + Sync,
+	C::Api: AuraApi<B, A>,
+	A: Codec,
+{
+	fn slot_schedule(&self, parent: &B::Header) -> Result<SlotSchedule, ConsensusError> {
+		slot_schedule_at(&*self.client, parent.hash())
+			.map_err(|e| ConsensusError::ChainLookup(e.to_string()))
+	}
+}
+
 /// Parameters of [`build_aura_worker`].
 pub struct BuildAuraWorkerParams<C, I, PF, SO, L, BS, N> {
 	/// The client to interact with the chain.
 	pub client: Arc<C>,
 	/// The block import.
+	///
+	/// Wrap it in a [`ForkChoiceBlockImport`](sc_consensus::ForkChoiceBlockImport) to choose the
+	/// best block with a custom fork choice rule.
 	pub block_import: I,
 	/// The proposer factory to build proposer instances.
 	pub proposer_factory: PF,
@@ -730,11 +762,8 @@ mod tests {
 					.for_each(move |_| future::ready(())),
 			);
 
-			let slot_duration = slot_duration(&*client).expect("slot duration available");
-
 			aura_futures.push(
 				start_aura::<AuthorityPair, _, _, _, _, _, _, _, _, _, _>(StartAuraParams {
-					slot_duration,
 					block_import: client.clone(),
 					select_chain,
 					client,
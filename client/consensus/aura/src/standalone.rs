@@ -26,7 +26,7 @@ use log::trace;
 use codec::Codec;
 
 use sc_client_api::{backend::AuxStore, UsageProvider};
-use sp_api::{Core, ProvideRuntimeApi};
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
 use sp_application_crypto::{AppCrypto, AppPublic};
 use sp_blockchain::Result as CResult;
 use sp_consensus::Error as ConsensusError;
@@ -41,7 +41,8 @@ use sp_runtime::{
 pub use sc_consensus_slots::check_equivocation;
 
 use super::{
-	AuraApi, AuthorityId, CompatibilityMode, CompatibleDigestItem, SlotDuration, LOG_TARGET,
+	AuraApi, AuthorityId, CompatibilityMode, CompatibleDigestItem, SlotDuration, SlotSchedule,
+	LOG_TARGET,
 };
 
 /// Get the slot duration for Aura by reading from a runtime API at the best block's state.
@@ -66,6 +67,28 @@ where
 	client.runtime_api().slot_duration(block_hash).map_err(|err| err.into())
 }
 
+/// Get the slot schedule of blocks built on top of a given block by reading from a runtime API
+/// at the block's state.
+///
+/// Runtimes which don't provide a schedule count slots of their slot duration since the unix epoch.
+pub fn slot_schedule_at<A, B, C>(client: &C, block_hash: B::Hash) -> CResult<SlotSchedule>
+where
+	A: Codec,
+	B: BlockT,
+	C: AuxStore + ProvideRuntimeApi<B>,
+	C::Api: AuraApi<B, A>,
+{
+	let runtime_api = client.runtime_api();
+	if runtime_api.has_api_with::<dyn AuraApi<B, A>, _>(block_hash, |v| v >= 2)? {
+		runtime_api.slot_schedule(block_hash).map_err(|err| err.into())
+	} else {
+		runtime_api
+			.slot_duration(block_hash)
+			.map(SlotSchedule::from_slot_duration)
+			.map_err(|err| err.into())
+	}
+}
+
 /// Get the slot author for given block along with authorities.
 pub fn slot_author<P: Pair>(slot: Slot, authorities: &[AuthorityId<P>]) -> Option<&AuthorityId<P>> {
 	if authorities.is_empty() {
@@ -240,6 +240,7 @@ mod tests {
 			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(
 				RejectAllTxPool::default(),
 			),
+			pipeline: None,
 		})
 		.unwrap();
 
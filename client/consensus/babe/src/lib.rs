@@ -97,7 +97,11 @@ use sc_consensus::{
 		BlockCheckParams, BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult,
 		StateAction,
 	},
-	import_queue::{BasicQueue, BoxJustificationImport, DefaultImportQueue, Verifier},
+	fork_choice::{ForkChoiceCandidate, SharedForkChoiceRule},
+	import_queue::{
+		BasicQueue, BoxJustificationImport, DefaultImportQueue, PipelineConfig, PipelinedVerifier,
+		Verifier,
+	},
 };
 use sc_consensus_epochs::{
 	descendent_query, Epoch as EpochT, EpochChangesFor, SharedEpochChanges, ViableEpochDescriptor,
@@ -1120,9 +1124,17 @@ where
 	}
 }
 
-#[async_trait::async_trait]
-impl<Block, Client, SelectChain, CIDP> Verifier<Block>
-	for BabeVerifier<Block, Client, SelectChain, CIDP>
+/// The seal of a BABE header, checked ahead of the import of its parent.
+pub struct BabeCheckedSeal<Block: BlockT> {
+	/// The epoch the seal was checked against.
+	epoch: Epoch,
+	/// The header without the seal.
+	pre_header: Block::Header,
+	/// The information gathered while checking the seal.
+	info: verification::VerifiedHeaderInfo,
+}
+
+impl<Block, Client, SelectChain, CIDP> BabeVerifier<Block, Client, SelectChain, CIDP>
 where
 	Block: BlockT,
 	Client: HeaderMetadata<Block, Error = sp_blockchain::Error>
@@ -1136,9 +1148,11 @@ where
 	CIDP: CreateInherentDataProviders<Block, ()> + Send + Sync,
 	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
 {
-	async fn verify(
-		&mut self,
+	/// Verify the given block, reusing the seal check if it was done against the right epoch.
+	async fn verify_with(
+		&self,
 		mut block: BlockImportParams<Block, ()>,
+		checked: Option<BabeCheckedSeal<Block>>,
 	) -> Result<BlockImportParams<Block, ()>, String> {
 		trace!(
 			target: LOG_TARGET,
@@ -1203,14 +1217,24 @@ where
 
 			// We add one to the current slot to allow for some small drift.
 			// FIXME #1019 in the future, alter this queue to allow deferring of headers
-			let v_params = verification::VerificationParams {
-				header: block.header.clone(),
-				pre_digest: Some(pre_digest),
-				slot_now: slot_now + 1,
-				epoch: viable_epoch.as_ref(),
+			let check_header = match checked {
+				// The seal was checked against the epoch the block is in.
+				Some(checked)
+					if checked.epoch == *viable_epoch.as_ref() &&
+						pre_digest.slot() <= slot_now + 1 =>
+					CheckedHeader::Checked(checked.pre_header, checked.info),
+				_ => {
+					let v_params = verification::VerificationParams {
+						header: block.header.clone(),
+						pre_digest: Some(pre_digest),
+						slot_now: slot_now + 1,
+						epoch: viable_epoch.as_ref(),
+					};
+					verification::check_header::<Block>(v_params)?
+				},
 			};
 
-			(verification::check_header::<Block>(v_params)?, epoch_descriptor)
+			(check_header, epoch_descriptor)
 		};
 
 		match check_header {
@@ -1297,6 +1321,103 @@ where
 	}
 }
 
+#[async_trait::async_trait]
+impl<Block, Client, SelectChain, CIDP> Verifier<Block>
+	for BabeVerifier<Block, Client, SelectChain, CIDP>
+where
+	Block: BlockT,
+	Client: HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ HeaderBackend<Block>
+		+ ProvideRuntimeApi<Block>
+		+ Send
+		+ Sync
+		+ AuxStore,
+	Client::Api: BlockBuilderApi<Block> + BabeApi<Block>,
+	SelectChain: sp_consensus::SelectChain<Block>,
+	CIDP: CreateInherentDataProviders<Block, ()> + Send + Sync,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
+{
+	async fn verify(
+		&mut self,
+		block: BlockImportParams<Block, ()>,
+	) -> Result<BlockImportParams<Block, ()>, String> {
+		self.verify_with(block, None).await
+	}
+}
+
+#[async_trait::async_trait]
+impl<Block, Client, SelectChain, CIDP> PipelinedVerifier<Block>
+	for BabeVerifier<Block, Client, SelectChain, CIDP>
+where
+	Block: BlockT,
+	Client: HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ HeaderBackend<Block>
+		+ ProvideRuntimeApi<Block>
+		+ Send
+		+ Sync
+		+ AuxStore,
+	Client::Api: BlockBuilderApi<Block> + BabeApi<Block>,
+	SelectChain: sp_consensus::SelectChain<Block>,
+	CIDP: CreateInherentDataProviders<Block, ()> + Send + Sync,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
+{
+	type CheckedSeal = BabeCheckedSeal<Block>;
+
+	fn check_seal_ahead(&self, header: &Block::Header) -> Option<BabeCheckedSeal<Block>> {
+		let info = self.client.info();
+		let number = *header.number();
+		if info.block_gap.map_or(false, |(s, e)| s <= number && number <= e) {
+			return None
+		}
+
+		let pre_digest = find_pre_digest::<Block>(header).ok()?;
+		// The parent is usually still being imported, in which case the epoch is looked up as
+		// for a child of the best block. If it turns out to be the wrong one, the seal is checked
+		// again once the parent was imported.
+		let parent_hash = *header.parent_hash();
+		let (parent_hash, parent_number) = match self.client.header_metadata(parent_hash) {
+			Ok(parent) => (parent_hash, parent.number),
+			Err(_) => (info.best_hash, info.best_number),
+		};
+		let epoch = {
+			let epoch_changes = self.epoch_changes.shared_data();
+			let epoch_descriptor = epoch_changes
+				.epoch_descriptor_for_child_of(
+					descendent_query(&*self.client),
+					&parent_hash,
+					parent_number,
+					pre_digest.slot(),
+				)
+				.ok()??;
+			epoch_changes
+				.viable_epoch(&epoch_descriptor, |slot| Epoch::genesis(&self.config, slot))?
+				.as_ref()
+				.clone()
+		};
+
+		// Whether the slot is too far in the future is checked once the block is verified.
+		let v_params = verification::VerificationParams {
+			header: header.clone(),
+			pre_digest: Some(pre_digest),
+			slot_now: Slot::from(u64::MAX),
+			epoch: &epoch,
+		};
+		match verification::check_header::<Block>(v_params).ok()? {
+			CheckedHeader::Checked(pre_header, info) =>
+				Some(BabeCheckedSeal { epoch, pre_header, info }),
+			CheckedHeader::Deferred(..) => None,
+		}
+	}
+
+	async fn verify_checked(
+		&self,
+		block: BlockImportParams<Block, ()>,
+		checked: Option<BabeCheckedSeal<Block>>,
+	) -> Result<BlockImportParams<Block, ()>, String> {
+		self.verify_with(block, checked).await
+	}
+}
+
 /// A block-import handler for BABE.
 ///
 /// This scans each imported block for epoch change signals. The signals are
@@ -1310,6 +1431,7 @@ pub struct BabeBlockImport<Block: BlockT, Client, I> {
 	client: Arc<Client>,
 	epoch_changes: SharedEpochChanges<Block, Epoch>,
 	config: BabeConfiguration,
+	fork_choice_rule: Option<SharedForkChoiceRule<Block>>,
 }
 
 impl<Block: BlockT, I: Clone, Client> Clone for BabeBlockImport<Block, Client, I> {
@@ -1319,6 +1441,7 @@ impl<Block: BlockT, I: Clone, Client> Clone for BabeBlockImport<Block, Client, I
 			client: self.client.clone(),
 			epoch_changes: self.epoch_changes.clone(),
 			config: self.config.clone(),
+			fork_choice_rule: self.fork_choice_rule.clone(),
 		}
 	}
 }
@@ -1330,7 +1453,22 @@ impl<Block: BlockT, Client, I> BabeBlockImport<Block, Client, I> {
 		block_import: I,
 		config: BabeConfiguration,
 	) -> Self {
-		BabeBlockImport { client, inner: block_import, epoch_changes, config }
+		BabeBlockImport {
+			client,
+			inner: block_import,
+			epoch_changes,
+			config,
+			fork_choice_rule: None,
+		}
+	}
+
+	/// Choose the best block with `rule` instead of BABE's heaviest chain rule.
+	///
+	/// The rule applies to every block imported through this block import, so the authored blocks
+	/// and the ones coming from the import queue are chosen alike.
+	pub fn with_fork_choice_rule(mut self, rule: SharedForkChoiceRule<Block>) -> Self {
+		self.fork_choice_rule = Some(rule);
+		self
 	}
 }
 
@@ -1644,9 +1782,8 @@ where
 					.extend(values.iter().map(|(k, v)| (k.to_vec(), Some(v.to_vec()))))
 			});
 
-			// The fork choice rule is that we pick the heaviest chain (i.e.
-			// more primary blocks), if there's a tie we go with the longest
-			// chain.
+			// Unless a custom rule was given, the fork choice rule is that we pick the heaviest
+			// chain (i.e. more primary blocks), if there's a tie we go with the longest chain.
 			block.fork_choice = {
 				let (last_best, last_best_number) = (info.best_hash, info.best_number);
 
@@ -1664,13 +1801,37 @@ where
 						})?
 				};
 
-				Some(ForkChoiceStrategy::Custom(if total_weight > last_best_weight {
-					true
-				} else if total_weight == last_best_weight {
-					number > last_best_number
-				} else {
-					false
-				}))
+				let is_new_best = match &self.fork_choice_rule {
+					Some(rule) => {
+						let candidate = ForkChoiceCandidate {
+							hash,
+							number,
+							parent_hash,
+							weight: Some(total_weight.into()),
+						};
+						let best = ForkChoiceCandidate {
+							hash: last_best,
+							number: last_best_number,
+							parent_hash: self
+								.client
+								.header_metadata(last_best)
+								.map_err(|e| ConsensusError::ChainLookup(e.to_string()))?
+								.parent,
+							weight: Some(last_best_weight.into()),
+						};
+						rule.is_new_best(&candidate, &best)?
+					},
+					None =>
+						if total_weight > last_best_weight {
+							true
+						} else if total_weight == last_best_weight {
+							number > last_best_number
+						} else {
+							false
+						},
+				};
+
+				Some(ForkChoiceStrategy::Custom(is_new_best))
 			};
 
 			// Release the mutex, but it stays locked
@@ -1804,6 +1965,10 @@ pub struct ImportQueueParams<'a, Block: BlockT, BI, Client, CIDP, SelectChain, S
 	///
 	/// Will be used when sending equivocation reports.
 	pub offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+	/// If set, the seals of queued blocks are checked ahead of their import.
+	///
+	/// See [`BasicQueue::new_pipelined`].
+	pub pipeline: Option<PipelineConfig>,
 }
 
 /// Start an import queue for the BABE consensus algorithm.
@@ -1827,6 +1992,7 @@ pub fn import_queue<Block: BlockT, Client, SelectChain, BI, CIDP, Spawn>(
 		registry,
 		telemetry,
 		offchain_tx_pool_factory,
+		pipeline,
 	}: ImportQueueParams<'_, Block, BI, Client, CIDP, SelectChain, Spawn>,
 ) -> ClientResult<(DefaultImportQueue<Block, Client>, BabeWorkerHandle<Block>)>
 where
@@ -1869,10 +2035,25 @@ where
 
 	spawner.spawn_essential("babe-worker", Some("babe"), answer_requests.boxed());
 
-	Ok((
-		BasicQueue::new(verifier, Box::new(block_import), justification_import, spawner, registry),
-		BabeWorkerHandle(worker_tx),
-	))
+	let import_queue = match pipeline {
+		Some(config) => BasicQueue::new_pipelined(
+			verifier,
+			Box::new(block_import),
+			justification_import,
+			spawner,
+			registry,
+			config,
+		),
+		None => BasicQueue::new(
+			verifier,
+			Box::new(block_import),
+			justification_import,
+			spawner,
+			registry,
+		),
+	};
+
+	Ok((import_queue, BabeWorkerHandle(worker_tx)))
 }
 
 /// Reverts protocol aux data to at most the last finalized block.
@@ -23,7 +23,7 @@ use super::*;
 use authorship::claim_slot;
 use sc_block_builder::{BlockBuilder, BlockBuilderProvider};
 use sc_client_api::{backend::TransactionFor, BlockchainEvents, Finalizer};
-use sc_consensus::{BoxBlockImport, BoxJustificationImport};
+use sc_consensus::{BoxBlockImport, BoxJustificationImport, ForkChoiceRule};
 use sc_consensus_epochs::{EpochIdentifier, EpochIdentifierPosition};
 use sc_consensus_slots::BackoffAuthoringOnFinalizedHeadLagging;
 use sc_network_test::{Block as TestBlock, *};
@@ -1022,6 +1022,63 @@ async fn verify_slots_are_strictly_increasing() {
 	propose_and_import_block(&b1, Some(999.into()), &mut proposer_factory, &mut block_import).await;
 }
 
+#[tokio::test]
+async fn custom_fork_choice_rule_is_applied() {
+	// never switches to another chain.
+	struct KeepBest;
+	impl ForkChoiceRule<TestBlock> for KeepBest {
+		fn is_new_best(
+			&self,
+			_candidate: &ForkChoiceCandidate<TestBlock>,
+			_best: &ForkChoiceCandidate<TestBlock>,
+		) -> Result<bool, ConsensusError> {
+			Ok(false)
+		}
+	}
+
+	let mut net = BabeTestNet::new(1);
+
+	let peer = net.peer(0);
+	let data = peer.data.as_ref().expect("babe link set up during initialization");
+
+	let client = peer.client().as_client();
+	let mut block_import = data.block_import.lock().take().expect("import set up during init");
+
+	let config = crate::configuration(&*client).expect("config available");
+	let (keep_best_import, _) = crate::block_import(config, client.clone(), client.clone())
+		.expect("can initialize block-import");
+	let mut keep_best_import: BoxBlockImport<_, _> =
+		Box::new(keep_best_import.with_fork_choice_rule(Arc::new(KeepBest)));
+
+	let mut proposer_factory = DummyFactory {
+		client: client.clone(),
+		epoch_changes: data.link.epoch_changes.clone(),
+		mutator: Arc::new(|_, _| ()),
+	};
+
+	let genesis_hash = client.chain_info().genesis_hash;
+	let genesis_header = client.header(genesis_hash).unwrap().unwrap();
+
+	propose_and_import_block(
+		&genesis_header,
+		Some(1.into()),
+		&mut proposer_factory,
+		&mut keep_best_import,
+	)
+	.await;
+	assert_eq!(client.chain_info().best_hash, genesis_hash);
+
+	// the default rule picks the longest chain among equally heavy ones.
+	let b2 = propose_and_import_block(
+		&genesis_header,
+		Some(2.into()),
+		&mut proposer_factory,
+		&mut block_import,
+	)
+	.await;
+	assert_eq!(client.chain_info().best_hash, b2);
+}
+
 #[tokio::test]
 async fn obsolete_blocks_aux_data_cleanup() {
 	let mut net = BabeTestNet::new(1);
@@ -0,0 +1,373 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Pluggable fork choice rules.
+//!
+//! Consensus engines decide whether an imported block becomes the new best block. A
+//! [`ForkChoiceRule`] allows the node to replace that decision, e.g. to prefer chains containing
+//! a checkpoint or to weigh chains by the stake behind them.
+
+use crate::block_import::{
+	BlockCheckParams, BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult,
+};
+use sp_blockchain::{HeaderBackend, HeaderMetadata};
+use sp_consensus::Error as ConsensusError;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use std::sync::Arc;
+
+/// The head of a chain considered by a [`ForkChoiceRule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkChoiceCandidate<Block: BlockT> {
+	/// Hash of the block.
+	pub hash: Block::Hash,
+	/// Number of the block.
+	pub number: NumberFor<Block>,
+	/// Hash of the parent of the block.
+	pub parent_hash: Block::Hash,
+	/// Weight of the chain ending in the block as tracked by the consensus engine, e.g. the
+	/// number of primary blocks in BABE. `None` if the engine doesn't weigh chains.
+	pub weight: Option<u64>,
+}
+
+/// A rule choosing between the current best chain and the chain of a block being imported.
+pub trait ForkChoiceRule<Block: BlockT>: Send + Sync {
+	/// Returns `true` if the chain ending in `candidate` should replace the chain ending in `best`
+	/// as the best chain.
+	///
+	/// `candidate` is being imported, so only its ancestors are known to the backend.
+	fn is_new_best(
+		&self,
+		candidate: &ForkChoiceCandidate<Block>,
+		best: &ForkChoiceCandidate<Block>,
+	) -> Result<bool, ConsensusError>;
+}
+
+impl<Block: BlockT, R: ForkChoiceRule<Block> + ?Sized> ForkChoiceRule<Block> for Arc<R> {
+	fn is_new_best(
+		&self,
+		candidate: &ForkChoiceCandidate<Block>,
+		best: &ForkChoiceCandidate<Block>,
+	) -> Result<bool, ConsensusError> {
+		(**self).is_new_best(candidate, best)
+	}
+}
+
+/// Shared, type-erased [`ForkChoiceRule`], as accepted by the consensus engines.
+pub type SharedForkChoiceRule<Block> = Arc<dyn ForkChoiceRule<Block>>;
+
+/// Prefers the longest chain.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LongestChainRule;
+
+impl<Block: BlockT> ForkChoiceRule<Block> for LongestChainRule {
+	fn is_new_best(
+		&self,
+		candidate: &ForkChoiceCandidate<Block>,
+		best: &ForkChoiceCandidate<Block>,
+	) -> Result<bool, ConsensusError> {
+		Ok(candidate.number > best.number)
+	}
+}
+
+/// Prefers the heaviest chain, and the longest chain among equally heavy ones. Chains without a
+/// weight are treated as having no weight at all.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeaviestChainRule;
+
+impl<Block: BlockT> ForkChoiceRule<Block> for HeaviestChainRule {
+	fn is_new_best(
+		&self,
+		candidate: &ForkChoiceCandidate<Block>,
+		best: &ForkChoiceCandidate<Block>,
+	) -> Result<bool, ConsensusError> {
+		let (candidate_weight, best_weight) =
+			(candidate.weight.unwrap_or_default(), best.weight.unwrap_or_default());
+		Ok(candidate_weight > best_weight ||
+			(candidate_weight == best_weight && candidate.number > best.number))
+	}
+}
+
+/// Prefers chains containing the given checkpoint block, deferring to `fallback` when both or
+/// neither of the chains contain it.
+///
+/// Chains are walked back to the height of the checkpoint, so the checkpoint should be recent.
+pub struct CheckpointRule<Block: BlockT, Client, R> {
+	client: Arc<Client>,
+	checkpoint: (Block::Hash, NumberFor<Block>),
+	fallback: R,
+}
+
+impl<Block: BlockT, Client, R> CheckpointRule<Block, Client, R> {
+	/// Create a new rule preferring chains containing the block `hash` at height `number`.
+	pub fn new(
+		client: Arc<Client>,
+		hash: Block::Hash,
+		number: NumberFor<Block>,
+		fallback: R,
+	) -> Self {
+		CheckpointRule { client, checkpoint: (hash, number), fallback }
+	}
+}
+
+impl<Block, Client, R> CheckpointRule<Block, Client, R>
+where
+	Block: BlockT,
+	Client: HeaderMetadata<Block, Error = sp_blockchain::Error>,
+{
+	fn contains_checkpoint(
+		&self,
+		head: &ForkChoiceCandidate<Block>,
+	) -> Result<bool, ConsensusError> {
+		let (checkpoint_hash, checkpoint_number) = self.checkpoint;
+		if head.number < checkpoint_number {
+			return Ok(false)
+		}
+		if head.number == checkpoint_number {
+			return Ok(head.hash == checkpoint_hash)
+		}
+
+		let mut current = head.parent_hash;
+		loop {
+			let meta = self
+				.client
+				.header_metadata(current)
+				.map_err(|e| ConsensusError::ChainLookup(e.to_string()))?;
+			if meta.number <= checkpoint_number {
+				return Ok(meta.hash == checkpoint_hash)
+			}
+			current = meta.parent;
+		}
+	}
+}
+
+impl<Block, Client, R> ForkChoiceRule<Block> for CheckpointRule<Block, Client, R>
+where
+	Block: BlockT,
+	Client: HeaderMetadata<Block, Error = sp_blockchain::Error> + Send + Sync,
+	R: ForkChoiceRule<Block>,
+{
+	fn is_new_best(
+		&self,
+		candidate: &ForkChoiceCandidate<Block>,
+		best: &ForkChoiceCandidate<Block>,
+	) -> Result<bool, ConsensusError> {
+		match (self.contains_checkpoint(candidate)?, self.contains_checkpoint(best)?) {
+			(true, false) => Ok(true),
+			(false, true) => Ok(false),
+			_ => self.fallback.is_new_best(candidate, best),
+		}
+	}
+}
+
+/// Block import applying a [`ForkChoiceRule`] to blocks the consensus engine imports with
+/// [`ForkChoiceStrategy::LongestChain`].
+///
+/// This allows engines without a fork choice of their own, like Aura, to use a custom rule. Both
+/// the authoring and the import side should import through it.
+pub struct ForkChoiceBlockImport<Block: BlockT, Client, I> {
+	inner: I,
+	client: Arc<Client>,
+	rule: SharedForkChoiceRule<Block>,
+}
+
+impl<Block: BlockT, Client, I: Clone> Clone for ForkChoiceBlockImport<Block, Client, I> {
+	fn clone(&self) -> Self {
+		ForkChoiceBlockImport {
+			inner: self.inner.clone(),
+			client: self.client.clone(),
+			rule: self.rule.clone(),
+		}
+	}
+}
+
+impl<Block: BlockT, Client, I> ForkChoiceBlockImport<Block, Client, I> {
+	/// Wrap `inner`, choosing the best block with `rule`.
+	pub fn new(inner: I, client: Arc<Client>, rule: SharedForkChoiceRule<Block>) -> Self {
+		ForkChoiceBlockImport { inner, client, rule }
+	}
+}
+
+impl<Block, Client, I> ForkChoiceBlockImport<Block, Client, I>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block> + HeaderMetadata<Block, Error = sp_blockchain::Error>,
+{
+	fn is_new_best<Transaction>(
+		&self,
+		block: &BlockImportParams<Block, Transaction>,
+	) -> Result<bool, ConsensusError> {
+		let candidate = ForkChoiceCandidate {
+			hash: block.post_hash(),
+			number: *block.header.number(),
+			parent_hash: *block.header.parent_hash(),
+			weight: None,
+		};
+
+		let info = self.client.info();
+		let best_parent = self
+			.client
+			.header_metadata(info.best_hash)
+			.map_err(|e| ConsensusError::ChainLookup(e.to_string()))?
+			.parent;
+		let best = ForkChoiceCandidate {
+			hash: info.best_hash,
+			number: info.best_number,
+			parent_hash: best_parent,
+			weight: None,
+		};
+
+		self.rule.is_new_best(&candidate, &best)
+	}
+}
+
+#[async_trait::async_trait]
+impl<Block, Client, I> BlockImport<Block> for ForkChoiceBlockImport<Block, Client, I>
+where
+	Block: BlockT,
+	Client:
+		HeaderBackend<Block> + HeaderMetadata<Block, Error = sp_blockchain::Error> + Send + Sync,
+	I: BlockImport<Block, Error = ConsensusError> + Send,
+{
+	type Error = ConsensusError;
+	type Transaction = I::Transaction;
+
+	async fn check_block(
+		&mut self,
+		block: BlockCheckParams<Block>,
+	) -> Result<ImportResult, Self::Error> {
+		self.inner.check_block(block).await
+	}
+
+	async fn import_block(
+		&mut self,
+		mut block: BlockImportParams<Block, Self::Transaction>,
+	) -> Result<ImportResult, Self::Error> {
+		if let Some(ForkChoiceStrategy::LongestChain) = block.fork_choice {
+			let is_new_best = self.is_new_best(&block)?;
+			block.fork_choice = Some(ForkChoiceStrategy::Custom(is_new_best));
+		}
+
+		self.inner.import_block(block).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_blockchain::CachedHeaderMetadata;
+	use sp_runtime::traits::Header as _;
+	use sp_test_primitives::{Block, Hash, Header};
+	use std::collections::HashMap;
+
+	#[derive(Default)]
+	struct TestClient {
+		headers: HashMap<Hash, Header>,
+	}
+
+	impl TestClient {
+		/// Adds a chain of `len` blocks on top of `parent`, returning the candidates of all of
+		/// them.
+		fn push_chain(
+			&mut self,
+			parent: Option<&ForkChoiceCandidate<Block>>,
+			len: u64,
+			fork: u8,
+		) -> Vec<ForkChoiceCandidate<Block>> {
+			let mut parent = parent.map(|p| (p.hash, p.number));
+			(0..len)
+				.map(|_| {
+					let (parent_hash, number) =
+						parent.map_or((Default::default(), 0), |(hash, number)| (hash, number + 1));
+					let header = Header::new(
+						number,
+						Default::default(),
+						Hash::repeat_byte(fork),
+						parent_hash,
+						Default::default(),
+					);
+					let hash = header.hash();
+					self.headers.insert(hash, header);
+					parent = Some((hash, number));
+					ForkChoiceCandidate { hash, number, parent_hash, weight: None }
+				})
+				.collect()
+		}
+	}
+
+	impl HeaderMetadata<Block> for TestClient {
+		type Error = sp_blockchain::Error;
+
+		fn header_metadata(&self, hash: Hash) -> Result<CachedHeaderMetadata<Block>, Self::Error> {
+			self.headers
+				.get(&hash)
+				.map(CachedHeaderMetadata::from)
+				.ok_or_else(|| sp_blockchain::Error::UnknownBlock(format!("{:?}", hash)))
+		}
+
+		fn insert_header_metadata(&self, _: Hash, _: CachedHeaderMetadata<Block>) {}
+
+		fn remove_header_metadata(&self, _: Hash) {}
+	}
+
+	fn candidate(number: u64, weight: Option<u64>) -> ForkChoiceCandidate<Block> {
+		ForkChoiceCandidate {
+			hash: Hash::from_low_u64_be(number),
+			number,
+			parent_hash: Default::default(),
+			weight,
+		}
+	}
+
+	#[test]
+	fn longest_chain_rule_prefers_longer_chains() {
+		assert!(LongestChainRule.is_new_best(&candidate(2, None), &candidate(1, None)).unwrap());
+		assert!(!LongestChainRule.is_new_best(&candidate(1, None), &candidate(1, None)).unwrap());
+	}
+
+	#[test]
+	fn heaviest_chain_rule_prefers_weight_over_length() {
+		let rule = HeaviestChainRule;
+		assert!(rule.is_new_best(&candidate(1, Some(2)), &candidate(5, Some(1))).unwrap());
+		assert!(!rule.is_new_best(&candidate(5, Some(1)), &candidate(1, Some(2))).unwrap());
+		assert!(rule.is_new_best(&candidate(2, Some(1)), &candidate(1, Some(1))).unwrap());
+	}
+
+	#[test]
+	fn checkpoint_rule_prefers_chain_with_checkpoint() {
+		let mut client = TestClient::default();
+		let common = client.push_chain(None, 3, 0);
+		let with_checkpoint = client.push_chain(common.last(), 2, 1);
+		let without_checkpoint = client.push_chain(common.last(), 4, 2);
+		let checkpoint = &with_checkpoint[0];
+
+		let rule = CheckpointRule::new(
+			Arc::new(client),
+			checkpoint.hash,
+			checkpoint.number,
+			LongestChainRule,
+		);
+
+		let short = with_checkpoint.last().unwrap();
+		let long = without_checkpoint.last().unwrap();
+		assert!(rule.is_new_best(short, long).unwrap());
+		assert!(!rule.is_new_best(long, short).unwrap());
+
+		// neither chain reached the checkpoint yet.
+		assert!(rule.is_new_best(&common[2], &common[1]).unwrap());
+	}
+}
@@ -45,6 +45,7 @@ use crate::{
 };
 
 pub use basic_queue::BasicQueue;
+pub use verification_pipeline::PipelineConfig;
 
 const LOG_TARGET: &str = "sync::import-queue";
 
@@ -57,6 +58,7 @@ pub type DefaultImportQueue<Block, Client> =
 mod basic_queue;
 pub mod buffered_link;
 pub mod mock;
+mod verification_pipeline;
 
 /// Shared block import struct used by the queue.
 pub type BoxBlockImport<B, Transaction> =
@@ -105,6 +107,37 @@ pub trait Verifier<B: BlockT>: Send + Sync {
 	) -> Result<BlockImportParams<B, ()>, String>;
 }
 
+/// A verifier able to check the seals of blocks ahead of their import.
+///
+/// Checking the seal of a block, e.g. its signature or VRF output, is usually the most expensive
+/// part of its verification, and only depends on consensus data which rarely changes from one
+/// block to the next. A queue created with [`BasicQueue::new_pipelined`] checks the seals of
+/// queued blocks in parallel, while the blocks before them are imported, and hands the results to
+/// [`PipelinedVerifier::verify_checked`] once the parent of the respective block was imported.
+#[async_trait::async_trait]
+pub trait PipelinedVerifier<B: BlockT>: Send + Sync {
+	/// The result of checking a seal ahead of time.
+	type CheckedSeal: Send + 'static;
+
+	/// Check the seal of `header` ahead of its import.
+	///
+	/// The parent of `header` may not have been imported yet, in which case the consensus data of
+	/// an already imported block has to be used instead. Returns `None` if the seal is invalid or
+	/// can't be checked ahead of time.
+	fn check_seal_ahead(&self, header: &B::Header) -> Option<Self::CheckedSeal>;
+
+	/// Verify `block` once its parent was imported, as [`Verifier::verify`] does.
+	///
+	/// `checked` is the result of [`Self::check_seal_ahead`] for the header of `block`, if it was
+	/// available in time. It must only be relied upon if it was checked against the consensus data
+	/// of the parent of `block`, the seal has to be checked again otherwise.
+	async fn verify_checked(
+		&self,
+		block: BlockImportParams<B, ()>,
+		checked: Option<Self::CheckedSeal>,
+	) -> Result<BlockImportParams<B, ()>, String>;
+}
+
 /// Blocks import queue API.
 ///
 /// The `import_*` methods can be called in order to send elements for the import queue to verify.
@@ -231,6 +264,33 @@ pub async fn import_single_block<B: BlockT, V: Verifier<B>, Transaction: Send +
 	import_single_block_metered(import_handle, block_origin, block, verifier, None).await
 }
 
+/// Build the parameters of an incoming block which are passed to the [`Verifier`].
+///
+/// The `header` of the block is passed separately, `block.header` is ignored.
+pub(crate) fn verification_params<B: BlockT>(
+	block_origin: BlockOrigin,
+	header: B::Header,
+	block: IncomingBlock<B>,
+) -> BlockImportParams<B, ()> {
+	let mut import_block = BlockImportParams::new(block_origin, header);
+	import_block.body = block.body;
+	import_block.justifications = block.justifications;
+	import_block.post_hash = Some(block.hash);
+	import_block.import_existing = block.import_existing;
+	import_block.indexed_body = block.indexed_body;
+
+	if let Some(state) = block.state {
+		let changes = crate::block_import::StorageChanges::Import(state);
+		import_block.state_action = StateAction::ApplyChanges(changes);
+	} else if block.skip_execution {
+		import_block.state_action = StateAction::Skip;
+	} else if block.allow_missing_state {
+		import_block.state_action = StateAction::ExecuteIfPossible;
+	}
+
+	import_block
+}
+
 /// Single block import function with metering.
 pub(crate) async fn import_single_block_metered<
 	B: BlockT,
@@ -239,15 +299,15 @@ pub(crate) async fn import_single_block_metered<
 >(
 	import_handle: &mut impl BlockImport<B, Transaction = Transaction, Error = ConsensusError>,
 	block_origin: BlockOrigin,
-	block: IncomingBlock<B>,
+	mut block: IncomingBlock<B>,
 	verifier: &mut V,
 	metrics: Option<Metrics>,
 ) -> BlockImportResult<B> {
 	let peer = block.origin;
 
-	let (header, justifications) = match (block.header, block.justifications) {
-		(Some(header), justifications) => (header, justifications),
-		(None, _) => {
+	let header = match block.header.take() {
+		Some(header) => header,
+		None => {
 			if let Some(ref peer) = peer {
 				debug!(target: LOG_TARGET, "Header {} was not provided by {} ", block.hash, peer);
 			} else {
@@ -312,22 +372,7 @@ pub(crate) async fn import_single_block_metered<
 
 	let started = std::time::Instant::now();
 
-	let mut import_block = BlockImportParams::new(block_origin, header);
-	import_block.body = block.body;
-	import_block.justifications = justifications;
-	import_block.post_hash = Some(hash);
-	import_block.import_existing = block.import_existing;
-	import_block.indexed_body = block.indexed_body;
-
-	if let Some(state) = block.state {
-		let changes = crate::block_import::StorageChanges::Import(state);
-		import_block.state_action = StateAction::ApplyChanges(changes);
-	} else if block.skip_execution {
-		import_block.state_action = StateAction::Skip;
-	} else if block.allow_missing_state {
-		import_block.state_action = StateAction::ExecuteIfPossible;
-	}
-
+	let import_block = verification_params(block_origin, header, block);
 	let import_block = verifier.verify(import_block).await.map_err(|msg| {
 		if let Some(ref peer) = peer {
 			trace!(
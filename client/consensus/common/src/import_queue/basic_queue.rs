@@ -25,6 +25,7 @@ use log::{debug, trace};
 use prometheus_endpoint::Registry;
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_consensus::BlockOrigin;
+use sp_core::traits::SpawnEssentialNamed;
 use sp_runtime::{
 	traits::{Block as BlockT, Header as HeaderT, NumberFor},
 	Justification, Justifications,
@@ -34,9 +35,13 @@ use std::{marker::PhantomData, pin::Pin, time::Duration};
 use crate::{
 	import_queue::{
 		buffered_link::{self, BufferedLinkReceiver, BufferedLinkSender},
-		import_single_block_metered, BlockImportError, BlockImportStatus, BoxBlockImport,
-		BoxJustificationImport, ImportQueue, ImportQueueService, IncomingBlock, Link,
-		RuntimeOrigin, Verifier, LOG_TARGET,
+		import_single_block_metered,
+		verification_pipeline::{
+			PipelineConfig, ScheduledVerifier, Sequential, VerificationPipeline,
+		},
+		BlockImportError, BlockImportStatus, BoxBlockImport, BoxJustificationImport, ImportQueue,
+		ImportQueueService, IncomingBlock, Link, PipelinedVerifier, RuntimeOrigin, Verifier,
+		LOG_TARGET,
 	},
 	metrics::Metrics,
 };
@@ -67,7 +72,46 @@ impl<B: BlockT, Transaction: Send + 'static> BasicQueue<B, Transaction> {
 		verifier: V,
 		block_import: BoxBlockImport<B, Transaction>,
 		justification_import: Option<BoxJustificationImport<B>>,
-		spawner: &impl sp_core::traits::SpawnEssentialNamed,
+		spawner: &impl SpawnEssentialNamed,
+		prometheus_registry: Option<&Registry>,
+	) -> Self {
+		Self::with_verifier(
+			Sequential(verifier),
+			block_import,
+			justification_import,
+			spawner,
+			prometheus_registry,
+		)
+	}
+
+	/// Instantiate a new basic queue which checks the seals of blocks ahead of their import.
+	///
+	/// Blocks are still verified and imported one after the other, in the order they were
+	/// received. While a block is imported, the seals of up to `max_parallel_verifications` of the
+	/// blocks following it are checked with [`PipelinedVerifier::check_seal_ahead`] on blocking
+	/// tasks, and handed to [`PipelinedVerifier::verify_checked`] once their parent is imported.
+	pub fn new_pipelined<V: 'static + PipelinedVerifier<B>>(
+		verifier: V,
+		block_import: BoxBlockImport<B, Transaction>,
+		justification_import: Option<BoxJustificationImport<B>>,
+		spawner: &impl SpawnEssentialNamed,
+		prometheus_registry: Option<&Registry>,
+		config: PipelineConfig,
+	) -> Self {
+		Self::with_verifier(
+			VerificationPipeline::new(verifier, config),
+			block_import,
+			justification_import,
+			spawner,
+			prometheus_registry,
+		)
+	}
+
+	fn with_verifier<V: 'static + ScheduledVerifier<B>>(
+		verifier: V,
+		block_import: BoxBlockImport<B, Transaction>,
+		justification_import: Option<BoxJustificationImport<B>>,
+		spawner: &impl SpawnEssentialNamed,
 		prometheus_registry: Option<&Registry>,
 	) -> Self {
 		let (result_sender, result_port) = buffered_link::buffered_link(100_000);
@@ -223,7 +267,7 @@ mod worker_messages {
 /// Returns when `block_import` ended.
 async fn block_import_process<B: BlockT, Transaction: Send + 'static>(
 	mut block_import: BoxBlockImport<B, Transaction>,
-	mut verifier: impl Verifier<B>,
+	mut verifier: impl ScheduledVerifier<B>,
 	mut result_sender: BufferedLinkSender<B>,
 	mut block_import_receiver: TracingUnboundedReceiver<worker_messages::ImportBlocks<B>>,
 	metrics: Option<Metrics>,
@@ -242,6 +286,7 @@ async fn block_import_process<B: BlockT, Transaction: Send + 'static>(
 			},
 		};
 
+		verifier.schedule(&blocks);
 		let res = import_many_blocks(
 			&mut block_import,
 			origin,
@@ -251,6 +296,7 @@ async fn block_import_process<B: BlockT, Transaction: Send + 'static>(
 			metrics.clone(),
 		)
 		.await;
+		verifier.finish();
 
 		result_sender.blocks_processed(res.imported, res.block_count, res.results);
 	}
@@ -263,7 +309,7 @@ struct BlockImportWorker<B: BlockT> {
 }
 
 impl<B: BlockT> BlockImportWorker<B> {
-	fn new<V: 'static + Verifier<B>, Transaction: Send + 'static>(
+	fn new<V: 'static + ScheduledVerifier<B>, Transaction: Send + 'static>(
 		result_sender: BufferedLinkSender<B>,
 		verifier: V,
 		block_import: BoxBlockImport<B, Transaction>,
@@ -508,7 +554,9 @@ mod tests {
 		import_queue::Verifier,
 	};
 	use futures::{executor::block_on, Future};
+	use parking_lot::Mutex;
 	use sp_test_primitives::{Block, BlockNumber, Extrinsic, Hash, Header};
+	use std::{num::NonZeroUsize, sync::Arc};
 
 	#[async_trait::async_trait]
 	impl Verifier<Block> for () {
@@ -596,8 +644,13 @@ mod tests {
 	fn prioritizes_finality_work_over_block_import() {
 		let (result_sender, mut result_port) = buffered_link::buffered_link(100_000);
 
-		let (worker, finality_sender, block_import_sender) =
-			BlockImportWorker::new(result_sender, (), Box::new(()), Some(Box::new(())), None);
+		let (worker, finality_sender, block_import_sender) = BlockImportWorker::new(
+			result_sender,
+			Sequential(()),
+			Box::new(()),
+			Some(Box::new(())),
+			None,
+		);
 		futures::pin_mut!(worker);
 
 		let import_block = |n| {
@@ -689,4 +742,200 @@ mod tests {
 			]
 		);
 	}
+
+	/// Records the blocks whose seals were checked ahead of their import.
+	#[derive(Clone, Default)]
+	struct SealCheckingVerifier {
+		checked_ahead: Arc<Mutex<Vec<BlockNumber>>>,
+		verified_with_seal: Arc<Mutex<Vec<BlockNumber>>>,
+	}
+
+	#[async_trait::async_trait]
+	impl PipelinedVerifier<Block> for SealCheckingVerifier {
+		type CheckedSeal = BlockNumber;
+
+		fn check_seal_ahead(&self, header: &Header) -> Option<BlockNumber> {
+			self.checked_ahead.lock().push(header.number);
+			Some(header.number)
+		}
+
+		async fn verify_checked(
+			&self,
+			block: BlockImportParams<Block, ()>,
+			checked: Option<BlockNumber>,
+		) -> Result<BlockImportParams<Block, ()>, String> {
+			if let Some(number) = checked {
+				assert_eq!(number, block.header.number);
+				self.verified_with_seal.lock().push(number);
+			}
+			Ok(BlockImportParams::new(block.origin, block.header))
+		}
+	}
+
+	/// Records the imported blocks, failing the import of `fail_at`.
+	///
+	/// The import of the first block waits for the seal of the second block to be checked, which
+	/// only finishes if the check runs while the first block is imported.
+	struct RecordingImport {
+		imported: Arc<Mutex<Vec<Hash>>>,
+		checked_ahead: Arc<Mutex<Vec<BlockNumber>>>,
+		fail_at: Option<BlockNumber>,
+	}
+
+	#[async_trait::async_trait]
+	impl BlockImport<Block> for RecordingImport {
+		type Error = sp_consensus::Error;
+		type Transaction = Extrinsic;
+
+		async fn check_block(
+			&mut self,
+			_block: BlockCheckParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			Ok(ImportResult::imported(false))
+		}
+
+		async fn import_block(
+			&mut self,
+			block: BlockImportParams<Block, Self::Transaction>,
+		) -> Result<ImportResult, Self::Error> {
+			if block.header.number == 1 {
+				let started = std::time::Instant::now();
+				while !self.checked_ahead.lock().contains(&2) &&
+					started.elapsed() < Duration::from_secs(10)
+				{
+					std::thread::sleep(Duration::from_millis(10));
+				}
+				assert!(
+					self.checked_ahead.lock().contains(&2),
+					"seal of block 2 should be checked while block 1 is imported",
+				);
+			}
+
+			if Some(block.header.number) == self.fail_at {
+				return Err(sp_consensus::Error::ClientImport("import failed".into()))
+			}
+			self.imported.lock().push(block.header.hash());
+			Ok(ImportResult::imported(true))
+		}
+	}
+
+	/// Collects the results of the imported blocks.
+	#[derive(Default)]
+	struct ResultsLink {
+		results: Vec<(bool, Hash)>,
+	}
+
+	impl Link<Block> for ResultsLink {
+		fn blocks_processed(
+			&mut self,
+			_imported: usize,
+			_count: usize,
+			results: Vec<(Result<BlockImportStatus<BlockNumber>, BlockImportError>, Hash)>,
+		) {
+			self.results.extend(results.into_iter().map(|(r, h)| (r.is_ok(), h)));
+		}
+	}
+
+	/// Import a chain of `count` blocks through a pipelined queue.
+	///
+	/// Returns the results of the import, the imported blocks and the verifier.
+	fn import_pipelined(
+		count: BlockNumber,
+		fail_at: Option<BlockNumber>,
+	) -> (Vec<(bool, Hash)>, Vec<Hash>, Vec<Hash>, SealCheckingVerifier) {
+		let verifier = SealCheckingVerifier::default();
+		let imported = Arc::new(Mutex::new(Vec::new()));
+		let (result_sender, mut result_port) = buffered_link::buffered_link(100_000);
+
+		let (worker, _finality_sender, block_import_sender) = BlockImportWorker::new(
+			result_sender,
+			VerificationPipeline::new(
+				verifier.clone(),
+				PipelineConfig {
+					spawner: Box::new(sp_core::testing::TaskExecutor::new()),
+					max_parallel_verifications: NonZeroUsize::new(4).unwrap(),
+				},
+			),
+			Box::new(RecordingImport {
+				imported: imported.clone(),
+				checked_ahead: verifier.checked_ahead.clone(),
+				fail_at,
+			}),
+			None,
+			None,
+		);
+		futures::pin_mut!(worker);
+
+		let mut parent_hash = Hash::random();
+		let blocks = (1..=count)
+			.map(|number| {
+				let header = Header {
+					parent_hash,
+					number,
+					extrinsics_root: Hash::random(),
+					state_root: Default::default(),
+					digest: Default::default(),
+				};
+				parent_hash = header.hash();
+
+				IncomingBlock {
+					hash: header.hash(),
+					header: Some(header),
+					body: None,
+					indexed_body: None,
+					justifications: None,
+					origin: None,
+					allow_missing_state: false,
+					import_existing: false,
+					state: None,
+					skip_execution: false,
+				}
+			})
+			.collect::<Vec<_>>();
+		let hashes = blocks.iter().map(|block| block.hash).collect::<Vec<_>>();
+
+		block_import_sender
+			.unbounded_send(worker_messages::ImportBlocks(BlockOrigin::NetworkInitialSync, blocks))
+			.unwrap();
+
+		let mut link = ResultsLink::default();
+		block_on(futures::future::poll_fn(|cx| {
+			while link.results.is_empty() {
+				match Future::poll(Pin::new(&mut worker), cx) {
+					Poll::Pending => {},
+					Poll::Ready(()) => panic!("import queue worker should not conclude."),
+				}
+
+				result_port.poll_actions(cx, &mut link).unwrap();
+			}
+
+			Poll::Ready(())
+		}));
+
+		let imported = imported.lock().clone();
+		(link.results, hashes, imported, verifier)
+	}
+
+	#[test]
+	fn pipelined_verification_checks_seals_while_importing() {
+		let (results, hashes, imported, verifier) = import_pipelined(10, None);
+
+		assert_eq!(imported, hashes);
+		assert_eq!(results, hashes.iter().map(|hash| (true, *hash)).collect::<Vec<_>>());
+		// Every block was verified with the seal checked ahead of time.
+		assert_eq!(*verifier.verified_with_seal.lock(), (1..=10).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn pipelined_verification_rejects_children_of_failed_block() {
+		let (results, hashes, imported, _) = import_pipelined(10, Some(3));
+
+		assert_eq!(imported, hashes[..2].to_vec());
+		let expected = hashes
+			.iter()
+			.enumerate()
+			.map(|(index, hash)| (index < 2, *hash))
+			.collect::<Vec<_>>();
+		assert_eq!(results, expected);
+	}
 }
@@ -0,0 +1,197 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Checking the seals of queued blocks ahead of their import.
+//!
+//! Blocks are imported strictly one after the other, yet checking the seal of a block (its
+//! signature, VRF output, ...) often doesn't depend on its parent having been imported. The
+//! [`VerificationPipeline`] checks the seals of the blocks of an import batch on separate tasks
+//! while the blocks before them are still being imported, and hands the results to the
+//! [`PipelinedVerifier`] once the import task reaches the respective block.
+
+use futures::{channel::oneshot, FutureExt};
+use log::trace;
+use sp_core::traits::SpawnNamed;
+use sp_runtime::traits::Block as BlockT;
+use std::{collections::VecDeque, num::NonZeroUsize, sync::Arc};
+
+use crate::{
+	block_import::BlockImportParams,
+	import_queue::{IncomingBlock, PipelinedVerifier, Verifier, LOG_TARGET},
+};
+
+/// Configuration of an import queue checking the seals of blocks ahead of their import.
+///
+/// See [`BasicQueue::new_pipelined`](super::BasicQueue::new_pipelined).
+pub struct PipelineConfig {
+	/// Spawner of the tasks checking the seals.
+	pub spawner: Box<dyn SpawnNamed>,
+	/// The maximum number of seals checked at once.
+	pub max_parallel_verifications: NonZeroUsize,
+}
+
+/// A [`Verifier`] which is told about the blocks it is going to verify.
+pub(crate) trait ScheduledVerifier<B: BlockT>: Verifier<B> {
+	/// Called with each batch of blocks before it is imported.
+	fn schedule(&mut self, blocks: &[IncomingBlock<B>]);
+
+	/// Called once the batch given to the last call of [`Self::schedule`] was imported.
+	fn finish(&mut self);
+}
+
+/// A [`Verifier`] verifying each block on its own, once the import task reaches it.
+pub(crate) struct Sequential<V>(pub(crate) V);
+
+#[async_trait::async_trait]
+impl<B: BlockT, V: Verifier<B>> Verifier<B> for Sequential<V> {
+	async fn verify(
+		&mut self,
+		block: BlockImportParams<B, ()>,
+	) -> Result<BlockImportParams<B, ()>, String> {
+		self.0.verify(block).await
+	}
+}
+
+impl<B: BlockT, V: Verifier<B>> ScheduledVerifier<B> for Sequential<V> {
+	fn schedule(&mut self, _: &[IncomingBlock<B>]) {}
+
+	fn finish(&mut self) {}
+}
+
+/// State of a block whose seal is checked ahead of its import.
+enum Pending<B: BlockT, C> {
+	/// The check wasn't started yet.
+	Queued(B::Header),
+	/// The check was started, the result is sent over the channel.
+	Running(oneshot::Receiver<Option<C>>),
+}
+
+/// A [`Verifier`] which checks the seals of scheduled blocks ahead of their import.
+///
+/// The wrapped [`PipelinedVerifier`] is shared with the tasks checking the seals. It is handed
+/// the result of the check once the import task reaches the respective block, or nothing if the
+/// check didn't finish by then.
+pub(crate) struct VerificationPipeline<B: BlockT, V: PipelinedVerifier<B>> {
+	/// The wrapped verifier.
+	verifier: Arc<V>,
+	/// Spawner of the tasks checking the seals.
+	spawner: Box<dyn SpawnNamed>,
+	/// The maximum number of checks running at once.
+	max_parallel: usize,
+	/// The blocks of the current batch which were not verified yet, in order.
+	pending: VecDeque<(B::Hash, Pending<B, V::CheckedSeal>)>,
+}
+
+impl<B: BlockT, V: PipelinedVerifier<B> + 'static> VerificationPipeline<B, V> {
+	/// Wrap the given verifier.
+	pub(crate) fn new(verifier: V, config: PipelineConfig) -> Self {
+		Self {
+			verifier: Arc::new(verifier),
+			spawner: config.spawner,
+			max_parallel: config.max_parallel_verifications.get(),
+			pending: VecDeque::new(),
+		}
+	}
+
+	/// Start queued checks until `max_parallel` of them are running.
+	fn spawn_queued(&mut self) {
+		let mut running = self
+			.pending
+			.iter()
+			.filter(|(_, pending)| matches!(pending, Pending::Running(_)))
+			.count();
+		for (_, pending) in self.pending.iter_mut() {
+			if running >= self.max_parallel {
+				break
+			}
+			let (result_sender, result_receiver) = oneshot::channel();
+			let header = match std::mem::replace(pending, Pending::Running(result_receiver)) {
+				Pending::Queued(header) => header,
+				started => {
+					*pending = started;
+					continue
+				},
+			};
+			let verifier = self.verifier.clone();
+			self.spawner.spawn_blocking(
+				"block-seal-check",
+				Some("block-import"),
+				async move {
+					// Skip the check if the block was verified or discarded meanwhile.
+					if !result_sender.is_canceled() {
+						let _ = result_sender.send(verifier.check_seal_ahead(&header));
+					}
+				}
+				.boxed(),
+			);
+			running += 1;
+		}
+	}
+
+	/// Take the check scheduled for the block with the given hash.
+	///
+	/// Blocks scheduled before it were skipped by the import task (e.g. as they were already
+	/// imported), so their checks are discarded.
+	fn take(&mut self, hash: &B::Hash) -> Option<Pending<B, V::CheckedSeal>> {
+		let position = self.pending.iter().position(|(pending_hash, _)| pending_hash == hash)?;
+		self.pending.drain(..position);
+		let pending = self.pending.pop_front().map(|(_, pending)| pending);
+		self.spawn_queued();
+		pending
+	}
+}
+
+impl<B: BlockT, V: PipelinedVerifier<B> + 'static> ScheduledVerifier<B>
+	for VerificationPipeline<B, V>
+{
+	fn schedule(&mut self, blocks: &[IncomingBlock<B>]) {
+		self.pending.clear();
+		self.pending.extend(blocks.iter().filter_map(|block| {
+			block
+				.header
+				.as_ref()
+				.map(|header| (block.hash, Pending::Queued(header.clone())))
+		}));
+		self.spawn_queued();
+	}
+
+	fn finish(&mut self) {
+		// Whatever is left wasn't imported, e.g. as the import of a block before it failed.
+		self.pending.clear();
+	}
+}
+
+#[async_trait::async_trait]
+impl<B: BlockT, V: PipelinedVerifier<B> + 'static> Verifier<B> for VerificationPipeline<B, V> {
+	async fn verify(
+		&mut self,
+		block: BlockImportParams<B, ()>,
+	) -> Result<BlockImportParams<B, ()>, String> {
+		let hash = block.post_hash();
+		let checked = match self.take(&hash) {
+			Some(Pending::Running(result)) => result.await.ok().flatten(),
+			_ => None,
+		};
+		if checked.is_none() {
+			trace!(target: LOG_TARGET, "Seal of {} wasn't checked ahead of time", hash);
+		}
+
+		self.verifier.verify_checked(block, checked).await
+	}
+}
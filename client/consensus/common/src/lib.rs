@@ -20,6 +20,7 @@
 //! Collection of common consensus specific implementations
 
 pub mod block_import;
+pub mod fork_choice;
 pub mod import_queue;
 pub mod metrics;
 
@@ -28,9 +29,14 @@ pub use block_import::{
 	ImportedAux, ImportedState, JustificationImport, JustificationSyncLink, StateAction,
 	StorageChanges,
 };
+pub use fork_choice::{
+	CheckpointRule, ForkChoiceBlockImport, ForkChoiceCandidate, ForkChoiceRule, HeaviestChainRule,
+	LongestChainRule, SharedForkChoiceRule,
+};
 pub use import_queue::{
 	import_single_block, BasicQueue, BlockImportError, BlockImportStatus, BoxBlockImport,
-	BoxJustificationImport, DefaultImportQueue, ImportQueue, IncomingBlock, Link, Verifier,
+	BoxJustificationImport, DefaultImportQueue, ImportQueue, IncomingBlock, Link, PipelineConfig,
+	PipelinedVerifier, Verifier,
 };
 
 mod longest_chain;
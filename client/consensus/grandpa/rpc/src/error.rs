@@ -37,6 +37,9 @@ pub enum Error {
 	/// GRANDPA prove finality failed.
 	#[error("GRANDPA prove finality rpc failed: {0}")]
 	ProveFinalityFailed(#[from] sc_consensus_grandpa::FinalityProofError),
+	/// The voting rules of the local voter are not exposed.
+	#[error("GRANDPA voting rules are not configurable on this node")]
+	VotingRulesUnavailable,
 }
 
 /// The error codes returned by jsonrpc.
@@ -49,6 +52,8 @@ pub enum ErrorCode {
 	VoterStateTooLarge,
 	/// Failed to prove finality.
 	ProveFinality,
+	/// Voting rules are not configurable.
+	VotingRulesUnavailable,
 }
 
 impl From<Error> for ErrorCode {
@@ -58,6 +63,7 @@ impl From<Error> for ErrorCode {
 			Error::AuthoritySetIdReportedAsUnreasonablyLarge => ErrorCode::AuthoritySetTooLarge,
 			Error::VoterStateReportsUnreasonablyLargeNumbers => ErrorCode::VoterStateTooLarge,
 			Error::ProveFinalityFailed(_) => ErrorCode::ProveFinality,
+			Error::VotingRulesUnavailable => ErrorCode::VotingRulesUnavailable,
 		}
 	}
 }
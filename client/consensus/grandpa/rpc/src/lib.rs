@@ -36,8 +36,10 @@ mod finality;
 mod notification;
 mod report;
 
-use sc_consensus_grandpa::GrandpaJustificationStream;
-use sc_rpc::SubscriptionTaskExecutor;
+use sc_consensus_grandpa::{
+	GrandpaJustificationStream, SharedVotingRulesConfig, VotingRulesConfig,
+};
+use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
 use finality::{EncodedFinalityProof, RpcFinalityProofProvider};
@@ -65,6 +67,15 @@ pub trait GrandpaApi<Notification, Hash, Number> {
 	/// in the set and all the intermediary headers to link them together.
 	#[method(name = "grandpa_proveFinality")]
 	async fn prove_finality(&self, block: Number) -> RpcResult<Option<EncodedFinalityProof>>;
+
+	/// Returns the voting rules currently applied by the local voter.
+	#[method(name = "grandpa_votingRules")]
+	async fn voting_rules(&self) -> RpcResult<VotingRulesConfig<Hash>>;
+
+	/// Replace the voting rules applied by the local voter, e.g. to pause votes on a fork for
+	/// emergency finality management. The new rules take effect from the next vote.
+	#[method(name = "grandpa_setVotingRules")]
+	async fn set_voting_rules(&self, config: VotingRulesConfig<Hash>) -> RpcResult<()>;
 }
 
 /// Provides RPC methods for interacting with GRANDPA.
@@ -74,6 +85,7 @@ pub struct Grandpa<AuthoritySet, VoterState, Block: BlockT, ProofProvider> {
 	voter_state: VoterState,
 	justification_stream: GrandpaJustificationStream<Block>,
 	finality_proof_provider: Arc<ProofProvider>,
+	voting_rules: Option<(SharedVotingRulesConfig<Block::Hash>, DenyUnsafe)>,
 }
 impl<AuthoritySet, VoterState, Block: BlockT, ProofProvider>
 	Grandpa<AuthoritySet, VoterState, Block, ProofProvider>
@@ -86,7 +98,25 @@ impl<AuthoritySet, VoterState, Block: BlockT, ProofProvider>
 		justification_stream: GrandpaJustificationStream<Block>,
 		finality_proof_provider: Arc<ProofProvider>,
 	) -> Self {
-		Self { executor, authority_set, voter_state, justification_stream, finality_proof_provider }
+		Self {
+			executor,
+			authority_set,
+			voter_state,
+			justification_stream,
+			finality_proof_provider,
+			voting_rules: None,
+		}
+	}
+
+	/// Expose the given reloadable voting rules of the local voter. Replacing them is
+	/// considered unsafe.
+	pub fn with_voting_rules(
+		mut self,
+		voting_rules: SharedVotingRulesConfig<Block::Hash>,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		self.voting_rules = Some((voting_rules, deny_unsafe));
+		self
 	}
 }
 
@@ -131,6 +161,22 @@ where
 			})
 			.map_err(Into::into)
 	}
+
+	async fn voting_rules(&self) -> RpcResult<VotingRulesConfig<Block::Hash>> {
+		let (voting_rules, _) =
+			self.voting_rules.as_ref().ok_or(error::Error::VotingRulesUnavailable)?;
+		Ok(voting_rules.get())
+	}
+
+	async fn set_voting_rules(&self, config: VotingRulesConfig<Block::Hash>) -> RpcResult<()> {
+		let (voting_rules, deny_unsafe) =
+			self.voting_rules.as_ref().ok_or(error::Error::VotingRulesUnavailable)?;
+		deny_unsafe.check_if_safe()?;
+
+		warn!("Replacing GRANDPA voting rules: {:?}", config);
+		voting_rules.set(config);
+		Ok(())
+	}
 }
 
 #[cfg(test)]
@@ -423,4 +469,49 @@ mod tests {
 		let finality_proof_rpc: FinalityProof<Header> = Decode::decode(&mut &bytes[..]).unwrap();
 		assert_eq!(finality_proof_rpc, finality_proof);
 	}
+	#[tokio::test]
+	async fn voting_rules_can_be_replaced() {
+		let voting_rules = SharedVotingRulesConfig::<H256>::default();
+		let (justification_stream, _) = GrandpaJustificationStream::channel();
+		let grandpa = |deny_unsafe| {
+			Grandpa::new(
+				Arc::new(TaskExecutor::default()),
+				TestAuthoritySet,
+				TestVoterState,
+				justification_stream.clone(),
+				Arc::new(TestFinalityProofProvider { finality_proof: None }),
+			)
+			.with_voting_rules(voting_rules.clone(), deny_unsafe)
+			.into_rpc()
+		};
+
+		let config = VotingRulesConfig {
+			max_finality_lag: Some(16),
+			paused_forks: vec![header(3).hash()],
+			..Default::default()
+		};
+
+		let rpc = grandpa(DenyUnsafe::Yes);
+		assert!(rpc.call::<_, ()>("grandpa_setVotingRules", [config.clone()]).await.is_err());
+		let current: VotingRulesConfig<H256> =
+			rpc.call("grandpa_votingRules", EmptyParams::new()).await.unwrap();
+		assert_eq!(current, VotingRulesConfig::default());
+
+		let rpc = grandpa(DenyUnsafe::No);
+		rpc.call::<_, ()>("grandpa_setVotingRules", [config.clone()]).await.unwrap();
+		let current: VotingRulesConfig<H256> =
+			rpc.call("grandpa_votingRules", EmptyParams::new()).await.unwrap();
+		assert_eq!(current, config);
+		assert_eq!(voting_rules.get(), config);
+	}
+
+	#[tokio::test]
+	async fn voting_rules_unavailable_by_default() {
+		let (rpc, _) = setup_io_handler(TestVoterState);
+		let expected_response = r#"{"jsonrpc":"2.0","error":{"code":5,"message":"GRANDPA voting rules are not configurable on this node"},"id":0}"#.to_string();
+		let request = r#"{"jsonrpc":"2.0","method":"grandpa_votingRules","params":[],"id":0}"#;
+		let (response, _) = rpc.raw_json_request(&request).await.unwrap();
+
+		assert_eq!(expected_response, response.result);
+	}
 }
@@ -93,7 +93,7 @@ use prometheus_endpoint::{register, CounterVec, Opts, PrometheusError, Registry,
 use rand::seq::SliceRandom;
 use sc_network::{PeerId, ReputationChange};
 use sc_network_common::role::ObservedRole;
-use sc_network_gossip::{MessageIntent, ValidatorContext};
+use sc_network_gossip::{MessageIntent, MessagePriority, ValidatorContext};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG};
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_consensus_grandpa::AuthorityId;
@@ -1647,6 +1647,28 @@ impl<Block: BlockT> sc_network_gossip::Validator<Block> for GossipValidator<Bloc
 		})
 	}
 
+	fn message_priority<'a>(
+		&'a self,
+	) -> Box<dyn FnMut(&Block::Hash, &[u8]) -> MessagePriority + 'a> {
+		let inner = self.inner.read();
+		Box::new(move |topic, _data| {
+			let local_view = match inner.local_view {
+				Some(ref v) => v,
+				None => return MessagePriority::Bulk,
+			};
+
+			// votes of the round we're in and commits of the current set are what peers need to
+			// make progress, while earlier rounds are only kept around for stragglers.
+			match inner.live_topics.topic_info(topic) {
+				Some((Some(round), set_id))
+					if round == local_view.round && set_id == local_view.set_id =>
+					MessagePriority::Urgent,
+				Some((None, set_id)) if set_id == local_view.set_id => MessagePriority::Urgent,
+				_ => MessagePriority::Bulk,
+			}
+		})
+	}
+
 	fn message_expired<'a>(&'a self) -> Box<dyn FnMut(Block::Hash, &[u8]) -> bool + 'a> {
 		let inner = self.inner.read();
 		Box::new(move |topic, mut data| {
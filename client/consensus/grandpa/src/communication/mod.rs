@@ -48,7 +48,7 @@ use finality_grandpa::{
 };
 use parity_scale_codec::{Decode, Encode};
 use sc_network::{NetworkBlock, NetworkSyncForkRequest, ReputationChange};
-use sc_network_gossip::{GossipEngine, Network as GossipNetwork};
+use sc_network_gossip::{GossipEngine, KnownMessagesTracking, Network as GossipNetwork};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_INFO};
 use sp_keystore::KeystorePtr;
 use sp_runtime::traits::{Block as BlockT, Hash as HashT, Header as HeaderT, NumberFor};
@@ -70,6 +70,10 @@ mod periodic;
 #[cfg(test)]
 pub(crate) mod tests;
 
+/// Number of gossip messages a single generation of the per-peer bloom filters holds. Each round
+/// produces about two messages per voter, so this covers a few rounds of a large voter set.
+const KNOWN_MESSAGES_BLOOM_CAPACITY: u32 = 16_384;
+
 // How often to rebroadcast neighbor packets, in cases where no new packets are created.
 pub(crate) const NEIGHBOR_REBROADCAST_PERIOD: Duration = Duration::from_secs(2 * 60);
 
@@ -258,13 +262,18 @@ impl<B: BlockT, N: Network<B>, S: Syncing<B>> NetworkBridge<B, N, S> {
 			GossipValidator::new(config, set_state.clone(), prometheus_registry, telemetry.clone());
 
 		let validator = Arc::new(validator);
-		let gossip_engine = Arc::new(Mutex::new(GossipEngine::new(
-			service.clone(),
-			sync.clone(),
-			protocol,
-			validator.clone(),
-			prometheus_registry,
-		)));
+		let gossip_engine = Arc::new(Mutex::new(
+			GossipEngine::new(
+				service.clone(),
+				sync.clone(),
+				protocol,
+				validator.clone(),
+				prometheus_registry,
+			)
+			.with_known_messages_tracking(KnownMessagesTracking::Bloom {
+				capacity: KNOWN_MESSAGES_BLOOM_CAPACITY,
+			}),
+		));
 
 		{
 			// register all previous votes with the gossip service so that they're
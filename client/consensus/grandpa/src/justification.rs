@@ -26,7 +26,7 @@ use std::{
 use finality_grandpa::{voter_set::VoterSet, Error as GrandpaError};
 use parity_scale_codec::{Decode, Encode};
 use sp_blockchain::{Error as ClientError, HeaderBackend};
-use sp_consensus_grandpa::AuthorityId;
+use sp_consensus_grandpa::{AuthorityId, CompactGrandpaJustification};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
 
 use crate::{AuthorityList, Commit, Error};
@@ -260,6 +260,16 @@ impl<Block: BlockT> GrandpaJustification<Block> {
 	pub fn target(&self) -> (NumberFor<Block>, Block::Hash) {
 		(self.justification.commit.target_number, self.justification.commit.target_hash)
 	}
+
+	/// Compact this justification for transfer to light clients, pruning the precommits in
+	/// excess of the supermajority threshold if the `authorities` which made it are given. See
+	/// [`CompactGrandpaJustification`], which also provides the matching verifier.
+	pub fn compact(
+		self,
+		authorities: Option<&AuthorityList>,
+	) -> CompactGrandpaJustification<Block::Header> {
+		CompactGrandpaJustification::new(self.justification, authorities)
+	}
 }
 
 /// A utility trait implementing `finality_grandpa::Chain` using a given set of headers.
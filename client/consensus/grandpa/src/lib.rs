@@ -138,8 +138,8 @@ pub use justification::GrandpaJustification;
 pub use notification::{GrandpaJustificationSender, GrandpaJustificationStream};
 pub use observer::run_grandpa_observer;
 pub use voting_rule::{
-	BeforeBestBlockBy, ThreeQuartersOfTheUnfinalizedChain, VotingRule, VotingRuleResult,
-	VotingRulesBuilder,
+	BeforeBestBlockBy, SharedVotingRulesConfig, ThreeQuartersOfTheUnfinalizedChain, VotingRule,
+	VotingRuleResult, VotingRulesBuilder, VotingRulesConfig,
 };
 
 use aux_schema::PersistentData;
@@ -18,6 +18,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use std::{
+	collections::{BTreeMap, HashMap, HashSet},
 	marker::{PhantomData, Unpin},
 	pin::Pin,
 	sync::Arc,
@@ -30,10 +31,11 @@ use log::{debug, info, warn};
 
 use sc_client_api::backend::Backend;
 use sc_telemetry::TelemetryHandle;
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sc_utils::mpsc::TracingUnboundedReceiver;
 use sp_blockchain::HeaderMetadata;
 use sp_consensus::SelectChain;
-use sp_consensus_grandpa::AuthorityId;
+use sp_consensus_grandpa::{AuthorityId, AuthoritySignature, Equivocation, GrandpaApi};
 use sp_keystore::KeystorePtr;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
@@ -47,6 +49,85 @@ use crate::{
 	VoterSetState, LOG_TARGET,
 };
 
+/// Number of most recent rounds for which the observer keeps the precommits
+/// seen in commit messages around in order to detect equivocations.
+const PRECOMMIT_TRACKER_ROUNDS: usize = 16;
+
+type PrecommitOf<Block> = finality_grandpa::Precommit<<Block as BlockT>::Hash, NumberFor<Block>>;
+
+/// Keeps track of the precommits contained in the commit messages received by
+/// the observer, in order to detect authorities that precommitted for different
+/// blocks in the same round. Only the precommits of the current authority set
+/// are tracked, the tracker is recreated whenever the set changes.
+struct PrecommitTracker<Block: BlockT> {
+	rounds: BTreeMap<u64, HashMap<AuthorityId, (PrecommitOf<Block>, AuthoritySignature)>>,
+	equivocators: HashSet<(u64, AuthorityId)>,
+}
+
+impl<Block: BlockT> PrecommitTracker<Block> {
+	fn new() -> Self {
+		PrecommitTracker { rounds: BTreeMap::new(), equivocators: HashSet::new() }
+	}
+
+	/// Note all precommits of the given commit, returning any newly detected
+	/// equivocations. Each offender is only returned once per round.
+	fn note_commit(
+		&mut self,
+		round: u64,
+		commit: &finality_grandpa::Commit<
+			Block::Hash,
+			NumberFor<Block>,
+			AuthoritySignature,
+			AuthorityId,
+		>,
+	) -> Vec<Equivocation<Block::Hash, NumberFor<Block>>> {
+		if self.rounds.len() >= PRECOMMIT_TRACKER_ROUNDS &&
+			!self.rounds.contains_key(&round) &&
+			self.rounds.keys().next().map_or(false, |oldest| round < *oldest)
+		{
+			// too old to be tracked
+			return Vec::new()
+		}
+
+		let mut equivocations = Vec::new();
+		let precommits = self.rounds.entry(round).or_default();
+
+		for signed in &commit.precommits {
+			let (precommit, signature) = match precommits.get(&signed.id) {
+				Some(first) => first.clone(),
+				None => {
+					precommits.insert(
+						signed.id.clone(),
+						(signed.precommit.clone(), signed.signature.clone()),
+					);
+					continue
+				},
+			};
+
+			if precommit.target_hash == signed.precommit.target_hash ||
+				!self.equivocators.insert((round, signed.id.clone()))
+			{
+				continue
+			}
+
+			equivocations.push(Equivocation::Precommit(finality_grandpa::Equivocation {
+				round_number: round,
+				identity: signed.id.clone(),
+				first: (precommit, signature),
+				second: (signed.precommit.clone(), signed.signature.clone()),
+			}));
+		}
+
+		while self.rounds.len() > PRECOMMIT_TRACKER_ROUNDS {
+			let oldest = *self.rounds.keys().next().expect("rounds is non-empty; qed");
+			self.rounds.remove(&oldest);
+			self.equivocators.retain(|(round, _)| *round != oldest);
+		}
+
+		equivocations
+	}
+}
+
 struct ObserverChain<'a, Block: BlockT, Client> {
 	client: &'a Arc<Client>,
 	_phantom: PhantomData<Block>,
@@ -68,7 +149,7 @@ where
 	}
 }
 
-fn grandpa_observer<BE, Block: BlockT, Client, S, F>(
+fn grandpa_observer<BE, Block: BlockT, Client, S, F, R>(
 	client: &Arc<Client>,
 	authority_set: &SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
 	voters: &Arc<VoterSet<AuthorityId>>,
@@ -76,12 +157,14 @@ fn grandpa_observer<BE, Block: BlockT, Client, S, F>(
 	last_finalized_number: NumberFor<Block>,
 	commits: S,
 	note_round: F,
+	report_equivocation: R,
 	telemetry: Option<TelemetryHandle>,
 ) -> impl Future<Output = Result<(), CommandOrError<Block::Hash, NumberFor<Block>>>>
 where
 	NumberFor<Block>: BlockNumberOps,
 	S: Stream<Item = Result<CommunicationIn<Block>, CommandOrError<Block::Hash, NumberFor<Block>>>>,
 	F: Fn(u64),
+	R: Fn(Equivocation<Block::Hash, NumberFor<Block>>),
 	BE: Backend<Block>,
 	Client: ClientForGrandpa<Block, BE>,
 {
@@ -89,6 +172,7 @@ where
 	let client = client.clone();
 	let voters = voters.clone();
 	let justification_sender = justification_sender.clone();
+	let mut precommit_tracker = PrecommitTracker::<Block>::new();
 
 	let observer = commits.try_fold(last_finalized_number, move |last_finalized_number, global| {
 		let (round, commit, callback) = match global {
@@ -102,6 +186,16 @@ where
 			},
 		};
 
+		// the signatures of all precommits have already been checked by the
+		// gossip validator, so any conflicting precommits can be reported.
+		for equivocation in precommit_tracker.note_commit(round, &commit) {
+			warn!(
+				target: LOG_TARGET,
+				"Detected precommit equivocation in the observer: {:?}", equivocation
+			);
+			report_equivocation(equivocation);
+		}
+
 		// if the commit we've received targets a block lower or equal to the last
 		// finalized, ignore it and continue with the current state
 		if commit.target_number <= last_finalized_number {
@@ -162,6 +256,17 @@ where
 /// listening for and validating GRANDPA commits instead of following the full
 /// protocol. Provide configuration and a link to a block import worker that has
 /// already been instantiated with `block_import`.
+///
+/// Authorities found to have precommitted for different blocks in the same
+/// round are logged and, if an `offchain_tx_pool_factory` is given, reported to
+/// the runtime through an unsigned equivocation report extrinsic. BABE
+/// equivocations need no observer, as the BABE block import reports them on
+/// every full node.
+///
+/// Only embedders calling this function get this reporting: the node binaries
+/// in this repository run the full voter on non-authorities instead, which
+/// already reports the prevote and precommit equivocations seen in each round,
+/// a superset of the conflicting precommits found in commit messages.
 /// NOTE: this is currently not part of the crate's public API since we don't consider
 /// it stable enough to use on a live network.
 pub fn run_grandpa_observer<BE, Block: BlockT, Client, N, S, SC>(
@@ -169,6 +274,7 @@ pub fn run_grandpa_observer<BE, Block: BlockT, Client, N, S, SC>(
 	link: LinkHalf<Block, Client, SC>,
 	network: N,
 	sync: S,
+	offchain_tx_pool_factory: Option<OffchainTransactionPoolFactory<Block>>,
 ) -> sp_blockchain::Result<impl Future<Output = ()> + Send>
 where
 	BE: Backend<Block> + Unpin + 'static,
@@ -177,6 +283,7 @@ where
 	SC: SelectChain<Block>,
 	NumberFor<Block>: BlockNumberOps,
 	Client: ClientForGrandpa<Block, BE> + 'static,
+	Client::Api: GrandpaApi<Block>,
 {
 	let LinkHalf {
 		client,
@@ -203,6 +310,7 @@ where
 		config.keystore,
 		voter_commands_rx,
 		Some(justification_sender),
+		offchain_tx_pool_factory,
 		telemetry,
 	);
 
@@ -224,6 +332,7 @@ struct ObserverWork<B: BlockT, BE, Client, N: NetworkT<B>, S: SyncingT<B>> {
 	keystore: Option<KeystorePtr>,
 	voter_commands_rx: TracingUnboundedReceiver<VoterCommand<B::Hash, NumberFor<B>>>,
 	justification_sender: Option<GrandpaJustificationSender<B>>,
+	offchain_tx_pool_factory: Option<OffchainTransactionPoolFactory<B>>,
 	telemetry: Option<TelemetryHandle>,
 	_phantom: PhantomData<BE>,
 }
@@ -233,6 +342,7 @@ where
 	B: BlockT,
 	BE: Backend<B> + 'static,
 	Client: ClientForGrandpa<B, BE> + 'static,
+	Client::Api: GrandpaApi<B>,
 	Network: NetworkT<B>,
 	Syncing: SyncingT<B>,
 	NumberFor<B>: BlockNumberOps,
@@ -244,6 +354,7 @@ where
 		keystore: Option<KeystorePtr>,
 		voter_commands_rx: TracingUnboundedReceiver<VoterCommand<B::Hash, NumberFor<B>>>,
 		justification_sender: Option<GrandpaJustificationSender<B>>,
+		offchain_tx_pool_factory: Option<OffchainTransactionPoolFactory<B>>,
 		telemetry: Option<TelemetryHandle>,
 	) -> Self {
 		let mut work = ObserverWork {
@@ -256,6 +367,7 @@ where
 			keystore: keystore.clone(),
 			voter_commands_rx,
 			justification_sender,
+			offchain_tx_pool_factory,
 			telemetry,
 			_phantom: PhantomData,
 		};
@@ -299,6 +411,28 @@ where
 			}
 		};
 
+		let report_equivocation = {
+			let client = self.client.clone();
+			let authority_set = self.persistent_data.authority_set.clone();
+			let offchain_tx_pool_factory = self.offchain_tx_pool_factory.clone();
+
+			move |equivocation| {
+				let offchain_tx_pool_factory = match &offchain_tx_pool_factory {
+					Some(offchain_tx_pool_factory) => offchain_tx_pool_factory,
+					None => return,
+				};
+
+				if let Err(err) = environment::report_equivocation::<B, BE, _>(
+					&*client,
+					&authority_set,
+					offchain_tx_pool_factory,
+					equivocation,
+				) {
+					warn!(target: LOG_TARGET, "Error reporting precommit equivocation: {}", err);
+				}
+			}
+		};
+
 		// create observer for the current set
 		let observer = grandpa_observer(
 			&self.client,
@@ -308,6 +442,7 @@ where
 			last_finalized_number,
 			global_in,
 			note_round,
+			report_equivocation,
 			self.telemetry.clone(),
 		);
 
@@ -357,6 +492,7 @@ where
 	B: BlockT,
 	BE: Backend<B> + Unpin + 'static,
 	C: ClientForGrandpa<B, BE> + 'static,
+	C::Api: GrandpaApi<B>,
 	N: NetworkT<B>,
 	S: SyncingT<B>,
 	NumberFor<B>: BlockNumberOps,
@@ -453,6 +589,7 @@ mod tests {
 			voter_command_rx,
 			None,
 			None,
+			None,
 		);
 
 		// Trigger a reputation change through the gossip validator.
@@ -473,4 +610,107 @@ mod tests {
 			assert_matches!(tester.events.next().now_or_never(), Some(Some(Event::Report(_, _))));
 		});
 	}
+
+	#[test]
+	fn observer_reports_conflicting_commits() {
+		use parking_lot::Mutex;
+		use sp_core::H256;
+		use substrate_test_runtime_client::runtime::Block;
+
+		let (client, backend) = {
+			let builder = TestClientBuilder::with_default_backend();
+			let backend = builder.backend();
+			let (client, _) = builder.build_with_longest_chain();
+			(Arc::new(client), backend)
+		};
+
+		let alice: AuthorityId = sp_keyring::Sr25519Keyring::Alice.public().into();
+		let voters = Arc::new(VoterSet::new(vec![(alice.clone(), 1)]).unwrap());
+		let persistent_data =
+			aux_schema::load_persistent(&*backend, client.info().genesis_hash, 0, || {
+				Ok(vec![(alice.clone(), 1)])
+			})
+			.unwrap();
+
+		// two commits of the same round with precommits of alice for different blocks. they
+		// target the genesis block so the observer doesn't try to finalize them.
+		let commit = |target: u8| {
+			let target_hash = H256::repeat_byte(target);
+			let commit = finality_grandpa::Commit {
+				target_hash,
+				target_number: 0,
+				precommits: vec![finality_grandpa::SignedPrecommit {
+					precommit: finality_grandpa::Precommit::new(target_hash, 0),
+					signature: sp_keyring::Sr25519Keyring::Alice.sign(&[target]).into(),
+					id: alice.clone(),
+				}],
+			};
+			Ok(voter::CommunicationIn::Commit(1, commit.into(), voter::Callback::Blank))
+		};
+		let commits = futures::stream::iter(vec![commit(1), commit(1), commit(2)]);
+
+		let reported = Mutex::new(Vec::new());
+		let observer = grandpa_observer::<substrate_test_runtime_client::Backend, Block, _, _, _, _>(
+			&client,
+			&persistent_data.authority_set,
+			&voters,
+			&None,
+			0,
+			commits,
+			|_| {},
+			|equivocation| reported.lock().push(equivocation),
+			None,
+		);
+		executor::block_on(observer).unwrap();
+
+		let reported = reported.into_inner();
+		assert_eq!(reported.len(), 1);
+		assert_eq!(reported[0].offender(), &alice);
+		assert_eq!(reported[0].round_number(), 1);
+		assert_matches!(&reported[0], Equivocation::Precommit(equivocation) => {
+			assert_eq!(equivocation.first.0.target_hash, H256::repeat_byte(1));
+			assert_eq!(equivocation.second.0.target_hash, H256::repeat_byte(2));
+		});
+	}
+
+	#[test]
+	fn precommit_tracker_detects_equivocations() {
+		use sp_core::H256;
+		use substrate_test_runtime_client::runtime::Block;
+
+		let alice: AuthorityId = sp_keyring::Sr25519Keyring::Alice.public().into();
+		let signature: AuthoritySignature = sp_keyring::Sr25519Keyring::Alice.sign(b"").into();
+
+		let commit = |target: u8| finality_grandpa::Commit {
+			target_hash: H256::repeat_byte(target),
+			target_number: 1,
+			precommits: vec![finality_grandpa::SignedPrecommit {
+				precommit: finality_grandpa::Precommit::new(H256::repeat_byte(target), 1),
+				signature: signature.clone(),
+				id: alice.clone(),
+			}],
+		};
+
+		let mut tracker = PrecommitTracker::<Block>::new();
+
+		// repeated and cross-round precommits are not equivocations
+		assert!(tracker.note_commit(1, &commit(1)).is_empty());
+		assert!(tracker.note_commit(1, &commit(1)).is_empty());
+		assert!(tracker.note_commit(2, &commit(2)).is_empty());
+
+		let equivocations = tracker.note_commit(1, &commit(2));
+		assert_eq!(equivocations.len(), 1);
+		assert_eq!(equivocations[0].offender(), &alice);
+		assert_eq!(equivocations[0].round_number(), 1);
+
+		// the offender is only reported once per round
+		assert!(tracker.note_commit(1, &commit(3)).is_empty());
+
+		// old rounds are eventually forgotten
+		for round in 3..(3 + PRECOMMIT_TRACKER_ROUNDS as u64) {
+			tracker.note_commit(round, &commit(1));
+		}
+		assert!(tracker.note_commit(1, &commit(4)).is_empty());
+		assert!(!tracker.rounds.contains_key(&1));
+	}
 }
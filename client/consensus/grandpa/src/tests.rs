@@ -1258,6 +1258,7 @@ async fn finalize_3_voters_1_light_observer() {
 		net.peers[3].data.lock().take().expect("link initialized at startup; qed"),
 		net.peers[3].network_service().clone(),
 		net.peers[3].sync_service().clone(),
+		None,
 	)
 	.unwrap();
 	net.peer(0).push_blocks(20, false);
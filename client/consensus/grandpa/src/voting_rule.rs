@@ -23,9 +23,11 @@
 //! restrictions that are taken into account by the GRANDPA environment when
 //! selecting a finality target to vote on.
 
-use std::{future::Future, pin::Pin, sync::Arc};
+use std::{future::Future, path::Path, pin::Pin, sync::Arc};
 
 use dyn_clone::DynClone;
+use parking_lot::RwLock;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use sc_client_api::blockchain::HeaderBackend;
 use sp_runtime::traits::{Block as BlockT, Header, NumberFor, One, Zero};
@@ -309,6 +311,165 @@ where
 	pub fn build(self) -> impl VotingRule<Block, B> + Clone {
 		VotingRules { rules: Arc::new(self.rules) }
 	}
+
+	/// Return a new voting rule builder applying the rules described by the given
+	/// (reloadable) configuration.
+	pub fn from_config(config: SharedVotingRulesConfig<Block::Hash>) -> Self {
+		VotingRulesBuilder::new().add(config)
+	}
+}
+
+/// A declarative description of the voting rules to apply, e.g. as loaded from a TOML file.
+///
+/// Missing fields take their default value, and the default configuration is equivalent to
+/// [`VotingRulesBuilder::default`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct VotingRulesConfig<Hash> {
+	/// Always vote at least this many blocks behind the best block, see [`BeforeBestBlockBy`].
+	/// Zero disables the rule.
+	pub min_delay: u32,
+	/// Limit votes towards 3/4 of the unfinalized chain, see
+	/// [`ThreeQuartersOfTheUnfinalizedChain`].
+	pub three_quarters_of_unfinalized_chain: bool,
+	/// Never let the rules above restrict the vote to more than this many blocks behind the
+	/// best block.
+	pub max_finality_lag: Option<u32>,
+	/// Never vote on any of these blocks nor on their descendants. This takes precedence over
+	/// all other rules, but can't restrict the vote past the round base.
+	pub paused_forks: Vec<Hash>,
+}
+
+impl<Hash> Default for VotingRulesConfig<Hash> {
+	fn default() -> Self {
+		VotingRulesConfig {
+			min_delay: 2,
+			three_quarters_of_unfinalized_chain: true,
+			max_finality_lag: None,
+			paused_forks: Vec::new(),
+		}
+	}
+}
+
+impl<Hash: DeserializeOwned> VotingRulesConfig<Hash> {
+	/// Load the configuration from the TOML file at the given path.
+	pub fn from_toml_file(path: &Path) -> Result<Self, String> {
+		let content = std::fs::read_to_string(path)
+			.map_err(|e| format!("Failed to read voting rules from {}: {}", path.display(), e))?;
+		toml::from_str(&content)
+			.map_err(|e| format!("Invalid voting rules in {}: {}", path.display(), e))
+	}
+}
+
+/// A handle to a [`VotingRulesConfig`] which is applied as a voting rule and can be replaced at
+/// runtime, e.g. to pause votes on a fork for emergency finality management. Changes take effect
+/// from the next vote.
+#[derive(Clone)]
+pub struct SharedVotingRulesConfig<Hash> {
+	inner: Arc<RwLock<VotingRulesConfig<Hash>>>,
+}
+
+impl<Hash> Default for SharedVotingRulesConfig<Hash> {
+	fn default() -> Self {
+		SharedVotingRulesConfig::new(Default::default())
+	}
+}
+
+impl<Hash: Clone> SharedVotingRulesConfig<Hash> {
+	/// Create a new handle with the given initial configuration.
+	pub fn new(config: VotingRulesConfig<Hash>) -> Self {
+		SharedVotingRulesConfig { inner: Arc::new(RwLock::new(config)) }
+	}
+
+	/// Return the current configuration.
+	pub fn get(&self) -> VotingRulesConfig<Hash> {
+		self.inner.read().clone()
+	}
+
+	/// Replace the current configuration.
+	pub fn set(&self, config: VotingRulesConfig<Hash>) {
+		*self.inner.write() = config;
+	}
+}
+
+impl<Block, B> VotingRule<Block, B> for SharedVotingRulesConfig<Block::Hash>
+where
+	Block: BlockT,
+	B: HeaderBackend<Block> + 'static,
+{
+	fn restrict_vote(
+		&self,
+		backend: Arc<B>,
+		base: &Block::Header,
+		best_target: &Block::Header,
+		current_target: &Block::Header,
+	) -> VotingRuleResult<Block> {
+		use sp_arithmetic::traits::Saturating;
+
+		let config = self.get();
+
+		let mut rules = VotingRulesBuilder::<Block, B>::new();
+		if config.min_delay > 0 {
+			rules = rules.add(BeforeBestBlockBy(config.min_delay.into()));
+		}
+		if config.three_quarters_of_unfinalized_chain {
+			rules = rules.add(ThreeQuartersOfTheUnfinalizedChain);
+		}
+		let restricted =
+			rules.build().restrict_vote(backend.clone(), base, best_target, current_target);
+
+		let base = base.clone();
+		let best_target = best_target.clone();
+		let current_target = current_target.clone();
+
+		Box::pin(async move {
+			// fail closed: if any of the headers below can't be read, vote on the round base, which
+			// is at least as restrictive as any of the rules
+			let target = async {
+				let mut target = match restricted.await {
+					Some((hash, _)) => backend.header(hash).ok()??,
+					None => current_target.clone(),
+				};
+
+				// undo any restriction beyond the maximum finality lag, without going past the
+				// original target
+				if let Some(max_finality_lag) = config.max_finality_lag {
+					let floor = best_target
+						.number()
+						.saturating_sub(max_finality_lag.into())
+						.min(*current_target.number());
+
+					if *target.number() < floor {
+						let (hash, _) = find_target(&*backend, floor, &current_target)?;
+						target = backend.header(hash).ok()??;
+					}
+				}
+
+				// vote on the parent of the lowest paused block between the base and our target
+				if !config.paused_forks.is_empty() {
+					let mut header = target.clone();
+					while header.number() > base.number() {
+						let parent = backend.header(*header.parent_hash()).ok()??;
+						if config.paused_forks.contains(&header.hash()) {
+							target = parent.clone();
+						}
+						header = parent;
+					}
+				}
+
+				Some(target)
+			}
+			.await
+			.unwrap_or_else(|| base.clone());
+
+			let target_hash = target.hash();
+			if target_hash != current_target.hash() {
+				Some((target_hash, *target.number()))
+			} else {
+				None
+			}
+		})
+	}
 }
 
 impl<Block, B> VotingRule<Block, B> for Box<dyn VotingRule<Block, B>>
@@ -438,4 +599,110 @@ mod tests {
 			assert_eq!(number, expected, "best = {}, lag = 2, base = {}", best_number, i);
 		}
 	}
+
+	#[test]
+	fn voting_rules_config_is_reloadable() {
+		let config = SharedVotingRulesConfig::default();
+		let rule = VotingRulesBuilder::from_config(config.clone()).build();
+
+		let mut client = Arc::new(TestClientBuilder::new().build());
+		let mut hashes = Vec::with_capacity(20);
+		for _ in 0..20 {
+			let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+			hashes.push(block.hash());
+
+			futures::executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+		}
+
+		let genesis = client.header(client.info().genesis_hash).unwrap().unwrap();
+		let best = client.header(client.info().best_hash).unwrap().unwrap();
+		let restrict = || {
+			futures::executor::block_on(rule.restrict_vote(client.clone(), &genesis, &best, &best))
+				.map(|(_, number)| number)
+		};
+
+		// the default configuration matches the default voting rules,
+		// i.e. 3/4 of the unfinalized chain at best - 2
+		assert_eq!(restrict(), Some(15));
+
+		// the maximum finality lag undoes restrictions of the other rules
+		config.set(VotingRulesConfig {
+			min_delay: 10,
+			three_quarters_of_unfinalized_chain: false,
+			max_finality_lag: Some(4),
+			paused_forks: Vec::new(),
+		});
+		assert_eq!(restrict(), Some(16));
+
+		// pausing a fork votes on the parent of the paused block, regardless of the lag
+		config.set(VotingRulesConfig {
+			min_delay: 0,
+			three_quarters_of_unfinalized_chain: false,
+			max_finality_lag: Some(1),
+			paused_forks: vec![hashes[14], hashes[9]],
+		});
+		assert_eq!(restrict(), Some(9));
+
+		// no rules, no restrictions
+		config.set(VotingRulesConfig {
+			min_delay: 0,
+			three_quarters_of_unfinalized_chain: false,
+			max_finality_lag: None,
+			paused_forks: Vec::new(),
+		});
+		assert_eq!(restrict(), None);
+	}
+
+	#[test]
+	fn voting_rules_config_fails_closed() {
+		let config = SharedVotingRulesConfig::new(VotingRulesConfig {
+			min_delay: 0,
+			three_quarters_of_unfinalized_chain: false,
+			max_finality_lag: None,
+			paused_forks: vec![substrate_test_runtime_client::runtime::Hash::repeat_byte(1)],
+		});
+		let rule = VotingRulesBuilder::from_config(config).build();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let genesis = client.header(client.info().genesis_hash).unwrap().unwrap();
+
+		// the ancestry of the target can't be read, so the rules fall back to the round base
+		let unknown = Header::new(
+			2,
+			Default::default(),
+			Default::default(),
+			substrate_test_runtime_client::runtime::Hash::repeat_byte(2),
+			Default::default(),
+		);
+		assert_eq!(
+			futures::executor::block_on(rule.restrict_vote(
+				client.clone(),
+				&genesis,
+				&unknown,
+				&unknown
+			)),
+			Some((genesis.hash(), 0)),
+		);
+	}
+
+	#[test]
+	fn voting_rules_config_from_toml() {
+		let hash = substrate_test_runtime_client::runtime::Hash::repeat_byte(1);
+		let config: VotingRulesConfig<substrate_test_runtime_client::runtime::Hash> =
+			toml::from_str(&format!("maxFinalityLag = 32\npausedForks = [\"{:?}\"]\n", hash))
+				.unwrap();
+
+		assert_eq!(
+			config,
+			VotingRulesConfig {
+				max_finality_lag: Some(32),
+				paused_forks: vec![hash],
+				..Default::default()
+			}
+		);
+		assert!(toml::from_str::<VotingRulesConfig<substrate_test_runtime_client::runtime::Hash>>(
+			"unknown = 1"
+		)
+		.is_err());
+	}
 }
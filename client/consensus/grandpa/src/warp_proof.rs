@@ -23,9 +23,11 @@ use crate::{
 	BlockNumberOps, GrandpaJustification, SharedAuthoritySet,
 };
 use sc_client_api::Backend as ClientBackend;
-use sc_network_common::sync::warp::{EncodedProof, VerificationResult, WarpSyncProvider};
+use sc_network_common::sync::warp::{
+	EncodedProof, VerificationResult, WarpProofVersion, WarpSyncProvider,
+};
 use sp_blockchain::{Backend as BlockchainBackend, HeaderBackend};
-use sp_consensus_grandpa::{AuthorityList, SetId, GRANDPA_ENGINE_ID};
+use sp_consensus_grandpa::{AuthorityList, CompactGrandpaJustification, SetId, GRANDPA_ENGINE_ID};
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, Header as HeaderT, NumberFor, One},
@@ -74,6 +76,105 @@ pub struct WarpSyncProof<Block: BlockT> {
 	is_finished: bool,
 }
 
+/// A proof of an authority set change with a compact justification, see [`WarpSyncFragment`].
+#[derive(Decode, Encode, Debug)]
+pub struct CompactWarpSyncFragment<Block: BlockT> {
+	/// The last block that the given authority set finalized.
+	pub header: Block::Header,
+	/// A compact justification for the header above which proves its finality.
+	pub justification: CompactGrandpaJustification<Block::Header>,
+}
+
+/// An accumulated proof of multiple authority set changes with compact justifications, served
+/// for [`WarpProofVersion::V2`] requests.
+#[derive(Decode, Encode)]
+pub struct CompactWarpSyncProof<Block: BlockT> {
+	proofs: Vec<CompactWarpSyncFragment<Block>>,
+	is_finished: bool,
+}
+
+/// A justification finalizing the header of a warp sync fragment.
+trait FragmentJustification<Block: BlockT> {
+	/// Verify the justification was made by the given set, returning the finalized block hash.
+	fn verify_target(
+		&self,
+		set_id: SetId,
+		authorities: &AuthorityList,
+	) -> Result<Block::Hash, Error>;
+}
+
+impl<Block: BlockT> FragmentJustification<Block> for GrandpaJustification<Block>
+where
+	NumberFor<Block>: BlockNumberOps,
+{
+	fn verify_target(
+		&self,
+		set_id: SetId,
+		authorities: &AuthorityList,
+	) -> Result<Block::Hash, Error> {
+		self.verify(set_id, authorities)
+			.map_err(|err| Error::InvalidProof(err.to_string()))?;
+		Ok(self.target().1)
+	}
+}
+
+impl<Block: BlockT> FragmentJustification<Block> for CompactGrandpaJustification<Block::Header> {
+	fn verify_target(
+		&self,
+		set_id: SetId,
+		authorities: &AuthorityList,
+	) -> Result<Block::Hash, Error> {
+		self.verify(set_id, authorities)
+			.map_err(|err| Error::InvalidProof(format!("Invalid justification: {:?}", err)))?;
+		Ok(self.target_hash)
+	}
+}
+
+/// Verifies the given fragments of a warp sync proof, see [`WarpSyncProof::verify`].
+fn verify_fragments<'a, Block, J>(
+	fragments: impl ExactSizeIterator<Item = (&'a Block::Header, &'a J)>,
+	is_finished: bool,
+	set_id: SetId,
+	authorities: AuthorityList,
+	hard_forks: &HashMap<(Block::Hash, NumberFor<Block>), (SetId, AuthorityList)>,
+) -> Result<(SetId, AuthorityList), Error>
+where
+	Block: BlockT,
+	J: FragmentJustification<Block> + 'a,
+{
+	let mut current_set_id = set_id;
+	let mut current_authorities = authorities;
+
+	let num_fragments = fragments.len();
+	for (fragment_num, (header, justification)) in fragments.enumerate() {
+		let hash = header.hash();
+		let number = *header.number();
+
+		if let Some((set_id, list)) = hard_forks.get(&(hash, number)) {
+			current_set_id = *set_id;
+			current_authorities = list.clone();
+		} else {
+			if justification.verify_target(current_set_id, &current_authorities)? != hash {
+				return Err(Error::InvalidProof(
+					"Mismatch between header and justification".to_owned(),
+				))
+			}
+
+			if let Some(scheduled_change) = find_scheduled_change::<Block>(header) {
+				current_authorities = scheduled_change.next_authorities;
+				current_set_id += 1;
+			} else if fragment_num != num_fragments - 1 || !is_finished {
+				// Only the last fragment of the last proof message is allowed to be missing the
+				// authority set change.
+				return Err(Error::InvalidProof(
+					"Header is missing authority set change digest".to_string(),
+				))
+			}
+		}
+	}
+	Ok((current_set_id, current_authorities))
+}
+
 impl<Block: BlockT> WarpSyncProof<Block> {
 	/// Generates a warp sync proof starting at the given block. It will generate authority set
 	/// change proofs for all changes that happened from `begin` until the current authority set
@@ -197,41 +298,50 @@ impl<Block: BlockT> WarpSyncProof<Block> {
 	where
 		NumberFor<Block>: BlockNumberOps,
 	{
-		let mut current_set_id = set_id;
-		let mut current_authorities = authorities;
-
-		for (fragment_num, proof) in self.proofs.iter().enumerate() {
-			let hash = proof.header.hash();
-			let number = *proof.header.number();
-
-			if let Some((set_id, list)) = hard_forks.get(&(hash, number)) {
-				current_set_id = *set_id;
-				current_authorities = list.clone();
-			} else {
-				proof
-					.justification
-					.verify(current_set_id, &current_authorities)
-					.map_err(|err| Error::InvalidProof(err.to_string()))?;
-
-				if proof.justification.target().1 != hash {
-					return Err(Error::InvalidProof(
-						"Mismatch between header and justification".to_owned(),
-					))
-				}
+		verify_fragments::<Block, _>(
+			self.proofs.iter().map(|proof| (&proof.header, &proof.justification)),
+			self.is_finished,
+			set_id,
+			authorities,
+			hard_forks,
+		)
+	}
 
-				if let Some(scheduled_change) = find_scheduled_change::<Block>(&proof.header) {
-					current_authorities = scheduled_change.next_authorities;
-					current_set_id += 1;
-				} else if fragment_num != self.proofs.len() - 1 || !self.is_finished {
-					// Only the last fragment of the last proof message is allowed to be missing the
-					// authority set change.
-					return Err(Error::InvalidProof(
-						"Header is missing authority set change digest".to_string(),
-					))
-				}
-			}
-		}
-		Ok((current_set_id, current_authorities))
+	/// Compacts the justifications of the proof. The authorities of each set are taken from the
+	/// change signaled by the previous fragment, so the precommits of the first fragment aren't
+	/// pruned.
+	fn compact(self) -> CompactWarpSyncProof<Block> {
+		let mut authorities = None;
+		let proofs = self
+			.proofs
+			.into_iter()
+			.map(|WarpSyncFragment { header, justification }| {
+				let justification = justification.compact(authorities.as_ref());
+				authorities = find_scheduled_change::<Block>(&header)
+					.map(|scheduled_change| scheduled_change.next_authorities);
+				CompactWarpSyncFragment { header, justification }
+			})
+			.collect();
+
+		CompactWarpSyncProof { proofs, is_finished: self.is_finished }
+	}
+}
+
+impl<Block: BlockT> CompactWarpSyncProof<Block> {
+	/// Verifies the compact warp sync proof, see [`WarpSyncProof::verify`].
+	fn verify(
+		&self,
+		set_id: SetId,
+		authorities: AuthorityList,
+		hard_forks: &HashMap<(Block::Hash, NumberFor<Block>), (SetId, AuthorityList)>,
+	) -> Result<(SetId, AuthorityList), Error> {
+		verify_fragments::<Block, _>(
+			self.proofs.iter().map(|proof| (&proof.header, &proof.justification)),
+			self.is_finished,
+			set_id,
+			authorities,
+			hard_forks,
+		)
 	}
 }
 
@@ -300,20 +410,93 @@ where
 			.ok_or_else(|| "Empty proof".to_string())?;
 		let (next_set_id, next_authorities) =
 			proof.verify(set_id, authorities, &self.hard_forks).map_err(Box::new)?;
-		if proof.is_finished {
-			Ok(VerificationResult::<Block>::Complete(next_set_id, next_authorities, last_header))
-		} else {
-			Ok(VerificationResult::<Block>::Partial(
-				next_set_id,
-				next_authorities,
-				last_header.hash(),
-			))
-		}
+		Ok(verification_result(next_set_id, next_authorities, last_header, proof.is_finished))
 	}
 
 	fn current_authorities(&self) -> AuthorityList {
 		self.authority_set.inner().current_authorities.clone()
 	}
+
+	fn proof_target(
+		&self,
+		proof: &EncodedProof,
+	) -> Result<(Block::Header, bool), Box<dyn std::error::Error + Send + Sync>> {
+		let EncodedProof(proof) = proof;
+		let proof = WarpSyncProof::<Block>::decode(&mut proof.as_slice())
+			.map_err(|e| format!("Proof decoding error: {:?}", e))?;
+		let last_header = proof
+			.proofs
+			.last()
+			.map(|p| p.header.clone())
+			.ok_or_else(|| "Empty proof".to_string())?;
+		Ok((last_header, proof.is_finished))
+	}
+
+	fn latest_proof_version(&self) -> WarpProofVersion {
+		WarpProofVersion::V2
+	}
+
+	fn generate_versioned(
+		&self,
+		start: Block::Hash,
+		version: WarpProofVersion,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		match version {
+			WarpProofVersion::V1 => self.generate(start),
+			WarpProofVersion::V2 => {
+				let proof = WarpSyncProof::<Block>::generate(
+					&*self.backend,
+					start,
+					&self.authority_set.authority_set_changes(),
+				)
+				.map_err(Box::new)?;
+				Ok(EncodedProof(proof.compact().encode()))
+			},
+		}
+	}
+
+	fn verify_versioned(
+		&self,
+		proof: &EncodedProof,
+		version: WarpProofVersion,
+		set_id: SetId,
+		authorities: AuthorityList,
+	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+		match version {
+			WarpProofVersion::V1 => self.verify(proof, set_id, authorities),
+			WarpProofVersion::V2 => {
+				let EncodedProof(proof) = proof;
+				let proof = CompactWarpSyncProof::<Block>::decode(&mut proof.as_slice())
+					.map_err(|e| format!("Proof decoding error: {:?}", e))?;
+				let last_header = proof
+					.proofs
+					.last()
+					.map(|p| p.header.clone())
+					.ok_or_else(|| "Empty proof".to_string())?;
+				let (next_set_id, next_authorities) =
+					proof.verify(set_id, authorities, &self.hard_forks).map_err(Box::new)?;
+				Ok(verification_result(
+					next_set_id,
+					next_authorities,
+					last_header,
+					proof.is_finished,
+				))
+			},
+		}
+	}
+}
+
+fn verification_result<Block: BlockT>(
+	set_id: SetId,
+	authorities: AuthorityList,
+	last_header: Block::Header,
+	is_finished: bool,
+) -> VerificationResult<Block> {
+	if is_finished {
+		VerificationResult::Complete(set_id, authorities, last_header)
+	} else {
+		VerificationResult::Partial(set_id, authorities, last_header.hash())
+	}
 }
 
 #[cfg(test)]
@@ -430,8 +613,9 @@ mod tests {
 			WarpSyncProof::generate(&*backend, genesis_hash, &authority_set_changes).unwrap();
 
 		// verifying the proof should yield the last set id and authorities
-		let (new_set_id, new_authorities) =
-			warp_sync_proof.verify(0, genesis_authorities, &Default::default()).unwrap();
+		let (new_set_id, new_authorities) = warp_sync_proof
+			.verify(0, genesis_authorities.clone(), &Default::default())
+			.unwrap();
 
 		let expected_authorities = current_authorities
 			.iter()
@@ -440,5 +624,23 @@ mod tests {
 
 		assert_eq!(new_set_id, current_set_id);
 		assert_eq!(new_authorities, expected_authorities);
+
+		// the compact proof should be smaller and yield the same result
+		let proof_size = warp_sync_proof.encoded_size();
+		let mut compact_proof = warp_sync_proof.compact();
+		assert!(compact_proof.encoded_size() < proof_size);
+
+		let (new_set_id, new_authorities) = compact_proof
+			.verify(0, genesis_authorities.clone(), &Default::default())
+			.unwrap();
+
+		assert_eq!(new_set_id, current_set_id);
+		assert_eq!(new_authorities, expected_authorities);
+
+		// removing a precommit drops the justification below the supermajority threshold
+		let last_fragment = compact_proof.proofs.last_mut().unwrap();
+		assert!(last_fragment.justification.precommits.iter().all(|p| p.target.is_none()));
+		last_fragment.justification.precommits.pop();
+		assert!(compact_proof.verify(0, genesis_authorities, &Default::default()).is_err());
 	}
 }
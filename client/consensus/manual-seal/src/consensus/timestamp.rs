@@ -20,7 +20,7 @@
 //! Mocked timestamp inherent, allows for manual seal to create blocks for runtimes
 //! that expect this inherent.
 
-use crate::Error;
+use crate::{Error, TimeTravel};
 use sc_client_api::{AuxStore, UsageProvider};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
@@ -123,6 +123,13 @@ impl SlotTimestampProvider {
 		Ok(time)
 	}
 
+	/// Move the mocked time forward by the time travelled since the last block.
+	pub fn with_time_travel(self, time_travel: &TimeTravel) -> Self {
+		self.unix_millis
+			.fetch_add(time_travel.take_pending().as_millis() as u64, atomic::Ordering::SeqCst);
+		self
+	}
+
 	/// Get the current slot number
 	pub fn slot(&self) -> Slot {
 		Slot::from_timestamp(
@@ -39,6 +39,8 @@ mod codes {
 	pub const CONSENSUS_ERROR: i32 = 14_000;
 	pub const INHERENTS_ERROR: i32 = 15_000;
 	pub const BLOCKCHAIN_ERROR: i32 = 16_000;
+	pub const UNSUPPORTED: i32 = 17_000;
+	pub const UNKNOWN_SNAPSHOT: i32 = 18_000;
 	pub const UNKNOWN_ERROR: i32 = 20_000;
 }
 
@@ -65,6 +67,12 @@ pub enum Error {
 	/// Supplied parent_hash doesn't exist in chain
 	#[error("Supplied parent_hash: {0} doesn't exist in chain")]
 	BlockNotFound(String),
+	/// The command requires a feature which wasn't enabled in the authorship task.
+	#[error("{0} is not enabled for this node")]
+	Unsupported(&'static str),
+	/// No snapshot with the supplied ID exists.
+	#[error("Snapshot {0} doesn't exist")]
+	UnknownSnapshot(u64),
 	/// Some string error
 	#[error("{0}")]
 	StringError(String),
@@ -101,6 +109,8 @@ impl Error {
 			ConsensusError(_) => codes::CONSENSUS_ERROR,
 			InherentError(_) => codes::INHERENTS_ERROR,
 			BlockchainError(_) => codes::BLOCKCHAIN_ERROR,
+			Unsupported(_) => codes::UNSUPPORTED,
+			UnknownSnapshot(_) => codes::UNKNOWN_SNAPSHOT,
 			SendError(_) | Canceled(_) => codes::SERVER_SHUTTING_DOWN,
 			_ => codes::UNKNOWN_ERROR,
 		}
@@ -35,12 +35,17 @@ use sp_blockchain::HeaderBackend;
 use sp_consensus::{Environment, Proposer, SelectChain};
 use sp_core::traits::SpawnNamed;
 use sp_inherents::CreateInherentDataProviders;
-use sp_runtime::{traits::Block as BlockT, ConsensusEngineId};
+use sp_runtime::{
+	traits::{Block as BlockT, HashFor},
+	ConsensusEngineId,
+};
 use std::{marker::PhantomData, sync::Arc, time::Duration};
 
 mod error;
 mod finalize_block;
 mod seal_block;
+mod snapshot;
+mod time_travel;
 
 pub mod consensus;
 pub mod rpc;
@@ -50,10 +55,12 @@ pub use self::{
 	error::Error,
 	finalize_block::{finalize_block, FinalizeBlockParams},
 	rpc::{CreatedBlock, EngineCommand},
-	seal_block::{seal_block, SealBlockParams, MAX_PROPOSAL_DURATION},
+	seal_block::{seal_block, SealBlockParams, StorageOverrides, MAX_PROPOSAL_DURATION},
+	snapshot::revert_blocks,
+	time_travel::TimeTravel,
 };
 use sc_transaction_pool_api::TransactionPool;
-use sp_api::{ProvideRuntimeApi, TransactionFor};
+use sp_api::{CallApiAt, ProvideRuntimeApi, StateBackend, TransactionFor};
 
 const LOG_TARGET: &str = "manual-seal";
 
@@ -89,7 +96,7 @@ where
 }
 
 /// Params required to start the instant sealing authorship task.
-pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, CS, CIDP, P> {
+pub struct ManualSealParams<B: BlockT, BI, CB, E, C: ProvideRuntimeApi<B>, TP, SC, CS, CIDP, P> {
 	/// Block import instance.
 	pub block_import: BI,
 
@@ -115,6 +122,17 @@ pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, C
 
 	/// Something that can create the inherent data providers.
 	pub create_inherent_data_providers: CIDP,
+
+	/// Backend of the client, used to revert blocks.
+	///
+	/// Reverting blocks and snapshots are unavailable if `None`.
+	pub backend: Option<Arc<CB>>,
+
+	/// Clock moved forward by [`EngineCommand::IncreaseTime`].
+	///
+	/// Applying it to the inherents is up to `create_inherent_data_providers`, see
+	/// [`TimeTravel`]. Time travel is unavailable if `None`.
+	pub time_travel: Option<TimeTravel>,
 }
 
 /// Params required to start the manual sealing authorship task.
@@ -165,14 +183,17 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
-	}: ManualSealParams<B, BI, E, C, TP, SC, CS, CIDP, P>,
+		backend,
+		time_travel,
+	}: ManualSealParams<B, BI, CB, E, C, TP, SC, CS, CIDP, P>,
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error, Transaction = sp_api::TransactionFor<C, B>>
 		+ Send
 		+ Sync
 		+ 'static,
-	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + CallApiAt<B> + 'static,
+	<C as CallApiAt<B>>::StateBackend: StateBackend<HashFor<B>, Transaction = TransactionFor<C, B>>,
 	CB: ClientBackend<B> + 'static,
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P, Transaction = TransactionFor<C, B>>,
@@ -183,11 +204,16 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 	CIDP: CreateInherentDataProviders<B, ()>,
 	P: Send + Sync + 'static,
 {
+	let mut snapshots = snapshot::Snapshots::<B>::new();
+	let mut storage_overrides = StorageOverrides::new();
+
 	while let Some(command) = commands_stream.next().await {
 		match command {
-			EngineCommand::SealNewBlock { create_empty, finalize, parent_hash, sender } => {
+			EngineCommand::SealNewBlock { create_empty, finalize, parent_hash, mut sender } => {
+				let pending_time = time_travel.as_ref().map(TimeTravel::pending);
+				let (block_sender, block_receiver) = futures::channel::oneshot::channel();
 				seal_block(SealBlockParams {
-					sender,
+					sender: Some(block_sender),
 					parent_hash,
 					finalize,
 					create_empty,
@@ -198,8 +224,17 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 					pool: pool.clone(),
 					client: client.clone(),
 					create_inherent_data_providers: &create_inherent_data_providers,
+					storage_overrides: &mut storage_overrides,
 				})
 				.await;
+
+				let result = block_receiver.await.map_err(Error::from).and_then(|block| block);
+				if let (Err(_), Some(time_travel), Some(pending)) =
+					(&result, &time_travel, pending_time)
+				{
+					time_travel.restore_pending(pending);
+				}
+				rpc::send_result(&mut sender, result)
 			},
 			EngineCommand::FinalizeBlock { hash, sender, justification } => {
 				let justification = justification.map(|j| (MANUAL_SEAL_ENGINE_ID, j));
@@ -212,6 +247,76 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 				})
 				.await
 			},
+			EngineCommand::SealNewBlocks { count, create_empty, finalize, mut sender } => {
+				let mut created = Vec::with_capacity(count as usize);
+				let mut result = Ok(());
+				for _ in 0..count {
+					let pending_time = time_travel.as_ref().map(TimeTravel::pending);
+					let (block_sender, block_receiver) = futures::channel::oneshot::channel();
+					seal_block(SealBlockParams {
+						sender: Some(block_sender),
+						parent_hash: None,
+						finalize,
+						create_empty,
+						env: &mut env,
+						select_chain: &select_chain,
+						block_import: &mut block_import,
+						consensus_data_provider: consensus_data_provider.as_deref(),
+						pool: pool.clone(),
+						client: client.clone(),
+						create_inherent_data_providers: &create_inherent_data_providers,
+						storage_overrides: &mut storage_overrides,
+					})
+					.await;
+
+					match block_receiver.await.map_err(Error::from).and_then(|block| block) {
+						Ok(block) => created.push(block),
+						Err(e) => {
+							if let (Some(time_travel), Some(pending)) = (&time_travel, pending_time)
+							{
+								time_travel.restore_pending(pending);
+							}
+							result = Err(e);
+							break
+						},
+					}
+				}
+				rpc::send_result(&mut sender, result.map(|()| created))
+			},
+			EngineCommand::IncreaseTime { by, mut sender } => {
+				let result = time_travel
+					.as_ref()
+					.map(|time_travel| time_travel.advance(by))
+					.ok_or(Error::Unsupported("Time travel"));
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::RevertBlocks { count, mut sender } => {
+				let result = backend
+					.as_deref()
+					.ok_or(Error::Unsupported("Reverting blocks"))
+					.and_then(|backend| revert_blocks::<B, _>(backend, count));
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::TakeSnapshot { mut sender } => {
+				let result = backend
+					.as_ref()
+					.map(|_| snapshots.take(&*client, time_travel.as_ref()))
+					.ok_or(Error::Unsupported("Taking snapshots"));
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::RevertToSnapshot { id, mut sender } => {
+				let result = backend
+					.as_deref()
+					.ok_or(Error::Unsupported("Reverting to snapshots"))
+					.and_then(|backend| {
+						snapshots.revert_to(id, &*client, backend, time_travel.as_ref())
+					});
+				rpc::send_result(&mut sender, result)
+			},
+			EngineCommand::SetStorage { changes, mut sender } => {
+				storage_overrides.extend(changes);
+				rpc::send_result(&mut sender, Ok(()))
+			},
 		}
 	}
 }
@@ -235,7 +340,8 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		+ Send
 		+ Sync
 		+ 'static,
-	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + CallApiAt<B> + 'static,
+	<C as CallApiAt<B>>::StateBackend: StateBackend<HashFor<B>, Transaction = TransactionFor<C, B>>,
 	CB: ClientBackend<B> + 'static,
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P, Transaction = TransactionFor<C, B>>,
@@ -263,6 +369,8 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
+		backend: None,
+		time_travel: None,
 	})
 	.await
 }
@@ -289,7 +397,8 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		+ Send
 		+ Sync
 		+ 'static,
-	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + CallApiAt<B> + 'static,
+	<C as CallApiAt<B>>::StateBackend: StateBackend<HashFor<B>, Transaction = TransactionFor<C, B>>,
 	CB: ClientBackend<B> + 'static,
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P, Transaction = TransactionFor<C, B>>,
@@ -317,6 +426,8 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
+		backend: None,
+		time_travel: None,
 	})
 	.await
 }
@@ -454,6 +565,8 @@ mod tests {
 			select_chain,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
 			consensus_data_provider: None,
+			backend: None,
+			time_travel: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -530,6 +643,8 @@ mod tests {
 			select_chain,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
 			consensus_data_provider: None,
+			backend: None,
+			time_travel: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -610,6 +725,8 @@ mod tests {
 			select_chain,
 			consensus_data_provider: None,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
+			time_travel: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -695,6 +812,8 @@ mod tests {
 			select_chain,
 			consensus_data_provider: None,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
+			time_travel: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -801,6 +920,8 @@ mod tests {
 			// use a provider that pushes some post digest data
 			consensus_data_provider: Some(Box::new(TestDigestProvider { _client: client.clone() })),
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
+			time_travel: None,
 		});
 		std::thread::spawn(|| {
 			let rt = tokio::runtime::Runtime::new().unwrap();
@@ -821,4 +942,233 @@ mod tests {
 		let header = client.header(created_block.hash).unwrap().unwrap();
 		assert_eq!(header.number, 1);
 	}
+
+	#[tokio::test]
+	async fn manual_seal_time_travel_and_snapshots() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			api(),
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+		let time_travel = TimeTravel::new();
+
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: Some(backend),
+			time_travel: Some(time_travel.clone()),
+		});
+		std::thread::spawn(|| {
+			let rt = tokio::runtime::Runtime::new().unwrap();
+			rt.block_on(future);
+		});
+
+		macro_rules! request {
+			($command:ident { $($field:ident: $value:expr),* }) => {{
+				let (tx, rx) = futures::channel::oneshot::channel();
+				sink.send(EngineCommand::$command { $($field: $value,)* sender: Some(tx) })
+					.await
+					.unwrap();
+				rx.await.unwrap()
+			}};
+		}
+
+		let created = request!(SealNewBlocks { count: 2, create_empty: true, finalize: false });
+		assert_eq!(created.unwrap().len(), 2);
+		let snapshot = request!(TakeSnapshot {}).unwrap();
+		let snapshot_hash = client.info().best_hash;
+
+		let created = request!(SealNewBlocks { count: 2, create_empty: true, finalize: false });
+		assert_eq!(created.unwrap().last().unwrap().hash, client.info().best_hash);
+		assert_eq!(client.info().best_number, 4);
+		assert_eq!(
+			request!(IncreaseTime { by: Duration::from_secs(60) }).unwrap(),
+			Duration::from_secs(60),
+		);
+		assert_eq!(time_travel.take_pending(), Duration::from_secs(60));
+
+		request!(RevertToSnapshot { id: snapshot }).unwrap();
+		assert_eq!(client.info().best_hash, snapshot_hash);
+		assert_eq!(time_travel.offset(), Duration::ZERO);
+		assert_matches::assert_matches!(
+			request!(RevertToSnapshot { id: snapshot }),
+			Err(Error::UnknownSnapshot(_))
+		);
+
+		assert_eq!(request!(RevertBlocks { count: 1 }).unwrap(), 1);
+		assert_eq!(client.info().best_number, 1);
+	}
+
+	#[tokio::test]
+	async fn manual_seal_restores_time_travel_when_sealing_fails() {
+		use std::sync::atomic::{AtomicBool, Ordering};
+
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			api(),
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+		let time_travel = TimeTravel::new();
+		let fail = Arc::new(AtomicBool::new(true));
+
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: {
+				let (time_travel, fail) = (time_travel.clone(), fail.clone());
+				move |_, _| {
+					// Take the time travelled, as a timestamp provider would, before failing.
+					time_travel.take_pending();
+					let fail = fail.load(Ordering::SeqCst);
+					async move {
+						if fail {
+							Err(Box::<dyn std::error::Error + Send + Sync>::from("no inherents"))
+						} else {
+							Ok(())
+						}
+					}
+				}
+			},
+			backend: Some(backend),
+			time_travel: Some(time_travel.clone()),
+		});
+		std::thread::spawn(|| {
+			let rt = tokio::runtime::Runtime::new().unwrap();
+			rt.block_on(future);
+		});
+
+		macro_rules! request {
+			($command:ident { $($field:ident: $value:expr),* }) => {{
+				let (tx, rx) = futures::channel::oneshot::channel();
+				sink.send(EngineCommand::$command { $($field: $value,)* sender: Some(tx) })
+					.await
+					.unwrap();
+				rx.await.unwrap()
+			}};
+		}
+
+		request!(IncreaseTime { by: Duration::from_secs(60) }).unwrap();
+		assert!(request!(SealNewBlock { create_empty: true, finalize: false, parent_hash: None })
+			.is_err());
+		assert_eq!(time_travel.pending(), Duration::from_secs(60));
+		assert!(request!(SealNewBlocks { count: 2, create_empty: true, finalize: false }).is_err());
+		assert_eq!(time_travel.pending(), Duration::from_secs(60));
+
+		fail.store(false, Ordering::SeqCst);
+		request!(SealNewBlock { create_empty: true, finalize: false, parent_hash: None }).unwrap();
+		assert_eq!(time_travel.pending(), Duration::ZERO);
+		assert_eq!(client.info().best_number, 1);
+	}
+
+	#[tokio::test]
+	async fn manual_seal_storage_overrides() {
+		use sc_client_api::StorageProvider;
+		use sp_core::storage::{StorageData, StorageKey};
+
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			api(),
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		assert!(rpc::ManualSeal::new(sink.clone())
+			.with_storage_overrides(&sc_chain_spec::ChainType::Live)
+			.is_err());
+
+		let future = run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
+			time_travel: None,
+		});
+		std::thread::spawn(|| {
+			let rt = tokio::runtime::Runtime::new().unwrap();
+			rt.block_on(future);
+		});
+
+		let key = StorageKey(b"fixture".to_vec());
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SetStorage {
+			changes: vec![(key.0.clone(), Some(b"value".to_vec()))],
+			sender: Some(tx),
+		})
+		.await
+		.unwrap();
+		rx.await.unwrap().unwrap();
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SealNewBlock {
+			parent_hash: None,
+			sender: Some(tx),
+			create_empty: true,
+			finalize: false,
+		})
+		.await
+		.unwrap();
+		let created_block = rx.await.unwrap().unwrap();
+
+		assert_eq!(
+			client.storage(created_block.hash, &key).unwrap(),
+			Some(StorageData(b"value".to_vec())),
+		);
+		assert_eq!(client.storage(genesis_hash, &key).unwrap(), None);
+	}
 }
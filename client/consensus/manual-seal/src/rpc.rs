@@ -28,9 +28,15 @@ use jsonrpsee::{
 	core::{async_trait, Error as JsonRpseeError, RpcResult},
 	proc_macros::rpc,
 };
+use sc_chain_spec::ChainType;
 use sc_consensus::ImportedAux;
 use serde::{Deserialize, Serialize};
+use sp_core::storage::{StorageData, StorageKey};
 use sp_runtime::EncodedJustification;
+use std::time::Duration;
+
+/// Maximum number of blocks created by a single `engine_createBlocks` call.
+pub const MAX_CREATE_BLOCKS: u32 = 1000;
 
 /// Sender passed to the authorship task to report errors or successes.
 pub type Sender<T> = Option<oneshot::Sender<std::result::Result<T, Error>>>;
@@ -63,6 +69,58 @@ pub enum EngineCommand<Hash> {
 		/// finalization justification
 		justification: Option<EncodedJustification>,
 	},
+	/// Tells the engine to propose `count` new blocks on top of each other, on the best chain.
+	///
+	/// Stops at the first block which can't be sealed.
+	SealNewBlocks {
+		/// number of blocks to seal.
+		count: u32,
+		/// if true, empty blocks(without extrinsics) will be created.
+		/// otherwise, will return Error::EmptyTransactionPool.
+		create_empty: bool,
+		/// instantly finalize these blocks?
+		finalize: bool,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<Vec<CreatedBlock<Hash>>>,
+	},
+	/// Tells the engine to move the time seen by the next blocks forward.
+	IncreaseTime {
+		/// amount of time to travel.
+		by: Duration,
+		/// sender to report the total time travelled, or errors, to the rpc.
+		sender: Sender<Duration>,
+	},
+	/// Tells the engine to revert the best chain by `count` blocks, including finalized ones.
+	RevertBlocks {
+		/// number of blocks to revert.
+		count: u32,
+		/// sender to report the number of reverted blocks, or errors, to the rpc.
+		sender: Sender<u32>,
+	},
+	/// Tells the engine to snapshot the best chain, to revert to it later on.
+	TakeSnapshot {
+		/// sender to report the snapshot ID, or errors, to the rpc.
+		sender: Sender<u64>,
+	},
+	/// Tells the engine to revert the best chain to a snapshot.
+	///
+	/// The snapshot, and all snapshots taken after it, can't be reverted to again.
+	RevertToSnapshot {
+		/// ID of the snapshot.
+		id: u64,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
+	/// Tells the engine to write the given storage values in the next sealed block.
+	///
+	/// The state of that block doesn't match the execution of its extrinsics, so only the local
+	/// node is able to import it.
+	SetStorage {
+		/// storage values to write, `None` removing the key.
+		changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
 }
 
 /// RPC trait that provides methods for interacting with the manual-seal authorship task over rpc.
@@ -84,11 +142,51 @@ pub trait ManualSealApi<Hash> {
 		hash: Hash,
 		justification: Option<EncodedJustification>,
 	) -> RpcResult<bool>;
+
+	/// Instructs the manual-seal authorship task to create `count` new blocks on the best chain
+	///
+	/// Fails if `count` exceeds [`MAX_CREATE_BLOCKS`].
+	#[method(name = "engine_createBlocks")]
+	async fn create_blocks(
+		&self,
+		count: u32,
+		create_empty: bool,
+		finalize: bool,
+	) -> RpcResult<Vec<CreatedBlock<Hash>>>;
+
+	/// Moves the time seen by the next blocks forward by `millis` milliseconds.
+	///
+	/// Returns the total time travelled in milliseconds.
+	#[method(name = "engine_increaseTime")]
+	async fn increase_time(&self, millis: u64) -> RpcResult<u64>;
+
+	/// Reverts the best chain by `count` blocks, including finalized ones.
+	///
+	/// Returns the number of reverted blocks.
+	#[method(name = "engine_revertBlocks")]
+	async fn revert_blocks(&self, count: u32) -> RpcResult<u32>;
+
+	/// Snapshots the best chain, returning the ID to revert to it with
+	/// `engine_revertToSnapshot`.
+	#[method(name = "engine_snapshot")]
+	async fn snapshot(&self) -> RpcResult<u64>;
+
+	/// Reverts the best chain to the snapshot with the given ID.
+	#[method(name = "engine_revertToSnapshot")]
+	async fn revert_to_snapshot(&self, id: u64) -> RpcResult<bool>;
+
+	/// Writes the given storage values in the next sealed block, `None` removing the key.
+	///
+	/// Only available on development chains.
+	#[method(name = "engine_setStorage")]
+	async fn set_storage(&self, changes: Vec<(StorageKey, Option<StorageData>)>)
+		-> RpcResult<bool>;
 }
 
 /// A struct that implements the [`ManualSealApiServer`].
 pub struct ManualSeal<Hash> {
 	import_block_channel: mpsc::Sender<EngineCommand<Hash>>,
+	storage_overrides: bool,
 }
 
 /// return type of `engine_createBlock`
@@ -103,7 +201,39 @@ pub struct CreatedBlock<Hash> {
 impl<Hash> ManualSeal<Hash> {
 	/// Create new `ManualSeal` with the given reference to the client.
 	pub fn new(import_block_channel: mpsc::Sender<EngineCommand<Hash>>) -> Self {
-		Self { import_block_channel }
+		Self { import_block_channel, storage_overrides: false }
+	}
+
+	/// Enable `engine_setStorage`.
+	///
+	/// Fails unless `chain_type` is [`ChainType::Development`], to refuse starting a node
+	/// overriding the storage of any other chain.
+	pub fn with_storage_overrides(mut self, chain_type: &ChainType) -> Result<Self, Error> {
+		if *chain_type != ChainType::Development {
+			return Err(Error::StringError(format!(
+				"engine_setStorage is only available on development chains, not on {:?} chains",
+				chain_type
+			)))
+		}
+
+		self.storage_overrides = true;
+		Ok(self)
+	}
+
+	/// Send the command built with the given sender and wait for its result.
+	async fn request<T>(
+		&self,
+		command: impl FnOnce(Sender<T>) -> EngineCommand<Hash>,
+	) -> RpcResult<T> {
+		let mut sink = self.import_block_channel.clone();
+		let (sender, receiver) = oneshot::channel();
+		sink.send(command(Some(sender))).await?;
+
+		match receiver.await {
+			Ok(Ok(rx)) => Ok(rx),
+			Ok(Err(e)) => Err(e.into()),
+			Err(e) => Err(JsonRpseeError::to_call_error(e)),
+		}
 	}
 }
 
@@ -145,6 +275,61 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 		sink.send(command).await?;
 		receiver.await.map(|_| true).map_err(|e| JsonRpseeError::to_call_error(e))
 	}
+
+	async fn create_blocks(
+		&self,
+		count: u32,
+		create_empty: bool,
+		finalize: bool,
+	) -> RpcResult<Vec<CreatedBlock<Hash>>> {
+		if count > MAX_CREATE_BLOCKS {
+			return Err(Error::StringError(format!(
+				"count exceeds maximum value. value: {}, max: {}",
+				count, MAX_CREATE_BLOCKS
+			))
+			.into())
+		}
+
+		self.request(|sender| EngineCommand::SealNewBlocks {
+			count,
+			create_empty,
+			finalize,
+			sender,
+		})
+		.await
+	}
+
+	async fn increase_time(&self, millis: u64) -> RpcResult<u64> {
+		let by = Duration::from_millis(millis);
+		let offset = self.request(|sender| EngineCommand::IncreaseTime { by, sender }).await?;
+		Ok(offset.as_millis() as u64)
+	}
+
+	async fn revert_blocks(&self, count: u32) -> RpcResult<u32> {
+		self.request(|sender| EngineCommand::RevertBlocks { count, sender }).await
+	}
+
+	async fn snapshot(&self) -> RpcResult<u64> {
+		self.request(|sender| EngineCommand::TakeSnapshot { sender }).await
+	}
+
+	async fn revert_to_snapshot(&self, id: u64) -> RpcResult<bool> {
+		self.request(|sender| EngineCommand::RevertToSnapshot { id, sender }).await?;
+		Ok(true)
+	}
+
+	async fn set_storage(
+		&self,
+		changes: Vec<(StorageKey, Option<StorageData>)>,
+	) -> RpcResult<bool> {
+		if !self.storage_overrides {
+			return Err(Error::Unsupported("engine_setStorage").into())
+		}
+
+		let changes = changes.into_iter().map(|(key, value)| (key.0, value.map(|v| v.0))).collect();
+		self.request(|sender| EngineCommand::SetStorage { changes, sender }).await?;
+		Ok(true)
+	}
 }
 
 /// report any errors or successes encountered by the authorship task back
@@ -23,16 +23,20 @@ use crate::{rpc, ConsensusDataProvider, CreatedBlock, Error};
 use futures::prelude::*;
 use sc_consensus::{BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult, StateAction};
 use sc_transaction_pool_api::TransactionPool;
-use sp_api::{ProvideRuntimeApi, TransactionFor};
+use sp_api::{CallApiAt, ProvideRuntimeApi, StateBackend, StateBackendFor, TransactionFor};
 use sp_blockchain::HeaderBackend;
 use sp_consensus::{self, BlockOrigin, Environment, Proposer, SelectChain};
+use sp_core::storage::ChildInfo;
 use sp_inherents::{CreateInherentDataProviders, InherentDataProvider};
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
-use std::{sync::Arc, time::Duration};
+use sp_runtime::traits::{Block as BlockT, HashFor, Header as HeaderT};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 /// max duration for creating a proposal in secs
 pub const MAX_PROPOSAL_DURATION: u64 = 10;
 
+/// Storage values to write in the next sealed block, `None` removing the key.
+pub type StorageOverrides = BTreeMap<Vec<u8>, Option<Vec<u8>>>;
+
 /// params for sealing a new block
 pub struct SealBlockParams<'a, B: BlockT, BI, SC, C: ProvideRuntimeApi<B>, E, TP, CIDP, P> {
 	/// if true, empty blocks(without extrinsics) will be created.
@@ -59,6 +63,8 @@ pub struct SealBlockParams<'a, B: BlockT, BI, SC, C: ProvideRuntimeApi<B>, E, TP
 	pub block_import: &'a mut BI,
 	/// Something that can create the inherent data providers.
 	pub create_inherent_data_providers: &'a CIDP,
+	/// Storage to override in the sealed block, cleared once the block is imported.
+	pub storage_overrides: &'a mut StorageOverrides,
 }
 
 /// seals a new block with the given params
@@ -75,6 +81,7 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 		create_inherent_data_providers,
 		consensus_data_provider: digest_provider,
 		mut sender,
+		storage_overrides,
 	}: SealBlockParams<'_, B, BI, SC, C, E, TP, CIDP, P>,
 ) where
 	B: BlockT,
@@ -82,7 +89,8 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 		+ Send
 		+ Sync
 		+ 'static,
-	C: HeaderBackend<B> + ProvideRuntimeApi<B>,
+	C: HeaderBackend<B> + ProvideRuntimeApi<B> + CallApiAt<B>,
+	<C as CallApiAt<B>>::StateBackend: StateBackend<HashFor<B>, Transaction = TransactionFor<C, B>>,
 	E: Environment<B>,
 	E::Proposer: Proposer<B, Proof = P, Transaction = TransactionFor<C, B>>,
 	TP: TransactionPool<Block = B>,
@@ -135,15 +143,25 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 			return Err(Error::EmptyTransactionPool)
 		}
 
-		let (header, body) = proposal.block.deconstruct();
+		let (mut header, body) = proposal.block.deconstruct();
 		let proof = proposal.proof;
+		let mut storage_changes = proposal.storage_changes;
+		if !storage_overrides.is_empty() {
+			override_storage::<B, C>(
+				&*client,
+				parent.hash(),
+				&mut header,
+				&mut storage_changes,
+				storage_overrides,
+			)?;
+		}
+
 		let mut params = BlockImportParams::new(BlockOrigin::Own, header.clone());
 		params.body = Some(body);
 		params.finalized = finalize;
 		params.fork_choice = Some(ForkChoiceStrategy::LongestChain);
-		params.state_action = StateAction::ApplyChanges(sc_consensus::StorageChanges::Changes(
-			proposal.storage_changes,
-		));
+		params.state_action =
+			StateAction::ApplyChanges(sc_consensus::StorageChanges::Changes(storage_changes));
 
 		if let Some(digest_provider) = digest_provider {
 			digest_provider.append_block_import(&parent, &mut params, &inherent_data, proof)?;
@@ -155,11 +173,69 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 		post_header.digest_mut().logs.extend(params.post_digests.iter().cloned());
 
 		match block_import.import_block(params).await? {
-			ImportResult::Imported(aux) =>
-				Ok(CreatedBlock { hash: <B as BlockT>::Header::hash(&post_header), aux }),
+			ImportResult::Imported(aux) => {
+				storage_overrides.clear();
+				Ok(CreatedBlock { hash: <B as BlockT>::Header::hash(&post_header), aux })
+			},
 			other => Err(other.into()),
 		}
 	};
 
 	rpc::send_result(&mut sender, future.await)
 }
+
+/// Applies `overrides` on top of the storage changes of a proposed block, updating its state root.
+///
+/// The state of the block doesn't match the execution of its extrinsics anymore, so only the local
+/// node is able to import it.
+fn override_storage<B, C>(
+	client: &C,
+	parent_hash: B::Hash,
+	header: &mut B::Header,
+	changes: &mut sp_api::StorageChanges<StateBackendFor<C, B>, B>,
+	overrides: &StorageOverrides,
+) -> Result<(), Error>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + CallApiAt<B>,
+	<C as CallApiAt<B>>::StateBackend: StateBackend<HashFor<B>, Transaction = TransactionFor<C, B>>,
+{
+	let state = client.state_at(parent_hash).map_err(|e| Error::StringError(e.to_string()))?;
+	let state_version = client
+		.runtime_version_at(parent_hash)
+		.map_err(|e| Error::StringError(e.to_string()))?
+		.state_version();
+
+	let mut main_changes = std::mem::take(&mut changes.main_storage_changes)
+		.into_iter()
+		.collect::<StorageOverrides>();
+	main_changes.extend(overrides.iter().map(|(key, value)| (key.clone(), value.clone())));
+
+	let child_infos = changes
+		.child_storage_changes
+		.iter()
+		.map(|(storage_key, _)| ChildInfo::new_default(storage_key))
+		.collect::<Vec<_>>();
+	let (root, transaction) = state.full_storage_root(
+		main_changes.iter().map(|(key, value)| (&key[..], value.as_deref())),
+		child_infos
+			.iter()
+			.zip(&changes.child_storage_changes)
+			.map(|(info, (_, changes))| {
+				(info, changes.iter().map(|(key, value)| (&key[..], value.as_deref())))
+			}),
+		state_version,
+	);
+
+	log::info!(
+		target: crate::LOG_TARGET,
+		"🛠️ Overriding {} storage keys in block #{}",
+		overrides.len(),
+		header.number(),
+	);
+	changes.main_storage_changes = main_changes.into_iter().collect();
+	changes.transaction = transaction;
+	changes.transaction_storage_root = root;
+	header.set_state_root(root);
+	Ok(())
+}
@@ -0,0 +1,120 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reverting blocks and chain snapshots for dev chains.
+
+use crate::{Error, TimeTravel};
+use sc_client_api::backend::Backend as ClientBackend;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, NumberFor, UniqueSaturatedInto};
+use std::{collections::BTreeMap, time::Duration};
+
+/// Reverts the best chain by `count` blocks, including finalized ones.
+///
+/// Returns the number of blocks reverted.
+pub fn revert_blocks<B, CB>(backend: &CB, count: u32) -> Result<u32, Error>
+where
+	B: BlockT,
+	CB: ClientBackend<B>,
+{
+	let (reverted, _) = backend.revert(count.into(), true)?;
+	log::info!(target: crate::LOG_TARGET, "⏪ Reverted {} blocks", reverted);
+	Ok(reverted.unique_saturated_into())
+}
+
+/// A snapshot of the chain.
+struct Snapshot<B: BlockT> {
+	/// Best block when the snapshot was taken.
+	hash: B::Hash,
+	/// Number of the best block when the snapshot was taken.
+	number: NumberFor<B>,
+	/// Time travelled when the snapshot was taken.
+	time_offset: Duration,
+}
+
+/// The snapshots taken of the chain, by ID.
+pub(crate) struct Snapshots<B: BlockT> {
+	snapshots: BTreeMap<u64, Snapshot<B>>,
+	next_id: u64,
+}
+
+impl<B: BlockT> Snapshots<B> {
+	pub fn new() -> Self {
+		Self { snapshots: BTreeMap::new(), next_id: 0 }
+	}
+
+	/// Snapshot the current best block, returning the ID of the snapshot.
+	pub fn take<C: HeaderBackend<B>>(
+		&mut self,
+		client: &C,
+		time_travel: Option<&TimeTravel>,
+	) -> u64 {
+		let info = client.info();
+		let id = self.next_id;
+		self.next_id += 1;
+		self.snapshots.insert(
+			id,
+			Snapshot {
+				hash: info.best_hash,
+				number: info.best_number,
+				time_offset: time_travel.map(TimeTravel::offset).unwrap_or_default(),
+			},
+		);
+		id
+	}
+
+	/// Revert the best chain to the snapshot with the given ID.
+	///
+	/// The snapshot and all snapshots taken after it are discarded.
+	pub fn revert_to<C, CB>(
+		&mut self,
+		id: u64,
+		client: &C,
+		backend: &CB,
+		time_travel: Option<&TimeTravel>,
+	) -> Result<(), Error>
+	where
+		C: HeaderBackend<B>,
+		CB: ClientBackend<B>,
+	{
+		let snapshot = self.snapshots.get(&id).ok_or(Error::UnknownSnapshot(id))?;
+		if client.hash(snapshot.number)? != Some(snapshot.hash) {
+			return Err(Error::StringError(format!(
+				"Snapshot {} is not on the best chain anymore",
+				id
+			)))
+		}
+
+		let count = client.info().best_number - snapshot.number;
+		let (reverted, _) = backend.revert(count, true)?;
+		if reverted != count {
+			return Err(Error::StringError(format!(
+				"Only reverted {} of the {} blocks since snapshot {}",
+				reverted, count, id
+			)))
+		}
+		if let Some(time_travel) = time_travel {
+			time_travel.reset(snapshot.time_offset);
+		}
+		log::info!(target: crate::LOG_TARGET, "⏪ Reverted to snapshot {} at {}", id, snapshot.hash);
+
+		self.snapshots.split_off(&id);
+		Ok(())
+	}
+}
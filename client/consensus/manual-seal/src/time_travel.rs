@@ -0,0 +1,100 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Time travel for dev chains, moving the clock seen by the sealed blocks forward.
+
+use std::{
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, SystemTime},
+};
+
+/// A clock offset shared between the manual-seal authorship task and the inherent data providers.
+///
+/// The authorship task moves it forward on [`EngineCommand::IncreaseTime`](crate::EngineCommand).
+/// Applying it is up to the inherent data providers:
+///
+/// - providers deriving the time from the system clock use [`TimeTravel::timestamp`], which
+///   includes all the time travelled so far;
+/// - providers deriving the time from the parent block, such as
+///   [`SlotTimestampProvider`](crate::consensus::timestamp::SlotTimestampProvider), only add the
+///   time travelled since the last block, see [`TimeTravel::take_pending`].
+///
+/// Slot inherents follow, as they are computed from the timestamp.
+#[derive(Clone, Debug, Default)]
+pub struct TimeTravel {
+	/// Total time travelled in milliseconds.
+	offset: Arc<AtomicU64>,
+	/// Time travelled in milliseconds and not yet taken by a block.
+	pending: Arc<AtomicU64>,
+}
+
+impl TimeTravel {
+	/// Create a new clock without any offset.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Move the clock forward by `by`, returning the total time travelled.
+	pub fn advance(&self, by: Duration) -> Duration {
+		let by = by.as_millis() as u64;
+		self.pending.fetch_add(by, Ordering::SeqCst);
+		Duration::from_millis(self.offset.fetch_add(by, Ordering::SeqCst).saturating_add(by))
+	}
+
+	/// Total time travelled.
+	pub fn offset(&self) -> Duration {
+		Duration::from_millis(self.offset.load(Ordering::SeqCst))
+	}
+
+	/// Reset the total time travelled to `offset`, e.g. when restoring a snapshot.
+	///
+	/// Discards the time travelled which wasn't taken by a block yet.
+	pub fn reset(&self, offset: Duration) {
+		self.offset.store(offset.as_millis() as u64, Ordering::SeqCst);
+		self.pending.store(0, Ordering::SeqCst);
+	}
+
+	/// Take the time travelled since the last call.
+	pub fn take_pending(&self) -> Duration {
+		Duration::from_millis(self.pending.swap(0, Ordering::SeqCst))
+	}
+
+	/// Time travelled and not yet taken by a block.
+	pub fn pending(&self) -> Duration {
+		Duration::from_millis(self.pending.load(Ordering::SeqCst))
+	}
+
+	/// Put back the time travelled which wasn't taken by a block to `pending`, e.g. when the
+	/// block which took it failed to be sealed.
+	pub fn restore_pending(&self, pending: Duration) {
+		self.pending.store(pending.as_millis() as u64, Ordering::SeqCst);
+	}
+
+	/// The system time moved forward by the time travelled.
+	pub fn timestamp(&self) -> sp_timestamp::Timestamp {
+		let now = SystemTime::now()
+			.duration_since(SystemTime::UNIX_EPOCH)
+			.expect("Current time is always after unix epoch; qed")
+			.as_millis() as u64;
+		sp_timestamp::Timestamp::new(now.saturating_add(self.offset().as_millis() as u64))
+	}
+}
@@ -30,8 +30,8 @@ mod aux_schema;
 mod slots;
 
 pub use aux_schema::{check_equivocation, MAX_SLOT_CAPACITY, PRUNING_BOUND};
-pub use slots::SlotInfo;
 use slots::Slots;
+pub use slots::{SlotInfo, SlotScheduleProvider};
 
 use futures::{future::Either, Future, TryFutureExt};
 use futures_timer::Delay;
@@ -40,7 +40,7 @@ use sc_consensus::{BlockImport, JustificationSyncLink};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_INFO, CONSENSUS_WARN};
 use sp_arithmetic::traits::BaseArithmetic;
 use sp_consensus::{Proposal, Proposer, SelectChain, SyncOracle};
-use sp_consensus_slots::{Slot, SlotDuration};
+use sp_consensus_slots::Slot;
 use sp_inherents::CreateInherentDataProviders;
 use sp_runtime::traits::{Block as BlockT, HashFor, Header as HeaderT};
 use std::{
@@ -516,8 +516,12 @@ impl_inherent_data_provider_ext_tuple!(S, A, B, C, D, E, F, G, H, I, J);
 ///
 /// Every time a new slot is triggered, `worker.on_slot` is called and the future it returns is
 /// polled until completion, unless we are major syncing.
-pub async fn start_slot_worker<B, C, W, SO, CIDP, Proof>(
-	slot_duration: SlotDuration,
+///
+/// The slot schedule is queried at every best block, so a fixed
+/// [`SlotDuration`](sp_consensus_slots::SlotDuration) as well as a
+/// [`SlotScheduleProvider`] following runtime upgrades can be passed.
+pub async fn start_slot_worker<B, C, W, SO, CIDP, SS, Proof>(
+	slot_schedule: SS,
 	client: C,
 	mut worker: W,
 	sync_oracle: SO,
@@ -529,8 +533,9 @@ pub async fn start_slot_worker<B, C, W, SO, CIDP, Proof>(
 	SO: SyncOracle + Send,
 	CIDP: CreateInherentDataProviders<B, ()> + Send + 'static,
 	CIDP::InherentDataProviders: InherentDataProviderExt + Send,
+	SS: SlotScheduleProvider<B>,
 {
-	let mut slots = Slots::new(slot_duration.as_duration(), create_inherent_data_providers, client);
+	let mut slots = Slots::new(slot_schedule, create_inherent_data_providers, client);
 
 	loop {
 		let slot_info = slots.next_slot().await;
@@ -22,7 +22,8 @@
 //! This is used instead of `futures_timer::Interval` because it was unreliable.
 
 use super::{InherentDataProviderExt, Slot, LOG_TARGET};
-use sp_consensus::SelectChain;
+use sp_consensus::{Error as ConsensusError, SelectChain};
+use sp_consensus_slots::{SlotDuration, SlotSchedule};
 use sp_inherents::{CreateInherentDataProviders, InherentDataProvider};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
@@ -47,6 +48,34 @@ pub fn time_until_next_slot(slot_duration: Duration) -> Duration {
 	Duration::from_millis(remaining_millis as u64)
 }
 
+/// Returns the duration until the next slot of `schedule` from now.
+pub fn time_until_next_slot_in_schedule(schedule: &SlotSchedule) -> Duration {
+	let now = duration_now().as_millis() as u64;
+
+	let next_slot_start = if now < schedule.activation_timestamp {
+		schedule.activation_timestamp
+	} else {
+		let current_slot = schedule.slot_at(now.into());
+		schedule.slot_start(current_slot + 1).as_millis()
+	};
+	Duration::from_millis(next_slot_start.saturating_sub(now))
+}
+
+/// Provides the [`SlotSchedule`] of blocks built on top of a given block.
+///
+/// This allows the slot duration to change over time, e.g. with a runtime upgrade.
+pub trait SlotScheduleProvider<B: BlockT>: Send {
+	/// Returns the slot schedule of blocks built on top of `parent`.
+	fn slot_schedule(&self, parent: &B::Header) -> Result<SlotSchedule, ConsensusError>;
+}
+
+/// A slot duration which never changes.
+impl<B: BlockT> SlotScheduleProvider<B> for SlotDuration {
+	fn slot_schedule(&self, _: &B::Header) -> Result<SlotSchedule, ConsensusError> {
+		Ok(SlotSchedule::from_slot_duration(*self))
+	}
+}
+
 /// Information about a slot.
 pub struct SlotInfo<B: BlockT> {
 	/// The slot number as found in the inherent data.
@@ -87,40 +116,54 @@ impl<B: BlockT> SlotInfo<B> {
 	}
 }
 
+/// Delay between attempts to fetch the slot schedule while it isn't known.
+const SLOT_SCHEDULE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
 /// A stream that returns every time there is a new slot.
-pub(crate) struct Slots<Block, SC, IDP> {
+pub(crate) struct Slots<Block, SC, IDP, SS> {
 	last_slot: Slot,
-	slot_duration: Duration,
+	slot_schedule: Option<SlotSchedule>,
+	slot_schedule_provider: SS,
 	until_next_slot: Option<Delay>,
 	create_inherent_data_providers: IDP,
 	select_chain: SC,
 	_phantom: std::marker::PhantomData<Block>,
 }
 
-impl<Block, SC, IDP> Slots<Block, SC, IDP> {
+impl<Block, SC, IDP, SS> Slots<Block, SC, IDP, SS> {
 	/// Create a new `Slots` stream.
 	pub fn new(
-		slot_duration: Duration,
+		slot_schedule_provider: SS,
 		create_inherent_data_providers: IDP,
 		select_chain: SC,
 	) -> Self {
 		Slots {
 			last_slot: 0.into(),
-			slot_duration,
+			slot_schedule: None,
+			slot_schedule_provider,
 			until_next_slot: None,
 			create_inherent_data_providers,
 			select_chain,
 			_phantom: Default::default(),
 		}
 	}
+
+	/// Returns the duration until the next slot, or until the next attempt to fetch the schedule
+	/// if it isn't known yet.
+	fn time_until_next_slot(&self) -> Duration {
+		self.slot_schedule
+			.as_ref()
+			.map_or(SLOT_SCHEDULE_RETRY_DELAY, time_until_next_slot_in_schedule)
+	}
 }
 
-impl<Block, SC, IDP> Slots<Block, SC, IDP>
+impl<Block, SC, IDP, SS> Slots<Block, SC, IDP, SS>
 where
 	Block: BlockT,
 	SC: SelectChain<Block>,
 	IDP: CreateInherentDataProviders<Block, ()> + 'static,
 	IDP::InherentDataProviders: crate::InherentDataProviderExt,
+	SS: SlotScheduleProvider<Block>,
 {
 	/// Returns a future that fires when the next slot starts.
 	pub async fn next_slot(&mut self) -> SlotInfo<Block> {
@@ -128,15 +171,12 @@ where
 			// Wait for slot timeout
 			self.until_next_slot
 				.take()
-				.unwrap_or_else(|| {
-					// Schedule first timeout.
-					let wait_dur = time_until_next_slot(self.slot_duration);
-					Delay::new(wait_dur)
-				})
+				// Fetch the schedule right away, the first timeout is scheduled with it.
+				.unwrap_or_else(|| Delay::new(Duration::ZERO))
 				.await;
 
 			// Schedule delay for next slot.
-			let wait_dur = time_until_next_slot(self.slot_duration);
+			let wait_dur = self.time_until_next_slot();
 			self.until_next_slot = Some(Delay::new(wait_dur));
 
 			let chain_head = match self.select_chain.best_chain().await {
@@ -152,6 +192,39 @@ where
 				},
 			};
 
+			let slot_schedule = match self.slot_schedule_provider.slot_schedule(&chain_head) {
+				Ok(x) => x,
+				Err(e) => {
+					log::warn!(
+						target: LOG_TARGET,
+						"Unable to author block in slot. No slot schedule: {}",
+						e,
+					);
+					// Let's retry at the next slot.
+					continue
+				},
+			};
+
+			if self.slot_schedule != Some(slot_schedule) {
+				let first_schedule = self.slot_schedule.is_none();
+				if !first_schedule {
+					log::info!(
+						target: LOG_TARGET,
+						"Slot duration changed to {}ms at slot {}.",
+						slot_schedule.slot_duration.as_millis(),
+						slot_schedule.activation_slot,
+					);
+				}
+
+				self.slot_schedule = Some(slot_schedule);
+				self.until_next_slot = Some(Delay::new(self.time_until_next_slot()));
+
+				// Wait for the start of the first slot.
+				if first_schedule {
+					continue
+				}
+			}
+
 			let inherent_data_providers = match self
 				.create_inherent_data_providers
 				.create_inherent_data_providers(chain_head.hash(), ())
@@ -175,14 +248,188 @@ where
 			if slot > self.last_slot {
 				self.last_slot = slot;
 
-				break SlotInfo::new(
+				let mut slot_info = SlotInfo::new(
 					slot,
 					Box::new(inherent_data_providers),
-					self.slot_duration,
+					slot_schedule.slot_duration.as_duration(),
 					chain_head,
 					None,
-				)
+				);
+				slot_info.ends_at =
+					Instant::now() + time_until_next_slot_in_schedule(&slot_schedule);
+
+				break slot_info
 			}
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{
+		ops::Deref,
+		sync::{Arc, Mutex},
+	};
+	use substrate_test_runtime_client::runtime::{Block, Header};
+
+	const SLOT_DURATION: SlotDuration = SlotDuration::from_millis(50);
+
+	type SharedSchedule = Arc<Mutex<Option<SlotSchedule>>>;
+
+	/// Provides the shared schedule, failing while there is none.
+	struct TestScheduleProvider(SharedSchedule);
+
+	impl SlotScheduleProvider<Block> for TestScheduleProvider {
+		fn slot_schedule(&self, _: &Header) -> Result<SlotSchedule, ConsensusError> {
+			self.0
+				.lock()
+				.unwrap()
+				.ok_or_else(|| ConsensusError::Other("No slot schedule yet".into()))
+		}
+	}
+
+	#[derive(Clone)]
+	struct TestSelectChain;
+
+	#[async_trait::async_trait]
+	impl SelectChain<Block> for TestSelectChain {
+		async fn leaves(&self) -> Result<Vec<<Block as BlockT>::Hash>, ConsensusError> {
+			Ok(vec![])
+		}
+
+		async fn best_chain(&self) -> Result<Header, ConsensusError> {
+			Ok(Header::new(
+				1,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			))
+		}
+	}
+
+	struct TestSlot(Slot);
+
+	impl Deref for TestSlot {
+		type Target = Slot;
+
+		fn deref(&self) -> &Slot {
+			&self.0
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl InherentDataProvider for TestSlot {
+		async fn provide_inherent_data(
+			&self,
+			_: &mut sp_inherents::InherentData,
+		) -> Result<(), sp_inherents::Error> {
+			Ok(())
+		}
+
+		async fn try_handle_error(
+			&self,
+			_: &sp_inherents::InherentIdentifier,
+			_: &[u8],
+		) -> Option<Result<(), sp_inherents::Error>> {
+			None
+		}
+	}
+
+	/// Creates a slot stream whose current slot follows `schedule`.
+	fn slots(
+		schedule: SharedSchedule,
+	) -> Slots<
+		Block,
+		TestSelectChain,
+		impl CreateInherentDataProviders<Block, (), InherentDataProviders = (TestSlot,)>,
+		TestScheduleProvider,
+	> {
+		let provider = TestScheduleProvider(schedule.clone());
+		let create_inherent_data_providers = move |_: <Block as BlockT>::Hash, _: ()| {
+			let now = duration_now().as_millis() as u64;
+			let slot = schedule.lock().unwrap().map_or(0.into(), |s| s.slot_at(now.into()));
+			async move { Ok::<_, Box<dyn std::error::Error + Send + Sync>>((TestSlot(slot),)) }
+		};
+		Slots::new(provider, create_inherent_data_providers, TestSelectChain)
+	}
+
+	#[test]
+	fn time_until_next_slot_waits_for_the_activation() {
+		let now = duration_now().as_millis() as u64;
+		let schedule = SlotSchedule {
+			slot_duration: SLOT_DURATION,
+			activation_slot: 10.into(),
+			activation_timestamp: now + 60_000,
+		};
+
+		let until_activation = time_until_next_slot_in_schedule(&schedule);
+		assert!(until_activation > Duration::from_secs(59));
+		assert!(until_activation <= Duration::from_secs(60));
+
+		let schedule = SlotSchedule::from_slot_duration(SLOT_DURATION);
+		assert!(time_until_next_slot_in_schedule(&schedule) <= SLOT_DURATION.as_duration());
+	}
+
+	#[test]
+	fn slots_follow_the_schedule() {
+		let schedule = Arc::new(Mutex::new(Some(SlotSchedule::from_slot_duration(SLOT_DURATION))));
+		let mut slots = slots(schedule);
+
+		futures::executor::block_on(async {
+			let first = slots.next_slot().await;
+			assert_eq!(first.duration, SLOT_DURATION.as_duration());
+			assert!(first.ends_at <= Instant::now() + SLOT_DURATION.as_duration());
+
+			let second = slots.next_slot().await;
+			assert!(second.slot > first.slot);
+			assert!(second.ends_at > first.ends_at);
+		});
+	}
+
+	#[test]
+	fn slots_wait_for_a_schedule() {
+		let schedule = Arc::new(Mutex::new(None));
+		let mut slots = slots(schedule.clone());
+
+		futures::executor::block_on(async {
+			let pending = futures::poll!(Box::pin(slots.next_slot()));
+			assert!(pending.is_pending());
+
+			*schedule.lock().unwrap() = Some(SlotSchedule::from_slot_duration(SLOT_DURATION));
+			let slot = slots.next_slot().await;
+			assert_eq!(slot.duration, SLOT_DURATION.as_duration());
+		});
+	}
+
+	#[test]
+	fn slots_follow_schedule_changes() {
+		let old = SlotSchedule::from_slot_duration(SLOT_DURATION);
+		let schedule = Arc::new(Mutex::new(Some(old)));
+		let mut slots = slots(schedule.clone());
+
+		futures::executor::block_on(async {
+			let before = slots.next_slot().await;
+			assert_eq!(before.duration, SLOT_DURATION.as_duration());
+
+			// like the runtime, activate the new duration from the slot after the last block on.
+			let new_duration = SlotDuration::from_millis(100);
+			let activation_slot = before.slot + 1;
+			let new = old.change_slot_duration(new_duration, activation_slot);
+			*schedule.lock().unwrap() = Some(new);
+
+			// the next slot is yielded with the new duration, continuing the slot numbers of the
+			// old schedule.
+			let after = slots.next_slot().await;
+			assert_eq!(after.duration, new_duration.as_duration());
+			assert!(after.slot > before.slot);
+			assert!(after.slot >= activation_slot);
+			assert!(after.slot < activation_slot + 3);
+
+			let next = slots.next_slot().await;
+			assert_eq!(next.duration, new_duration.as_duration());
+			assert!(next.slot > after.slot);
+		});
+	}
+}
@@ -38,6 +38,7 @@ mod parity_db;
 mod pinned_blocks_cache;
 mod record_stats_state;
 mod stats;
+mod trie_cache_snapshot;
 #[cfg(any(feature = "rocksdb", test))]
 mod upgrade;
 mod utils;
@@ -286,6 +287,10 @@ pub struct DatabaseSettings {
 	///
 	/// If `None` is given, the cache is disabled.
 	pub trie_cache_maximum_size: Option<usize>,
+	/// Persist the trie cache across restarts.
+	///
+	/// If `None` is given, the trie cache starts cold after every restart.
+	pub trie_cache_persistence: Option<TrieCachePersistence>,
 	/// Requested state pruning mode.
 	pub state_pruning: Option<PruningMode>,
 	/// Where to find the database.
@@ -296,6 +301,15 @@ pub struct DatabaseSettings {
 	pub blocks_pruning: BlocksPruning,
 }
 
+/// Settings for persisting the trie cache across restarts.
+#[derive(Debug, Clone)]
+pub struct TrieCachePersistence {
+	/// Where to store the snapshot of the trie cache.
+	pub path: PathBuf,
+	/// The maximum size in bytes of the snapshot written on shutdown.
+	pub maximum_size: usize,
+}
+
 /// Block pruning settings.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BlocksPruning {
@@ -1100,6 +1114,7 @@ pub struct Backend<Block: BlockT> {
 	state_usage: Arc<StateUsageStats>,
 	genesis_state: RwLock<Option<Arc<DbGenesisStorage<Block>>>>,
 	shared_trie_cache: Option<sp_trie::cache::SharedTrieCache<HashFor<Block>>>,
+	trie_cache_persistence: Option<TrieCachePersistence>,
 }
 
 impl<Block: BlockT> Backend<Block> {
@@ -1146,6 +1161,7 @@ impl<Block: BlockT> Backend<Block> {
 		};
 		let db_setting = DatabaseSettings {
 			trie_cache_maximum_size: Some(16 * 1024 * 1024),
+			trie_cache_persistence: None,
 			state_pruning: Some(state_pruning),
 			source: DatabaseSource::Custom { db, require_create_flag: true },
 			blocks_pruning,
@@ -1216,6 +1232,7 @@ impl<Block: BlockT> Backend<Block> {
 			shared_trie_cache: config.trie_cache_maximum_size.map(|maximum_size| {
 				SharedTrieCache::new(sp_trie::cache::CacheSize::new(maximum_size))
 			}),
+			trie_cache_persistence: config.trie_cache_persistence.clone(),
 		};
 
 		// Older DB versions have no last state key. Check if the state is available and set it.
@@ -1238,9 +1255,27 @@ impl<Block: BlockT> Backend<Block> {
 
 		db.commit(db_init_transaction)?;
 
+		if let (Some(cache), Some(persistence)) =
+			(&backend.shared_trie_cache, &backend.trie_cache_persistence)
+		{
+			if let Some(storage_root) = backend.best_state_root() {
+				trie_cache_snapshot::load(cache, &persistence.path, storage_root);
+			}
+		}
+
 		Ok(backend)
 	}
 
+	/// Returns the state root of the best block, if it is known.
+	fn best_state_root(&self) -> Option<Block::Hash> {
+		let best_hash = self.blockchain.info().best_hash;
+		self.blockchain
+			.header(best_hash)
+			.ok()
+			.flatten()
+			.map(|header| *header.state_root())
+	}
+
 	/// Handle setting head within a transaction. `route_to` should be the last
 	/// block that existed in the database. `best_to` should be the best block
 	/// to be set.
@@ -2025,6 +2060,26 @@ where
 	}
 }
 
+impl<Block: BlockT> Drop for Backend<Block> {
+	fn drop(&mut self) {
+		let (Some(cache), Some(persistence)) =
+			(&self.shared_trie_cache, &self.trie_cache_persistence)
+		else {
+			return
+		};
+
+		let Some(storage_root) = self.best_state_root() else { return };
+		if let Err(e) = trie_cache_snapshot::store(
+			cache,
+			&persistence.path,
+			storage_root,
+			persistence.maximum_size,
+		) {
+			warn!("Failed to write trie cache snapshot to {}: {}", persistence.path.display(), e);
+		}
+	}
+}
+
 impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 	type BlockImportOperation = BlockImportOperation<Block>;
 	type Blockchain = BlockchainDb<Block>;
@@ -2664,6 +2719,7 @@ pub(crate) mod tests {
 		let backend = Backend::<Block>::new(
 			DatabaseSettings {
 				trie_cache_maximum_size: Some(16 * 1024 * 1024),
+				trie_cache_persistence: None,
 				state_pruning: Some(PruningMode::blocks_pruning(1)),
 				source: DatabaseSource::Custom { db: backing, require_create_flag: false },
 				blocks_pruning: BlocksPruning::KeepFinalized,
@@ -111,6 +111,38 @@ impl sp_core::offchain::OffchainStorage for LocalStorage {
 		}
 		is_set
 	}
+
+	fn iter_prefix(
+		&self,
+		prefix: &[u8],
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		count: usize,
+	) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+		let mut entries = Vec::new();
+		if count == 0 {
+			return Some(entries)
+		}
+
+		let start_key = start_key.map(|start| concatenate_prefix_and_key(prefix, start));
+		let result = self.db.iter_prefix(
+			columns::OFFCHAIN,
+			&concatenate_prefix_and_key(prefix, key_prefix),
+			start_key.as_deref(),
+			&mut |key, value| {
+				entries.push((key[prefix.len()..].to_vec(), value.to_vec()));
+				entries.len() < count
+			},
+		);
+
+		match result {
+			Ok(()) => Some(entries),
+			Err(err) => {
+				error!("Error iterating local storage: {}", err);
+				None
+			},
+		}
+	}
 }
 
 /// Concatenate the prefix and key to create an offchain key in the db.
@@ -148,4 +180,67 @@ mod tests {
 		assert_eq!(storage.get(prefix, key), Some(b"asd".to_vec()));
 		assert!(storage.locks.lock().is_empty(), "Locks map should be empty!");
 	}
+
+	#[test]
+	fn should_iterate_keys_with_prefix_and_pagination() {
+		let mut storage = LocalStorage::new_test();
+		let prefix = b"prefix";
+
+		storage.set(prefix, b"index/2", b"two");
+		storage.set(prefix, b"index/1", b"one");
+		storage.set(prefix, b"index/3", b"three");
+		storage.set(prefix, b"other", b"other");
+		storage.set(b"elsewhere", b"index/0", b"zero");
+
+		assert_eq!(
+			storage.iter_prefix(prefix, b"index/", None, 2),
+			Some(vec![
+				(b"index/1".to_vec(), b"one".to_vec()),
+				(b"index/2".to_vec(), b"two".to_vec())
+			]),
+		);
+		assert_eq!(
+			storage.iter_prefix(prefix, b"index/", Some(&b"index/2"[..]), 2),
+			Some(vec![(b"index/3".to_vec(), b"three".to_vec())]),
+		);
+		assert_eq!(storage.iter_prefix(prefix, b"index/", None, 0), Some(vec![]));
+	}
+
+	#[test]
+	fn should_seek_to_start_key() {
+		let mut storage = LocalStorage::new_test();
+		let prefix = b"prefix";
+		let keys: Vec<&[u8]> = vec![
+			&b"index/1"[..],
+			&b"index/1/a"[..],
+			&b"index/1\xff"[..],
+			&b"index/1\xff\xff"[..],
+			&b"index/2"[..],
+			&b"index\xff"[..],
+		];
+		for key in &keys {
+			storage.set(prefix, key, key);
+		}
+		let keys_after = |start_key: &[u8]| {
+			storage
+				.iter_prefix(prefix, b"index/", Some(start_key), 10)
+				.unwrap()
+				.into_iter()
+				.map(|(key, _)| key)
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(keys_after(b"index/1"), vec![keys[1], keys[2], keys[3], keys[4]]);
+		assert_eq!(keys_after(b"index/1/"), vec![keys[1], keys[2], keys[3], keys[4]]);
+		assert_eq!(keys_after(b"index/1/a"), vec![keys[2], keys[3], keys[4]]);
+		assert_eq!(keys_after(b"index/1\xff"), vec![keys[3], keys[4]]);
+		assert_eq!(keys_after(b"index/1\xff\xff"), vec![keys[4]]);
+		assert_eq!(keys_after(b"index/2"), Vec::<&[u8]>::new());
+		assert_eq!(keys_after(b"index"), keys_after(b"index/0"));
+		assert_eq!(keys_after(b"other"), Vec::<&[u8]>::new());
+		assert_eq!(
+			storage.iter_prefix(prefix, b"index/", Some(&b"index/1"[..]), 1).unwrap().len(),
+			1
+		);
+	}
 }
@@ -160,4 +160,40 @@ impl<H: Clone + AsRef<[u8]>> Database<H> for DbAdapter {
 	fn sanitize_key(&self, key: &mut Vec<u8>) {
 		let _prefix = key.drain(0..key.len() - crate::DB_HASH_LEN);
 	}
+
+	// parity-db can only iterate over btree indexed columns. None of the columns opened above are,
+	// and changing that would make existing databases fail to open, so this notably doesn't
+	// support iterating the offchain local storage.
+	fn iter_prefix(
+		&self,
+		col: ColumnId,
+		prefix: &[u8],
+		start_key: Option<&[u8]>,
+		f: &mut dyn FnMut(&[u8], &[u8]) -> bool,
+	) -> Result<(), DatabaseError> {
+		let mut iter = match self.0.iter(col as u8) {
+			Ok(iter) => iter,
+			Err(parity_db::Error::InvalidConfiguration(_)) =>
+				return Err(DatabaseError(Box::new(std::io::Error::new(
+					std::io::ErrorKind::Unsupported,
+					format!("parity-db can't iterate column {} as it isn't btree indexed", col),
+				)))),
+			Err(e) => return Err(DatabaseError(Box::new(e))),
+		};
+
+		iter.seek(start_key.filter(|start| *start > prefix).unwrap_or(prefix))
+			.map_err(|e| DatabaseError(Box::new(e)))?;
+		while let Some((key, value)) = iter.next().map_err(|e| DatabaseError(Box::new(e)))? {
+			if !key.starts_with(prefix) {
+				break
+			}
+			if start_key.map_or(false, |start| key.as_slice() <= start) {
+				continue
+			}
+			if !f(&key, &value) {
+				break
+			}
+		}
+		Ok(())
+	}
 }
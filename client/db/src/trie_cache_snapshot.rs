@@ -0,0 +1,198 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Persisting the shared trie cache across restarts.
+//!
+//! On shutdown the hottest entries of the [`SharedTrieCache`] are written to disk, together with
+//! the state root of the best block. On startup they are loaded back, discarding the cached values
+//! if the best block changed in the meantime.
+
+use codec::{Decode, Encode};
+use hash_db::Hasher;
+use sp_trie::cache::{SharedTrieCache, SharedTrieCacheSnapshot};
+use std::{fs, io, path::Path};
+
+const LOG_TARGET: &str = "db::trie-cache";
+
+/// The version of the on-disk format, bumped whenever it changes.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// The file written to disk.
+#[derive(Encode, Decode)]
+struct SnapshotFile {
+	version: u32,
+	/// The `blake2_256` hash of `snapshot`.
+	checksum: [u8; 32],
+	/// The encoded [`SharedTrieCacheSnapshot`].
+	snapshot: Vec<u8>,
+}
+
+/// Write a snapshot of `cache`, taken at `storage_root`, to `path`.
+///
+/// The snapshot is first written to a temporary file, which then replaces `path`, so an interrupted
+/// write never leaves a truncated snapshot behind.
+pub(crate) fn store<H: Hasher>(
+	cache: &SharedTrieCache<H>,
+	path: &Path,
+	storage_root: H::Out,
+	maximum_size: usize,
+) -> io::Result<()>
+where
+	H::Out: Encode,
+{
+	let snapshot = cache.snapshot(storage_root, maximum_size).encode();
+	let file = SnapshotFile {
+		version: SNAPSHOT_VERSION,
+		checksum: sp_core::blake2_256(&snapshot),
+		snapshot,
+	};
+
+	let tmp_path = path.with_extension("tmp");
+	fs::write(&tmp_path, file.encode())?;
+	fs::rename(&tmp_path, path)?;
+
+	log::info!(
+		target: LOG_TARGET,
+		"💾 Wrote trie cache snapshot ({} bytes) to {}",
+		file.snapshot.len(),
+		path.display(),
+	);
+
+	Ok(())
+}
+
+/// Load the snapshot stored at `path` into `cache`.
+///
+/// `storage_root` is the state root of the current best block. A missing, outdated or corrupted
+/// snapshot is ignored and the cache just starts cold.
+pub(crate) fn load<H: Hasher>(cache: &SharedTrieCache<H>, path: &Path, storage_root: H::Out)
+where
+	H::Out: Decode,
+{
+	let data = match fs::read(path) {
+		Ok(data) => data,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+		Err(e) => {
+			log::warn!(target: LOG_TARGET, "Failed to read trie cache snapshot: {}", e);
+			return
+		},
+	};
+
+	let snapshot = match SnapshotFile::decode(&mut &data[..]) {
+		Ok(file) if file.version != SNAPSHOT_VERSION => {
+			log::debug!(
+				target: LOG_TARGET,
+				"Ignoring trie cache snapshot with unsupported version {}",
+				file.version,
+			);
+			return
+		},
+		Ok(file) if file.checksum != sp_core::blake2_256(&file.snapshot) => {
+			log::warn!(target: LOG_TARGET, "Ignoring corrupted trie cache snapshot");
+			return
+		},
+		Ok(file) => SharedTrieCacheSnapshot::<H::Out>::decode(&mut &file.snapshot[..]),
+		Err(e) => Err(e),
+	};
+
+	match snapshot {
+		Ok(snapshot) => {
+			let (nodes, values) = cache.restore(snapshot, storage_root);
+			log::info!(
+				target: LOG_TARGET,
+				"💾 Pre-warmed trie cache with {} nodes and {} values",
+				nodes,
+				values,
+			);
+		},
+		Err(e) => log::warn!(target: LOG_TARGET, "Failed to decode trie cache snapshot: {}", e),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{Blake2Hasher, H256};
+	use sp_trie::{
+		cache::CacheSize, LayoutV1, MemoryDB, Trie, TrieDBBuilder, TrieDBMutBuilder, TrieMut,
+	};
+
+	type Layout = LayoutV1<Blake2Hasher>;
+
+	fn populated_cache() -> (SharedTrieCache<Blake2Hasher>, H256) {
+		let mut db = MemoryDB::<Blake2Hasher>::default();
+		let mut root = Default::default();
+		{
+			let mut trie = TrieDBMutBuilder::<Layout>::new(&mut db, &mut root).build();
+			for i in 0u8..16 {
+				trie.insert(&[i], &[i; 64]).unwrap();
+			}
+		}
+
+		let cache = SharedTrieCache::new(CacheSize::new(1024 * 1024));
+		{
+			let local_cache = cache.local_cache();
+			let mut trie_cache = local_cache.as_trie_db_cache(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root).with_cache(&mut trie_cache).build();
+			for i in 0u8..16 {
+				trie.get(&[i]).unwrap().unwrap();
+			}
+		}
+
+		(cache, root)
+	}
+
+	#[test]
+	fn store_and_load_work() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("trie_cache");
+		let (cache, root) = populated_cache();
+
+		store(&cache, &path, root, usize::MAX).unwrap();
+
+		let restored = SharedTrieCache::<Blake2Hasher>::new(CacheSize::new(1024 * 1024));
+		load(&restored, &path, root);
+		assert_eq!(
+			cache.snapshot(root, usize::MAX).encode(),
+			restored.snapshot(root, usize::MAX).encode(),
+		);
+	}
+
+	#[test]
+	fn missing_or_corrupted_snapshot_is_ignored() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("trie_cache");
+		let (cache, root) = populated_cache();
+
+		let restored = SharedTrieCache::<Blake2Hasher>::new(CacheSize::new(1024 * 1024));
+		load(&restored, &path, root);
+		assert!(restored.snapshot(root, usize::MAX).is_empty());
+
+		fs::write(&path, b"garbage").unwrap();
+		load(&restored, &path, root);
+		assert!(restored.snapshot(root, usize::MAX).is_empty());
+
+		store(&cache, &path, root, usize::MAX).unwrap();
+		let mut data = fs::read(&path).unwrap();
+		*data.last_mut().unwrap() ^= 1;
+		fs::write(&path, data).unwrap();
+		load(&restored, &path, root);
+		assert!(restored.snapshot(root, usize::MAX).is_empty());
+	}
+}
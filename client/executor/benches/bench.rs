@@ -67,6 +67,8 @@ fn initialize(
 					wasm_bulk_memory: false,
 					wasm_reference_types: false,
 					wasm_simd: false,
+					fuel_limit: None,
+					profiling_interval: None,
 				},
 			};
 
@@ -98,6 +98,9 @@ pub enum Error {
 	#[error("Execution aborted due to trap: {0}")]
 	AbortedDueToTrap(MessageWithBacktrace),
 
+	#[error("Execution aborted due to running out of fuel")]
+	OutOfFuel,
+
 	#[error("Output exceeds bounds of wasm memory")]
 	OutputExceedsBounds,
 }
@@ -23,6 +23,7 @@
 #![deny(unused_crate_dependencies)]
 
 pub mod error;
+pub mod profile;
 pub mod runtime_blob;
 pub mod util;
 pub mod wasm_runtime;
@@ -0,0 +1,95 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Profiles of runtime calls.
+
+use std::{collections::HashMap, io, time::Duration};
+
+/// The frame the time spent inside of host functions is attributed to.
+///
+/// It is placed on top of the wasm call stack which called into the host.
+pub const HOST_FRAME: &str = "<host>";
+
+/// A profile of the call stacks observed while executing runtime calls.
+///
+/// Every distinct call stack, outermost frame first, is mapped to the total time it was observed
+/// for.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Profile {
+	stacks: HashMap<Vec<String>, Duration>,
+}
+
+impl Profile {
+	/// Attributes `duration` to the given call `stack`.
+	pub fn add_sample(&mut self, stack: Vec<String>, duration: Duration) {
+		*self.stacks.entry(stack).or_default() += duration;
+	}
+
+	/// Merges `other` into this profile.
+	pub fn merge(&mut self, other: Profile) {
+		for (stack, duration) in other.stacks {
+			self.add_sample(stack, duration);
+		}
+	}
+
+	/// Returns `true` if no samples were recorded.
+	pub fn is_empty(&self) -> bool {
+		self.stacks.is_empty()
+	}
+
+	/// Returns the total time attributed to all call stacks.
+	pub fn total(&self) -> Duration {
+		self.stacks.values().sum()
+	}
+
+	/// Returns the time attributed to all call stacks which contain a frame named `frame`.
+	pub fn time_in(&self, frame: &str) -> Duration {
+		self.stacks
+			.iter()
+			.filter(|(stack, _)| stack.iter().any(|f| f == frame))
+			.map(|(_, duration)| *duration)
+			.sum()
+	}
+
+	/// Returns the time spent inside of host functions.
+	pub fn host_time(&self) -> Duration {
+		self.time_in(HOST_FRAME)
+	}
+
+	/// Writes the profile in the folded stacks format.
+	///
+	/// Every line consists of the frames of a call stack separated by `;`, followed by the
+	/// number of microseconds attributed to it. This is the input format of `flamegraph.pl`
+	/// and `inferno-flamegraph`.
+	pub fn write_folded(&self, mut out: impl io::Write) -> io::Result<()> {
+		let mut stacks = self.stacks.iter().collect::<Vec<_>>();
+		stacks.sort();
+
+		for (stack, duration) in stacks {
+			let micros = duration.as_micros();
+			if micros == 0 {
+				continue
+			}
+
+			writeln!(out, "{} {}", stack.join(";"), micros)?;
+		}
+
+		Ok(())
+	}
+}
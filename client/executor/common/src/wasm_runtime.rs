@@ -19,7 +19,7 @@
 
 //! Definitions for a wasm runtime.
 
-use crate::error::Error;
+use crate::{error::Error, profile::Profile};
 use sp_wasm_interface::Value;
 
 pub use sc_allocator::AllocationStats;
@@ -117,6 +117,20 @@ pub trait WasmInstance: Send {
 	/// This method is only suitable for getting immutable globals.
 	fn get_global_const(&mut self, name: &str) -> Result<Option<Value>, Error>;
 
+	/// Returns the fuel consumed by the last call into this instance.
+	///
+	/// This is `None` if fuel metering isn't supported or wasn't enabled.
+	fn consumed_fuel(&self) -> Option<u64> {
+		None
+	}
+
+	/// Takes the profile of the calls made into this instance since it was last taken.
+	///
+	/// This is `None` if profiling isn't supported or wasn't enabled.
+	fn take_profile(&mut self) -> Option<Profile> {
+		None
+	}
+
 	/// **Testing Only**. This function returns the base address of the linear memory.
 	///
 	/// This is meant to be the starting address of the memory mapped area for the linear memory.
@@ -27,11 +27,17 @@ use std::{
 	marker::PhantomData,
 	panic::{AssertUnwindSafe, UnwindSafe},
 	path::PathBuf,
-	sync::Arc,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
 };
 
 use codec::Encode;
+use parking_lot::Mutex;
 use sc_executor_common::{
+	profile::Profile,
 	runtime_blob::RuntimeBlob,
 	wasm_runtime::{
 		AllocationStats, HeapAllocStrategy, WasmInstance, WasmModule, DEFAULT_HEAP_ALLOC_STRATEGY,
@@ -79,6 +85,11 @@ pub trait NativeExecutionDispatch: Send + Sync {
 	fn native_version() -> NativeVersion;
 }
 
+/// The default interval at which runtime calls are sampled while profiling.
+///
+/// See [`WasmExecutorBuilder::with_profiling`].
+pub const DEFAULT_PROFILING_INTERVAL: Duration = Duration::from_micros(100);
+
 fn unwrap_heap_pages(pages: Option<HeapAllocStrategy>) -> HeapAllocStrategy {
 	pages.unwrap_or_else(|| DEFAULT_HEAP_ALLOC_STRATEGY)
 }
@@ -93,6 +104,8 @@ pub struct WasmExecutorBuilder<H> {
 	cache_path: Option<PathBuf>,
 	allow_missing_host_functions: bool,
 	runtime_cache_size: u8,
+	fuel_limit: Option<u64>,
+	profiling_interval: Option<Duration>,
 }
 
 impl<H> WasmExecutorBuilder<H> {
@@ -109,6 +122,8 @@ impl<H> WasmExecutorBuilder<H> {
 			runtime_cache_size: 4,
 			allow_missing_host_functions: false,
 			cache_path: None,
+			fuel_limit: None,
+			profiling_interval: None,
 		}
 	}
 
@@ -184,6 +199,32 @@ impl<H> WasmExecutorBuilder<H> {
 		self
 	}
 
+	/// Create the wasm executor with deterministic fuel metering enabled.
+	///
+	/// Every call into the runtime starts with `fuel_limit` units of fuel, which are consumed by
+	/// the executed wasm instructions. A call running out of fuel is aborted with
+	/// [`Error::OutOfFuel`](sc_executor_common::error::Error::OutOfFuel). The fuel consumed so far
+	/// can be retrieved using [`WasmExecutor::take_consumed_fuel`].
+	///
+	/// Fuel metering is only supported by the compiled execution method. By default it is disabled.
+	pub fn with_fuel_limit(mut self, fuel_limit: u64) -> Self {
+		self.fuel_limit = Some(fuel_limit);
+		self
+	}
+
+	/// Create the wasm executor with the sampling profiler enabled.
+	///
+	/// The wasm call stack of every call into the runtime is sampled at the given `interval`,
+	/// while the time spent in host functions is measured separately. The collected profile can
+	/// be retrieved using [`WasmExecutor::take_profile`]. Profiling slows down the execution
+	/// considerably, so it must not be enabled on nodes importing blocks.
+	///
+	/// Profiling is only supported by the compiled execution method. By default it is disabled.
+	pub fn with_profiling(mut self, interval: Duration) -> Self {
+		self.profiling_interval = Some(interval);
+		self
+	}
+
 	/// Build the configured [`WasmExecutor`].
 	pub fn build(self) -> WasmExecutor<H> {
 		WasmExecutor {
@@ -198,9 +239,15 @@ impl<H> WasmExecutorBuilder<H> {
 				self.max_runtime_instances,
 				self.cache_path.clone(),
 				self.runtime_cache_size,
+				self.fuel_limit,
+				self.profiling_interval,
 			)),
 			cache_path: self.cache_path,
 			allow_missing_host_functions: self.allow_missing_host_functions,
+			fuel_limit: self.fuel_limit,
+			consumed_fuel: Default::default(),
+			profiling_interval: self.profiling_interval,
+			profile: Default::default(),
 			phantom: PhantomData,
 		}
 	}
@@ -222,6 +269,14 @@ pub struct WasmExecutor<H> {
 	cache_path: Option<PathBuf>,
 	/// Ignore missing function imports.
 	allow_missing_host_functions: bool,
+	/// The fuel every call starts with, if fuel metering is enabled.
+	fuel_limit: Option<u64>,
+	/// The fuel consumed by all calls since it was last taken.
+	consumed_fuel: Arc<AtomicU64>,
+	/// The interval at which calls are sampled, if profiling is enabled.
+	profiling_interval: Option<Duration>,
+	/// The profile of all calls since it was last taken.
+	profile: Arc<Mutex<Profile>>,
 	phantom: PhantomData<H>,
 }
 
@@ -234,6 +289,10 @@ impl<H> Clone for WasmExecutor<H> {
 			cache: self.cache.clone(),
 			cache_path: self.cache_path.clone(),
 			allow_missing_host_functions: self.allow_missing_host_functions,
+			fuel_limit: self.fuel_limit,
+			consumed_fuel: self.consumed_fuel.clone(),
+			profiling_interval: self.profiling_interval,
+			profile: self.profile.clone(),
 			phantom: self.phantom,
 		}
 	}
@@ -281,9 +340,15 @@ where
 				max_runtime_instances,
 				cache_path.clone(),
 				runtime_cache_size,
+				None,
+				None,
 			)),
 			cache_path,
 			allow_missing_host_functions: false,
+			fuel_limit: None,
+			consumed_fuel: Default::default(),
+			profiling_interval: None,
+			profile: Default::default(),
 			phantom: PhantomData,
 		}
 	}
@@ -299,6 +364,33 @@ where
 		self.allow_missing_host_functions = allow_missing_host_functions
 	}
 
+	/// Returns the fuel consumed by all calls since the last time this was called and resets the
+	/// counter.
+	///
+	/// Returns `None` if fuel metering wasn't enabled using
+	/// [`WasmExecutorBuilder::with_fuel_limit`].
+	pub fn take_consumed_fuel(&self) -> Option<u64> {
+		self.fuel_limit.map(|_| self.consumed_fuel.swap(0, Ordering::Relaxed))
+	}
+
+	/// Returns the profile of all calls since the last time this was called and resets it.
+	///
+	/// Returns `None` if profiling wasn't enabled using [`WasmExecutorBuilder::with_profiling`].
+	pub fn take_profile(&self) -> Option<Profile> {
+		self.profiling_interval.map(|_| std::mem::take(&mut *self.profile.lock()))
+	}
+
+	/// Adds the fuel consumed and the profile collected by the last call into `instance` to the
+	/// totals of the executor.
+	fn record_call_metering(&self, instance: &mut dyn WasmInstance) {
+		if let Some(consumed_fuel) = instance.consumed_fuel() {
+			self.consumed_fuel.fetch_add(consumed_fuel, Ordering::Relaxed);
+		}
+		if let Some(profile) = instance.take_profile() {
+			self.profile.lock().merge(profile);
+		}
+	}
+
 	/// Execute the given closure `f` with the latest runtime (based on `runtime_code`).
 	///
 	/// The closure `f` is expected to return `Err(_)` when there happened a `panic!` in native code
@@ -408,6 +500,8 @@ where
 			runtime_blob,
 			allow_missing_host_functions,
 			self.cache_path.as_deref(),
+			self.fuel_limit,
+			self.profiling_interval,
 		)
 		.map_err(|e| format!("Failed to create module: {}", e))?;
 
@@ -502,7 +596,12 @@ where
 			ext,
 			heap_alloc_strategy,
 			|_, mut instance, _onchain_version, mut ext| {
-				with_externalities_safe(&mut **ext, move || instance.call_export(method, data))
+				let mut call_instance = AssertUnwindSafe(&mut **instance);
+				let result = with_externalities_safe(&mut **ext, move || {
+					call_instance.call_export(method, data)
+				});
+				self.record_call_metering(&mut **instance);
+				result
 			},
 		);
 
@@ -591,6 +690,16 @@ impl<D: NativeExecutionDispatch> NativeElseWasmExecutor<D> {
 		Self { native_version: D::native_version(), wasm: executor }
 	}
 
+	/// Returns the fuel consumed by the wasm executor, see [`WasmExecutor::take_consumed_fuel`].
+	pub fn take_consumed_fuel(&self) -> Option<u64> {
+		self.wasm.take_consumed_fuel()
+	}
+
+	/// Returns the profile collected by the wasm executor, see [`WasmExecutor::take_profile`].
+	pub fn take_profile(&self) -> Option<Profile> {
+		self.wasm.take_profile()
+	}
+
 	/// Ignore missing function imports if set true.
 	#[deprecated(note = "use `Self::new_with_wasm_executor` method instead of it")]
 	pub fn allow_missing_host_functions(&mut self, allow_missing_host_functions: bool) {
@@ -675,7 +784,12 @@ impl<D: NativeExecutionDispatch + 'static> CodeExecutor for NativeElseWasmExecut
 						);
 					}
 
-					with_externalities_safe(&mut **ext, move || instance.call_export(method, data))
+					let mut call_instance = AssertUnwindSafe(&mut **instance);
+					let result = with_externalities_safe(&mut **ext, move || {
+						call_instance.call_export(method, data)
+					});
+					self.wasm.record_call_metering(&mut **instance);
+					result
 				}
 			},
 		);
@@ -455,6 +455,8 @@ fn mk_test_runtime(
 		blob,
 		true,
 		None,
+		None,
+		None,
 	)
 	.expect("failed to instantiate wasm runtime")
 }
@@ -681,6 +683,8 @@ fn memory_is_cleared_between_invocations(wasm_method: WasmExecutionMethod) {
 		RuntimeBlob::uncompress_if_needed(&binary[..]).unwrap(),
 		true,
 		None,
+		None,
+		None,
 	)
 	.unwrap();
 
@@ -41,6 +41,7 @@ mod wasm_runtime;
 pub use self::{
 	executor::{
 		with_externalities_safe, NativeElseWasmExecutor, NativeExecutionDispatch, WasmExecutor,
+		WasmExecutorBuilder, DEFAULT_PROFILING_INTERVAL,
 	},
 	wasm_runtime::{read_embedded_version, WasmExecutionMethod},
 };
@@ -53,6 +54,7 @@ pub use sp_wasm_interface;
 
 pub use sc_executor_common::{
 	error,
+	profile::Profile,
 	wasm_runtime::{HeapAllocStrategy, DEFAULT_HEAP_ALLOC_PAGES, DEFAULT_HEAP_ALLOC_STRATEGY},
 };
 pub use sc_executor_wasmtime::InstantiationStrategy as WasmtimeInstantiationStrategy;
@@ -39,6 +39,7 @@ use std::{
 	panic::AssertUnwindSafe,
 	path::{Path, PathBuf},
 	sync::Arc,
+	time::Duration,
 };
 
 /// Specification of different methods of executing the runtime Wasm code.
@@ -168,6 +169,10 @@ pub struct RuntimeCache {
 	/// The size of the instances cache for each runtime.
 	max_runtime_instances: usize,
 	cache_path: Option<PathBuf>,
+	/// The fuel every call into the created runtimes starts with, if fuel metering is enabled.
+	fuel_limit: Option<u64>,
+	/// The interval at which the created runtimes are sampled, if profiling is enabled.
+	profiling_interval: Option<Duration>,
 }
 
 impl RuntimeCache {
@@ -181,13 +186,25 @@ impl RuntimeCache {
 	///
 	/// `runtime_cache_size` specifies the number of different runtimes versions preserved in an
 	/// in-memory cache, must always be at least 1.
+	///
+	/// `fuel_limit` enables deterministic fuel metering for all runtimes created by the cache.
+	///
+	/// `profiling_interval` enables the sampling profiler for all runtimes created by the cache.
 	pub fn new(
 		max_runtime_instances: usize,
 		cache_path: Option<PathBuf>,
 		runtime_cache_size: u8,
+		fuel_limit: Option<u64>,
+		profiling_interval: Option<Duration>,
 	) -> RuntimeCache {
 		let cap = ByLength::new(runtime_cache_size.max(1) as u32);
-		RuntimeCache { runtimes: Mutex::new(LruMap::new(cap)), max_runtime_instances, cache_path }
+		RuntimeCache {
+			runtimes: Mutex::new(LruMap::new(cap)),
+			max_runtime_instances,
+			cache_path,
+			fuel_limit,
+			profiling_interval,
+		}
 	}
 
 	/// Prepares a WASM module instance and executes given function for it.
@@ -256,6 +273,8 @@ impl RuntimeCache {
 				allow_missing_func_imports,
 				self.max_runtime_instances,
 				self.cache_path.as_deref(),
+				self.fuel_limit,
+				self.profiling_interval,
 			);
 
 			match result {
@@ -294,6 +313,8 @@ pub fn create_wasm_runtime_with_code<H>(
 	blob: RuntimeBlob,
 	allow_missing_func_imports: bool,
 	cache_path: Option<&Path>,
+	fuel_limit: Option<u64>,
+	profiling_interval: Option<Duration>,
 ) -> Result<Box<dyn WasmModule>, WasmError>
 where
 	H: HostFunctions,
@@ -315,6 +336,8 @@ where
 						wasm_bulk_memory: false,
 						wasm_reference_types: false,
 						wasm_simd: false,
+						fuel_limit,
+						profiling_interval,
 					},
 				},
 			)
@@ -386,6 +409,8 @@ fn create_versioned_wasm_runtime<H>(
 	allow_missing_func_imports: bool,
 	max_instances: usize,
 	cache_path: Option<&Path>,
+	fuel_limit: Option<u64>,
+	profiling_interval: Option<Duration>,
 ) -> Result<VersionedRuntime, WasmError>
 where
 	H: HostFunctions,
@@ -405,6 +430,8 @@ where
 		blob,
 		allow_missing_func_imports,
 		cache_path,
+		fuel_limit,
+		profiling_interval,
 	)?;
 
 	// If the runtime blob doesn't embed the runtime version then use the legacy version query
@@ -17,7 +17,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{host::HostContext, runtime::StoreData};
+use crate::{host::HostContext, profiler::capture_stack, runtime::StoreData};
 use sc_executor_common::error::WasmError;
 use sp_wasm_interface::{FunctionContext, HostFunctions};
 use std::collections::HashMap;
@@ -99,10 +99,28 @@ impl<'a, 'b> sp_wasm_interface::HostFunctionRegistry for Registry<'a, 'b> {
 	type FunctionContext = HostContext<'a>;
 
 	fn with_function_context<R>(
-		caller: wasmtime::Caller<Self::State>,
+		mut caller: wasmtime::Caller<Self::State>,
 		callback: impl FnOnce(&mut dyn FunctionContext) -> R,
 	) -> R {
-		callback(&mut HostContext { caller })
+		if caller.data().sampler.is_none() {
+			return callback(&mut HostContext { caller })
+		}
+
+		// While profiling, the time spent in wasm up to this point is attributed to the calling
+		// stack, while the time spent in the host function is attributed to it separately.
+		let stack = capture_stack(&caller);
+		if let Some(sampler) = caller.data_mut().sampler.as_mut() {
+			sampler.sample(stack.clone());
+		}
+
+		let mut context = HostContext { caller };
+		let result = callback(&mut context);
+
+		if let Some(sampler) = context.caller.data_mut().sampler.as_mut() {
+			sampler.sample_host_call(stack);
+		}
+
+		result
 	}
 
 	fn register_static<Params, Results>(
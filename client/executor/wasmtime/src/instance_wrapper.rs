@@ -20,7 +20,10 @@
 //! Defines data and logic needed for interaction with an WebAssembly instance of a substrate
 //! runtime module.
 
-use crate::runtime::{Store, StoreData};
+use crate::{
+	profiler::{capture_stack, Sampler},
+	runtime::{Store, StoreData},
+};
 use sc_executor_common::{
 	error::{Backtrace, Error, MessageWithBacktrace, Result, WasmError},
 	wasm_runtime::InvokeMethod,
@@ -84,6 +87,8 @@ impl EntryPoint {
 
 			if let Some(message) = host_state.take_panic_message() {
 				Error::AbortedDueToPanic(MessageWithBacktrace { message, backtrace })
+			} else if trap.downcast_ref::<wasmtime::Trap>() == Some(&wasmtime::Trap::OutOfFuel) {
+				Error::OutOfFuel
 			} else {
 				let message = trap.root_cause().to_string();
 				Error::AbortedDueToTrap(MessageWithBacktrace { message, backtrace })
@@ -382,6 +387,40 @@ impl InstanceWrapper {
 		self.memory.data_mut(self.store.as_context_mut()).fill(0);
 	}
 
+	/// Sets the fuel available to the next call to exactly `fuel`.
+	///
+	/// Fuel metering must be enabled in the engine's config.
+	pub(crate) fn set_fuel(&mut self, fuel: u64) -> Result<()> {
+		let remaining = self.remaining_fuel();
+		if remaining < fuel {
+			self.store.add_fuel(fuel - remaining)
+		} else {
+			self.store.consume_fuel(remaining - fuel).map(|_| ())
+		}
+		.map_err(|e| Error::Other(format!("failed to set the fuel: {:#}", e)))
+	}
+
+	/// Starts sampling the wasm call stack every time the epoch of the engine is incremented.
+	///
+	/// Epoch interruption must be enabled in the engine's config.
+	pub(crate) fn start_sampling(&mut self) {
+		self.store.data_mut().sampler = Some(Sampler::new());
+		self.store.epoch_deadline_callback(|mut ctx| {
+			let stack = capture_stack(&ctx);
+			if let Some(sampler) = ctx.data_mut().sampler.as_mut() {
+				sampler.sample(stack);
+			}
+			Ok(wasmtime::UpdateDeadline::Continue(1))
+		});
+		self.store.set_epoch_deadline(1);
+	}
+
+	/// Returns the fuel left in the store, or zero if fuel metering is disabled.
+	pub(crate) fn remaining_fuel(&mut self) -> u64 {
+		// Consuming no fuel is the only way to query the remaining fuel.
+		self.store.consume_fuel(0).unwrap_or(0)
+	}
+
 	pub(crate) fn store(&self) -> &Store {
 		&self.store
 	}
@@ -31,6 +31,7 @@
 mod host;
 mod imports;
 mod instance_wrapper;
+mod profiler;
 mod runtime;
 mod util;
 
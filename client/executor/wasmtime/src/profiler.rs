@@ -0,0 +1,114 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Sampling profiler for runtime calls.
+//!
+//! While profiling is enabled, a background thread increments the epoch of the engine at a fixed
+//! interval. Every time wasm code observes a new epoch, the current wasm call stack is sampled and
+//! the time since the previous sample is attributed to it. Calls into host functions are timed
+//! precisely instead and attributed to the calling wasm stack topped by a
+//! [`HOST_FRAME`](sc_executor_common::profile::HOST_FRAME).
+
+use sc_executor_common::profile::{Profile, HOST_FRAME};
+use std::{
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	thread::JoinHandle,
+	time::{Duration, Instant},
+};
+use wasmtime::{AsContext, Engine, FrameInfo, WasmBacktrace};
+
+/// Collects the samples of a single runtime call.
+pub(crate) struct Sampler {
+	profile: Profile,
+	last_sample: Instant,
+}
+
+impl Sampler {
+	pub(crate) fn new() -> Self {
+		Self { profile: Profile::default(), last_sample: Instant::now() }
+	}
+
+	/// Attributes the time passed since the last sample to the given wasm call `stack`.
+	pub(crate) fn sample(&mut self, stack: Vec<String>) {
+		let now = Instant::now();
+		self.profile.add_sample(stack, now - self.last_sample);
+		self.last_sample = now;
+	}
+
+	/// Attributes the time passed since the last sample to a host function called from the given
+	/// wasm call `stack`.
+	pub(crate) fn sample_host_call(&mut self, mut stack: Vec<String>) {
+		stack.push(HOST_FRAME.into());
+		self.sample(stack);
+	}
+
+	pub(crate) fn into_profile(self) -> Profile {
+		self.profile
+	}
+}
+
+/// Captures the current wasm call stack, outermost frame first.
+pub(crate) fn capture_stack(ctx: impl AsContext) -> Vec<String> {
+	WasmBacktrace::capture(ctx).frames().iter().rev().map(frame_name).collect()
+}
+
+fn frame_name(frame: &FrameInfo) -> String {
+	match frame.func_name() {
+		Some(name) => name.to_owned(),
+		None => format!("wasm-function[{}]", frame.func_index()),
+	}
+}
+
+/// Increments the epoch of an engine at a fixed interval until dropped.
+pub(crate) struct EpochTicker {
+	stop: Arc<AtomicBool>,
+	thread: Option<JoinHandle<()>>,
+}
+
+impl EpochTicker {
+	pub(crate) fn start(engine: Engine, interval: Duration) -> Self {
+		let stop = Arc::new(AtomicBool::new(false));
+		let thread = std::thread::Builder::new()
+			.name("wasmtime-profiler".into())
+			.spawn({
+				let stop = stop.clone();
+				move || {
+					while !stop.load(Ordering::Relaxed) {
+						std::thread::sleep(interval);
+						engine.increment_epoch();
+					}
+				}
+			})
+			.expect("spawning a thread only fails if the OS is out of resources; qed");
+
+		Self { stop, thread: Some(thread) }
+	}
+}
+
+impl Drop for EpochTicker {
+	fn drop(&mut self) {
+		self.stop.store(true, Ordering::Relaxed);
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
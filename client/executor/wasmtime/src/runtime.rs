@@ -22,12 +22,14 @@
 use crate::{
 	host::HostState,
 	instance_wrapper::{EntryPoint, InstanceWrapper, MemoryWrapper},
+	profiler::{EpochTicker, Sampler},
 	util::{self, replace_strategy_if_broken},
 };
 
 use sc_allocator::{AllocationStats, FreeingBumpHeapAllocator};
 use sc_executor_common::{
 	error::{Error, Result, WasmError},
+	profile::Profile,
 	runtime_blob::{
 		self, DataSegmentsSnapshot, ExposedMutableGlobalsSet, GlobalsSnapshot, RuntimeBlob,
 	},
@@ -42,6 +44,7 @@ use std::{
 		atomic::{AtomicBool, Ordering},
 		Arc,
 	},
+	time::Duration,
 };
 use wasmtime::{AsContext, Engine, Memory, Table};
 
@@ -53,6 +56,8 @@ pub(crate) struct StoreData {
 	pub(crate) memory: Option<Memory>,
 	/// This will be set only if the runtime actually contains a table.
 	pub(crate) table: Option<Table>,
+	/// This will only be set when we call into the runtime with profiling enabled.
+	pub(crate) sampler: Option<Sampler>,
 }
 
 impl StoreData {
@@ -126,6 +131,9 @@ pub struct WasmtimeRuntime {
 	engine: wasmtime::Engine,
 	instance_pre: Arc<wasmtime::InstancePre<StoreData>>,
 	instantiation_strategy: InternalInstantiationStrategy,
+	fuel_limit: Option<u64>,
+	/// Drives the sampling of the instances while profiling is enabled.
+	epoch_ticker: Option<EpochTicker>,
 }
 
 impl WasmModule for WasmtimeRuntime {
@@ -157,7 +165,12 @@ impl WasmModule for WasmtimeRuntime {
 			}),
 		};
 
-		Ok(Box::new(WasmtimeInstance { strategy }))
+		Ok(Box::new(WasmtimeInstance {
+			strategy,
+			fuel_limit: self.fuel_limit,
+			consumed_fuel: None,
+			profile: self.epoch_ticker.as_ref().map(|_| Profile::default()),
+		}))
 	}
 }
 
@@ -165,6 +178,11 @@ impl WasmModule for WasmtimeRuntime {
 /// to execute the compiled code.
 pub struct WasmtimeInstance {
 	strategy: Strategy,
+	fuel_limit: Option<u64>,
+	/// The fuel consumed by the last call, if fuel metering is enabled.
+	consumed_fuel: Option<u64>,
+	/// The profile of the calls since it was last taken, if profiling is enabled.
+	profile: Option<Profile>,
 }
 
 impl WasmtimeInstance {
@@ -174,6 +192,13 @@ impl WasmtimeInstance {
 		data: &[u8],
 		allocation_stats: &mut Option<AllocationStats>,
 	) -> Result<Vec<u8>> {
+		self.consumed_fuel = None;
+		let metering = CallMetering {
+			fuel_limit: self.fuel_limit,
+			consumed_fuel: &mut self.consumed_fuel,
+			profile: self.profile.as_mut(),
+		};
+
 		match &mut self.strategy {
 			Strategy::LegacyInstanceReuse {
 				ref mut instance_wrapper,
@@ -193,8 +218,14 @@ impl WasmtimeInstance {
 				globals_snapshot.apply(&mut InstanceGlobals { instance: instance_wrapper });
 				let allocator = FreeingBumpHeapAllocator::new(*heap_base);
 
-				let result =
-					perform_call(data, instance_wrapper, entrypoint, allocator, allocation_stats);
+				let result = perform_call(
+					data,
+					instance_wrapper,
+					entrypoint,
+					allocator,
+					allocation_stats,
+					metering,
+				);
 
 				// Signal to the OS that we are done with the linear memory and that it can be
 				// reclaimed.
@@ -208,7 +239,14 @@ impl WasmtimeInstance {
 				let entrypoint = instance_wrapper.resolve_entrypoint(method)?;
 
 				let allocator = FreeingBumpHeapAllocator::new(heap_base);
-				perform_call(data, &mut instance_wrapper, entrypoint, allocator, allocation_stats)
+				perform_call(
+					data,
+					&mut instance_wrapper,
+					entrypoint,
+					allocator,
+					allocation_stats,
+					metering,
+				)
 			},
 		}
 	}
@@ -225,6 +263,14 @@ impl WasmInstance for WasmtimeInstance {
 		(result, allocation_stats)
 	}
 
+	fn consumed_fuel(&self) -> Option<u64> {
+		self.consumed_fuel
+	}
+
+	fn take_profile(&mut self) -> Option<Profile> {
+		self.profile.as_mut().map(std::mem::take)
+	}
+
 	fn get_global_const(&mut self, name: &str) -> Result<Option<Value>> {
 		match &mut self.strategy {
 			Strategy::LegacyInstanceReuse { instance_wrapper, .. } =>
@@ -319,6 +365,11 @@ fn common_config(semantics: &Semantics) -> std::result::Result<wasmtime::Config,
 
 	config.parallel_compilation(semantics.parallel_compilation);
 
+	// Fuel is refilled by us before every call into the runtime, see [`FuelMeter`].
+	config.consume_fuel(semantics.fuel_limit.is_some());
+	// The epoch is only incremented while profiling, see [`EpochTicker`].
+	config.epoch_interruption(semantics.profiling_interval.is_some());
+
 	// Be clear and specific about the extensions we support. If an update brings new features
 	// they should be introduced here as well.
 	config.wasm_reference_types(semantics.wasm_reference_types);
@@ -512,6 +563,23 @@ pub struct Semantics {
 
 	/// Enables WASM Fixed-Width SIMD proposal
 	pub wasm_simd: bool,
+
+	/// Specifying `Some` will enable deterministic fuel metering. That is, every call into the
+	/// runtime starts with exactly this amount of fuel, each executed wasm instruction consumes
+	/// some of it and the execution is aborted with [`Error::OutOfFuel`] once it runs out.
+	///
+	/// Unlike wall-clock timeouts, the point at which the execution is aborted is the same across
+	/// machines. The fuel consumed by the last call is exposed through
+	/// [`WasmInstance::consumed_fuel`].
+	pub fuel_limit: Option<u64>,
+
+	/// Specifying `Some` will enable the sampling profiler. That is, the wasm call stack is
+	/// sampled at the given interval during every call into the runtime, while the time spent in
+	/// host functions is measured separately.
+	///
+	/// Profiling slows down the execution, so it should never be enabled on a node importing
+	/// blocks. The collected profile is exposed through [`WasmInstance::take_profile`].
+	pub profiling_interval: Option<Duration>,
 }
 
 #[derive(Clone)]
@@ -715,7 +783,16 @@ where
 		.instantiate_pre(&module)
 		.map_err(|e| WasmError::Other(format!("cannot preinstantiate module: {:#}", e)))?;
 
-	Ok(WasmtimeRuntime { engine, instance_pre: Arc::new(instance_pre), instantiation_strategy })
+	Ok(WasmtimeRuntime {
+		engine,
+		instance_pre: Arc::new(instance_pre),
+		instantiation_strategy,
+		fuel_limit: config.semantics.fuel_limit,
+		epoch_ticker: config
+			.semantics
+			.profiling_interval
+			.map(|interval| EpochTicker::start(engine.clone(), interval)),
+	})
 }
 
 fn prepare_blob_for_compilation(
@@ -762,13 +839,30 @@ pub fn prepare_runtime_artifact(
 		.map_err(|e| WasmError::Other(format!("cannot precompile module: {:#}", e)))
 }
 
+/// The metering of a single call into the runtime.
+struct CallMetering<'a> {
+	/// The fuel the call starts with, `None` if fuel metering is disabled.
+	fuel_limit: Option<u64>,
+	/// Where the fuel consumed by the call is written to.
+	consumed_fuel: &'a mut Option<u64>,
+	/// The profile the samples of the call are added to, `None` if profiling is disabled.
+	profile: Option<&'a mut Profile>,
+}
+
 fn perform_call(
 	data: &[u8],
 	instance_wrapper: &mut InstanceWrapper,
 	entrypoint: EntryPoint,
 	mut allocator: FreeingBumpHeapAllocator,
 	allocation_stats: &mut Option<AllocationStats>,
+	metering: CallMetering,
 ) -> Result<Vec<u8>> {
+	if let Some(fuel_limit) = metering.fuel_limit {
+		// Instances may be reused, so the fuel left over from a previous call is discarded to
+		// make every call start from the very same amount.
+		instance_wrapper.set_fuel(fuel_limit)?;
+	}
+
 	let (data_ptr, data_len) = inject_input_data(instance_wrapper, &mut allocator, data)?;
 
 	let host_state = HostState::new(allocator);
@@ -776,6 +870,10 @@ fn perform_call(
 	// Set the host state before calling into wasm.
 	instance_wrapper.store_mut().data_mut().host_state = Some(host_state);
 
+	if metering.profile.is_some() {
+		instance_wrapper.start_sampling();
+	}
+
 	let ret = entrypoint
 		.call(instance_wrapper.store_mut(), data_ptr, data_len)
 		.map(unpack_ptr_and_len);
@@ -785,6 +883,15 @@ fn perform_call(
 		"the host state is always set before calling into WASM so it can't be None here; qed",
 	);
 	*allocation_stats = Some(host_state.allocation_stats());
+	*metering.consumed_fuel = metering
+		.fuel_limit
+		.map(|fuel_limit| fuel_limit - instance_wrapper.remaining_fuel());
+
+	if let Some(profile) = metering.profile {
+		if let Some(sampler) = instance_wrapper.store_mut().data_mut().sampler.take() {
+			profile.merge(sampler.into_profile());
+		}
+	}
 
 	let (output_ptr, output_len) = ret?;
 	let output = extract_output_data(instance_wrapper, output_ptr, output_len)?;
@@ -20,10 +20,12 @@
 use codec::{Decode as _, Encode as _};
 use sc_executor_common::{
 	error::Error,
+	profile::Profile,
 	runtime_blob::RuntimeBlob,
 	wasm_runtime::{HeapAllocStrategy, WasmModule, DEFAULT_HEAP_ALLOC_STRATEGY},
 };
 use sc_runtime_test::wasm_binary_unwrap;
+use std::time::Duration;
 
 use crate::InstantiationStrategy;
 
@@ -84,6 +86,8 @@ struct RuntimeBuilder {
 	deterministic_stack: bool,
 	heap_pages: HeapAllocStrategy,
 	precompile_runtime: bool,
+	fuel_limit: Option<u64>,
+	profiling_interval: Option<Duration>,
 	tmpdir: Option<tempfile::TempDir>,
 }
 
@@ -96,6 +100,8 @@ impl RuntimeBuilder {
 			deterministic_stack: false,
 			heap_pages: DEFAULT_HEAP_ALLOC_STRATEGY,
 			precompile_runtime: false,
+			fuel_limit: None,
+			profiling_interval: None,
 			tmpdir: None,
 		}
 	}
@@ -125,6 +131,16 @@ impl RuntimeBuilder {
 		self
 	}
 
+	fn fuel_limit(mut self, fuel_limit: u64) -> Self {
+		self.fuel_limit = Some(fuel_limit);
+		self
+	}
+
+	fn profiling_interval(mut self, interval: Duration) -> Self {
+		self.profiling_interval = Some(interval);
+		self
+	}
+
 	fn build(&mut self) -> impl WasmModule + '_ {
 		let blob = {
 			let wasm: Vec<u8>;
@@ -160,6 +176,8 @@ impl RuntimeBuilder {
 				wasm_bulk_memory: false,
 				wasm_reference_types: false,
 				wasm_simd: false,
+				fuel_limit: self.fuel_limit,
+				profiling_interval: self.profiling_interval,
 			},
 		};
 
@@ -319,6 +337,85 @@ fn test_stack_depth_reaching(instantiation_strategy: InstantiationStrategy) {
 	}
 }
 
+/// A runtime whose `main` spins for as many iterations as given by the `u32` it is called with.
+const COUNTING_LOOP_WAT: &str = r#"
+(module
+  (memory $0 32)
+  (export "memory" (memory $0))
+  (global (export "__heap_base") i32 (i32.const 0))
+
+  (func $main (export "main") (param $ptr i32) (param $len i32) (result i64)
+    (local $i i32)
+    (local.set $i (i32.load (local.get $ptr)))
+    (block $done
+      (loop $loop
+        (br_if $done (i32.eqz (local.get $i)))
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (br $loop)
+      )
+    )
+    (i64.const 0)
+  )
+)
+"#;
+
+test_wasm_execution!(test_fuel_metering_is_deterministic);
+fn test_fuel_metering_is_deterministic(instantiation_strategy: InstantiationStrategy) {
+	let mut builder = RuntimeBuilder::new(instantiation_strategy)
+		.use_wat(COUNTING_LOOP_WAT.to_string())
+		.fuel_limit(1_000_000);
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+
+	let mut consumed_fuel = |iterations: u32| {
+		instance.call_export("main", &iterations.to_le_bytes()).unwrap();
+		instance.consumed_fuel().expect("fuel metering is enabled")
+	};
+
+	let first = consumed_fuel(100);
+	assert!(first > 0);
+	// Calling again with the same input must consume exactly the same amount of fuel, even if
+	// the instance is reused.
+	assert_eq!(consumed_fuel(100), first);
+	assert!(consumed_fuel(200) > first);
+}
+
+test_wasm_execution!(test_running_out_of_fuel_does_trap);
+fn test_running_out_of_fuel_does_trap(instantiation_strategy: InstantiationStrategy) {
+	let mut builder = RuntimeBuilder::new(instantiation_strategy)
+		.use_wat(COUNTING_LOOP_WAT.to_string())
+		.fuel_limit(1_000);
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+
+	match instance.call_export("main", &u32::MAX.to_le_bytes()).unwrap_err() {
+		Error::OutOfFuel => {},
+		error => panic!("unexpected error: {:?}", error),
+	}
+	assert_eq!(instance.consumed_fuel(), Some(1_000));
+
+	// The fuel is refilled for the next call.
+	instance.call_export("main", &10u32.to_le_bytes()).unwrap();
+}
+
+test_wasm_execution!(test_profiling_samples_the_call_stack);
+fn test_profiling_samples_the_call_stack(instantiation_strategy: InstantiationStrategy) {
+	let mut builder = RuntimeBuilder::new(instantiation_strategy)
+		.use_wat(COUNTING_LOOP_WAT.to_string())
+		.profiling_interval(Duration::from_millis(1));
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+
+	instance.call_export("main", &100_000_000u32.to_le_bytes()).unwrap();
+
+	let profile = instance.take_profile().expect("profiling is enabled");
+	assert!(profile.time_in("main") > Duration::ZERO);
+	// The loop doesn't call into the host.
+	assert_eq!(profile.host_time(), Duration::ZERO);
+	// The profile was taken.
+	assert_eq!(instance.take_profile(), Some(Profile::default()));
+}
+
 test_wasm_execution!(test_max_memory_pages_imported_memory_without_precompilation);
 fn test_max_memory_pages_imported_memory_without_precompilation(
 	instantiation_strategy: InstantiationStrategy,
@@ -485,6 +582,8 @@ fn test_instances_without_reuse_are_not_leaked() {
 				wasm_bulk_memory: false,
 				wasm_reference_types: false,
 				wasm_simd: false,
+				fuel_limit: None,
+				profiling_interval: None,
 			},
 		},
 	)
@@ -19,7 +19,7 @@
 
 use crate::{
 	state_machine::{ConsensusGossip, TopicNotification, PERIODIC_MAINTENANCE_INTERVAL},
-	Network, Syncing, Validator,
+	KnownMessagesTracking, Network, Syncing, Validator,
 };
 
 use sc_network::{event::Event, types::ProtocolName, ReputationChange};
@@ -111,6 +111,12 @@ impl<B: BlockT> GossipEngine<B> {
 		}
 	}
 
+	/// Use the given strategy to track the messages known to each peer.
+	pub fn with_known_messages_tracking(mut self, tracking: KnownMessagesTracking) -> Self {
+		self.state_machine = self.state_machine.with_known_messages_tracking(tracking);
+		self
+	}
+
 	pub fn report(&self, who: PeerId, reputation: ReputationChange) {
 		self.network.report_peer(who, reputation);
 	}
@@ -0,0 +1,182 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Tracking of the messages each peer is known to have.
+
+use ahash::{AHashSet, RandomState};
+use std::hash::Hash;
+
+/// Number of filter bits reserved per message. Together with [`BLOOM_HASHES`], this gives a false
+/// positive rate below 1% for a filter holding its full capacity.
+const BLOOM_BITS_PER_MESSAGE: usize = 10;
+
+/// Number of bit positions set per message.
+const BLOOM_HASHES: u32 = 7;
+
+/// How the gossip state machine remembers which messages were already exchanged with a peer.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum KnownMessagesTracking {
+	/// Keep the exact hash of every message per peer. Hashes are dropped once the message falls
+	/// out of the cache of recently seen messages, after which it may be sent again.
+	#[default]
+	Exact,
+	/// Keep a pair of rotating bloom filters per peer, each sized for `capacity` messages.
+	///
+	/// Memory use per peer is fixed at roughly `capacity * 2.5` bytes regardless of the size of
+	/// the hashes, and messages are remembered for between one and two filter generations. A
+	/// false positive means a message is not sent to a peer on broadcast, but it is still
+	/// offered again on periodic rebroadcast.
+	Bloom {
+		/// Number of messages a single filter generation holds before being rotated out.
+		capacity: u32,
+	},
+}
+
+/// Set of messages known to a single peer.
+pub(crate) enum PeerKnownMessages<H> {
+	Exact(AHashSet<H>),
+	Bloom(RotatingBloom),
+}
+
+impl<H: Hash + Eq + Clone> PeerKnownMessages<H> {
+	/// Create an empty set using the given tracking strategy.
+	pub(crate) fn new(tracking: KnownMessagesTracking) -> Self {
+		match tracking {
+			KnownMessagesTracking::Exact => PeerKnownMessages::Exact(Default::default()),
+			KnownMessagesTracking::Bloom { capacity } =>
+				PeerKnownMessages::Bloom(RotatingBloom::new(capacity)),
+		}
+	}
+
+	/// Whether the peer (probably) knows the given message.
+	pub(crate) fn contains(&self, message_hash: &H) -> bool {
+		match self {
+			PeerKnownMessages::Exact(set) => set.contains(message_hash),
+			PeerKnownMessages::Bloom(bloom) => bloom.contains(message_hash),
+		}
+	}
+
+	/// Note the peer knows the given message. Returns `false` if it was (probably) known already.
+	pub(crate) fn insert(&mut self, message_hash: H) -> bool {
+		match self {
+			PeerKnownMessages::Exact(set) => set.insert(message_hash),
+			PeerKnownMessages::Bloom(bloom) => bloom.insert(&message_hash),
+		}
+	}
+
+	/// Forget messages for which `is_live` returns `false`.
+	///
+	/// Bloom filters cannot forget individual entries and age out through rotation instead.
+	pub(crate) fn prune(&mut self, is_live: impl FnMut(&H) -> bool) {
+		if let PeerKnownMessages::Exact(set) = self {
+			set.retain(is_live);
+		}
+	}
+}
+
+/// Two generations of bloom filters. Messages are inserted into the current generation, which
+/// replaces the previous one once it holds `capacity` messages.
+pub(crate) struct RotatingBloom {
+	hasher: RandomState,
+	capacity: u32,
+	inserted: u32,
+	current: Vec<u64>,
+	previous: Vec<u64>,
+}
+
+impl RotatingBloom {
+	fn new(capacity: u32) -> Self {
+		let capacity = capacity.max(1);
+		let words = capacity as usize * BLOOM_BITS_PER_MESSAGE / 64 + 1;
+		RotatingBloom {
+			hasher: RandomState::new(),
+			capacity,
+			inserted: 0,
+			current: vec![0; words],
+			previous: vec![0; words],
+		}
+	}
+
+	fn contains<T: Hash>(&self, item: &T) -> bool {
+		let bits = self.bits(item);
+		bits.clone().all(|bit| is_set(&self.current, bit)) ||
+			bits.all(|bit| is_set(&self.previous, bit))
+	}
+
+	fn insert<T: Hash>(&mut self, item: &T) -> bool {
+		if self.contains(item) {
+			return false
+		}
+
+		if self.inserted >= self.capacity {
+			std::mem::swap(&mut self.current, &mut self.previous);
+			self.current.iter_mut().for_each(|word| *word = 0);
+			self.inserted = 0;
+		}
+
+		for bit in self.bits(item) {
+			self.current[bit / 64] |= 1 << (bit % 64);
+		}
+		self.inserted += 1;
+		true
+	}
+
+	/// Bit positions of an item, derived from a single 64 bit hash by double hashing.
+	fn bits<T: Hash>(&self, item: &T) -> impl Iterator<Item = usize> + Clone {
+		let hash = self.hasher.hash_one(item);
+		let (h1, h2) = (hash as u32 as usize, (hash >> 32) as usize | 1);
+		let len = self.current.len() * 64;
+		(0..BLOOM_HASHES as usize).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % len)
+	}
+}
+
+fn is_set(words: &[u64], bit: usize) -> bool {
+	words[bit / 64] & (1 << (bit % 64)) != 0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bloom_remembers_inserted_messages() {
+		let mut known = PeerKnownMessages::new(KnownMessagesTracking::Bloom { capacity: 128 });
+
+		for i in 0u64..128 {
+			known.insert(i);
+		}
+		for i in 0u64..128 {
+			assert!(known.contains(&i));
+			assert!(!known.insert(i));
+		}
+	}
+
+	#[test]
+	fn bloom_forgets_messages_after_rotation() {
+		let mut bloom = RotatingBloom::new(16);
+		bloom.insert(&u64::MAX);
+
+		let forgotten_after = (0u64..64).find(|i| {
+			bloom.insert(i);
+			!bloom.contains(&u64::MAX)
+		});
+
+		// the message survives at least one full generation.
+		assert!(matches!(forgotten_after, Some(i) if i >= 15));
+	}
+}
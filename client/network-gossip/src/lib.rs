@@ -64,8 +64,11 @@
 
 pub use self::{
 	bridge::GossipEngine,
+	known_messages::KnownMessagesTracking,
 	state_machine::TopicNotification,
-	validator::{DiscardAll, MessageIntent, ValidationResult, Validator, ValidatorContext},
+	validator::{
+		DiscardAll, MessageIntent, MessagePriority, ValidationResult, Validator, ValidatorContext,
+	},
 };
 
 use libp2p_identity::PeerId;
@@ -78,6 +81,7 @@ use sp_runtime::traits::{Block as BlockT, NumberFor};
 use std::iter;
 
 mod bridge;
+mod known_messages;
 mod state_machine;
 mod validator;
 
@@ -17,9 +17,11 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{MessageIntent, Network, ValidationResult, Validator, ValidatorContext};
+use crate::{
+	known_messages::{KnownMessagesTracking, PeerKnownMessages},
+	MessageIntent, MessagePriority, Network, ValidationResult, Validator, ValidatorContext,
+};
 
-use ahash::AHashSet;
 use libp2p_identity::PeerId;
 use schnellru::{ByLength, LruMap};
 
@@ -53,7 +55,7 @@ mod rep {
 }
 
 struct PeerConsensus<H> {
-	known_messages: AHashSet<H>,
+	known_messages: PeerKnownMessages<H>,
 }
 
 /// Topic stream message with sender.
@@ -72,6 +74,42 @@ struct MessageEntry<B: BlockT> {
 	sender: Option<PeerId>,
 }
 
+/// Messages kept for propagation, with one lane per [`MessagePriority`].
+///
+/// Iterating yields all urgent messages ahead of the bulk ones, each lane in registration order.
+struct MessageQueue<B: BlockT> {
+	urgent: Vec<MessageEntry<B>>,
+	bulk: Vec<MessageEntry<B>>,
+}
+
+impl<B: BlockT> Default for MessageQueue<B> {
+	fn default() -> Self {
+		MessageQueue { urgent: Vec::new(), bulk: Vec::new() }
+	}
+}
+
+impl<B: BlockT> MessageQueue<B> {
+	fn push(&mut self, priority: MessagePriority, entry: MessageEntry<B>) {
+		match priority {
+			MessagePriority::Urgent => self.urgent.push(entry),
+			MessagePriority::Bulk => self.bulk.push(entry),
+		}
+	}
+
+	fn iter(&self) -> impl Iterator<Item = &MessageEntry<B>> + Clone {
+		self.urgent.iter().chain(self.bulk.iter())
+	}
+
+	fn retain(&mut self, mut f: impl FnMut(&MessageEntry<B>) -> bool) {
+		self.urgent.retain(&mut f);
+		self.bulk.retain(f);
+	}
+
+	fn len(&self) -> usize {
+		self.urgent.len() + self.bulk.len()
+	}
+}
+
 /// Local implementation of `ValidatorContext`.
 struct NetworkContext<'g, 'p, B: BlockT> {
 	gossip: &'g mut ConsensusGossip<B>,
@@ -156,8 +194,9 @@ where
 /// Consensus network protocol handler. Manages statements and candidate requests.
 pub struct ConsensusGossip<B: BlockT> {
 	peers: HashMap<PeerId, PeerConsensus<B::Hash>>,
-	messages: Vec<MessageEntry<B>>,
+	messages: MessageQueue<B>,
 	known_messages: LruMap<B::Hash, ()>,
+	known_messages_tracking: KnownMessagesTracking,
 	protocol: ProtocolName,
 	validator: Arc<dyn Validator<B>>,
 	next_broadcast: Instant,
@@ -184,6 +223,7 @@ impl<B: BlockT> ConsensusGossip<B> {
 			peers: HashMap::new(),
 			messages: Default::default(),
 			known_messages: { LruMap::new(ByLength::new(KNOWN_MESSAGES_CACHE_SIZE)) },
+			known_messages_tracking: KnownMessagesTracking::Exact,
 			protocol,
 			validator,
 			next_broadcast: Instant::now() + REBROADCAST_INTERVAL,
@@ -191,6 +231,14 @@ impl<B: BlockT> ConsensusGossip<B> {
 		}
 	}
 
+	/// Use the given strategy to track the messages known to each peer.
+	///
+	/// Only affects peers connected after the call.
+	pub fn with_known_messages_tracking(mut self, tracking: KnownMessagesTracking) -> Self {
+		self.known_messages_tracking = tracking;
+		self
+	}
+
 	/// Handle new connected peer.
 	pub fn new_peer(&mut self, network: &mut dyn Network<B>, who: PeerId, role: ObservedRole) {
 		tracing::trace!(
@@ -200,7 +248,10 @@ impl<B: BlockT> ConsensusGossip<B> {
 			?role,
 			"Registering peer",
 		);
-		self.peers.insert(who, PeerConsensus { known_messages: Default::default() });
+		self.peers.insert(
+			who,
+			PeerConsensus { known_messages: PeerKnownMessages::new(self.known_messages_tracking) },
+		);
 
 		let validator = self.validator.clone();
 		let mut context = NetworkContext { gossip: self, network };
@@ -215,7 +266,9 @@ impl<B: BlockT> ConsensusGossip<B> {
 		sender: Option<PeerId>,
 	) {
 		if self.known_messages.insert(message_hash, ()) {
-			self.messages.push(MessageEntry { message_hash, topic, message, sender });
+			let priority = self.validator.message_priority()(&topic, &message);
+			let entry = MessageEntry { message_hash, topic, message, sender };
+			self.messages.push(priority, entry);
 
 			if let Some(ref metrics) = self.metrics {
 				metrics.registered_messages.inc();
@@ -311,7 +364,7 @@ impl<B: BlockT> ConsensusGossip<B> {
 		);
 
 		for (_, ref mut peer) in self.peers.iter_mut() {
-			peer.known_messages.retain(|h| known_messages.get(h).is_some());
+			peer.known_messages.prune(|h| known_messages.get(h).is_some());
 		}
 	}
 
@@ -544,12 +597,10 @@ mod tests {
 	macro_rules! push_msg {
 		($consensus:expr, $topic:expr, $hash: expr, $m:expr) => {
 			if $consensus.known_messages.insert($hash, ()) {
-				$consensus.messages.push(MessageEntry {
-					message_hash: $hash,
-					topic: $topic,
-					message: $m,
-					sender: None,
-				});
+				$consensus.messages.push(
+					MessagePriority::Bulk,
+					MessageEntry { message_hash: $hash, topic: $topic, message: $m, sender: None },
+				);
 			}
 		};
 	}
@@ -586,6 +637,7 @@ mod tests {
 	#[derive(Clone, Default)]
 	struct NoOpNetworkInner {
 		peer_reports: Vec<(PeerId, ReputationChange)>,
+		notifications: Vec<(PeerId, Vec<u8>)>,
 	}
 
 	impl NetworkPeers for NoOpNetwork {
@@ -655,8 +707,8 @@ mod tests {
 	}
 
 	impl NetworkNotification for NoOpNetwork {
-		fn write_notification(&self, _target: PeerId, _protocol: ProtocolName, _message: Vec<u8>) {
-			unimplemented!();
+		fn write_notification(&self, target: PeerId, _protocol: ProtocolName, message: Vec<u8>) {
+			self.inner.lock().unwrap().notifications.push((target, message));
 		}
 
 		fn notification_sender(
@@ -835,4 +887,68 @@ mod tests {
 			network.inner.lock().unwrap().peer_reports
 		);
 	}
+
+	#[test]
+	fn urgent_messages_are_sent_before_bulk_messages() {
+		struct UrgentOnes;
+		impl Validator<Block> for UrgentOnes {
+			fn validate(
+				&self,
+				_context: &mut dyn ValidatorContext<Block>,
+				_sender: &PeerId,
+				_data: &[u8],
+			) -> ValidationResult<H256> {
+				ValidationResult::ProcessAndKeep(H256::default())
+			}
+
+			fn message_priority<'a>(
+				&'a self,
+			) -> Box<dyn FnMut(&H256, &[u8]) -> MessagePriority + 'a> {
+				Box::new(move |_topic, data| match data[0] {
+					1 => MessagePriority::Urgent,
+					_ => MessagePriority::Bulk,
+				})
+			}
+		}
+
+		let mut consensus =
+			ConsensusGossip::<Block>::new(Arc::new(UrgentOnes), "/foo".into(), None);
+		let mut network = NoOpNetwork::default();
+		let topic = H256::default();
+
+		consensus.register_message(topic, vec![0, 1]);
+		consensus.register_message(topic, vec![1, 1]);
+		consensus.register_message(topic, vec![0, 2]);
+		consensus.register_message(topic, vec![1, 2]);
+
+		let peer_id = PeerId::random();
+		consensus.new_peer(&mut network, peer_id, ObservedRole::Full);
+		consensus.broadcast_topic(&mut network, topic, false);
+
+		let sent = network
+			.inner
+			.lock()
+			.unwrap()
+			.notifications
+			.iter()
+			.map(|(_, message)| message.clone())
+			.collect::<Vec<_>>();
+		assert_eq!(sent, vec![vec![1, 1], vec![1, 2], vec![0, 1], vec![0, 2]]);
+	}
+
+	#[test]
+	fn bloom_tracking_does_not_resend_known_messages() {
+		let mut consensus = ConsensusGossip::<Block>::new(Arc::new(AllowAll), "/foo".into(), None)
+			.with_known_messages_tracking(KnownMessagesTracking::Bloom { capacity: 1024 });
+		let mut network = NoOpNetwork::default();
+		let topic = H256::default();
+
+		let peer_id = PeerId::random();
+		consensus.new_peer(&mut network, peer_id, ObservedRole::Full);
+		consensus.multicast(&mut network, topic, vec![1, 2, 3], false);
+		consensus.broadcast_topic(&mut network, topic, false);
+		consensus.send_topic(&mut network, &peer_id, topic, false);
+
+		assert_eq!(network.inner.lock().unwrap().notifications, vec![(peer_id, vec![1, 2, 3])]);
+	}
 }
@@ -49,6 +49,14 @@ pub trait Validator<B: BlockT>: Send + Sync {
 	) -> Box<dyn FnMut(&PeerId, MessageIntent, &B::Hash, &[u8]) -> bool + 'a> {
 		Box::new(move |_who, _intent, _topic, _data| true)
 	}
+
+	/// Produce a closure for assigning messages to a priority lane.
+	///
+	/// Urgent messages are sent ahead of bulk messages whenever several messages go out together,
+	/// such as when broadcasting a topic or rebroadcasting to a peer.
+	fn message_priority<'a>(&'a self) -> Box<dyn FnMut(&B::Hash, &[u8]) -> MessagePriority + 'a> {
+		Box::new(move |_topic, _data| MessagePriority::Bulk)
+	}
 }
 
 /// Validation context. Allows reacting to incoming messages by sending out further messages.
@@ -75,6 +83,15 @@ pub enum MessageIntent {
 	PeriodicRebroadcast,
 }
 
+/// The lane a message is sent on.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum MessagePriority {
+	/// Sent ahead of all bulk messages.
+	Urgent,
+	/// Sent after all urgent messages.
+	Bulk,
+}
+
 /// Message validation result.
 pub enum ValidationResult<H> {
 	/// Message should be stored and propagated under given topic.
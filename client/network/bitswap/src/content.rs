@@ -0,0 +1,86 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Content addressed by the CIDs served over bitswap.
+//!
+//! All content is addressed by the Blake2b-256 hash of its encoding. The codec of the CID tells
+//! what kind of content it references. Codecs other than the ones defined here reference
+//! transactions indexed with `storage_index_transaction`.
+
+use cid::{
+	multihash::{Code, Multihash},
+	Cid, Version,
+};
+
+/// Multicodec of raw binary data, for indexed transactions and data put into the
+/// [`BitswapStore`](crate::BitswapStore).
+pub const RAW_CODEC: u64 = 0x55;
+
+/// Multicodec of SCALE-encoded block headers, addressed by the block hash.
+///
+/// Codecs from `0x300000` onwards are reserved for private use.
+pub const HEADER_CODEC: u64 = 0x30_0000;
+
+/// Multicodec of SCALE-encoded extrinsics of finalized blocks.
+pub const EXTRINSIC_CODEC: u64 = 0x30_0001;
+
+/// Multicodec of state chunks, see [`StateChunk`](crate::StateChunk).
+pub const STATE_CHUNK_CODEC: u64 = 0x30_0002;
+
+/// Multicodec of state manifests, see [`StateManifest`](crate::StateManifest).
+pub const STATE_MANIFEST_CODEC: u64 = 0x30_0003;
+
+/// Content referenced by a CID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Content {
+	/// The header of the block with the given hash.
+	Header([u8; 32]),
+	/// The extrinsic with the given hash.
+	Extrinsic([u8; 32]),
+	/// Data with the given hash from the [`BitswapStore`](crate::BitswapStore), or an indexed
+	/// transaction.
+	Data([u8; 32]),
+}
+
+impl Content {
+	/// Parse the content referenced by `cid`, `None` if the CID isn't supported.
+	pub(crate) fn from_cid(cid: &Cid) -> Option<Self> {
+		if cid.version() != Version::V1 ||
+			cid.hash().code() != u64::from(Code::Blake2b256) ||
+			cid.hash().size() != 32
+		{
+			return None
+		}
+
+		let mut hash = [0; 32];
+		hash.copy_from_slice(&cid.hash().digest()[0..32]);
+		Some(match cid.codec() {
+			HEADER_CODEC => Self::Header(hash),
+			EXTRINSIC_CODEC => Self::Extrinsic(hash),
+			_ => Self::Data(hash),
+		})
+	}
+}
+
+/// Build the CID of the content with the given `codec` and Blake2b-256 `hash`.
+pub fn blake2b_cid(codec: u64, hash: &[u8; 32]) -> Cid {
+	let multihash = Multihash::wrap(u64::from(Code::Blake2b256), hash)
+		.expect("32 byte digests always fit into a multihash; qed");
+	Cid::new_v1(codec, multihash)
+}
@@ -16,11 +16,14 @@
 
 //! Bitswap server for Substrate.
 //!
-//! Allows querying transactions by hash over standard bitswap protocol
-//! Only supports bitswap 1.2.0.
-//! CID is expected to reference 256-bit Blake2b transaction hash.
+//! Allows querying transactions, headers, extrinsics and state chunks by hash over standard
+//! bitswap protocol. Only supports bitswap 1.2.0.
+//! CID is expected to reference a 256-bit Blake2b hash, its codec tells what kind of content is
+//! requested (see [`content`]). The [`ContentProvider`] announces the local node as a provider of
+//! the content of finalized blocks in the DHT.
 
 use cid::{self, Version};
+use content::Content;
 use futures::StreamExt;
 use libp2p_identity::PeerId;
 use log::{debug, error, trace};
@@ -34,11 +37,19 @@ use schema::bitswap::{
 	message::{wantlist::WantType, Block as MessageBlock, BlockPresence, BlockPresenceType},
 	Message as BitswapMessage,
 };
-use sp_runtime::traits::Block as BlockT;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockStatus;
+use sp_runtime::{codec::Encode, traits::Block as BlockT};
 use std::{io, sync::Arc, time::Duration};
 use unsigned_varint::encode as varint_encode;
 
+pub mod content;
+mod provider;
 mod schema;
+mod store;
+
+pub use provider::{ContentProvider, StateChunk, StateChunkEntry, StateManifest};
+pub use store::BitswapStore;
 
 const LOG_TARGET: &str = "bitswap";
 
@@ -90,15 +101,29 @@ impl Prefix {
 	}
 }
 
+/// The client the [`BitswapRequestHandler`] serves content from.
+pub trait BitswapClient<B: BlockT>: BlockBackend<B> + HeaderBackend<B> + Send + Sync {}
+
+impl<B: BlockT, T: BlockBackend<B> + HeaderBackend<B> + Send + Sync> BitswapClient<B> for T {}
+
 /// Bitswap request handler
-pub struct BitswapRequestHandler<B> {
-	client: Arc<dyn BlockBackend<B> + Send + Sync>,
+pub struct BitswapRequestHandler<B: BlockT> {
+	client: Arc<dyn BitswapClient<B>>,
+	store: BitswapStore<B::Hash>,
 	request_receiver: async_channel::Receiver<IncomingRequest>,
 }
 
 impl<B: BlockT> BitswapRequestHandler<B> {
 	/// Create a new [`BitswapRequestHandler`].
-	pub fn new(client: Arc<dyn BlockBackend<B> + Send + Sync>) -> (Self, ProtocolConfig) {
+	pub fn new(client: Arc<dyn BitswapClient<B>>) -> (Self, ProtocolConfig) {
+		Self::new_with_store(client, BitswapStore::new())
+	}
+
+	/// Create a new [`BitswapRequestHandler`], also serving the content of `store`.
+	pub fn new_with_store(
+		client: Arc<dyn BitswapClient<B>>,
+		store: BitswapStore<B::Hash>,
+	) -> (Self, ProtocolConfig) {
 		let (tx, request_receiver) = async_channel::bounded(MAX_REQUEST_QUEUE);
 
 		let config = ProtocolConfig {
@@ -108,9 +133,10 @@ impl<B: BlockT> BitswapRequestHandler<B> {
 			max_response_size: MAX_PACKET_SIZE,
 			request_timeout: Duration::from_secs(15),
 			inbound_queue: Some(tx),
+			inbound_rate_limit: None,
 		};
 
-		(Self { client, request_receiver }, config)
+		(Self { client, store, request_receiver }, config)
 	}
 
 	/// Run [`BitswapRequestHandler`].
@@ -194,38 +220,37 @@ impl<B: BlockT> BitswapRequestHandler<B> {
 				},
 			};
 
-			if cid.version() != cid::Version::V1 ||
-				cid.hash().code() != u64::from(cid::multihash::Code::Blake2b256) ||
-				cid.hash().size() != 32
-			{
+			let Some(content) = Content::from_cid(&cid) else {
 				debug!(target: LOG_TARGET, "Ignoring unsupported CID {}: {}", peer, cid);
 				continue
-			}
+			};
 
-			let mut hash = B::Hash::default();
-			hash.as_mut().copy_from_slice(&cid.hash().digest()[0..32]);
-			let transaction = match self.client.indexed_transaction(hash) {
-				Ok(ex) => ex,
+			// `want-have` entries are answered without loading the content.
+			let found = if entry.want_type == WantType::Block as i32 {
+				self.content(content).map(|data| data.map(Some))
+			} else {
+				self.has_content(content).map(|has| has.then_some(None))
+			};
+			let found = match found {
+				Ok(found) => found,
 				Err(e) => {
-					error!(target: LOG_TARGET, "Error retrieving transaction {}: {}", hash, e);
+					error!(target: LOG_TARGET, "Error retrieving CID {:?}: {}", cid, e);
 					None
 				},
 			};
 
-			match transaction {
-				Some(transaction) => {
-					trace!(target: LOG_TARGET, "Found CID {:?}, hash {:?}", cid, hash);
+			match found {
+				Some(data) => {
+					trace!(target: LOG_TARGET, "Found CID {:?}", cid);
 
-					if entry.want_type == WantType::Block as i32 {
+					if let Some(data) = data {
 						let prefix = Prefix {
 							version: cid.version(),
 							codec: cid.codec(),
 							mh_type: cid.hash().code(),
 							mh_len: cid.hash().size(),
 						};
-						response
-							.payload
-							.push(MessageBlock { prefix: prefix.to_bytes(), data: transaction });
+						response.payload.push(MessageBlock { prefix: prefix.to_bytes(), data });
 					} else {
 						response.block_presences.push(BlockPresence {
 							r#type: BlockPresenceType::Have as i32,
@@ -234,7 +259,7 @@ impl<B: BlockT> BitswapRequestHandler<B> {
 					}
 				},
 				None => {
-					trace!(target: LOG_TARGET, "Missing CID {:?}, hash {:?}", cid, hash);
+					trace!(target: LOG_TARGET, "Missing CID {:?}", cid);
 
 					if entry.send_dont_have {
 						response.block_presences.push(BlockPresence {
@@ -248,6 +273,46 @@ impl<B: BlockT> BitswapRequestHandler<B> {
 
 		Ok(response.encode_to_vec())
 	}
+
+	/// Load the given content, `None` if it's not available.
+	fn content(&self, content: Content) -> Result<Option<Vec<u8>>, BitswapError> {
+		Ok(match content {
+			Content::Header(hash) =>
+				self.client.header(block_hash::<B>(&hash))?.map(|header| header.encode()),
+			Content::Extrinsic(hash) => match self.store.extrinsic(&hash) {
+				Some((block, index)) => self
+					.client
+					.block_body(block)?
+					.and_then(|body| body.get(index as usize).map(Encode::encode)),
+				None => None,
+			},
+			Content::Data(hash) => match self.store.get(&hash) {
+				Some(data) => Some(data),
+				None => self.client.indexed_transaction(block_hash::<B>(&hash))?,
+			},
+		})
+	}
+
+	/// Whether the given content is available.
+	fn has_content(&self, content: Content) -> Result<bool, BitswapError> {
+		Ok(match content {
+			Content::Header(hash) => matches!(
+				self.client.block_status(block_hash::<B>(&hash))?,
+				BlockStatus::InChainWithState | BlockStatus::InChainPruned
+			),
+			Content::Extrinsic(hash) => self.store.extrinsic(&hash).is_some(),
+			Content::Data(hash) =>
+				self.store.contains(&hash) ||
+					self.client.has_indexed_transaction(block_hash::<B>(&hash))?,
+		})
+	}
+}
+
+/// Convert a digest to the hash type of the block.
+fn block_hash<B: BlockT>(digest: &[u8; 32]) -> B::Hash {
+	let mut hash = B::Hash::default();
+	hash.as_mut().copy_from_slice(digest);
+	hash
 }
 
 /// Bitswap protocol error.
@@ -296,7 +361,6 @@ mod tests {
 		Message as BitswapMessage,
 	};
 	use sp_consensus::BlockOrigin;
-	use sp_runtime::codec::Encode;
 	use substrate_test_runtime::ExtrinsicBuilder;
 	use substrate_test_runtime_client::{self, prelude::*, TestClientBuilder};
 
@@ -524,4 +588,94 @@ mod tests {
 			panic!("invalid event received");
 		}
 	}
+
+	#[tokio::test]
+	async fn header_found() {
+		let mut client = TestClientBuilder::new().build();
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		let (hash, header) = (block.hash(), block.header().clone());
+
+		client.import(BlockOrigin::File, block).await.unwrap();
+
+		let (bitswap, config) = BitswapRequestHandler::new(Arc::new(client));
+
+		tokio::spawn(async move { bitswap.run().await });
+
+		let (tx, rx) = oneshot::channel();
+		config
+			.inbound_queue
+			.unwrap()
+			.send(IncomingRequest {
+				peer: PeerId::random(),
+				payload: BitswapMessage {
+					wantlist: Some(Wantlist {
+						entries: vec![Entry {
+							block: content::blake2b_cid(
+								content::HEADER_CODEC,
+								hash.as_fixed_bytes(),
+							)
+							.to_bytes(),
+							..Default::default()
+						}],
+						full: false,
+					}),
+					..Default::default()
+				}
+				.encode_to_vec(),
+				pending_response: tx,
+			})
+			.await
+			.unwrap();
+
+		if let Ok(OutgoingResponse { result, .. }) = rx.await {
+			let response =
+				schema::bitswap::Message::decode(&result.expect("fetch to succeed")[..]).unwrap();
+			assert_eq!(response.payload[0].data, header.encode());
+		} else {
+			panic!("invalid event received");
+		}
+	}
+
+	#[tokio::test]
+	async fn want_have_answered_from_store() {
+		let client = TestClientBuilder::new().build();
+		let store = BitswapStore::new();
+		let hash = store.insert(vec![0x13, 0x37]);
+
+		let (bitswap, config) = BitswapRequestHandler::new_with_store(Arc::new(client), store);
+
+		tokio::spawn(async move { bitswap.run().await });
+
+		let (tx, rx) = oneshot::channel();
+		config
+			.inbound_queue
+			.unwrap()
+			.send(IncomingRequest {
+				peer: PeerId::random(),
+				payload: BitswapMessage {
+					wantlist: Some(Wantlist {
+						entries: vec![Entry {
+							block: content::blake2b_cid(content::RAW_CODEC, &hash).to_bytes(),
+							want_type: WantType::Have as i32,
+							..Default::default()
+						}],
+						full: false,
+					}),
+					..Default::default()
+				}
+				.encode_to_vec(),
+				pending_response: tx,
+			})
+			.await
+			.unwrap();
+
+		if let Ok(OutgoingResponse { result, .. }) = rx.await {
+			let response =
+				schema::bitswap::Message::decode(&result.expect("fetch to succeed")[..]).unwrap();
+			assert!(response.payload.is_empty());
+			assert_eq!(response.block_presences[0].r#type, BlockPresenceType::Have as i32);
+		} else {
+			panic!("invalid event received");
+		}
+	}
 }
@@ -0,0 +1,457 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Announcing the content of finalized blocks in the DHT.
+
+use crate::{
+	content::{blake2b_cid, EXTRINSIC_CODEC, HEADER_CODEC, STATE_MANIFEST_CODEC},
+	BitswapStore, LOG_TARGET,
+};
+use futures::StreamExt;
+use log::{debug, info};
+use sc_client_api::{BlockBackend, BlockchainEvents, ProofProvider};
+use sc_network::{KademliaKey, NetworkDHTProvider};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	codec::{Decode, Encode},
+	traits::{BlakeTwo256, Block as BlockT, Hash, NumberFor, Zero},
+};
+use std::{collections::VecDeque, sync::Arc};
+
+/// Maximum number of CIDs announced in the DHT at once.
+///
+/// Kademlia keeps at most 1024 provider records of the local node, the oldest announcements are
+/// withdrawn first.
+const MAX_PROVIDED_CIDS: usize = 512;
+
+/// Maximum number of blocks of a finality notification whose content is announced.
+///
+/// When many blocks are finalized at once, e.g. while syncing, only the content of the last ones
+/// is announced. The content of the other ones is still served.
+const MAX_ANNOUNCED_BLOCKS: usize = 16;
+
+/// Maximum number of extrinsics of a block announced in the DHT, the other ones are still served.
+const MAX_ANNOUNCED_EXTRINSICS: usize = 8;
+
+/// Size of the key-value pairs after which a state chunk is finished.
+const STATE_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Key-value pairs of a piece of the state of a finalized block.
+///
+/// The pieces are built like state sync responses: the pairs of the main trie are followed by the
+/// ones of the child tries whose roots are part of the chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct StateChunk {
+	/// The main trie followed by child tries.
+	pub entries: Vec<StateChunkEntry>,
+}
+
+/// Key-value pairs of a single trie within a [`StateChunk`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct StateChunkEntry {
+	/// The root of the child trie, empty for the main trie.
+	pub state_root: Vec<u8>,
+	/// The key-value pairs, ordered by key.
+	pub key_values: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Whether these are the last pairs of the trie.
+	pub complete: bool,
+}
+
+/// The hashes of the [`StateChunk`]s making up the state of a finalized block, in order.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct StateManifest<H> {
+	/// The hash of the block.
+	pub block: H,
+	/// The Blake2b-256 hashes of the encoded chunks.
+	pub chunks: Vec<[u8; 32]>,
+}
+
+/// Announces the local node as a provider of the headers and extrinsics of finalized blocks.
+///
+/// Extrinsics are indexed in the [`BitswapStore`] so they can be served. If enabled with
+/// [`ContentProvider::with_state_interval`], the state of finalized blocks is also periodically
+/// put into the store in [`StateChunk`]s, along with their [`StateManifest`]. Only the manifest is
+/// announced, the chunks are fetched from the peers providing it.
+///
+/// To keep the number of provider records low, only the header and the first few extrinsics of
+/// the last blocks of each finality notification are announced.
+///
+/// The index of the extrinsics and the published state only live in memory. After a restart, the
+/// extrinsics of blocks finalized before are no longer served and no state is served until the
+/// next one is published.
+pub struct ContentProvider<B: BlockT, C, N> {
+	client: Arc<C>,
+	network: N,
+	store: BitswapStore<B::Hash>,
+	state_interval: Option<NumberFor<B>>,
+	/// Keys of the CIDs currently announced, oldest first.
+	provided: VecDeque<KademliaKey>,
+	/// Hashes of the manifest and chunks of the last published state.
+	state: Option<([u8; 32], Vec<[u8; 32]>)>,
+}
+
+impl<B, C, N> ContentProvider<B, C, N>
+where
+	B: BlockT,
+	C: BlockBackend<B> + BlockchainEvents<B> + HeaderBackend<B> + ProofProvider<B>,
+	N: NetworkDHTProvider,
+{
+	/// Create a new [`ContentProvider`], indexing extrinsics in `store`.
+	pub fn new(client: Arc<C>, network: N, store: BitswapStore<B::Hash>) -> Self {
+		Self {
+			client,
+			network,
+			store,
+			state_interval: None,
+			provided: VecDeque::new(),
+			state: None,
+		}
+	}
+
+	/// Publish the state of every finalized block whose number is a multiple of `interval`.
+	///
+	/// The chunks are kept in memory, so this is only suitable for states fitting into the
+	/// [`BitswapStore`].
+	pub fn with_state_interval(mut self, interval: NumberFor<B>) -> Self {
+		self.state_interval = (!interval.is_zero()).then_some(interval);
+		self
+	}
+
+	/// Run the [`ContentProvider`] until the client stops finalizing blocks.
+	///
+	/// Publishing states blocks the thread for a while, so this should run on a blocking task.
+	pub async fn run(mut self) {
+		let mut finality_notifications = self.client.finality_notification_stream();
+		while let Some(notification) = finality_notifications.next().await {
+			let finalized =
+				notification.tree_route.iter().chain(std::iter::once(&notification.hash));
+			let announced_from =
+				(notification.tree_route.len() + 1).saturating_sub(MAX_ANNOUNCED_BLOCKS);
+			for (index, hash) in finalized.enumerate() {
+				if let Err(e) = self.on_finalized(*hash, index >= announced_from) {
+					debug!(target: LOG_TARGET, "Failed to provide the content of {}: {}", hash, e);
+				}
+			}
+		}
+	}
+
+	/// Index the content of a finalized block, announcing it if `announce` is set.
+	fn on_finalized(&mut self, hash: B::Hash, announce: bool) -> sp_blockchain::Result<()> {
+		let Some(block_hash) = as_digest(hash.as_ref()) else { return Ok(()) };
+		if announce {
+			self.provide(HEADER_CODEC, &block_hash);
+		}
+
+		for (index, extrinsic) in
+			self.client.block_body(hash)?.unwrap_or_default().iter().enumerate()
+		{
+			let extrinsic_hash = BlakeTwo256::hash(&extrinsic.encode()).0;
+			self.store.index_extrinsic(extrinsic_hash, hash, index as u32);
+			if announce && index < MAX_ANNOUNCED_EXTRINSICS {
+				self.provide(EXTRINSIC_CODEC, &extrinsic_hash);
+			}
+		}
+
+		if let (Some(interval), Some(number)) = (self.state_interval, self.client.number(hash)?) {
+			if (number % interval).is_zero() {
+				self.publish_state(hash, number)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Put the state of the block into the store, replacing the previously published state.
+	fn publish_state(&mut self, hash: B::Hash, number: NumberFor<B>) -> sp_blockchain::Result<()> {
+		let mut chunks = Vec::new();
+		let mut start_key = Vec::<Vec<u8>>::new();
+		loop {
+			let entries = self.client.storage_collection(hash, &start_key, STATE_CHUNK_SIZE)?;
+
+			// Continue from the last key of the first incomplete trie, like state sync does.
+			if start_key.len() == 2 &&
+				entries.first().map_or(false, |(top, _)| top.key_values.is_empty())
+			{
+				start_key.pop();
+			} else {
+				start_key.clear();
+			}
+			let mut complete = true;
+			for (state, state_complete) in &entries {
+				if !state_complete {
+					if let Some((key, _)) = state.key_values.last() {
+						start_key.push(key.clone());
+					}
+					complete = false;
+				}
+			}
+
+			let chunk = StateChunk {
+				entries: entries
+					.into_iter()
+					.map(|(state, complete)| StateChunkEntry {
+						state_root: state.state_root,
+						key_values: state.key_values,
+						complete,
+					})
+					.collect(),
+			};
+			chunks.push(self.store.insert(chunk.encode()));
+
+			if complete {
+				break
+			}
+		}
+
+		let manifest = self
+			.store
+			.insert(StateManifest { block: hash, chunks: chunks.clone() }.encode());
+		if let Some((old_manifest, old_chunks)) = self.state.take() {
+			for old in old_chunks {
+				if !chunks.contains(&old) {
+					self.store.remove(&old);
+				}
+			}
+			if old_manifest != manifest {
+				self.store.remove(&old_manifest);
+				self.withdraw(STATE_MANIFEST_CODEC, &old_manifest);
+			}
+		}
+		self.provide(STATE_MANIFEST_CODEC, &manifest);
+
+		info!(
+			target: LOG_TARGET,
+			"Published the state of block #{} in {} chunks, manifest: {}",
+			number,
+			chunks.len(),
+			blake2b_cid(STATE_MANIFEST_CODEC, &manifest),
+		);
+
+		self.state = Some((manifest, chunks));
+		Ok(())
+	}
+
+	/// Announce the local node as a provider of the CID, withdrawing the oldest announcement if
+	/// there are too many.
+	fn provide(&mut self, codec: u64, hash: &[u8; 32]) {
+		let key = provider_key(codec, hash);
+		if self.provided.contains(&key) {
+			return
+		}
+		self.network.start_providing(key.clone());
+		self.provided.push_back(key);
+
+		if self.provided.len() > MAX_PROVIDED_CIDS {
+			if let Some(oldest) = self.provided.pop_front() {
+				self.network.stop_providing(oldest);
+			}
+		}
+	}
+
+	/// Withdraw the announcement of the CID, if it's still announced.
+	fn withdraw(&mut self, codec: u64, hash: &[u8; 32]) {
+		let key = provider_key(codec, hash);
+		if let Some(position) = self.provided.iter().position(|provided| *provided == key) {
+			self.provided.remove(position);
+			self.network.stop_providing(key);
+		}
+	}
+}
+
+/// The key of the provider records of the CID, the multihash of the CID.
+fn provider_key(codec: u64, hash: &[u8; 32]) -> KademliaKey {
+	KademliaKey::new(&blake2b_cid(codec, hash).hash().to_bytes())
+}
+
+/// Convert a 32 byte hash to a digest, `None` if it has another length.
+fn as_digest(hash: &[u8]) -> Option<[u8; 32]> {
+	hash.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use parking_lot::Mutex;
+	use sc_block_builder::BlockBuilderProvider;
+	use sc_client_api::StorageProvider;
+	use sp_consensus::BlockOrigin;
+	use std::collections::{BTreeMap, HashSet};
+	use substrate_test_runtime_client::{prelude::*, runtime::Block, TestClientBuilder};
+
+	/// Records the CIDs announced in the DHT.
+	#[derive(Clone, Default)]
+	struct TestNetwork {
+		provided: Arc<Mutex<HashSet<KademliaKey>>>,
+	}
+
+	impl TestNetwork {
+		fn provides(&self, codec: u64, hash: &[u8; 32]) -> bool {
+			self.provided.lock().contains(&provider_key(codec, hash))
+		}
+	}
+
+	impl NetworkDHTProvider for TestNetwork {
+		fn get_value(&self, _key: &KademliaKey) {
+			unimplemented!()
+		}
+
+		fn put_value(&self, _key: KademliaKey, _value: Vec<u8>) {
+			unimplemented!()
+		}
+
+		fn start_providing(&self, key: KademliaKey) {
+			self.provided.lock().insert(key);
+		}
+
+		fn stop_providing(&self, key: KademliaKey) {
+			self.provided.lock().remove(&key);
+		}
+	}
+
+	/// Import a block setting each of `keys` to a value of `value_size` bytes, returning its hash.
+	async fn import_block(
+		client: &mut TestClient,
+		keys: &[&str],
+		value_size: usize,
+	) -> <Block as BlockT>::Hash {
+		let mut builder = client.new_block(Default::default()).unwrap();
+		for key in keys {
+			builder
+				.push_storage_change(
+					key.as_bytes().to_vec(),
+					Some(vec![keys.len() as u8; value_size]),
+				)
+				.unwrap();
+		}
+		let block = builder.build().unwrap().block;
+		let hash = block.hash();
+		client.import(BlockOrigin::Own, block).await.unwrap();
+		hash
+	}
+
+	/// Reassemble the state published with the manifest with the given hash.
+	fn published_state(
+		store: &BitswapStore<<Block as BlockT>::Hash>,
+		manifest: &[u8; 32],
+	) -> (<Block as BlockT>::Hash, BTreeMap<Vec<u8>, Vec<u8>>) {
+		let manifest = StateManifest::<<Block as BlockT>::Hash>::decode(
+			&mut &store.get(manifest).unwrap()[..],
+		)
+		.unwrap();
+
+		let mut state = BTreeMap::new();
+		for chunk in &manifest.chunks {
+			let chunk = StateChunk::decode(&mut &store.get(chunk).unwrap()[..]).unwrap();
+			for entry in chunk.entries.into_iter().filter(|entry| entry.state_root.is_empty()) {
+				state.extend(entry.key_values);
+			}
+		}
+		(manifest.block, state)
+	}
+
+	#[tokio::test]
+	async fn publish_state_splits_state_into_chunks() {
+		let mut client = TestClientBuilder::new().build();
+		let keys = ["key-1", "key-2", "key-3", "key-4"];
+		let hash = import_block(&mut client, &keys, STATE_CHUNK_SIZE / 2).await;
+
+		let client = Arc::new(client);
+		let network = TestNetwork::default();
+		let store = BitswapStore::new();
+		let mut provider = ContentProvider::new(client.clone(), network.clone(), store.clone());
+		provider.publish_state(hash, 1).unwrap();
+
+		let (manifest, chunks) = provider.state.clone().unwrap();
+		assert!(chunks.len() > 1);
+		let (block, state) = published_state(&store, &manifest);
+		assert_eq!(block, hash);
+
+		let expected = client
+			.storage_pairs(hash, None, None)
+			.unwrap()
+			.map(|(key, value)| (key.0, value.0))
+			.collect::<BTreeMap<_, _>>();
+		assert_eq!(state, expected);
+
+		// Only the manifest is announced.
+		assert!(network.provides(STATE_MANIFEST_CODEC, &manifest));
+		assert_eq!(network.provided.lock().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn publish_state_replaces_previous_state() {
+		let mut client = TestClientBuilder::new().build();
+		let first = import_block(&mut client, &["key-1"], STATE_CHUNK_SIZE).await;
+		let second = import_block(&mut client, &["key-1", "key-2"], STATE_CHUNK_SIZE).await;
+
+		let network = TestNetwork::default();
+		let store = BitswapStore::new();
+		let mut provider = ContentProvider::new(Arc::new(client), network.clone(), store.clone());
+
+		provider.publish_state(first, 1).unwrap();
+		let (first_manifest, first_chunks) = provider.state.clone().unwrap();
+		provider.publish_state(second, 2).unwrap();
+		let (second_manifest, second_chunks) = provider.state.clone().unwrap();
+
+		assert_eq!(published_state(&store, &second_manifest).0, second);
+		assert!(!store.contains(&first_manifest));
+		for chunk in &first_chunks {
+			assert_eq!(store.contains(chunk), second_chunks.contains(chunk));
+		}
+
+		assert!(!network.provides(STATE_MANIFEST_CODEC, &first_manifest));
+		assert!(network.provides(STATE_MANIFEST_CODEC, &second_manifest));
+	}
+
+	#[tokio::test]
+	async fn only_some_extrinsics_are_announced() {
+		let mut client = TestClientBuilder::new().build();
+		let keys = (0..MAX_ANNOUNCED_EXTRINSICS + 4)
+			.map(|i| format!("key-{}", i))
+			.collect::<Vec<_>>();
+		let keys = keys.iter().map(String::as_str).collect::<Vec<_>>();
+		let hash = import_block(&mut client, &keys, 1).await;
+		let body = client.block_body(hash).unwrap().unwrap();
+
+		let network = TestNetwork::default();
+		let store = BitswapStore::new();
+		let mut provider = ContentProvider::new(Arc::new(client), network.clone(), store.clone());
+		provider.on_finalized(hash, true).unwrap();
+
+		assert!(network.provides(HEADER_CODEC, hash.as_fixed_bytes()));
+		for (index, extrinsic) in body.iter().enumerate() {
+			let extrinsic_hash = BlakeTwo256::hash(&extrinsic.encode()).0;
+			assert_eq!(store.extrinsic(&extrinsic_hash), Some((hash, index as u32)));
+			assert_eq!(
+				network.provides(EXTRINSIC_CODEC, &extrinsic_hash),
+				index < MAX_ANNOUNCED_EXTRINSICS,
+			);
+		}
+
+		// Blocks which aren't announced are still indexed.
+		let network = TestNetwork::default();
+		let store = BitswapStore::new();
+		let mut provider =
+			ContentProvider::new(provider.client.clone(), network.clone(), store.clone());
+		provider.on_finalized(hash, false).unwrap();
+
+		assert!(network.provided.lock().is_empty());
+		let extrinsic_hash = BlakeTwo256::hash(&body[0].encode()).0;
+		assert_eq!(store.extrinsic(&extrinsic_hash), Some((hash, 0)));
+	}
+}
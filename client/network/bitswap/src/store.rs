@@ -0,0 +1,134 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Content served over bitswap which isn't part of the client's database.
+
+use parking_lot::RwLock;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::Arc,
+};
+
+/// Maximum total size of the data kept in a [`BitswapStore`].
+const MAX_DATA_SIZE: usize = 256 * 1024 * 1024;
+
+/// Maximum number of extrinsics indexed by a [`BitswapStore`].
+const MAX_INDEXED_EXTRINSICS: usize = 250_000;
+
+/// Shared store of the data served over bitswap in addition to indexed transactions and headers.
+///
+/// It keeps arbitrary data by its Blake2b-256 hash, and the location of the extrinsics of
+/// finalized blocks. Once full, the oldest entries are evicted first. Everything is kept in memory
+/// only and is lost on restart.
+pub struct BitswapStore<H> {
+	inner: Arc<RwLock<Inner<H>>>,
+}
+
+impl<H> Clone for BitswapStore<H> {
+	fn clone(&self) -> Self {
+		Self { inner: self.inner.clone() }
+	}
+}
+
+impl<H> Default for BitswapStore<H> {
+	fn default() -> Self {
+		Self {
+			inner: Arc::new(RwLock::new(Inner {
+				data: HashMap::new(),
+				data_order: VecDeque::new(),
+				data_size: 0,
+				extrinsics: HashMap::new(),
+				extrinsic_order: VecDeque::new(),
+			})),
+		}
+	}
+}
+
+struct Inner<H> {
+	data: HashMap<[u8; 32], Vec<u8>>,
+	data_order: VecDeque<[u8; 32]>,
+	data_size: usize,
+	extrinsics: HashMap<[u8; 32], (H, u32)>,
+	extrinsic_order: VecDeque<[u8; 32]>,
+}
+
+impl<H: Copy> BitswapStore<H> {
+	/// Create an empty store.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Insert `data`, returning its Blake2b-256 hash.
+	pub fn insert(&self, data: Vec<u8>) -> [u8; 32] {
+		let hash = BlakeTwo256::hash(&data).0;
+		let mut inner = self.inner.write();
+		if inner.data.contains_key(&hash) {
+			return hash
+		}
+
+		inner.data_size += data.len();
+		inner.data.insert(hash, data);
+		inner.data_order.push_back(hash);
+		while inner.data_size > MAX_DATA_SIZE {
+			let Some(oldest) = inner.data_order.pop_front() else { break };
+			if let Some(data) = inner.data.remove(&oldest) {
+				inner.data_size -= data.len();
+			}
+		}
+		hash
+	}
+
+	/// Remove the data with the given hash.
+	pub fn remove(&self, hash: &[u8; 32]) {
+		let mut inner = self.inner.write();
+		if let Some(data) = inner.data.remove(hash) {
+			inner.data_size -= data.len();
+			inner.data_order.retain(|h| h != hash);
+		}
+	}
+
+	/// Get the data with the given hash.
+	pub fn get(&self, hash: &[u8; 32]) -> Option<Vec<u8>> {
+		self.inner.read().data.get(hash).cloned()
+	}
+
+	/// Whether the store contains the data with the given hash.
+	pub fn contains(&self, hash: &[u8; 32]) -> bool {
+		self.inner.read().data.contains_key(hash)
+	}
+
+	/// Record the extrinsic with the given hash to be at `index` in the body of `block`.
+	pub(crate) fn index_extrinsic(&self, hash: [u8; 32], block: H, index: u32) {
+		let mut inner = self.inner.write();
+		if inner.extrinsics.insert(hash, (block, index)).is_none() {
+			inner.extrinsic_order.push_back(hash);
+		}
+		while inner.extrinsic_order.len() > MAX_INDEXED_EXTRINSICS {
+			if let Some(oldest) = inner.extrinsic_order.pop_front() {
+				inner.extrinsics.remove(&oldest);
+			}
+		}
+	}
+
+	/// The block and index in its body of the extrinsic with the given hash.
+	pub(crate) fn extrinsic(&self, hash: &[u8; 32]) -> Option<(H, u32)> {
+		self.inner.read().extrinsics.get(hash).copied()
+	}
+}
@@ -35,7 +35,7 @@ use sp_runtime::{
 	traits::{Block as BlockT, NumberFor},
 	Justifications,
 };
-use warp::WarpSyncProgress;
+use warp::{WarpProofVersion, WarpSyncProgress};
 
 use std::{any::Any, fmt, fmt::Formatter, pin::Pin, sync::Arc, task::Poll};
 
@@ -234,7 +234,7 @@ pub struct Metrics {
 pub enum PeerRequest<B: BlockT> {
 	Block(BlockRequest<B>),
 	State,
-	WarpProof,
+	WarpProof(WarpProofVersion),
 }
 
 /// Wrapper for implementation-specific state request.
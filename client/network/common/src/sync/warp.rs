@@ -23,11 +23,25 @@ use std::{fmt, sync::Arc};
 /// Scale-encoded warp sync proof response.
 pub struct EncodedProof(pub Vec<u8>);
 
+/// Format version of warp sync proofs, negotiated through the name of the request protocol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WarpProofVersion {
+	/// Proofs with full GRANDPA justifications, supported by all peers.
+	#[default]
+	V1,
+	/// Proofs with compact GRANDPA justifications.
+	V2,
+}
+
 /// Warp sync request
 #[derive(Encode, Decode, Debug)]
 pub struct WarpProofRequest<B: BlockT> {
 	/// Start collecting proofs from this block.
 	pub begin: B::Hash,
+	/// The proof format version to request. This isn't encoded as it's negotiated through the
+	/// name of the request protocol.
+	#[codec(skip)]
+	pub version: WarpProofVersion,
 }
 
 /// The different types of warp syncing.
@@ -66,6 +80,42 @@ pub trait WarpSyncProvider<Block: BlockT>: Send + Sync {
 	/// Get current list of authorities. This is supposed to be genesis authorities when starting
 	/// sync.
 	fn current_authorities(&self) -> AuthorityList;
+	/// The header of the last block of the given proof, and whether the proof is the last one
+	/// generated on the way to the latest finalized block. The proof is not verified.
+	fn proof_target(
+		&self,
+		_proof: &EncodedProof,
+	) -> Result<(Block::Header, bool), Box<dyn std::error::Error + Send + Sync>> {
+		Err("Inspecting warp sync proofs is not supported".into())
+	}
+	/// The latest proof format version supported by the provider.
+	fn latest_proof_version(&self) -> WarpProofVersion {
+		WarpProofVersion::V1
+	}
+	/// Generate proof in the given format version, see [`WarpSyncProvider::generate`].
+	fn generate_versioned(
+		&self,
+		start: Block::Hash,
+		version: WarpProofVersion,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		match version {
+			WarpProofVersion::V1 => self.generate(start),
+			version => Err(format!("Unsupported warp proof version {:?}", version).into()),
+		}
+	}
+	/// Verify warp proof in the given format version, see [`WarpSyncProvider::verify`].
+	fn verify_versioned(
+		&self,
+		proof: &EncodedProof,
+		version: WarpProofVersion,
+		set_id: SetId,
+		authorities: AuthorityList,
+	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+		match version {
+			WarpProofVersion::V1 => self.verify(proof, set_id, authorities),
+			version => Err(format!("Unsupported warp proof version {:?}", version).into()),
+		}
+	}
 }
 
 /// Reported warp sync phase.
@@ -56,5 +56,6 @@ pub fn generate_protocol_config<Hash: AsRef<[u8]>>(
 		max_response_size: 16 * 1024 * 1024,
 		request_timeout: Duration::from_secs(15),
 		inbound_queue: None,
+		inbound_rate_limit: None,
 	}
 }
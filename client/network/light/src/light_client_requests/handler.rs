@@ -29,7 +29,7 @@ use futures::prelude::*;
 use libp2p_identity::PeerId;
 use log::{debug, trace};
 use prost::Message;
-use sc_client_api::{BlockBackend, ProofProvider};
+use sc_client_api::{BlockBackend, ProofProvider, StorageProof};
 use sc_network::{
 	config::ProtocolId,
 	request_responses::{IncomingRequest, OutgoingResponse, ProtocolConfig},
@@ -48,6 +48,16 @@ const LOG_TARGET: &str = "light-client-request-handler";
 /// handling in production systems, this value is chosen to match the block request limit.
 const MAX_LIGHT_REQUEST_QUEUE: usize = 20;
 
+/// Maximum number of entries returned by a single range read.
+const MAX_RANGE_ENTRIES: u32 = 4096;
+
+/// Maximum size of the proof returned by a single range read, leaving room in the 16 MiB
+/// response for its encoding.
+const MAX_RANGE_PROOF_SIZE: usize = 8 * 1024 * 1024;
+
+/// Maximum number of keys, over all child tries, read by a single batched child read.
+const MAX_CHILD_BATCH_KEYS: usize = 4096;
+
 /// Handler for incoming light client requests from a remote peer.
 pub struct LightClientRequestHandler<B, Client> {
 	request_receiver: async_channel::Receiver<IncomingRequest>,
@@ -156,6 +166,10 @@ where
 				self.on_remote_read_request(&peer, r)?,
 			Some(schema::v1::light::request::Request::RemoteReadChildRequest(r)) =>
 				self.on_remote_read_child_request(&peer, r)?,
+			Some(schema::v1::light::request::Request::RemoteReadRangeRequest(r)) =>
+				self.on_remote_read_range_request(&peer, r)?,
+			Some(schema::v1::light::request::Request::RemoteReadChildBatchRequest(r)) =>
+				self.on_remote_read_child_batch_request(&peer, r)?,
 			None =>
 				return Err(HandleRequestError::BadRequest("Remote request without request data.")),
 		};
@@ -253,12 +267,7 @@ where
 
 		let block = Decode::decode(&mut request.block.as_ref())?;
 
-		let prefixed_key = PrefixedStorageKey::new_ref(&request.storage_key);
-		let child_info = match ChildType::from_prefixed_key(prefixed_key) {
-			Some((ChildType::ParentKeyId, storage_key)) => Ok(ChildInfo::new_default(storage_key)),
-			None => Err(sp_blockchain::Error::InvalidChildStorageKey),
-		};
-		let response = match child_info.and_then(|child_info| {
+		let response = match child_info(&request.storage_key).and_then(|child_info| {
 			self.client.read_child_proof(
 				block,
 				&child_info,
@@ -283,6 +292,148 @@ where
 			response: Some(schema::v1::light::response::Response::RemoteReadResponse(response)),
 		})
 	}
+
+	fn on_remote_read_range_request(
+		&mut self,
+		peer: &PeerId,
+		request: &schema::v1::light::RemoteReadRangeRequest,
+	) -> Result<schema::v1::light::Response, HandleRequestError> {
+		trace!(
+			"Remote read range request from {} ({} {} at {:?}).",
+			peer,
+			request
+				.storage_key
+				.as_ref()
+				.map_or(String::new(), |key| HexDisplay::from(key).to_string()),
+			HexDisplay::from(&request.prefix),
+			request.block,
+		);
+
+		if let Some(continuation) = &request.continuation {
+			if !continuation.starts_with(&request.prefix) {
+				debug!("Invalid remote read range request sent by {}.", peer);
+				return Err(HandleRequestError::BadRequest(
+					"Remote read range request with a continuation outside of the range.",
+				))
+			}
+		}
+
+		let block = Decode::decode(&mut request.block.as_ref())?;
+
+		let max_size = request
+			.max_size
+			.map_or(MAX_RANGE_PROOF_SIZE, |size| MAX_RANGE_PROOF_SIZE.min(size as usize));
+		let max_entries =
+			request.max_entries.unwrap_or(MAX_RANGE_ENTRIES).clamp(1, MAX_RANGE_ENTRIES);
+
+		// The proof stops growing once it reaches `max_size`, but always covers at least one entry
+		// so the requester makes progress.
+		let child_info = request.storage_key.as_deref().map(child_info).transpose();
+		let result = child_info.and_then(|child_info| {
+			self.client.read_range_proof(
+				block,
+				child_info.as_ref(),
+				Some(request.prefix.as_slice()),
+				request.continuation.as_deref(),
+				max_entries,
+				max_size,
+			)
+		});
+		let response = match result {
+			Ok((proof, keys, complete)) => {
+				// The range continues after the last key, which is excluded from the next page.
+				let continuation = keys.last().filter(|_| !complete).cloned();
+				schema::v1::light::RemoteReadRangeResponse {
+					proof: Some(proof.encode()),
+					entries: Some(keys.len() as u32),
+					continuation,
+				}
+			},
+			Err(error) => {
+				trace!(
+					"remote read range request from {} ({} at {:?}) failed with: {}",
+					peer,
+					HexDisplay::from(&request.prefix),
+					request.block,
+					error,
+				);
+				schema::v1::light::RemoteReadRangeResponse {
+					proof: None,
+					entries: None,
+					continuation: None,
+				}
+			},
+		};
+
+		Ok(schema::v1::light::Response {
+			response: Some(schema::v1::light::response::Response::RemoteReadRangeResponse(
+				response,
+			)),
+		})
+	}
+
+	fn on_remote_read_child_batch_request(
+		&mut self,
+		peer: &PeerId,
+		request: &schema::v1::light::RemoteReadChildBatchRequest,
+	) -> Result<schema::v1::light::Response, HandleRequestError> {
+		let total_keys = request.children.iter().map(|child| child.keys.len()).sum::<usize>();
+		if total_keys == 0 || request.children.iter().any(|child| child.keys.is_empty()) {
+			debug!("Invalid remote child batch read request sent by {}.", peer);
+			return Err(HandleRequestError::BadRequest(
+				"Remote read child batch request without keys.",
+			))
+		}
+		if total_keys > MAX_CHILD_BATCH_KEYS {
+			debug!("Invalid remote child batch read request sent by {}.", peer);
+			return Err(HandleRequestError::BadRequest(
+				"Remote read child batch request with too many keys.",
+			))
+		}
+
+		trace!(
+			"Remote read child batch request from {} ({} keys in {} child tries at {:?}).",
+			peer,
+			total_keys,
+			request.children.len(),
+			request.block,
+		);
+
+		let block = Decode::decode(&mut request.block.as_ref())?;
+
+		let proofs = request
+			.children
+			.iter()
+			.map(|child| {
+				child_info(&child.storage_key).and_then(|child_info| {
+					self.client.read_child_proof(
+						block,
+						&child_info,
+						&mut child.keys.iter().map(AsRef::as_ref),
+					)
+				})
+			})
+			.collect::<Result<Vec<_>, _>>();
+		let response = match proofs {
+			Ok(proofs) => schema::v1::light::RemoteReadResponse {
+				proof: Some(StorageProof::merge(proofs).encode()),
+			},
+			Err(error) => {
+				trace!(
+					"remote read child batch request from {} ({} keys at {:?}) failed with: {}",
+					peer,
+					total_keys,
+					request.block,
+					error,
+				);
+				schema::v1::light::RemoteReadResponse { proof: None }
+			},
+		};
+
+		Ok(schema::v1::light::Response {
+			response: Some(schema::v1::light::response::Response::RemoteReadResponse(response)),
+		})
+	}
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -312,3 +463,131 @@ fn fmt_keys(first: Option<&Vec<u8>>, last: Option<&Vec<u8>>) -> String {
 		String::from("n/a")
 	}
 }
+
+fn child_info(storage_key: &[u8]) -> Result<ChildInfo, sp_blockchain::Error> {
+	match ChildType::from_prefixed_key(PrefixedStorageKey::new_ref(storage_key)) {
+		Some((ChildType::ParentKeyId, storage_key)) => Ok(ChildInfo::new_default(storage_key)),
+		None => Err(sp_blockchain::Error::InvalidChildStorageKey),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_client_api::HeaderBackend;
+	use sp_runtime::traits::{BlakeTwo256, Header};
+	use substrate_test_runtime_client::{prelude::*, runtime::Block as TestBlock};
+
+	const PREFIX: &[u8] = b"range:";
+
+	/// Build a handler serving `count` entries of `value_size` bytes under `PREFIX`.
+	fn handler(
+		count: u32,
+		value_size: usize,
+	) -> (LightClientRequestHandler<TestBlock, TestClient>, Arc<TestClient>) {
+		let mut builder = TestClientBuilder::new();
+		for i in 0..count {
+			builder = builder
+				.add_extra_storage([PREFIX, &i.to_be_bytes()[..]].concat(), vec![1; value_size]);
+		}
+		let client = Arc::new(builder.build());
+		let (handler, _) = LightClientRequestHandler::new(&"test".into(), None, client.clone());
+		(handler, client)
+	}
+
+	/// Read the range from `continuation`, checking the proof of the returned entries.
+	fn read_range(
+		handler: &mut LightClientRequestHandler<TestBlock, TestClient>,
+		client: &TestClient,
+		continuation: Option<Vec<u8>>,
+		max_entries: Option<u32>,
+		max_size: Option<u32>,
+	) -> (schema::v1::light::RemoteReadRangeResponse, Vec<(Vec<u8>, Vec<u8>)>) {
+		let hash = client.info().best_hash;
+		let request = schema::v1::light::RemoteReadRangeRequest {
+			block: hash.encode(),
+			storage_key: None,
+			prefix: PREFIX.to_vec(),
+			continuation: continuation.clone(),
+			max_entries,
+			max_size,
+		};
+		let response = match handler
+			.on_remote_read_range_request(&PeerId::random(), &request)
+			.unwrap()
+			.response
+		{
+			Some(schema::v1::light::response::Response::RemoteReadRangeResponse(response)) =>
+				response,
+			_ => panic!("Unexpected response"),
+		};
+
+		// The end of the range is only proven when reading past the returned entries.
+		let entries = response.entries.unwrap();
+		let count = if response.continuation.is_some() { entries } else { u32::MAX };
+		let proof = StorageProof::decode(&mut response.proof.as_deref().unwrap()).unwrap();
+		let (entries, complete) =
+			sp_state_machine::read_range_proof_check_with_count::<BlakeTwo256>(
+				*client.header(hash).unwrap().unwrap().state_root(),
+				proof,
+				None,
+				Some(PREFIX),
+				count,
+				continuation.as_deref(),
+			)
+			.unwrap();
+		assert_eq!(entries.len() as u32, response.entries.unwrap());
+		assert_eq!(complete, response.continuation.is_none());
+		(response, entries)
+	}
+
+	#[test]
+	fn range_read_reports_proven_entries() {
+		let (mut handler, client) = handler(10, 1);
+
+		let (response, entries) = read_range(&mut handler, &client, None, Some(100), None);
+		assert_eq!(response.entries, Some(10));
+		assert_eq!(entries.len(), 10);
+		assert!(response.continuation.is_none());
+
+		// Pages continue right after the last returned entry.
+		let (response, first) = read_range(&mut handler, &client, None, Some(4), None);
+		assert_eq!(response.entries, Some(4));
+		let (response, second) =
+			read_range(&mut handler, &client, response.continuation, Some(100), None);
+		assert_eq!(response.entries, Some(6));
+		assert_eq!([first, second].concat(), entries);
+	}
+
+	#[test]
+	fn range_read_clamps_entries() {
+		let (mut handler, client) = handler(MAX_RANGE_ENTRIES + 10, 1);
+
+		// At least one entry is always returned.
+		let (response, _) = read_range(&mut handler, &client, None, Some(0), None);
+		assert_eq!(response.entries, Some(1));
+
+		let (response, _) = read_range(&mut handler, &client, None, Some(u32::MAX), None);
+		assert_eq!(response.entries, Some(MAX_RANGE_ENTRIES));
+		assert!(response.continuation.is_some());
+
+		let (response, _) = read_range(&mut handler, &client, None, None, None);
+		assert_eq!(response.entries, Some(MAX_RANGE_ENTRIES));
+	}
+
+	#[test]
+	fn range_read_limits_proof_size() {
+		let (mut handler, client) = handler(20, 1024);
+
+		let (response, entries) = read_range(&mut handler, &client, None, None, Some(8 * 1024));
+		let proof_size = response.proof.as_ref().unwrap().len();
+		assert!(entries.len() > 1 && entries.len() < 20);
+		assert_eq!(response.entries, Some(entries.len() as u32));
+		// The proof only exceeds the limit by the last entry.
+		assert!(proof_size < 8 * 1024 + 2 * 1024);
+
+		// A single entry is returned even if its proof is larger than the limit.
+		let (response, _) = read_range(&mut handler, &client, None, None, Some(1));
+		assert_eq!(response.entries, Some(1));
+	}
+}
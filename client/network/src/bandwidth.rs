@@ -0,0 +1,78 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Accounting of the bandwidth used by each protocol.
+//!
+//! The transport only measures the total number of bytes sent and received. The
+//! [`ProtocolBandwidth`] here is fed the size of each notification, request and response by the
+//! code handling them, and is queried for the metrics and the network state.
+
+use crate::{network_state, types::ProtocolName};
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Number of bytes received and sent on each notification and request-response protocol.
+///
+/// Only the payloads are accounted for, not the framing and the substream negotiation.
+#[derive(Debug, Default)]
+pub struct ProtocolBandwidth {
+	protocols: Mutex<HashMap<ProtocolName, (u64, u64)>>,
+}
+
+impl ProtocolBandwidth {
+	/// Reports `bytes` received on `protocol`.
+	pub fn report_inbound(&self, protocol: &ProtocolName, bytes: usize) {
+		self.report(protocol, bytes, 0);
+	}
+
+	/// Reports `bytes` sent on `protocol`.
+	pub fn report_outbound(&self, protocol: &ProtocolName, bytes: usize) {
+		self.report(protocol, 0, bytes);
+	}
+
+	fn report(&self, protocol: &ProtocolName, inbound: usize, outbound: usize) {
+		let mut protocols = self.protocols.lock();
+		let counters = protocols.entry(protocol.clone()).or_default();
+		counters.0 = counters.0.saturating_add(inbound as u64);
+		counters.1 = counters.1.saturating_add(outbound as u64);
+	}
+
+	/// Returns the total number of bytes received and sent on each protocol.
+	pub fn totals(&self) -> HashMap<ProtocolName, (u64, u64)> {
+		self.protocols.lock().clone()
+	}
+
+	/// Returns the bandwidth usage in the format of the network state.
+	pub fn network_state(&self) -> HashMap<String, network_state::ProtocolBandwidth> {
+		self.protocols
+			.lock()
+			.iter()
+			.map(|(protocol, (total_inbound, total_outbound))| {
+				(
+					protocol.to_string(),
+					network_state::ProtocolBandwidth {
+						total_inbound: *total_inbound,
+						total_outbound: *total_outbound,
+					},
+				)
+			})
+			.collect()
+	}
+}
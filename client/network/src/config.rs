@@ -26,7 +26,7 @@ pub use crate::{
 	discovery::DEFAULT_KADEMLIA_REPLICATION_FACTOR,
 	protocol::NotificationsSink,
 	request_responses::{
-		IncomingRequest, OutgoingResponse, ProtocolConfig as RequestResponseConfig,
+		IncomingRequest, OutgoingResponse, ProtocolConfig as RequestResponseConfig, RateLimit,
 	},
 	types::ProtocolName,
 };
@@ -557,6 +557,9 @@ pub struct NetworkConfiguration {
 	/// Initial syncing mode.
 	pub sync_mode: SyncMode,
 
+	/// Snapshot imported by the service before syncing starts.
+	pub snapshot: Option<PathBuf>,
+
 	/// True if Kademlia random discovery should be enabled.
 	///
 	/// If true, the node will automatically randomly walk the DHT in order to find new peers.
@@ -575,9 +578,16 @@ pub struct NetworkConfiguration {
 	/// `kademlia_replication_factor` peers to consider record successfully put.
 	pub kademlia_replication_factor: NonZeroUsize,
 
+	/// Limits on the incoming block and state requests of each peer. `None` to not limit them.
+	pub request_rate_limit: Option<RateLimit>,
+
 	/// Enable serving block data over IPFS bitswap.
 	pub ipfs_server: bool,
 
+	/// Publish the state of every finalized block whose number is a multiple of this interval over
+	/// IPFS bitswap. `None` to not publish states. Requires `ipfs_server`.
+	pub ipfs_state_interval: Option<u32>,
+
 	/// Size of Yamux receive window of all substreams. `None` for the default (256kiB).
 	/// Any value less than 256kiB is invalid.
 	///
@@ -623,13 +633,16 @@ impl NetworkConfiguration {
 			max_parallel_downloads: 5,
 			max_blocks_per_request: 64,
 			sync_mode: SyncMode::Full,
+			snapshot: None,
 			enable_dht_random_walk: true,
 			allow_non_globals_in_dht: false,
 			kademlia_disjoint_query_paths: false,
 			kademlia_replication_factor: NonZeroUsize::new(DEFAULT_KADEMLIA_REPLICATION_FACTOR)
 				.expect("value is a constant; constant is non-zero; qed."),
 			yamux_window_size: None,
+			request_rate_limit: None,
 			ipfs_server: false,
+			ipfs_state_interval: None,
 		}
 	}
 
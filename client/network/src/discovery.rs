@@ -408,6 +408,24 @@ impl DiscoveryBehaviour {
 		}
 	}
 
+	/// Start announcing the local node as a provider of the value under `key` in the DHT.
+	///
+	/// The provider record is republished until [`DiscoveryBehaviour::stop_providing`] is called.
+	pub fn start_providing(&mut self, key: RecordKey) {
+		if let Some(k) = self.kademlia.as_mut() {
+			if let Err(e) = k.start_providing(key) {
+				warn!(target: "sub-libp2p", "Libp2p => Failed to start providing: {:?}", e);
+			}
+		}
+	}
+
+	/// Stop announcing the local node as a provider of the value under `key`.
+	pub fn stop_providing(&mut self, key: &RecordKey) {
+		if let Some(k) = self.kademlia.as_mut() {
+			k.stop_providing(key);
+		}
+	}
+
 	/// Returns the number of nodes in each Kademlia kbucket for each Kademlia instance.
 	///
 	/// Identifies Kademlia instances by their [`ProtocolId`] and kbuckets by the base 2 logarithm
@@ -891,6 +909,22 @@ impl NetworkBehaviour for DiscoveryBehaviour {
 							e.key(), e,
 						),
 					},
+					KademliaEvent::OutboundQueryProgressed {
+						result:
+							QueryResult::StartProviding(res) | QueryResult::RepublishProvider(res),
+						..
+					} => match res {
+						Ok(ok) => debug!(
+							target: "sub-libp2p",
+							"Libp2p => Provider record published: {:?}",
+							ok.key,
+						),
+						Err(e) => debug!(
+							target: "sub-libp2p",
+							"Libp2p => Publishing of provider record {:?} failed with: {:?}",
+							e.key(), e,
+						),
+					},
 					// We never start any other type of query.
 					KademliaEvent::OutboundQueryProgressed { result: e, .. } => {
 						warn!(target: "sub-libp2p", "Libp2p => Unhandled Kademlia event: {:?}", e)
@@ -250,6 +250,7 @@ mod protocol;
 mod protocol_controller;
 mod service;
 
+pub mod bandwidth;
 pub mod config;
 pub mod discovery;
 pub mod error;
@@ -51,6 +51,19 @@ pub struct NetworkState {
 	pub not_connected_peers: HashMap<String, NotConnectedPeer>,
 	/// State of the peerset manager.
 	pub peerset: serde_json::Value,
+	/// Bandwidth used by each notification and request-response protocol.
+	#[serde(default)]
+	pub protocols_bandwidth: HashMap<String, ProtocolBandwidth>,
+}
+
+/// Part of the `NetworkState` struct. Unstable.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolBandwidth {
+	/// Number of payload bytes received on this protocol.
+	pub total_inbound: u64,
+	/// Number of payload bytes sent on this protocol.
+	pub total_outbound: u64,
 }
 
 /// Part of the `NetworkState` struct. Unstable.
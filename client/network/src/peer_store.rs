@@ -18,16 +18,20 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use libp2p::PeerId;
-use log::trace;
+use log::{trace, warn};
 use parking_lot::Mutex;
 use partial_sort::PartialSort;
 use sc_network_common::types::ReputationChange;
+use serde::{Deserialize, Serialize};
 use std::{
 	cmp::{Ord, Ordering, PartialOrd},
 	collections::{hash_map::Entry, HashMap, HashSet},
 	fmt::Debug,
-	sync::Arc,
-	time::{Duration, Instant},
+	fs, io,
+	path::{Path, PathBuf},
+	sync::{mpsc, Arc},
+	thread,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use wasm_timer::Delay;
 
@@ -49,6 +53,12 @@ const INVERSE_DECREMENT: i32 = 50;
 /// Amount of time between the moment we last updated the [`PeerStore`] entry and the moment we
 /// remove it, once the reputation value reaches 0.
 const FORGET_AFTER: Duration = Duration::from_secs(3600);
+/// Interval at which the reputations and bans are saved, if the [`PeerStore`] is persistent.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Name of the file the reputations and bans are saved to, in the network configuration
+/// directory.
+pub const PEER_STORE_FILE: &str = "peer_store.json";
 
 pub trait PeerStoreProvider: Debug + Send {
 	/// Check whether the peer is banned.
@@ -114,6 +124,21 @@ impl PeerStoreHandle {
 	pub fn add_known_peer(&mut self, peer_id: PeerId) {
 		self.inner.lock().add_known_peer(peer_id);
 	}
+
+	/// Ban a peer until `duration` has passed, or until it is unbanned if `duration` is `None`.
+	pub fn ban_peer(&mut self, peer_id: PeerId, duration: Option<Duration>) {
+		self.inner.lock().ban_peer(peer_id, duration);
+	}
+
+	/// Lift the ban of a peer, also resetting its reputation if it was banned because of it.
+	pub fn unban_peer(&mut self, peer_id: PeerId) {
+		self.inner.lock().unban_peer(peer_id);
+	}
+
+	/// Get the manually banned peers, along with the time their ban expires at.
+	pub fn banned_peers(&self) -> Vec<(PeerId, Option<SystemTime>)> {
+		self.inner.lock().bans.clone().into_iter().collect()
+	}
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -183,15 +208,119 @@ impl PeerInfo {
 	}
 }
 
+/// Reputations and bans as saved to disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedPeers {
+	/// Non-zero reputations, by base58-encoded `PeerId`.
+	reputations: HashMap<String, i32>,
+	/// Manual bans, by base58-encoded `PeerId`, with the UNIX timestamp in seconds they expire
+	/// at.
+	bans: HashMap<String, Option<u64>>,
+}
+
+/// Reputations and bans taken from the [`PeerStore`] to be saved.
+#[derive(Debug)]
+struct PeerStoreSnapshot {
+	reputations: Vec<(PeerId, i32)>,
+	bans: Vec<(PeerId, Option<SystemTime>)>,
+}
+
+impl From<PeerStoreSnapshot> for PersistedPeers {
+	fn from(snapshot: PeerStoreSnapshot) -> Self {
+		PersistedPeers {
+			reputations: snapshot
+				.reputations
+				.into_iter()
+				.map(|(peer_id, reputation)| (peer_id.to_base58(), reputation))
+				.collect(),
+			bans: snapshot
+				.bans
+				.into_iter()
+				.map(|(peer_id, until)| {
+					let until = until.map(|until| {
+						until.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+					});
+					(peer_id.to_base58(), until)
+				})
+				.collect(),
+		}
+	}
+}
+
+/// Writes the snapshots it's sent on a dedicated thread, so the lock of the [`PeerStore`] is never
+/// held while writing to disk.
+#[derive(Debug)]
+struct PeerStoreWriter {
+	snapshots: Option<mpsc::Sender<PeerStoreSnapshot>>,
+	thread: Option<thread::JoinHandle<()>>,
+}
+
+impl PeerStoreWriter {
+	fn new(path: PathBuf) -> Self {
+		let (snapshots, receiver) = mpsc::channel::<PeerStoreSnapshot>();
+		let thread = thread::Builder::new().name("peer-store-writer".into()).spawn(move || {
+			while let Ok(snapshot) = receiver.recv() {
+				// Only the latest snapshot needs to be written.
+				let snapshot = receiver.try_iter().last().unwrap_or(snapshot);
+				if let Err(e) = write_persisted_peers(&path, &snapshot.into()) {
+					warn!(
+						target: LOG_TARGET,
+						"Failed to save the peer store to {}: {}",
+						path.display(),
+						e,
+					);
+				}
+			}
+		});
+
+		match thread {
+			Ok(thread) => Self { snapshots: Some(snapshots), thread: Some(thread) },
+			Err(e) => {
+				warn!(target: LOG_TARGET, "Failed to start the peer store writer: {}", e);
+				Self { snapshots: None, thread: None }
+			},
+		}
+	}
+
+	/// Queue `snapshot` to be written.
+	fn write(&self, snapshot: PeerStoreSnapshot) {
+		if let Some(snapshots) = &self.snapshots {
+			let _ = snapshots.send(snapshot);
+		}
+	}
+}
+
+impl Drop for PeerStoreWriter {
+	fn drop(&mut self) {
+		// Closing the channel stops the thread once the last snapshot is written.
+		self.snapshots = None;
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
+
 #[derive(Debug)]
 struct PeerStoreInner {
 	peers: HashMap<PeerId, PeerInfo>,
+	/// Manually banned peers, with the time their ban expires at.
+	bans: HashMap<PeerId, Option<SystemTime>>,
 	protocols: Vec<ProtocolHandle>,
+	/// Writer of the reputations and bans, if the store is persistent.
+	writer: Option<PeerStoreWriter>,
 }
 
 impl PeerStoreInner {
 	fn is_banned(&self, peer_id: &PeerId) -> bool {
-		self.peers.get(peer_id).map_or(false, |info| info.is_banned())
+		self.is_manually_banned(peer_id) ||
+			self.peers.get(peer_id).map_or(false, |info| info.is_banned())
+	}
+
+	fn is_manually_banned(&self, peer_id: &PeerId) -> bool {
+		self.bans
+			.get(peer_id)
+			.map_or(false, |until| until.map_or(true, |until| until > SystemTime::now()))
 	}
 
 	fn register_protocol(&mut self, protocol_handle: ProtocolHandle) {
@@ -239,6 +368,12 @@ impl PeerStoreInner {
 	}
 
 	fn peer_reputation(&self, peer_id: &PeerId) -> i32 {
+		// Manually banned peers are reported with the lowest reputation, so that everything
+		// checking the reputation against `BANNED_THRESHOLD` also refuses them.
+		if self.is_manually_banned(peer_id) {
+			return i32::MIN
+		}
+
 		self.peers.get(peer_id).map_or(0, |info| info.reputation)
 	}
 
@@ -247,7 +382,10 @@ impl PeerStoreInner {
 			.peers
 			.iter()
 			.filter_map(|(peer_id, info)| {
-				(!info.is_banned() && !ignored.contains(peer_id)).then_some((*peer_id, *info))
+				(!info.is_banned() &&
+					!self.is_manually_banned(peer_id) &&
+					!ignored.contains(peer_id))
+				.then_some((*peer_id, *info))
 			})
 			.collect::<Vec<_>>();
 		let count = std::cmp::min(count, candidates.len());
@@ -271,6 +409,55 @@ impl PeerStoreInner {
 		let now = Instant::now();
 		self.peers
 			.retain(|_, info| info.reputation != 0 || info.last_updated + FORGET_AFTER > now);
+
+		// Forget expired bans.
+		let now = SystemTime::now();
+		self.bans.retain(|_, until| until.map_or(true, |until| until > now));
+	}
+
+	fn ban_peer(&mut self, peer_id: PeerId, duration: Option<Duration>) {
+		// A ban ending past what `SystemTime` can represent is as good as permanent.
+		let until = duration.and_then(|duration| SystemTime::now().checked_add(duration));
+		self.bans.insert(peer_id, until);
+		self.protocols.iter().for_each(|handle| handle.disconnect_peer(peer_id));
+
+		log::debug!(
+			target: LOG_TARGET,
+			"Banned {} {}, disconnecting.",
+			peer_id,
+			duration.map_or("until unbanned".into(), |duration| format!("for {:?}", duration)),
+		);
+
+		self.save();
+	}
+
+	fn unban_peer(&mut self, peer_id: PeerId) {
+		self.bans.remove(&peer_id);
+		if let Some(info) = self.peers.get_mut(&peer_id) {
+			if info.is_banned() {
+				info.reputation = 0;
+				info.bump_last_updated();
+			}
+		}
+
+		log::debug!(target: LOG_TARGET, "Unbanned {}.", peer_id);
+
+		self.save();
+	}
+
+	/// Save the reputations and bans in the background, if the store is persistent.
+	fn save(&self) {
+		let Some(writer) = &self.writer else { return };
+
+		writer.write(PeerStoreSnapshot {
+			reputations: self
+				.peers
+				.iter()
+				.filter(|(_, info)| info.reputation != 0)
+				.map(|(peer_id, info)| (*peer_id, info.reputation))
+				.collect(),
+			bans: self.bans.iter().map(|(peer_id, until)| (*peer_id, *until)).collect(),
+		});
 	}
 
 	fn add_known_peer(&mut self, peer_id: PeerId) {
@@ -297,14 +484,56 @@ pub struct PeerStore {
 
 impl PeerStore {
 	/// Create a new peer store from the list of bootnodes.
-	pub fn new(bootnodes: Vec<PeerId>) -> Self {
+	///
+	/// If `path` is provided, the reputations and bans saved to it are restored, and they are
+	/// saved to it periodically and whenever a peer is banned or unbanned.
+	pub fn new(bootnodes: Vec<PeerId>, path: Option<PathBuf>) -> Self {
+		let mut peers = bootnodes
+			.into_iter()
+			.map(|peer_id| (peer_id, PeerInfo::default()))
+			.collect::<HashMap<_, _>>();
+		let mut bans = HashMap::new();
+
+		if let Some(path) = &path {
+			match read_persisted_peers(path) {
+				Ok(persisted) => {
+					for (peer_id, reputation) in persisted.reputations {
+						if let Ok(peer_id) = peer_id.parse::<PeerId>() {
+							peers.entry(peer_id).or_default().reputation = reputation;
+						}
+					}
+
+					let now = SystemTime::now();
+					for (peer_id, until) in persisted.bans {
+						let until =
+							until.map(|until| UNIX_EPOCH.checked_add(Duration::from_secs(until)));
+						let until = match until {
+							None => None,
+							Some(Some(until)) if until > now => Some(until),
+							// Expired, or not representable on this platform.
+							Some(_) => continue,
+						};
+						if let Ok(peer_id) = peer_id.parse::<PeerId>() {
+							bans.insert(peer_id, until);
+						}
+					}
+				},
+				Err(e) if e.kind() == io::ErrorKind::NotFound => {},
+				Err(e) => warn!(
+					target: LOG_TARGET,
+					"Failed to restore the peer store from {}: {}",
+					path.display(),
+					e,
+				),
+			}
+		}
+
 		PeerStore {
 			inner: Arc::new(Mutex::new(PeerStoreInner {
-				peers: bootnodes
-					.into_iter()
-					.map(|peer_id| (peer_id, PeerInfo::default()))
-					.collect(),
+				peers,
+				bans,
 				protocols: Vec::new(),
+				writer: path.map(PeerStoreWriter::new),
 			})),
 		}
 	}
@@ -318,6 +547,7 @@ impl PeerStore {
 	pub async fn run(self) {
 		let started = Instant::now();
 		let mut latest_time_update = started;
+		let mut latest_save = started;
 
 		loop {
 			let now = Instant::now();
@@ -330,15 +560,40 @@ impl PeerStore {
 				elapsed_now.as_secs() - elapsed_latest.as_secs()
 			};
 
-			self.inner.lock().progress_time(seconds_passed);
+			{
+				let mut inner = self.inner.lock();
+				inner.progress_time(seconds_passed);
+				if now.duration_since(latest_save) >= SAVE_INTERVAL {
+					inner.save();
+					latest_save = now;
+				}
+			}
 			let _ = Delay::new(Duration::from_secs(1)).await;
 		}
 	}
 }
 
+fn read_persisted_peers(path: &Path) -> io::Result<PersistedPeers> {
+	let file = fs::File::open(path)?;
+	serde_json::from_reader(io::BufReader::new(file))
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write the reputations and bans to a temporary file first, so a crash never leaves a truncated
+/// file behind.
+fn write_persisted_peers(path: &Path, persisted: &PersistedPeers) -> io::Result<()> {
+	let tmp_path = path.with_extension("tmp");
+	let file = fs::File::create(&tmp_path)?;
+	serde_json::to_writer(io::BufWriter::new(file), persisted)
+		.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	fs::rename(tmp_path, path)
+}
+
 #[cfg(test)]
 mod tests {
-	use super::PeerInfo;
+	use super::{write_persisted_peers, PeerInfo, PeerStore, PersistedPeers};
+	use libp2p::PeerId;
+	use std::time::Duration;
 
 	#[test]
 	fn decaying_zero_reputation_yields_zero() {
@@ -405,4 +660,72 @@ mod tests {
 		peer_info.decay_reputation(SECONDS / 2);
 		assert_eq!(peer_info.reputation, 0);
 	}
+
+	#[test]
+	fn bans_and_reputations_are_persisted() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(super::PEER_STORE_FILE);
+		let banned = PeerId::random();
+		let expired = PeerId::random();
+		let reported = PeerId::random();
+
+		{
+			let store = PeerStore::new(Vec::new(), Some(path.clone()));
+			let mut handle = store.handle();
+			handle.ban_peer(banned, None);
+			handle.ban_peer(expired, Some(Duration::ZERO));
+			store.inner.lock().peers.entry(reported).or_default().reputation = -100;
+			store.inner.lock().save();
+			// Dropping the store waits for the pending snapshot to be written.
+		}
+
+		let store = PeerStore::new(Vec::new(), Some(path));
+		let inner = store.inner.lock();
+		assert!(inner.is_banned(&banned));
+		assert_eq!(inner.peer_reputation(&banned), i32::MIN);
+		assert!(!inner.is_banned(&expired));
+		assert!(!inner.bans.contains_key(&expired));
+		assert_eq!(inner.peer_reputation(&reported), -100);
+	}
+
+	#[test]
+	fn bans_past_the_end_of_time_are_permanent() {
+		let store = PeerStore::new(Vec::new(), None);
+		let mut handle = store.handle();
+		let peer_id = PeerId::random();
+
+		handle.ban_peer(peer_id, Some(Duration::from_secs(u64::MAX)));
+		assert_eq!(handle.banned_peers(), vec![(peer_id, None)]);
+		assert!(store.inner.lock().is_banned(&peer_id));
+	}
+
+	#[test]
+	fn unrepresentable_persisted_bans_are_dropped() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(super::PEER_STORE_FILE);
+		let peer_id = PeerId::random();
+
+		let mut persisted = PersistedPeers::default();
+		persisted.bans.insert(peer_id.to_base58(), Some(u64::MAX));
+		write_persisted_peers(&path, &persisted).unwrap();
+
+		let store = PeerStore::new(Vec::new(), Some(path));
+		assert!(store.handle().banned_peers().is_empty());
+		assert!(!store.inner.lock().is_banned(&peer_id));
+	}
+
+	#[test]
+	fn unbanning_resets_reputation() {
+		let store = PeerStore::new(Vec::new(), None);
+		let mut handle = store.handle();
+		let peer_id = PeerId::random();
+
+		store.inner.lock().peers.entry(peer_id).or_default().reputation = i32::MIN;
+		handle.ban_peer(peer_id, None);
+		assert_eq!(handle.banned_peers(), vec![(peer_id, None)]);
+
+		handle.unban_peer(peer_id);
+		assert!(handle.banned_peers().is_empty());
+		assert!(!store.inner.lock().is_banned(&peer_id));
+	}
 }
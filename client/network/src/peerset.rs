@@ -50,8 +50,10 @@ use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnbound
 use serde_json::json;
 use std::{
 	collections::HashSet,
+	path::PathBuf,
 	pin::Pin,
 	task::{Context, Poll},
+	time::{Duration, SystemTime},
 };
 
 use libp2p::PeerId;
@@ -67,6 +69,9 @@ enum Action {
 	ReportPeer(PeerId, ReputationChange),
 	AddKnownPeer(PeerId),
 	PeerReputation(PeerId, oneshot::Sender<i32>),
+	BanPeer(PeerId, Option<Duration>),
+	UnbanPeer(PeerId),
+	BannedPeers(oneshot::Sender<Vec<(PeerId, Option<SystemTime>)>>),
 }
 
 /// Identifier of a set in the peerset.
@@ -151,6 +156,27 @@ impl PeersetHandle {
 		// The channel can only be closed if the peerset no longer exists.
 		rx.await.map_err(|_| ())
 	}
+
+	/// Bans a peer, disconnecting from it and refusing any connection with it until `duration`
+	/// has passed, or until it is unbanned if `duration` is `None`.
+	pub fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>) {
+		let _ = self.tx.unbounded_send(Action::BanPeer(peer_id, duration));
+	}
+
+	/// Lifts the ban of a peer.
+	pub fn unban_peer(&self, peer_id: PeerId) {
+		let _ = self.tx.unbounded_send(Action::UnbanPeer(peer_id));
+	}
+
+	/// Returns the manually banned peers, along with the time their ban expires at.
+	pub async fn banned_peers(self) -> Result<Vec<(PeerId, Option<SystemTime>)>, ()> {
+		let (tx, rx) = oneshot::channel();
+
+		let _ = self.tx.unbounded_send(Action::BannedPeers(tx));
+
+		// The channel can only be closed if the peerset no longer exists.
+		rx.await.map_err(|_| ())
+	}
 }
 
 /// Message that can be sent by the peer set manager (PSM).
@@ -195,6 +221,10 @@ impl From<u64> for IncomingIndex {
 pub struct PeersetConfig {
 	/// List of sets of nodes the peerset manages.
 	pub sets: Vec<SetConfig>,
+
+	/// File the reputations and bans of the peers are saved to. If `None`, they are only kept
+	/// in memory.
+	pub peer_store_path: Option<PathBuf>,
 }
 
 /// Configuration for a single set of nodes.
@@ -246,7 +276,8 @@ impl Peerset {
 	/// Builds a new peerset from the given configuration.
 	pub fn from_config(config: PeersetConfig) -> (Peerset, PeersetHandle) {
 		let default_set_config = &config.sets[0];
-		let peer_store = PeerStore::new(default_set_config.bootnodes.clone());
+		let peer_store =
+			PeerStore::new(default_set_config.bootnodes.clone(), config.peer_store_path);
 
 		let (to_notifications, from_controllers) =
 			tracing_unbounded("mpsc_protocol_controllers_to_notifications", 10_000);
@@ -360,6 +391,12 @@ impl Stream for Peerset {
 						let _ =
 							pending_response.send(self.peer_store_handle.peer_reputation(&peer_id));
 					},
+					Action::BanPeer(peer_id, duration) =>
+						self.peer_store_handle.ban_peer(peer_id, duration),
+					Action::UnbanPeer(peer_id) => self.peer_store_handle.unban_peer(peer_id),
+					Action::BannedPeers(pending_response) => {
+						let _ = pending_response.send(self.peer_store_handle.banned_peers());
+					},
 				}
 			} else {
 				debug!(target: LOG_TARGET, "`PeersetHandle` was dropped, terminating `Peerset`.");
@@ -157,7 +157,13 @@ impl<B: BlockT> Protocol<B> {
 				});
 			}
 
-			crate::peerset::Peerset::from_config(crate::peerset::PeersetConfig { sets })
+			crate::peerset::Peerset::from_config(crate::peerset::PeersetConfig {
+				sets,
+				peer_store_path: network_config
+					.net_config_path
+					.as_ref()
+					.map(|path| path.join(crate::peer_store::PEER_STORE_FILE)),
+			})
 		};
 
 		let behaviour = {
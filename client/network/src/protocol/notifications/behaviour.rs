@@ -2149,7 +2149,10 @@ mod tests {
 				reserved_only: false,
 			});
 
-			crate::peerset::Peerset::from_config(crate::peerset::PeersetConfig { sets })
+			crate::peerset::Peerset::from_config(crate::peerset::PeersetConfig {
+				sets,
+				peer_store_path: None,
+			})
 		};
 
 		(
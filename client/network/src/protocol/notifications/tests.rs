@@ -83,6 +83,7 @@ fn build_nodes() -> (Swarm<CustomProtoWithAddr>, Swarm<CustomProtoWithAddr>) {
 					reserved_nodes: Default::default(),
 					reserved_only: false,
 				}],
+				peer_store_path: None,
 			});
 
 		let behaviour = CustomProtoWithAddr {
@@ -34,9 +34,13 @@
 //!
 //! - If provided, a ["requests processing"](ProtocolConfig::inbound_queue) channel
 //! is used to handle incoming requests.
+//!
+//! - If provided, a [rate limit](ProtocolConfig::inbound_rate_limit) bounds the number of requests
+//! each peer can make and the number of response bytes sent back to it.
 
 use crate::{
-	peer_store::BANNED_THRESHOLD, peerset::PeersetHandle, types::ProtocolName, ReputationChange,
+	bandwidth::ProtocolBandwidth, peer_store::BANNED_THRESHOLD, peerset::PeersetHandle,
+	types::ProtocolName, ReputationChange,
 };
 
 use futures::{channel::oneshot, prelude::*};
@@ -56,12 +60,17 @@ use std::{
 	collections::{hash_map::Entry, HashMap},
 	io, iter,
 	pin::Pin,
+	sync::Arc,
 	task::{Context, Poll},
 	time::{Duration, Instant},
 };
 
 pub use libp2p::request_response::{Config, InboundFailure, OutboundFailure, RequestId};
 
+/// Reputation change of a peer whose request is refused as it exceeded the rate limit.
+const RATE_LIMIT_EXCEEDED: ReputationChange =
+	ReputationChange::new(-(1 << 10), "Request rate limit exceeded");
+
 /// Error in a request.
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -125,6 +134,68 @@ pub struct ProtocolConfig {
 	/// advertise support for this protocol, but any incoming request will lead to an error being
 	/// sent back.
 	pub inbound_queue: Option<async_channel::Sender<IncomingRequest>>,
+
+	/// Limits on the incoming requests of each peer.
+	///
+	/// Requests of a peer exceeding the limit are refused without being pushed on the
+	/// `inbound_queue`. `None` if requests are not limited.
+	pub inbound_rate_limit: Option<RateLimit>,
+}
+
+/// Limits on the incoming requests of a single peer on a request-response protocol.
+///
+/// Within each `period`, a peer can make at most `max_requests` requests, and its requests are
+/// refused once `max_response_bytes` bytes of responses have been sent to it. The size of a
+/// response isn't known before the request is handled, so the response crossing the byte limit is
+/// still sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+	/// Maximum number of requests per period.
+	pub max_requests: u32,
+	/// Maximum number of response bytes per period.
+	pub max_response_bytes: u64,
+	/// Duration of a period.
+	pub period: Duration,
+}
+
+impl Default for RateLimit {
+	/// Limits high enough for a peer syncing from the local node, while keeping it from
+	/// saturating the uplink.
+	fn default() -> Self {
+		Self {
+			max_requests: 300,
+			max_response_bytes: 256 * 1024 * 1024,
+			period: Duration::from_secs(60),
+		}
+	}
+}
+
+/// Requests made by a peer on a rate limited protocol during the current period.
+#[derive(Debug, Clone, Copy)]
+struct RateLimitWindow {
+	start: Instant,
+	requests: u32,
+	response_bytes: u64,
+}
+
+impl RateLimitWindow {
+	fn new() -> Self {
+		Self { start: Instant::now(), requests: 0, response_bytes: 0 }
+	}
+
+	/// Accounts for a new request, returning `false` if it exceeds `limit`.
+	fn on_request(&mut self, limit: &RateLimit) -> bool {
+		if self.start.elapsed() >= limit.period {
+			*self = Self::new();
+		}
+
+		if self.requests >= limit.max_requests || self.response_bytes >= limit.max_response_bytes {
+			return false
+		}
+
+		self.requests += 1;
+		true
+	}
 }
 
 /// A single request received by a peer on a request-response protocol.
@@ -282,6 +353,15 @@ pub struct RequestResponsesBehaviour {
 	/// Primarily used to get a reputation of a node.
 	peerset: PeersetHandle,
 
+	/// Limits on the incoming requests of each peer, by protocol.
+	rate_limits: HashMap<ProtocolName, RateLimit>,
+
+	/// Requests of each peer during the current period, on rate limited protocols.
+	rate_limit_windows: HashMap<(PeerId, ProtocolName), RateLimitWindow>,
+
+	/// Bytes received and sent on each protocol.
+	bandwidth: Arc<ProtocolBandwidth>,
+
 	/// Pending message request, holds `MessageRequest` as a Future state to poll it
 	/// until we get a response from `Peerset`
 	message_request: Option<MessageRequest>,
@@ -319,8 +399,10 @@ impl RequestResponsesBehaviour {
 	pub fn new(
 		list: impl Iterator<Item = ProtocolConfig>,
 		peerset: PeersetHandle,
+		bandwidth: Arc<ProtocolBandwidth>,
 	) -> Result<Self, RegisterError> {
 		let mut protocols = HashMap::new();
+		let mut rate_limits = HashMap::new();
 		for protocol in list {
 			let mut cfg = Config::default();
 			cfg.set_connection_keep_alive(Duration::from_secs(10));
@@ -343,6 +425,10 @@ impl RequestResponsesBehaviour {
 				cfg,
 			);
 
+			if let Some(rate_limit) = protocol.inbound_rate_limit {
+				rate_limits.insert(protocol.name.clone(), rate_limit);
+			}
+
 			match protocols.entry(protocol.name) {
 				Entry::Vacant(e) => e.insert((rq_rp, protocol.inbound_queue)),
 				Entry::Occupied(e) => return Err(RegisterError::DuplicateProtocol(e.key().clone())),
@@ -356,6 +442,9 @@ impl RequestResponsesBehaviour {
 			pending_responses_arrival_time: Default::default(),
 			send_feedback: Default::default(),
 			peerset,
+			rate_limits,
+			rate_limit_windows: Default::default(),
+			bandwidth,
 			message_request: None,
 		})
 	}
@@ -376,11 +465,12 @@ impl RequestResponsesBehaviour {
 	) {
 		if let Some((protocol, _)) = self.protocols.get_mut(protocol_name) {
 			if protocol.is_connected(target) || connect.should_connect() {
+				let protocol_name = ProtocolName::from(protocol_name.to_string());
+				self.bandwidth.report_outbound(&protocol_name, request.len());
 				let request_id = protocol.send_request(target, request);
-				let prev_req_id = self.pending_requests.insert(
-					(protocol_name.to_string().into(), request_id).into(),
-					(Instant::now(), pending_response),
-				);
+				let prev_req_id = self
+					.pending_requests
+					.insert((protocol_name, request_id).into(), (Instant::now(), pending_response));
 				debug_assert!(prev_req_id.is_none(), "Expect request id to be unique.");
 			} else if pending_response.send(Err(RequestFailure::NotConnected)).is_err() {
 				log::debug!(
@@ -486,7 +576,11 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 				endpoint,
 				handler,
 				remaining_established,
-			}) =>
+			}) => {
+				if remaining_established == 0 {
+					self.rate_limit_windows.retain(|(peer, _), _| *peer != peer_id);
+				}
+
 				for (p_name, p_handler) in handler.into_iter() {
 					if let Some((proto, _)) = self.protocols.get_mut(p_name.as_str()) {
 						proto.on_swarm_event(FromSwarm::ConnectionClosed(ConnectionClosed {
@@ -503,7 +597,8 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 						  p_name,
 						)
 					}
-				},
+				}
+			},
 			FromSwarm::DialFailure(e) =>
 				for (p, _) in self.protocols.values_mut() {
 					NetworkBehaviour::on_swarm_event(p, FromSwarm::DialFailure(e));
@@ -680,6 +775,7 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 
 				if let Ok(payload) = result {
 					if let Some((protocol, _)) = self.protocols.get_mut(&*protocol_name) {
+						let response_len = payload.len();
 						if protocol.send_response(inner_channel, Ok(payload)).is_err() {
 							// Note: Failure is handled further below when receiving
 							// `InboundFailure` event from request-response [`Behaviour`].
@@ -690,9 +786,19 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 								 Dropping response",
 								request_id, protocol_name,
 							);
-						} else if let Some(sent_feedback) = sent_feedback {
-							self.send_feedback
-								.insert((protocol_name, request_id).into(), sent_feedback);
+						} else {
+							self.bandwidth.report_outbound(&protocol_name, response_len);
+							if let Some(window) =
+								self.rate_limit_windows.get_mut(&(peer, protocol_name.clone()))
+							{
+								window.response_bytes =
+									window.response_bytes.saturating_add(response_len as u64);
+							}
+
+							if let Some(sent_feedback) = sent_feedback {
+								self.send_feedback
+									.insert((protocol_name, request_id).into(), sent_feedback);
+							}
 						}
 					}
 				}
@@ -748,6 +854,33 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 							peer,
 							message: Message::Request { request_id, request, channel, .. },
 						} => {
+							self.bandwidth.report_inbound(protocol, request.len());
+
+							if let Some(rate_limit) = self.rate_limits.get(protocol) {
+								let within_limit = self
+									.rate_limit_windows
+									.entry((peer, protocol.clone()))
+									.or_insert_with(RateLimitWindow::new)
+									.on_request(rate_limit);
+								if !within_limit {
+									// Dropping `channel` refuses the request, which is reported by
+									// the request-response [`Behaviour`] as an
+									// `InboundFailure::ResponseOmission`.
+									log::debug!(
+										target: "sub-libp2p",
+										"Refusing request from {} on {}: rate limit exceeded",
+										peer,
+										protocol,
+									);
+									return Poll::Ready(ToSwarm::GenerateEvent(
+										Event::ReputationChanges {
+											peer,
+											changes: vec![RATE_LIMIT_EXCEEDED],
+										},
+									))
+								}
+							}
+
 							self.pending_responses_arrival_time
 								.insert((protocol.clone(), request_id).into(), Instant::now());
 
@@ -778,6 +911,10 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 							message: Message::Response { request_id, response },
 							..
 						} => {
+							if let Ok(payload) = &response {
+								self.bandwidth.report_inbound(protocol, payload.len());
+							}
+
 							let (started, delivered) = match self
 								.pending_requests
 								.remove(&(protocol.clone(), request_id).into())
@@ -1093,11 +1230,12 @@ mod tests {
 				reserved_nodes: Default::default(),
 				reserved_only: false,
 			}],
+			peer_store_path: None,
 		};
 
 		let (peerset, handle) = Peerset::from_config(config);
 
-		let behaviour = RequestResponsesBehaviour::new(list, handle).unwrap();
+		let behaviour = RequestResponsesBehaviour::new(list, handle, Default::default()).unwrap();
 
 		let runtime = tokio::runtime::Runtime::new().unwrap();
 		let mut swarm = SwarmBuilder::with_executor(
@@ -1153,6 +1291,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: Some(tx),
+					inbound_rate_limit: None,
 				};
 
 				build_swarm(iter::once(protocol_config))
@@ -1220,6 +1359,140 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn requests_exceeding_rate_limit_are_refused() {
+		let protocol_name = "/test/req-resp/1";
+		let mut pool = LocalPool::new();
+
+		// Build swarms accepting a single request per peer.
+		let mut swarms = (0..2)
+			.map(|_| {
+				let (tx, mut rx) = async_channel::bounded::<IncomingRequest>(64);
+
+				pool.spawner()
+					.spawn_obj(
+						async move {
+							while let Some(rq) = rx.next().await {
+								let _ = rq.pending_response.send(super::OutgoingResponse {
+									result: Ok(b"this is a response".to_vec()),
+									reputation_changes: Vec::new(),
+									sent_feedback: None,
+								});
+							}
+						}
+						.boxed()
+						.into(),
+					)
+					.unwrap();
+
+				let protocol_config = ProtocolConfig {
+					name: From::from(protocol_name),
+					fallback_names: Vec::new(),
+					max_request_size: 1024,
+					max_response_size: 1024 * 1024,
+					request_timeout: Duration::from_secs(30),
+					inbound_queue: Some(tx),
+					inbound_rate_limit: Some(RateLimit {
+						max_requests: 1,
+						max_response_bytes: 1024,
+						period: Duration::from_secs(60),
+					}),
+				};
+
+				build_swarm(iter::once(protocol_config))
+			})
+			.collect::<Vec<_>>();
+
+		{
+			let dial_addr = swarms[1].1.clone();
+			Swarm::dial(&mut swarms[0].0, dial_addr).unwrap();
+		}
+
+		// Run `swarm[0]` in the background, forwarding the reputation changes it issues.
+		let (reputation_tx, mut reputation_rx) = futures::channel::mpsc::unbounded();
+		let (mut swarm, _, peerset) = swarms.remove(0);
+		pool.spawner().spawn_obj(loop_peerset(peerset).boxed().into()).unwrap();
+		pool.spawner()
+			.spawn_obj({
+				async move {
+					loop {
+						if let SwarmEvent::Behaviour(Event::ReputationChanges { peer, changes }) =
+							swarm.select_next_some().await
+						{
+							let _ = reputation_tx.unbounded_send((peer, changes));
+						}
+					}
+				}
+				.boxed()
+				.into()
+			})
+			.unwrap();
+
+		// Send two requests from the remaining swarm, only one of which is answered.
+		let (mut swarm, _, peerset) = swarms.remove(0);
+		let local_peer_id = *swarm.local_peer_id();
+		pool.spawner().spawn_obj(loop_peerset(peerset).boxed().into()).unwrap();
+		pool.run_until(async move {
+			let mut response_receivers = Vec::new();
+			let mut finished = 0;
+
+			while finished < 2 {
+				match swarm.select_next_some().await {
+					SwarmEvent::ConnectionEstablished { peer_id, .. } =>
+						for _ in 0..2 {
+							let (sender, receiver) = oneshot::channel();
+							swarm.behaviour_mut().send_request(
+								&peer_id,
+								protocol_name,
+								b"this is a request".to_vec(),
+								sender,
+								IfDisconnected::ImmediateError,
+							);
+							response_receivers.push(receiver);
+						},
+					SwarmEvent::Behaviour(Event::RequestFinished { .. }) => finished += 1,
+					_ => {},
+				}
+			}
+
+			let mut answered = 0;
+			for receiver in response_receivers {
+				if receiver.await.unwrap().is_ok() {
+					answered += 1;
+				}
+			}
+			assert_eq!(answered, 1);
+
+			assert_eq!(
+				reputation_rx.next().await,
+				Some((local_peer_id, vec![RATE_LIMIT_EXCEEDED])),
+			);
+		});
+	}
+
+	#[test]
+	fn rate_limit_window_refuses_excess_requests() {
+		let limit =
+			RateLimit { max_requests: 2, max_response_bytes: 100, period: Duration::from_secs(60) };
+
+		let mut window = RateLimitWindow::new();
+		assert!(window.on_request(&limit));
+		assert!(window.on_request(&limit));
+		assert!(!window.on_request(&limit));
+
+		let mut window = RateLimitWindow::new();
+		assert!(window.on_request(&limit));
+		window.response_bytes = 100;
+		assert!(!window.on_request(&limit));
+
+		// A new period starts from scratch.
+		let limit = RateLimit { period: Duration::from_millis(1), ..limit };
+		std::thread::sleep(limit.period);
+		assert!(window.on_request(&limit));
+		assert_eq!(window.requests, 1);
+		assert_eq!(window.response_bytes, 0);
+	}
+
 	#[test]
 	fn max_response_size_exceeded() {
 		let protocol_name = "/test/req-resp/1";
@@ -1254,6 +1527,7 @@ mod tests {
 					max_response_size: 8, // <-- important for the test
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: Some(tx),
+					inbound_rate_limit: None,
 				};
 
 				build_swarm(iter::once(protocol_config))
@@ -1351,6 +1625,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: None,
+					inbound_rate_limit: None,
 				},
 				ProtocolConfig {
 					name: From::from(protocol_name_2),
@@ -1359,6 +1634,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: None,
+					inbound_rate_limit: None,
 				},
 			];
 
@@ -1377,6 +1653,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: Some(tx_1),
+					inbound_rate_limit: None,
 				},
 				ProtocolConfig {
 					name: From::from(protocol_name_2),
@@ -1385,6 +1662,7 @@ mod tests {
 					max_response_size: 1024 * 1024,
 					request_timeout: Duration::from_secs(30),
 					inbound_queue: Some(tx_2),
+					inbound_rate_limit: None,
 				},
 			];
 
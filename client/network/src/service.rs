@@ -29,6 +29,7 @@
 //! which is then processed by [`NetworkWorker::next_action`].
 
 use crate::{
+	bandwidth::ProtocolBandwidth,
 	behaviour::{self, Behaviour, BehaviourOut},
 	config::{parse_addr, FullNetworkConfiguration, MultiaddrWithPeerId, Params, TransportConfig},
 	discovery::DiscoveryConfig,
@@ -88,6 +89,7 @@ use std::{
 		atomic::{AtomicUsize, Ordering},
 		Arc,
 	},
+	time::{Duration, SystemTime},
 };
 
 pub use behaviour::{InboundFailure, OutboundFailure, ResponseFailure};
@@ -114,6 +116,8 @@ pub struct NetworkService<B: BlockT + 'static, H: ExHashT> {
 	local_identity: Keypair,
 	/// Bandwidth logging system. Can be queried to know the average bandwidth consumed.
 	bandwidth: Arc<transport::BandwidthSinks>,
+	/// Bytes received and sent on each notification and request-response protocol.
+	protocol_bandwidth: Arc<ProtocolBandwidth>,
 	/// Peerset manager (PSM); manages the reputation of nodes and indicates the network which
 	/// nodes it should be connected to or not.
 	peerset: PeersetHandle,
@@ -302,6 +306,7 @@ where
 		})?;
 
 		let num_connected = Arc::new(AtomicUsize::new(0));
+		let protocol_bandwidth = Arc::new(ProtocolBandwidth::default());
 
 		// Build the swarm.
 		let (mut swarm, bandwidth): (Swarm<Behaviour<B>>, _) = {
@@ -350,6 +355,7 @@ where
 					discovery_config,
 					request_response_protocols,
 					peerset_handle.clone(),
+					protocol_bandwidth.clone(),
 					ConnectionLimits::default()
 						.with_max_established_per_peer(Some(crate::MAX_CONNECTIONS_PER_PEER as u32))
 						.with_max_established_incoming(Some(
@@ -395,6 +401,7 @@ where
 				registry,
 				MetricSources {
 					bandwidth: bandwidth.clone(),
+					protocol_bandwidth: protocol_bandwidth.clone(),
 					connected_peers: num_connected.clone(),
 				},
 			)?),
@@ -419,6 +426,7 @@ where
 
 		let service = Arc::new(NetworkService {
 			bandwidth,
+			protocol_bandwidth,
 			external_addresses: external_addresses.clone(),
 			listen_addresses: listen_addresses.clone(),
 			num_connected: num_connected.clone(),
@@ -603,6 +611,7 @@ where
 			connected_peers,
 			not_connected_peers,
 			peerset: swarm.behaviour_mut().user_protocol_mut().peerset_debug_info(),
+			protocols_bandwidth: self.service.protocol_bandwidth.network_state(),
 		}
 	}
 
@@ -646,6 +655,27 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
 		}
 	}
 
+	/// Bans a peer, disconnecting from it and refusing any connection with it until `duration`
+	/// has passed, or until it is unbanned if `duration` is `None`.
+	///
+	/// Bans are saved along with the reputations of the peers, and thus survive restarts if the
+	/// network configuration has a `net_config_path`.
+	pub fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>) {
+		self.peerset.ban_peer(peer_id, duration);
+	}
+
+	/// Lifts the ban of a peer, also resetting its reputation if it was too low to connect.
+	pub fn unban_peer(&self, peer_id: PeerId) {
+		self.peerset.unban_peer(peer_id);
+	}
+
+	/// Get the list of manually banned peers, along with the time their ban expires at.
+	///
+	/// Returns an error if the `NetworkWorker` is no longer running.
+	pub async fn banned_peers(&self) -> Result<Vec<(PeerId, Option<SystemTime>)>, ()> {
+		self.peerset.clone().banned_peers().await
+	}
+
 	/// Get the list of reserved peers.
 	///
 	/// Returns an error if the `NetworkWorker` is no longer running.
@@ -739,6 +769,18 @@ where
 	fn put_value(&self, key: KademliaKey, value: Vec<u8>) {
 		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::PutValue(key, value));
 	}
+
+	/// Start announcing the local node as a provider of `key` in the DHT.
+	///
+	/// The provider record is republished periodically until `stop_providing` is called.
+	fn start_providing(&self, key: KademliaKey) {
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::StartProviding(key));
+	}
+
+	/// Stop announcing the local node as a provider of `key`.
+	fn stop_providing(&self, key: KademliaKey) {
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::StopProviding(key));
+	}
 }
 
 #[async_trait::async_trait]
@@ -926,6 +968,7 @@ where
 				.with_label_values(&["out", &protocol])
 				.observe(message.len() as f64);
 		}
+		self.protocol_bandwidth.report_outbound(&protocol, message.len());
 
 		// Sending is communicated to the `NotificationsSink`.
 		trace!(
@@ -958,7 +1001,12 @@ where
 			.as_ref()
 			.map(|histogram| histogram.with_label_values(&["out", &protocol]));
 
-		Ok(Box::new(NotificationSender { sink, protocol_name: protocol, notification_size_metric }))
+		Ok(Box::new(NotificationSender {
+			sink,
+			protocol_name: protocol,
+			notification_size_metric,
+			protocol_bandwidth: self.protocol_bandwidth.clone(),
+		}))
 	}
 
 	fn set_notification_handshake(&self, protocol: ProtocolName, handshake: Vec<u8>) {
@@ -1023,6 +1071,9 @@ pub struct NotificationSender {
 	/// Field extracted from the [`Metrics`] struct and necessary to report the
 	/// notifications-related metrics.
 	notification_size_metric: Option<Histogram>,
+
+	/// Bytes received and sent on each protocol.
+	protocol_bandwidth: Arc<ProtocolBandwidth>,
 }
 
 #[async_trait::async_trait]
@@ -1038,6 +1089,7 @@ impl NotificationSenderT for NotificationSender {
 			peer_id: self.sink.peer_id(),
 			protocol_name: &self.protocol_name,
 			notification_size_metric: self.notification_size_metric.clone(),
+			protocol_bandwidth: &self.protocol_bandwidth,
 		}))
 	}
 }
@@ -1056,6 +1108,9 @@ pub struct NotificationSenderReady<'a> {
 	/// Field extracted from the [`Metrics`] struct and necessary to report the
 	/// notifications-related metrics.
 	notification_size_metric: Option<Histogram>,
+
+	/// Bytes received and sent on each protocol.
+	protocol_bandwidth: &'a ProtocolBandwidth,
 }
 
 impl<'a> NotificationSenderReadyT for NotificationSenderReady<'a> {
@@ -1063,6 +1118,7 @@ impl<'a> NotificationSenderReadyT for NotificationSenderReady<'a> {
 		if let Some(notification_size_metric) = &self.notification_size_metric {
 			notification_size_metric.observe(notification.len() as f64);
 		}
+		self.protocol_bandwidth.report_outbound(self.protocol_name, notification.len());
 
 		trace!(
 			target: "sub-libp2p",
@@ -1085,6 +1141,8 @@ impl<'a> NotificationSenderReadyT for NotificationSenderReady<'a> {
 enum ServiceToWorkerMsg {
 	GetValue(KademliaKey),
 	PutValue(KademliaKey, Vec<u8>),
+	StartProviding(KademliaKey),
+	StopProviding(KademliaKey),
 	AddKnownAddress(PeerId, Multiaddr),
 	SetReservedOnly(bool),
 	AddReserved(PeerId),
@@ -1233,6 +1291,10 @@ where
 				self.network_service.behaviour_mut().get_value(key),
 			ServiceToWorkerMsg::PutValue(key, value) =>
 				self.network_service.behaviour_mut().put_value(key, value),
+			ServiceToWorkerMsg::StartProviding(key) =>
+				self.network_service.behaviour_mut().start_providing(key),
+			ServiceToWorkerMsg::StopProviding(key) =>
+				self.network_service.behaviour_mut().stop_providing(&key),
 			ServiceToWorkerMsg::SetReservedOnly(reserved_only) => self
 				.network_service
 				.behaviour_mut()
@@ -1517,6 +1579,9 @@ where
 							.observe(message.len() as f64);
 					}
 				}
+				for (protocol, message) in &messages {
+					self.service.protocol_bandwidth.report_inbound(protocol, message.len());
+				}
 				self.event_streams.send(Event::NotificationsReceived { remote, messages });
 			},
 			SwarmEvent::Behaviour(BehaviourOut::Dht(event, duration)) => {
@@ -17,7 +17,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::transport::BandwidthSinks;
+use crate::{bandwidth::ProtocolBandwidth, transport::BandwidthSinks};
 use prometheus_endpoint::{
 	self as prometheus, Counter, CounterVec, Gauge, GaugeVec, HistogramOpts, MetricSource, Opts,
 	PrometheusError, Registry, SourcedCounter, SourcedGauge, U64,
@@ -35,6 +35,7 @@ pub use prometheus_endpoint::{Histogram, HistogramVec};
 /// Registers all networking metrics with the given registry.
 pub fn register(registry: &Registry, sources: MetricSources) -> Result<Metrics, PrometheusError> {
 	BandwidthCounters::register(registry, sources.bandwidth)?;
+	ProtocolBandwidthCounters::register(registry, sources.protocol_bandwidth)?;
 	NumConnectedGauge::register(registry, sources.connected_peers)?;
 	Metrics::register(registry)
 }
@@ -42,6 +43,7 @@ pub fn register(registry: &Registry, sources: MetricSources) -> Result<Metrics,
 /// Predefined metric sources that are fed directly into prometheus.
 pub struct MetricSources {
 	pub bandwidth: Arc<BandwidthSinks>,
+	pub protocol_bandwidth: Arc<ProtocolBandwidth>,
 	pub connected_peers: Arc<AtomicUsize>,
 }
 
@@ -265,6 +267,45 @@ impl MetricSource for BandwidthCounters {
 	}
 }
 
+/// The per-protocol bandwidth counter metric.
+#[derive(Clone)]
+pub struct ProtocolBandwidthCounters(Arc<ProtocolBandwidth>);
+
+impl ProtocolBandwidthCounters {
+	/// Registers the `ProtocolBandwidthCounters` metric whose values are
+	/// obtained from the given accounting.
+	fn register(
+		registry: &Registry,
+		bandwidth: Arc<ProtocolBandwidth>,
+	) -> Result<(), PrometheusError> {
+		prometheus::register(
+			SourcedCounter::new(
+				&Opts::new(
+					"substrate_sub_libp2p_protocol_bytes_total",
+					"Bandwidth usage of each notification and request-response protocol",
+				)
+				.variable_label("direction")
+				.variable_label("protocol"),
+				ProtocolBandwidthCounters(bandwidth),
+			)?,
+			registry,
+		)?;
+
+		Ok(())
+	}
+}
+
+impl MetricSource for ProtocolBandwidthCounters {
+	type N = u64;
+
+	fn collect(&self, mut set: impl FnMut(&[&str], Self::N)) {
+		for (protocol, (total_inbound, total_outbound)) in self.0.totals() {
+			set(&["in", &protocol], total_inbound);
+			set(&["out", &protocol], total_outbound);
+		}
+	}
+}
+
 /// The connected peers metric.
 #[derive(Clone)]
 pub struct NumConnectedGauge(Arc<AtomicUsize>);
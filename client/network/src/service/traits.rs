@@ -58,6 +58,12 @@ pub trait NetworkDHTProvider {
 
 	/// Start putting a value in the DHT.
 	fn put_value(&self, key: KademliaKey, value: Vec<u8>);
+
+	/// Start announcing the local node as a provider of `key` in the DHT.
+	fn start_providing(&self, key: KademliaKey);
+
+	/// Stop announcing the local node as a provider of `key`.
+	fn stop_providing(&self, key: KademliaKey);
 }
 
 impl<T> NetworkDHTProvider for Arc<T>
@@ -72,6 +78,14 @@ where
 	fn put_value(&self, key: KademliaKey, value: Vec<u8>) {
 		T::put_value(self, key, value)
 	}
+
+	fn start_providing(&self, key: KademliaKey) {
+		T::start_providing(self, key)
+	}
+
+	fn stop_providing(&self, key: KademliaKey) {
+		T::stop_providing(self, key)
+	}
 }
 
 /// Provides an ability to set a fork sync request for a particular block.
@@ -64,7 +64,10 @@ use sc_network_common::{
 			BlockAnnounce, BlockAnnouncesHandshake, BlockAttributes, BlockData, BlockRequest,
 			BlockResponse, Direction, FromBlock,
 		},
-		warp::{EncodedProof, WarpProofRequest, WarpSyncParams, WarpSyncPhase, WarpSyncProgress},
+		warp::{
+			EncodedProof, WarpProofRequest, WarpProofVersion, WarpSyncParams, WarpSyncPhase,
+			WarpSyncProgress,
+		},
 		BadPeer, ChainSync as ChainSyncT, ImportResult, Metrics, OnBlockData, OnBlockJustification,
 		OnStateData, OpaqueBlockRequest, OpaqueBlockResponse, OpaqueStateRequest,
 		OpaqueStateResponse, PeerInfo, PeerRequest, PollBlockAnnounceValidation, SyncMode,
@@ -344,6 +347,8 @@ pub struct ChainSync<B: BlockT, Client> {
 	state_request_protocol_name: ProtocolName,
 	/// Protocol name used to send out warp sync requests
 	warp_sync_protocol_name: Option<ProtocolName>,
+	/// Peers which don't support the latest warp sync proof format version
+	legacy_warp_sync_peers: HashSet<PeerId>,
 	/// Pending responses
 	pending_responses: HashMap<PeerId, PendingResponse<B>>,
 	/// Handle to import queue.
@@ -1230,6 +1235,7 @@ where
 			gap_sync.blocks.clear_peer_download(who)
 		}
 		self.peers.remove(who);
+		self.legacy_warp_sync_peers.remove(who);
 		self.pending_responses.remove(who);
 		self.extra_justifications.peer_disconnected(who);
 		self.allowed_requests.set_all();
@@ -1450,6 +1456,7 @@ where
 				.notifications_protocol
 				.clone()
 				.into(),
+			legacy_warp_sync_peers: HashSet::new(),
 			pending_responses: HashMap::new(),
 			import_queue,
 			metrics: if let Some(r) = &metrics_registry {
@@ -2014,18 +2021,26 @@ where
 		}
 	}
 
-	fn send_warp_sync_request(&mut self, who: PeerId, request: WarpProofRequest<B>) {
+	fn send_warp_sync_request(&mut self, who: PeerId, mut request: WarpProofRequest<B>) {
 		let (tx, rx) = oneshot::channel();
 
+		// fall back to the original proof format for peers which don't support newer ones
+		if self.legacy_warp_sync_peers.contains(&who) {
+			request.version = WarpProofVersion::V1;
+		}
+		let version = request.version;
+
 		if self.peers.contains_key(&who) {
-			self.pending_responses
-				.insert(who, Box::pin(async move { (who, PeerRequest::WarpProof, rx.await) }));
+			self.pending_responses.insert(
+				who,
+				Box::pin(async move { (who, PeerRequest::WarpProof(version), rx.await) }),
+			);
 		}
 
 		match &self.warp_sync_protocol_name {
 			Some(name) => self.network_service.start_request(
 				who,
-				name.clone(),
+				warp_request_handler::versioned_protocol_name(name, version),
 				request.encode(),
 				tx,
 				IfDisconnected::ImmediateError,
@@ -2130,8 +2145,13 @@ where
 		}
 	}
 
-	pub fn on_warp_sync_response(&mut self, peer_id: PeerId, response: EncodedProof) {
-		if let Err(BadPeer(id, repu)) = self.on_warp_sync_data(&peer_id, response) {
+	pub fn on_warp_sync_response(
+		&mut self,
+		peer_id: PeerId,
+		response: EncodedProof,
+		version: WarpProofVersion,
+	) {
+		if let Err(BadPeer(id, repu)) = self.on_warp_sync_data(&peer_id, response, version) {
 			self.network_service
 				.disconnect_peer(id, self.block_announce_protocol_name.clone());
 			self.network_service.report_peer(id, repu);
@@ -2215,13 +2235,32 @@ where
 							return Poll::Ready(import)
 						}
 					},
-					PeerRequest::WarpProof => {
-						self.on_warp_sync_response(id, EncodedProof(resp));
+					PeerRequest::WarpProof(version) => {
+						self.on_warp_sync_response(id, EncodedProof(resp), version);
 					},
 				},
 				Ok(Err(e)) => {
 					debug!(target: "sync", "Request to peer {:?} failed: {:?}.", id, e);
 
+					if let (
+						PeerRequest::WarpProof(version),
+						RequestFailure::Network(OutboundFailure::UnsupportedProtocols),
+					) = (&request, &e)
+					{
+						if *version > WarpProofVersion::V1 {
+							// the peer doesn't support the newer proof format, retry with the
+							// original one
+							self.legacy_warp_sync_peers.insert(id);
+							if let Some(peer) = self.peers.get_mut(&id) {
+								if let PeerSyncState::DownloadingWarpProof = peer.state {
+									peer.state = PeerSyncState::Available;
+									self.allowed_requests.set_all();
+								}
+							}
+							continue
+						}
+					}
+
 					match e {
 						RequestFailure::Network(OutboundFailure::Timeout) => {
 							self.network_service.report_peer(id, rep::TIMEOUT);
@@ -2608,7 +2647,12 @@ where
 		}
 	}
 
-	fn on_warp_sync_data(&mut self, who: &PeerId, response: EncodedProof) -> Result<(), BadPeer> {
+	fn on_warp_sync_data(
+		&mut self,
+		who: &PeerId,
+		response: EncodedProof,
+		version: WarpProofVersion,
+	) -> Result<(), BadPeer> {
 		if let Some(peer) = self.peers.get_mut(who) {
 			if let PeerSyncState::DownloadingWarpProof = peer.state {
 				peer.state = PeerSyncState::Available;
@@ -2622,7 +2666,7 @@ where
 				who,
 				response.0.len(),
 			);
-			sync.import_warp_proof(response)
+			sync.import_warp_proof(response, version)
 		} else {
 			debug!(target: "sync", "Ignored obsolete warp sync response from {}", who);
 			return Err(BadPeer(*who, rep::NOT_REQUESTED))
@@ -29,7 +29,7 @@ use schnellru::{ByLength, LruMap};
 use sc_client_api::{BlockBackend, ProofProvider};
 use sc_network::{
 	config::ProtocolId,
-	request_responses::{IncomingRequest, OutgoingResponse, ProtocolConfig},
+	request_responses::{IncomingRequest, OutgoingResponse, ProtocolConfig, RateLimit},
 };
 use sp_runtime::traits::Block as BlockT;
 
@@ -64,6 +64,7 @@ pub fn generate_protocol_config<Hash: AsRef<[u8]>>(
 		max_response_size: 16 * 1024 * 1024,
 		request_timeout: Duration::from_secs(40),
 		inbound_queue: None,
+		inbound_rate_limit: None,
 	}
 }
 
@@ -122,12 +123,13 @@ where
 	B: BlockT,
 	Client: BlockBackend<B> + ProofProvider<B> + Send + Sync + 'static,
 {
-	/// Create a new [`StateRequestHandler`].
+	/// Create a new [`StateRequestHandler`], limiting the requests of each peer to `rate_limit`.
 	pub fn new(
 		protocol_id: &ProtocolId,
 		fork_id: Option<&str>,
 		client: Arc<Client>,
 		num_peer_hint: usize,
+		rate_limit: Option<RateLimit>,
 	) -> (Self, ProtocolConfig) {
 		// Reserve enough request slots for one request per peer when we are at the maximum
 		// number of peers.
@@ -143,6 +145,7 @@ where
 			fork_id,
 		);
 		protocol_config.inbound_queue = Some(tx);
+		protocol_config.inbound_rate_limit = rate_limit;
 
 		let capacity = ByLength::new(num_peer_hint.max(1) as u32 * 2);
 		let seen_requests = LruMap::new(capacity);
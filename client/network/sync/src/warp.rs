@@ -30,8 +30,8 @@ use sc_client_api::ProofProvider;
 use sc_network_common::sync::{
 	message::{BlockAttributes, BlockData, BlockRequest, Direction, FromBlock},
 	warp::{
-		EncodedProof, VerificationResult, WarpProofRequest, WarpSyncParams, WarpSyncPhase,
-		WarpSyncProgress, WarpSyncProvider,
+		EncodedProof, VerificationResult, WarpProofRequest, WarpProofVersion, WarpSyncParams,
+		WarpSyncPhase, WarpSyncProgress, WarpSyncProvider,
 	},
 };
 use sp_blockchain::HeaderBackend;
@@ -139,14 +139,23 @@ where
 	}
 
 	///  Validate and import a warp proof response.
-	pub fn import_warp_proof(&mut self, response: EncodedProof) -> WarpProofImportResult {
+	pub fn import_warp_proof(
+		&mut self,
+		response: EncodedProof,
+		version: WarpProofVersion,
+	) -> WarpProofImportResult {
 		match &mut self.phase {
 			Phase::State(_) | Phase::TargetBlock(_) | Phase::PendingTargetBlock { .. } => {
 				log::debug!(target: "sync", "Unexpected warp proof response");
 				WarpProofImportResult::BadResponse
 			},
 			Phase::WarpProof { set_id, authorities, last_hash, warp_sync_provider } =>
-				match warp_sync_provider.verify(&response, *set_id, authorities.clone()) {
+				match warp_sync_provider.verify_versioned(
+					&response,
+					version,
+					*set_id,
+					authorities.clone(),
+				) {
 					Err(e) => {
 						log::debug!(target: "sync", "Bad warp proof response: {}", e);
 						WarpProofImportResult::BadResponse
@@ -222,7 +231,10 @@ where
 	/// Produce next warp proof request.
 	pub fn next_warp_proof_request(&self) -> Option<WarpProofRequest<B>> {
 		match &self.phase {
-			Phase::WarpProof { last_hash, .. } => Some(WarpProofRequest { begin: *last_hash }),
+			Phase::WarpProof { last_hash, warp_sync_provider, .. } => Some(WarpProofRequest {
+				begin: *last_hash,
+				version: warp_sync_provider.latest_proof_version(),
+			}),
 			Phase::TargetBlock(_) | Phase::State(_) | Phase::PendingTargetBlock { .. } => None,
 		}
 	}
@@ -17,7 +17,10 @@
 //! Helper for handling (i.e. answering) grandpa warp sync requests from a remote peer.
 
 use codec::Decode;
-use futures::{channel::oneshot, stream::StreamExt};
+use futures::{
+	channel::oneshot,
+	stream::{self, StreamExt},
+};
 use log::debug;
 
 use sc_network::{
@@ -25,8 +28,11 @@ use sc_network::{
 	request_responses::{
 		IncomingRequest, OutgoingResponse, ProtocolConfig as RequestResponseConfig,
 	},
+	types::ProtocolName,
+};
+use sc_network_common::sync::warp::{
+	EncodedProof, WarpProofRequest, WarpProofVersion, WarpSyncProvider,
 };
-use sc_network_common::sync::warp::{EncodedProof, WarpProofRequest, WarpSyncProvider};
 use sp_runtime::traits::Block as BlockT;
 
 use std::{sync::Arc, time::Duration};
@@ -51,6 +57,7 @@ pub fn generate_request_response_config<Hash: AsRef<[u8]>>(
 		max_response_size: MAX_RESPONSE_SIZE,
 		request_timeout: Duration::from_secs(10),
 		inbound_queue: None,
+		inbound_rate_limit: None,
 	}
 }
 
@@ -69,10 +76,24 @@ fn generate_legacy_protocol_name(protocol_id: ProtocolId) -> String {
 	format!("/{}/sync/warp", protocol_id.as_ref())
 }
 
+/// Name of the request protocol negotiating the given proof format version, derived from the
+/// name of the grandpa warp sync protocol.
+pub fn versioned_protocol_name(
+	protocol_name: &ProtocolName,
+	version: WarpProofVersion,
+) -> ProtocolName {
+	match version {
+		WarpProofVersion::V1 => protocol_name.clone(),
+		WarpProofVersion::V2 => format!("{}/2", protocol_name).into(),
+	}
+}
+
 /// Handler for incoming grandpa warp sync requests from a remote peer.
 pub struct RequestHandler<TBlock: BlockT> {
 	backend: Arc<dyn WarpSyncProvider<TBlock>>,
+	protocol_name: ProtocolName,
 	request_receiver: async_channel::Receiver<IncomingRequest>,
+	versioned_request_receivers: Vec<(WarpProofVersion, async_channel::Receiver<IncomingRequest>)>,
 }
 
 impl<TBlock: BlockT> RequestHandler<TBlock> {
@@ -89,19 +110,54 @@ impl<TBlock: BlockT> RequestHandler<TBlock> {
 			generate_request_response_config(protocol_id, genesis_hash, fork_id);
 		request_response_config.inbound_queue = Some(tx);
 
-		(Self { backend, request_receiver }, request_response_config)
+		let protocol_name = request_response_config.name.clone();
+		(
+			Self {
+				backend,
+				protocol_name,
+				request_receiver,
+				versioned_request_receivers: Vec::new(),
+			},
+			request_response_config,
+		)
+	}
+
+	/// Serve proofs in the newer format versions supported by the backend as well, returning the
+	/// configurations of the request protocols negotiating them.
+	pub fn versioned_protocol_configs(&mut self) -> Vec<RequestResponseConfig> {
+		let latest_version = self.backend.latest_proof_version();
+
+		[WarpProofVersion::V2]
+			.into_iter()
+			.filter(|version| *version <= latest_version)
+			.map(|version| {
+				let (tx, request_receiver) = async_channel::bounded(MAX_WARP_REQUEST_QUEUE);
+				self.versioned_request_receivers.push((version, request_receiver));
+
+				RequestResponseConfig {
+					name: versioned_protocol_name(&self.protocol_name, version),
+					fallback_names: Vec::new(),
+					max_request_size: 32,
+					max_response_size: MAX_RESPONSE_SIZE,
+					request_timeout: Duration::from_secs(10),
+					inbound_queue: Some(tx),
+					inbound_rate_limit: None,
+				}
+			})
+			.collect()
 	}
 
 	fn handle_request(
 		&self,
 		payload: Vec<u8>,
+		version: WarpProofVersion,
 		pending_response: oneshot::Sender<OutgoingResponse>,
 	) -> Result<(), HandleRequestError> {
 		let request = WarpProofRequest::<TBlock>::decode(&mut &payload[..])?;
 
 		let EncodedProof(proof) = self
 			.backend
-			.generate(request.begin)
+			.generate_versioned(request.begin, version)
 			.map_err(HandleRequestError::InvalidRequest)?;
 
 		pending_response
@@ -115,10 +171,16 @@ impl<TBlock: BlockT> RequestHandler<TBlock> {
 
 	/// Run [`RequestHandler`].
 	pub async fn run(mut self) {
-		while let Some(request) = self.request_receiver.next().await {
+		let mut requests = stream::select_all(
+			std::iter::once((WarpProofVersion::V1, self.request_receiver.clone()))
+				.chain(std::mem::take(&mut self.versioned_request_receivers))
+				.map(|(version, receiver)| receiver.map(move |request| (version, request)).boxed()),
+		);
+
+		while let Some((version, request)) = requests.next().await {
 			let IncomingRequest { peer, payload, pending_response } = request;
 
-			match self.handle_request(payload, pending_response) {
+			match self.handle_request(payload, version, pending_response) {
 				Ok(()) => {
 					debug!(target: "sync", "Handled grandpa warp sync request from {}.", peer)
 				},
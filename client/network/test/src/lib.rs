@@ -821,14 +821,14 @@ where
 
 		let block_request_protocol_config = {
 			let (handler, protocol_config) =
-				BlockRequestHandler::new(&protocol_id, None, client.clone(), 50);
+				BlockRequestHandler::new(&protocol_id, None, client.clone(), 50, None);
 			self.spawn_task(handler.run().boxed());
 			protocol_config
 		};
 
 		let state_request_protocol_config = {
 			let (handler, protocol_config) =
-				StateRequestHandler::new(&protocol_id, None, client.clone(), 50);
+				StateRequestHandler::new(&protocol_id, None, client.clone(), 50, None);
 			self.spawn_task(handler.run().boxed());
 			protocol_config
 		};
@@ -153,6 +153,7 @@ fn test_once() {
 			out_peers: Uniform::new_inclusive(0, 25).sample(&mut rng),
 			reserved_only: Uniform::new_inclusive(0, 10).sample(&mut rng) == 0,
 		}],
+		peer_store_path: None,
 	});
 
 	let new_id = PeerId::random();
@@ -158,14 +158,14 @@ impl TestNetworkBuilder {
 
 		let block_request_protocol_config = {
 			let (handler, protocol_config) =
-				BlockRequestHandler::new(&protocol_id, None, client.clone(), 50);
+				BlockRequestHandler::new(&protocol_id, None, client.clone(), 50, None);
 			tokio::spawn(handler.run().boxed());
 			protocol_config
 		};
 
 		let state_request_protocol_config = {
 			let (handler, protocol_config) =
-				StateRequestHandler::new(&protocol_id, None, client.clone(), 50);
+				StateRequestHandler::new(&protocol_id, None, client.clone(), 50, None);
 			tokio::spawn(handler.run().boxed());
 			protocol_config
 		};
@@ -21,7 +21,7 @@ use std::{collections::HashSet, str::FromStr, sync::Arc, thread::sleep};
 
 use crate::NetworkProvider;
 use codec::{Decode, Encode};
-use futures::Future;
+use futures::{Future, FutureExt};
 use libp2p::{Multiaddr, PeerId};
 use sp_core::{
 	offchain::{
@@ -32,8 +32,13 @@ use sp_core::{
 };
 pub use sp_offchain::STORAGE_PREFIX;
 
+mod http;
+
 mod timestamp;
 
+pub use http::HttpConfig;
+pub(crate) use http::SharedClient;
+
 /// Asynchronous offchain API.
 ///
 /// NOTE this is done to prevent recursive calls into the runtime
@@ -43,6 +48,8 @@ pub(crate) struct Api {
 	network_provider: Arc<dyn NetworkProvider + Send + Sync>,
 	/// Is this node a potential validator?
 	is_validator: bool,
+	/// Everything HTTP-related is handled by a different struct.
+	http: http::HttpApi,
 }
 
 impl offchain::Externalities for Api {
@@ -69,6 +76,54 @@ impl offchain::Externalities for Api {
 		rand::random()
 	}
 
+	fn http_request_start(
+		&mut self,
+		method: &str,
+		uri: &str,
+		_meta: &[u8],
+	) -> Result<HttpRequestId, ()> {
+		self.http.request_start(method, uri)
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		request_id: HttpRequestId,
+		name: &str,
+		value: &str,
+	) -> Result<(), ()> {
+		self.http.request_add_header(request_id, name, value)
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		request_id: HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<Timestamp>,
+	) -> Result<(), HttpError> {
+		self.http.request_write_body(request_id, chunk, deadline)
+	}
+
+	fn http_response_wait(
+		&mut self,
+		ids: &[HttpRequestId],
+		deadline: Option<Timestamp>,
+	) -> Vec<HttpRequestStatus> {
+		self.http.response_wait(ids, deadline)
+	}
+
+	fn http_response_headers(&mut self, request_id: HttpRequestId) -> Vec<(Vec<u8>, Vec<u8>)> {
+		self.http.response_headers(request_id)
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		request_id: HttpRequestId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>,
+	) -> Result<usize, HttpError> {
+		self.http.response_read_body(request_id, buffer, deadline)
+	}
+
 	fn set_authorized_nodes(&mut self, nodes: Vec<OpaquePeerId>, authorized_only: bool) {
 		let peer_ids: HashSet<PeerId> =
 			nodes.into_iter().filter_map(|node| PeerId::from_bytes(&node.0).ok()).collect();
@@ -135,23 +190,36 @@ impl TryFrom<OpaqueNetworkState> for NetworkState {
 	}
 }
 
-pub struct AsyncApi {}
+/// Offchain extensions implementation API
+///
+/// This is the asynchronous processing part of the API.
+pub struct AsyncApi {
+	/// Everything HTTP-related is handled by a different struct.
+	http: Option<http::HttpWorker>,
+}
+
 impl AsyncApi {
 	/// Creates new Offchain extensions API implementation and the asynchronous processing part.
 	pub fn new(
 		network_provider: Arc<dyn NetworkProvider + Send + Sync>,
 		is_validator: bool,
+		shared_http_client: SharedClient,
+		http_config: Arc<HttpConfig>,
 	) -> (Api, Self) {
-		let api = Api { network_provider, is_validator };
+		let (http_api, http_worker) = http::http(shared_http_client, http_config);
 
-		let async_api = Self { };
+		let api = Api { network_provider, is_validator, http: http_api };
+
+		let async_api = Self { http: Some(http_worker) };
 
 		(api, async_api)
 	}
 
 	/// Run a processing task for the API
 	pub fn process(self) -> impl Future<Output = ()> {
-		async {}
+		let http = self.http.expect("`process` is only called once; qed");
+
+		http.map(|_| ())
 	}
 }
 
@@ -248,7 +316,7 @@ mod tests {
 		sp_tracing::try_init_simple();
 		let mock = Arc::new(TestNetwork());
 
-		AsyncApi::new(mock, false)
+		AsyncApi::new(mock, false, SharedClient::new(), Default::default())
 	}
 
 	fn offchain_db() -> OffchainDb<LocalStorage> {
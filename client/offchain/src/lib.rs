@@ -36,13 +36,20 @@
 
 #![warn(missing_docs)]
 
-use std::{fmt, sync::Arc};
+use std::{
+	collections::HashSet,
+	fmt,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
 use futures::{
-	future::{ready, Future},
+	future::{self, ready, BoxFuture, Future},
 	prelude::*,
+	stream,
 };
 use parking_lot::Mutex;
+use prometheus_endpoint::Registry;
 use sc_client_api::BlockchainEvents;
 use sc_network::{NetworkPeers, NetworkStateInfo};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
@@ -50,11 +57,15 @@ use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_core::{offchain, traits::SpawnNamed};
 use sp_externalities::Extension;
 use sp_keystore::{KeystoreExt, KeystorePtr};
-use sp_runtime::traits::{self, Header};
+use sp_offchain::PalletOffchainWorkerSchedule;
+use sp_runtime::traits::{self, Header, UniqueSaturatedInto};
 use threadpool::ThreadPool;
 
 mod api;
+mod metrics;
+mod schedule;
 
+pub use api::HttpConfig;
 pub use sp_core::offchain::storage::OffchainDb;
 pub use sp_offchain::{OffchainWorkerApi, STORAGE_PREFIX};
 
@@ -111,6 +122,10 @@ pub struct OffchainWorkerOptions<RA, Block: traits::Block, Storage, CE> {
 	///
 	/// If not enabled, any http request will panic.
 	pub enable_http_requests: bool,
+	/// Limits and permissions of the http requests made by offchain workers.
+	pub http_config: HttpConfig,
+	/// Registry for the metrics of the per-pallet offchain worker runs.
+	pub prometheus_registry: Option<Registry>,
 	/// Callback to create custom [`Extension`]s that should be registered for the
 	/// `offchain_worker` runtime call.
 	///
@@ -127,17 +142,34 @@ pub struct OffchainWorkerOptions<RA, Block: traits::Block, Storage, CE> {
 	pub custom_extensions: CE,
 }
 
+/// A run of the offchain workers of the pallets due at a block, in a single runtime call.
+struct PalletsRun {
+	/// Names of the pallets.
+	pallets: Vec<Vec<u8>>,
+	/// Time after which the HTTP requests of the run are aborted, the longest timeout of the
+	/// pallets. `None` if any of the pallets has no timeout.
+	///
+	/// The runtime call itself isn't interrupted, see
+	/// [`OffchainWorkerSchedule::timeout_ms`](sp_offchain::OffchainWorkerSchedule::timeout_ms).
+	timeout: Option<Duration>,
+}
+
 /// An offchain workers manager.
 pub struct OffchainWorkers<RA, Block: traits::Block, Storage> {
 	runtime_api_provider: Arc<RA>,
 	thread_pool: Mutex<ThreadPool>,
+	shared_http_client: api::SharedClient,
 	enable_http_requests: bool,
+	http_config: Arc<HttpConfig>,
 	keystore: Option<KeystorePtr>,
 	offchain_db: Option<OffchainDb<Storage>>,
 	transaction_pool: Option<OffchainTransactionPoolFactory<Block>>,
 	network_provider: Arc<dyn NetworkProvider + Send + Sync>,
 	is_validator: bool,
 	custom_extensions: Box<dyn Fn(Block::Hash) -> Vec<Box<dyn Extension>> + Send>,
+	storage_triggers: Mutex<schedule::StorageTriggers<Block::Hash>>,
+	running: Arc<Mutex<HashSet<Vec<u8>>>>,
+	metrics: Option<metrics::Metrics>,
 }
 
 impl<RA, Block: traits::Block, Storage> OffchainWorkers<RA, Block, Storage> {
@@ -151,22 +183,40 @@ impl<RA, Block: traits::Block, Storage> OffchainWorkers<RA, Block, Storage> {
 			network_provider,
 			is_validator,
 			enable_http_requests,
+			http_config,
+			prometheus_registry,
 			custom_extensions,
 		}: OffchainWorkerOptions<RA, Block, Storage, CE>,
 	) -> Self {
+		let metrics = prometheus_registry.as_ref().and_then(|registry| {
+			metrics::Metrics::register(registry)
+				.map_err(|error| {
+					tracing::warn!(
+						target: LOG_TARGET,
+						"Failed to register offchain worker metrics: {error}",
+					)
+				})
+				.ok()
+		});
+
 		Self {
 			runtime_api_provider,
 			thread_pool: Mutex::new(ThreadPool::with_name(
 				"offchain-worker".into(),
 				num_cpus::get(),
 			)),
+			shared_http_client: api::SharedClient::new(),
 			enable_http_requests,
+			http_config: Arc::new(http_config),
 			keystore,
 			offchain_db: offchain_db.map(OffchainDb::new),
 			transaction_pool,
 			is_validator,
 			network_provider,
 			custom_extensions: Box::new(custom_extensions),
+			storage_triggers: Mutex::new(schedule::StorageTriggers::new()),
+			running: Arc::new(Mutex::new(HashSet::new())),
+			metrics,
 		}
 	}
 }
@@ -186,28 +236,51 @@ where
 	RA::Api: OffchainWorkerApi<Block>,
 	Storage: offchain::OffchainStorage + 'static,
 {
-	/// Run the offchain workers on every block import.
-	pub async fn run<BE: BlockchainEvents<Block>>(
-		self,
-		import_events: Arc<BE>,
-		spawner: impl SpawnNamed,
-	) {
-		import_events
-			.import_notification_stream()
-			.for_each(move |n| {
-				if n.is_new_best {
-					spawner.spawn(
-						"offchain-on-block",
-						Some("offchain-worker"),
-						self.on_block_imported(&n.header).boxed(),
-					);
-				} else {
-					tracing::debug!(
+	/// Run the offchain workers on every block import and finalization.
+	///
+	/// Runtimes supporting version 3 of the [`OffchainWorkerApi`] only run the offchain workers of
+	/// the pallets due according to their schedules. Older runtimes run all offchain workers on
+	/// every new best block.
+	pub async fn run<BE>(self, import_events: Arc<BE>, spawner: impl SpawnNamed)
+	where
+		BE: BlockchainEvents<Block> + Send + Sync + 'static,
+	{
+		let storage_events = import_events.clone();
+		self.storage_triggers.lock().set_subscribe(Box::new(move |keys| {
+			storage_events
+				.storage_changes_notification_stream(Some(keys), None)
+				.map_err(|error| {
+					tracing::warn!(
 						target: LOG_TARGET,
-						"Skipping offchain workers for non-canon block: {:?}",
-						n.header,
+						"Failed to subscribe to storage changes triggering offchain workers: {error}",
 					)
-				}
+				})
+				.ok()
+		}));
+
+		let imported = import_events.import_notification_stream().filter_map(|n| {
+			if n.is_new_best {
+				ready(Some((n.header, schedule::Wakeup::Imported)))
+			} else {
+				tracing::debug!(
+					target: LOG_TARGET,
+					"Skipping offchain workers for non-canon block: {:?}",
+					n.header,
+				);
+				ready(None)
+			}
+		});
+		let finalized = import_events
+			.finality_notification_stream()
+			.map(|n| (n.header, schedule::Wakeup::Finalized));
+
+		stream::select(imported, finalized)
+			.for_each(move |(header, wakeup)| {
+				spawner.spawn(
+					"offchain-on-block",
+					Some("offchain-worker"),
+					self.on_block(&header, wakeup).boxed(),
+				);
 
 				ready(())
 			})
@@ -217,95 +290,251 @@ where
 	/// Start the offchain workers after given block.
 	#[must_use]
 	fn on_block_imported(&self, header: &Block::Header) -> impl Future<Output = ()> {
+		self.on_block(header, schedule::Wakeup::Imported)
+	}
+
+	/// Start the offchain workers which are due after the given block was imported or finalized.
+	#[must_use]
+	fn on_block(
+		&self,
+		header: &Block::Header,
+		wakeup: schedule::Wakeup,
+	) -> impl Future<Output = ()> {
 		let runtime = self.runtime_api_provider.runtime_api();
 		let hash = header.hash();
 		let has_api_v1 = runtime.has_api_with::<dyn OffchainWorkerApi<Block>, _>(hash, |v| v == 1);
 		let has_api_v2 = runtime.has_api_with::<dyn OffchainWorkerApi<Block>, _>(hash, |v| v == 2);
-		let version = match (has_api_v1, has_api_v2) {
-			(_, Ok(true)) => 2,
-			(Ok(true), _) => 1,
+		let has_api_v3 = runtime.has_api_with::<dyn OffchainWorkerApi<Block>, _>(hash, |v| v >= 3);
+		let version = match (has_api_v1, has_api_v2, has_api_v3) {
+			(_, _, Ok(true)) => 3,
+			(_, Ok(true), _) => 2,
+			(Ok(true), _, _) => 1,
 			err => {
-				let help =
-					"Consider turning off offchain workers if they are not part of your runtime.";
-				tracing::error!(
-					target: LOG_TARGET,
-					"Unsupported Offchain Worker API version: {:?}. {}.",
-					err,
-					help
-				);
+				// Reported once per block, on its import.
+				if wakeup == schedule::Wakeup::Imported {
+					let help =
+						"Consider turning off offchain workers if they are not part of your runtime.";
+					tracing::error!(
+						target: LOG_TARGET,
+						"Unsupported Offchain Worker API version: {:?}. {}.",
+						err,
+						help
+					);
+				}
 				0
 			},
 		};
 		tracing::debug!(
 			target: LOG_TARGET,
-			"Checking offchain workers at {hash:?}: version: {version}",
+			"Checking offchain workers at {hash:?}: version: {version}, wakeup: {wakeup:?}",
 		);
 
-		let process = (version > 0).then(|| {
-			let (api, runner) = api::AsyncApi::new(
-				self.network_provider.clone(),
-				self.is_validator,
-			);
-			tracing::debug!(target: LOG_TARGET, "Spawning offchain workers at {hash:?}");
-			let header = header.clone();
-			let client = self.runtime_api_provider.clone();
+		let runs = match version {
+			0 => Vec::new(),
+			1 | 2 if wakeup == schedule::Wakeup::Imported =>
+				vec![self.spawn_run(header, version, None)],
+			1 | 2 => Vec::new(),
+			_ => {
+				let due = self
+					.due_pallets(header, wakeup)
+					.into_iter()
+					.filter_map(|schedule| {
+						let timeout = schedule.schedule.timeout_ms.map(Duration::from_millis);
+						self.start_pallet_run(schedule.pallet).map(|pallet| (pallet, timeout))
+					})
+					.collect::<Vec<_>>();
+
+				// Don't call into the runtime if no offchain worker is due.
+				if due.is_empty() {
+					Vec::new()
+				} else {
+					let timeout = due.iter().try_fold(Duration::ZERO, |longest, (_, timeout)| {
+						timeout.map(|timeout| longest.max(timeout))
+					});
+					let pallets = due.into_iter().map(|(pallet, _)| pallet).collect();
+					vec![self.spawn_run(header, version, Some(PalletsRun { pallets, timeout }))]
+				}
+			},
+		};
+
+		future::join_all(runs).map(|_| ())
+	}
 
-			let mut capabilities = offchain::Capabilities::all();
-			capabilities.set(offchain::Capabilities::HTTP, self.enable_http_requests);
+	/// Returns the schedules of the pallets whose offchain workers are due at `header`.
+	fn due_pallets(
+		&self,
+		header: &Block::Header,
+		wakeup: schedule::Wakeup,
+	) -> Vec<PalletOffchainWorkerSchedule> {
+		let hash = header.hash();
+		let schedules =
+			match self.runtime_api_provider.runtime_api().offchain_worker_schedules(hash) {
+				Ok(schedules) => schedules,
+				Err(e) => {
+					tracing::error!(
+						target: LOG_TARGET,
+						"Error fetching offchain worker schedules at {:?}: {}",
+						hash,
+						e
+					);
+					return Vec::new()
+				},
+			};
+
+		let changed_keys = match wakeup {
+			schedule::Wakeup::Imported => {
+				let keys = schedules
+					.iter()
+					.flat_map(|schedule| schedule.schedule.storage_triggers())
+					.map(|key| key.to_vec())
+					.collect();
+				self.storage_triggers.lock().changed_keys(&hash, keys)
+			},
+			schedule::Wakeup::Finalized => HashSet::new(),
+		};
 
-			let keystore = self.keystore.clone();
-			let db = self.offchain_db.clone();
-			let tx_pool = self.transaction_pool.clone();
-			let custom_extensions = (*self.custom_extensions)(hash);
+		let number = (*header.number()).unique_saturated_into();
+		schedules
+			.into_iter()
+			.filter(|schedule| schedule::is_due(schedule, wakeup, number, &changed_keys))
+			.collect()
+	}
 
-			self.spawn_worker(move || {
-				let mut runtime = client.runtime_api();
-				let api = Box::new(api);
-				tracing::debug!(target: LOG_TARGET, "Running offchain workers at {hash:?}");
+	/// Marks the offchain worker of `pallet` as running.
+	///
+	/// Returns `None` if the previous run of the offchain worker is still ongoing.
+	fn start_pallet_run(&self, pallet: Vec<u8>) -> Option<Vec<u8>> {
+		let name = String::from_utf8_lossy(&pallet).into_owned();
+		if !self.running.lock().insert(pallet.clone()) {
+			tracing::debug!(
+				target: LOG_TARGET,
+				"Skipping offchain worker of {name}, its previous run is still ongoing",
+			);
+			if let Some(metrics) = &self.metrics {
+				metrics.report_skipped(&name);
+			}
+			return None
+		}
 
-				if let Some(keystore) = keystore {
-					runtime.register_extension(KeystoreExt(keystore.clone()));
-				}
+		if let Some(metrics) = &self.metrics {
+			metrics.report_run(&name);
+		}
+		Some(pallet)
+	}
 
-				if let Some(pool) = tx_pool {
-					runtime.register_extension(pool.offchain_transaction_pool(hash));
-				}
+	/// Spawns an offchain worker run at `header`, returning the future processing its requests.
+	///
+	/// Runs the offchain workers of the given pallets only, or all offchain workers if `pallets`
+	/// is `None`.
+	fn spawn_run(
+		&self,
+		header: &Block::Header,
+		version: u32,
+		pallets: Option<PalletsRun>,
+	) -> BoxFuture<'static, ()> {
+		let hash = header.hash();
+		let (api, runner) = api::AsyncApi::new(
+			self.network_provider.clone(),
+			self.is_validator,
+			self.shared_http_client.clone(),
+			self.http_config.clone(),
+		);
+		tracing::debug!(target: LOG_TARGET, "Spawning offchain workers at {hash:?}");
+		let header = header.clone();
+		let client = self.runtime_api_provider.clone();
+
+		let mut capabilities = offchain::Capabilities::all();
+		capabilities.set(offchain::Capabilities::HTTP, self.enable_http_requests);
+
+		let keystore = self.keystore.clone();
+		let db = self.offchain_db.clone();
+		let tx_pool = self.transaction_pool.clone();
+		let custom_extensions = (*self.custom_extensions)(hash);
+		let running = self.running.clone();
+		let metrics = self.metrics.clone();
+		let names = pallets.as_ref().map(|run| {
+			run.pallets
+				.iter()
+				.map(|pallet| String::from_utf8_lossy(pallet).into_owned())
+				.collect::<Vec<_>>()
+		});
+		let timeout = pallets.as_ref().and_then(|run| run.timeout);
+		let worker_names = names.clone();
 
-				if let Some(offchain_db) = db {
-					runtime.register_extension(offchain::OffchainDbExt::new(
-						offchain::LimitedExternalities::new(capabilities, offchain_db.clone()),
-					));
-				}
+		self.spawn_worker(move || {
+			let mut runtime = client.runtime_api();
+			let api = Box::new(api);
+			tracing::debug!(target: LOG_TARGET, "Running offchain workers at {hash:?}");
 
-				runtime.register_extension(offchain::OffchainWorkerExt::new(
-					offchain::LimitedExternalities::new(capabilities, api),
-				));
+			if let Some(keystore) = keystore {
+				runtime.register_extension(KeystoreExt(keystore.clone()));
+			}
 
-				custom_extensions.into_iter().for_each(|ext| runtime.register_extension(ext));
+			if let Some(pool) = tx_pool {
+				runtime.register_extension(pool.offchain_transaction_pool(hash));
+			}
 
-				let run = if version == 2 {
-					runtime.offchain_worker(hash, &header)
-				} else {
+			if let Some(offchain_db) = db {
+				runtime.register_extension(offchain::OffchainDbExt::new(
+					offchain::LimitedExternalities::new(capabilities, offchain_db.clone()),
+				));
+			}
+
+			runtime.register_extension(offchain::OffchainWorkerExt::new(
+				offchain::LimitedExternalities::new(capabilities, api),
+			));
+
+			custom_extensions.into_iter().for_each(|ext| runtime.register_extension(ext));
+
+			let started = Instant::now();
+			let run = match pallets {
+				Some(PalletsRun { pallets, .. }) => {
+					let run = runtime.offchain_worker_of(hash, &header, pallets.clone());
+					let mut running = running.lock();
+					pallets.iter().for_each(|pallet| {
+						running.remove(pallet);
+					});
+					run
+				},
+				None if version == 2 => runtime.offchain_worker(hash, &header),
+				None =>
 					#[allow(deprecated)]
-					runtime.offchain_worker_before_version_2(hash, *header.number())
-				};
+					runtime.offchain_worker_before_version_2(hash, *header.number()),
+			};
 
-				if let Err(e) = run {
-					tracing::error!(
-						target: LOG_TARGET,
-						"Error running offchain workers at {:?}: {}",
-						hash,
-						e
-					);
-				}
-			});
+			if let (Some(metrics), Some(names)) = (&metrics, &worker_names) {
+				let seconds = started.elapsed().as_secs_f64();
+				names.iter().for_each(|name| metrics.report_duration(name, seconds));
+			}
 
-			runner.process()
+			if let Err(e) = run {
+				tracing::error!(
+					target: LOG_TARGET,
+					"Error running offchain workers at {:?}: {}",
+					hash,
+					e
+				);
+			}
 		});
 
-		async move {
-			futures::future::OptionFuture::from(process).await;
-		}
+		let Some(timeout) = timeout else { return runner.process().boxed() };
+		let metrics = self.metrics.clone();
+		future::select(runner.process().boxed(), futures_timer::Delay::new(timeout))
+			.map(move |result| {
+				if let future::Either::Right(_) = result {
+					// Dropping the runner fails the pending requests of the offchain worker.
+					let names = names.unwrap_or_default();
+					tracing::warn!(
+						target: LOG_TARGET,
+						"Offchain workers of {} at {hash:?} exceeded their timeout of {timeout:?}",
+						names.join(", "),
+					);
+					if let Some(metrics) = &metrics {
+						names.iter().for_each(|name| metrics.report_timeout(name));
+					}
+				}
+			})
+			.boxed()
 	}
 
 	/// Spawns a new offchain worker.
@@ -324,6 +553,7 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use codec::{Decode, Encode};
 	use futures::executor::block_on;
 	use libp2p::{Multiaddr, PeerId};
 	use sc_block_builder::BlockBuilderProvider as _;
@@ -331,11 +561,14 @@ mod tests {
 	use sc_network::{config::MultiaddrWithPeerId, types::ProtocolName, ReputationChange};
 	use sc_transaction_pool::BasicPool;
 	use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+	use sp_api::{ApiError, RuntimeApiInfo, RuntimeVersion};
 	use sp_consensus::BlockOrigin;
+	use sp_offchain::{Cadence, OffchainWorkerSchedule};
 	use std::{collections::HashSet, sync::Arc};
 	use substrate_test_runtime_client::{
 		runtime::{
-			substrate_test_pallet::pallet::Call as PalletCall, ExtrinsicBuilder, RuntimeCall,
+			substrate_test_pallet::pallet::Call as PalletCall, Block, ExtrinsicBuilder, Hash,
+			Header as BlockHeader, RuntimeCall,
 		},
 		ClientBlockImportExt, DefaultTestClientBuilderExt, TestClientBuilderExt,
 	};
@@ -418,6 +651,184 @@ mod tests {
 		}
 	}
 
+	/// Runtime calls by function name, along with their encoded arguments.
+	type Calls = Arc<Mutex<Vec<(&'static str, Vec<u8>)>>>;
+
+	/// Runtime supporting version 3 of the [`OffchainWorkerApi`], recording the calls into it.
+	struct ScheduledRuntime {
+		schedules: Vec<PalletOffchainWorkerSchedule>,
+		calls: Calls,
+	}
+
+	impl ProvideRuntimeApi<Block> for ScheduledRuntime {
+		type Api = ScheduledRuntimeApi;
+
+		fn runtime_api(&self) -> sp_api::ApiRef<Self::Api> {
+			ScheduledRuntimeApi { schedules: self.schedules.clone(), calls: self.calls.clone() }
+				.into()
+		}
+	}
+
+	struct ScheduledRuntimeApi {
+		schedules: Vec<PalletOffchainWorkerSchedule>,
+		calls: Calls,
+	}
+
+	impl ApiExt<Block> for ScheduledRuntimeApi {
+		type StateBackend = sp_api::InMemoryBackend<sp_api::HashFor<Block>>;
+
+		fn execute_in_transaction<F: FnOnce(&Self) -> sp_api::TransactionOutcome<R>, R>(
+			&self,
+			call: F,
+		) -> R {
+			call(self).into_inner()
+		}
+
+		fn has_api<A: RuntimeApiInfo + ?Sized>(&self, _: Hash) -> Result<bool, ApiError> {
+			Ok(true)
+		}
+
+		fn has_api_with<A: RuntimeApiInfo + ?Sized, P: Fn(u32) -> bool>(
+			&self,
+			_: Hash,
+			pred: P,
+		) -> Result<bool, ApiError> {
+			Ok(pred(3))
+		}
+
+		fn api_version<A: RuntimeApiInfo + ?Sized>(
+			&self,
+			_: Hash,
+		) -> Result<Option<u32>, ApiError> {
+			Ok(Some(3))
+		}
+
+		fn record_proof(&mut self) {
+			unimplemented!()
+		}
+
+		fn extract_proof(&mut self) -> Option<sp_api::StorageProof> {
+			unimplemented!()
+		}
+
+		fn proof_recorder(&self) -> Option<sp_api::ProofRecorder<Block>> {
+			unimplemented!()
+		}
+
+		fn into_storage_changes(
+			&self,
+			_: &Self::StateBackend,
+			_: Hash,
+		) -> Result<sp_api::StorageChanges<Self::StateBackend, Block>, String> {
+			unimplemented!()
+		}
+
+		fn set_call_context(&mut self, _: sp_api::CallContext) {
+			unimplemented!()
+		}
+
+		fn register_extension<E: Extension>(&mut self, _: E) {}
+	}
+
+	impl sp_api::Core<Block> for ScheduledRuntimeApi {
+		fn __runtime_api_internal_call_api_at(
+			&self,
+			_: Hash,
+			_: Vec<u8>,
+			_: &dyn Fn(RuntimeVersion) -> &'static str,
+		) -> Result<Vec<u8>, ApiError> {
+			unimplemented!()
+		}
+	}
+
+	impl OffchainWorkerApi<Block> for ScheduledRuntimeApi {
+		fn __runtime_api_internal_call_api_at(
+			&self,
+			_: Hash,
+			params: Vec<u8>,
+			fn_name: &dyn Fn(RuntimeVersion) -> &'static str,
+		) -> Result<Vec<u8>, ApiError> {
+			let function = fn_name(RuntimeVersion::default());
+			self.calls.lock().push((function, params));
+			match function {
+				"OffchainWorkerApi_offchain_worker_schedules" => Ok(self.schedules.encode()),
+				_ => Ok(Vec::new()),
+			}
+		}
+	}
+
+	fn scheduled_offchain_workers(
+		schedules: Vec<PalletOffchainWorkerSchedule>,
+	) -> (OffchainWorkers<ScheduledRuntime, Block, NoOffchainStorage>, Calls) {
+		let calls = Calls::default();
+		let offchain = OffchainWorkers::new(OffchainWorkerOptions {
+			runtime_api_provider: Arc::new(ScheduledRuntime { schedules, calls: calls.clone() }),
+			keystore: None,
+			offchain_db: None::<NoOffchainStorage>,
+			transaction_pool: None,
+			network_provider: Arc::new(TestNetwork()),
+			is_validator: false,
+			enable_http_requests: false,
+			http_config: Default::default(),
+			prometheus_registry: None,
+			custom_extensions: |_| Vec::new(),
+		});
+		(offchain, calls)
+	}
+
+	fn schedule(pallet: &str, cadence: Cadence) -> PalletOffchainWorkerSchedule {
+		PalletOffchainWorkerSchedule {
+			pallet: pallet.as_bytes().to_vec(),
+			schedule: OffchainWorkerSchedule::new(cadence),
+			triggered_by_events: false,
+		}
+	}
+
+	fn header(number: u64) -> BlockHeader {
+		BlockHeader::new(
+			number,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		)
+	}
+
+	#[test]
+	fn no_runtime_call_without_due_offchain_workers() {
+		let (offchain, calls) =
+			scheduled_offchain_workers(vec![schedule("Pallet", Cadence::EveryNBlocks(2))]);
+
+		block_on(offchain.on_block_imported(&header(1)));
+		offchain.thread_pool.lock().join();
+
+		// Only the schedules are fetched.
+		assert_eq!(
+			calls.lock().iter().map(|(function, _)| *function).collect::<Vec<_>>(),
+			vec!["OffchainWorkerApi_offchain_worker_schedules"],
+		);
+	}
+
+	#[test]
+	fn due_offchain_workers_run_in_a_single_runtime_call() {
+		let (offchain, calls) = scheduled_offchain_workers(vec![
+			schedule("First", Cadence::EveryBlock),
+			schedule("Finality", Cadence::OnFinality),
+			schedule("Second", Cadence::EveryNBlocks(2)),
+		]);
+
+		block_on(offchain.on_block_imported(&header(2)));
+		offchain.thread_pool.lock().join();
+
+		let calls = calls.lock();
+		assert_eq!(calls.len(), 2);
+		assert_eq!(calls[1].0, "OffchainWorkerApi_offchain_worker_of");
+		let (_, pallets) = <(BlockHeader, Vec<Vec<u8>>)>::decode(&mut &calls[1].1[..]).unwrap();
+		assert_eq!(pallets, vec![b"First".to_vec(), b"Second".to_vec()]);
+		// The offchain workers can run again once the call returned.
+		assert!(offchain.running.lock().is_empty());
+	}
+
 	#[test]
 	fn should_call_into_runtime_and_produce_extrinsic() {
 		sp_tracing::try_init_simple();
@@ -438,6 +849,8 @@ mod tests {
 			network_provider: network,
 			is_validator: false,
 			enable_http_requests: false,
+			http_config: Default::default(),
+			prometheus_registry: None,
 			custom_extensions: |_| Vec::new(),
 		});
 		futures::executor::block_on(offchain.on_block_imported(&header));
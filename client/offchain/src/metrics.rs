@@ -0,0 +1,96 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Metrics of the offchain workers of individual pallets.
+
+use prometheus_endpoint::{
+	register, CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry, U64,
+};
+
+/// Prometheus metrics of the per-pallet offchain worker runs.
+#[derive(Clone)]
+pub(crate) struct Metrics {
+	runs: CounterVec<U64>,
+	timeouts: CounterVec<U64>,
+	skipped: CounterVec<U64>,
+	duration: HistogramVec,
+}
+
+impl Metrics {
+	pub(crate) fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			runs: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_offchain_worker_runs_total",
+						"Number of offchain worker runs started",
+					),
+					&["pallet"],
+				)?,
+				registry,
+			)?,
+			timeouts: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_offchain_worker_timeouts_total",
+						"Number of offchain worker runs which exceeded their timeout",
+					),
+					&["pallet"],
+				)?,
+				registry,
+			)?,
+			skipped: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_offchain_worker_skipped_total",
+						"Number of offchain worker runs skipped as the previous run was still ongoing",
+					),
+					&["pallet"],
+				)?,
+				registry,
+			)?,
+			duration: register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						"substrate_offchain_worker_duration",
+						"Time taken by offchain worker runs, shared by the pallets run together",
+					),
+					&["pallet"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	pub fn report_run(&self, pallet: &str) {
+		self.runs.with_label_values(&[pallet]).inc();
+	}
+
+	pub fn report_timeout(&self, pallet: &str) {
+		self.timeouts.with_label_values(&[pallet]).inc();
+	}
+
+	pub fn report_skipped(&self, pallet: &str) {
+		self.skipped.with_label_values(&[pallet]).inc();
+	}
+
+	pub fn report_duration(&self, pallet: &str, seconds: f64) {
+		self.duration.with_label_values(&[pallet]).observe(seconds);
+	}
+}
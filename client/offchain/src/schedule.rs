@@ -0,0 +1,273 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Scheduling of the offchain workers of individual pallets.
+//!
+//! Runtimes implementing version 3 of the [`OffchainWorkerApi`](crate::OffchainWorkerApi) report
+//! an [`OffchainWorkerSchedule`](sp_offchain::OffchainWorkerSchedule) per pallet. The node then
+//! only runs the offchain workers which are due on a block, instead of all of them.
+
+use std::collections::{HashSet, VecDeque};
+
+use futures::{FutureExt, StreamExt};
+use sc_client_api::StorageEventStream;
+use sp_core::storage::StorageKey;
+use sp_offchain::{Cadence, PalletOffchainWorkerSchedule};
+
+use crate::LOG_TARGET;
+
+/// Number of blocks whose storage changes are kept until their import notification arrives.
+const MAX_RECENT_CHANGES: usize = 64;
+
+/// What woke up the offchain workers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Wakeup {
+	/// A block was imported as the new best block.
+	Imported,
+	/// A block was finalized.
+	Finalized,
+}
+
+/// Returns if the offchain worker with `schedule` is due at the block with the given number.
+///
+/// `changed_keys` are the storage keys changed by the block.
+pub(crate) fn is_due(
+	schedule: &PalletOffchainWorkerSchedule,
+	wakeup: Wakeup,
+	number: u64,
+	changed_keys: &HashSet<Vec<u8>>,
+) -> bool {
+	match wakeup {
+		Wakeup::Finalized => schedule.schedule.cadence == Cadence::OnFinality,
+		Wakeup::Imported =>
+			schedule.schedule.is_due_at(number) ||
+				schedule.triggered_by_events ||
+				schedule.schedule.storage_triggers().any(|key| changed_keys.contains(key)),
+	}
+}
+
+/// Subscribes to storage changes of the given keys.
+pub(crate) type SubscribeStorage<H> =
+	Box<dyn Fn(&[StorageKey]) -> Option<StorageEventStream<H>> + Send>;
+
+/// Tracks the changes of the storage keys offchain workers are triggered by.
+///
+/// The client announces the storage changes of a block before its import, so the changes of a
+/// block are queued until its import notification arrives. The watched keys are updated to the
+/// storage triggers of each imported block before looking up its changes, so keys added by a
+/// block only trigger offchain workers from the following block on.
+pub(crate) struct StorageTriggers<H> {
+	/// Subscribes to storage changes; `None` if storage triggers are disabled.
+	subscribe: Option<SubscribeStorage<H>>,
+	/// The watched keys.
+	keys: HashSet<Vec<u8>>,
+	/// Storage changes of the watched keys.
+	stream: Option<StorageEventStream<H>>,
+	/// Changed keys of recent blocks whose import wasn't processed yet.
+	recent: VecDeque<(H, HashSet<Vec<u8>>)>,
+}
+
+impl<H: PartialEq> StorageTriggers<H> {
+	/// Create a new tracker, watching no keys until [`StorageTriggers::set_subscribe`] is called.
+	pub fn new() -> Self {
+		Self { subscribe: None, keys: HashSet::new(), stream: None, recent: VecDeque::new() }
+	}
+
+	/// Set the function subscribing to storage changes.
+	///
+	/// The keys are subscribed to again on the next call to [`StorageTriggers::changed_keys`].
+	pub fn set_subscribe(&mut self, subscribe: SubscribeStorage<H>) {
+		self.subscribe = Some(subscribe);
+		self.keys.clear();
+	}
+
+	/// Watches `keys` from now on and returns the ones changed by the block `hash`.
+	pub fn changed_keys(&mut self, hash: &H, keys: HashSet<Vec<u8>>) -> HashSet<Vec<u8>> {
+		if keys != self.keys {
+			self.watch(keys);
+		}
+		self.poll_changes();
+
+		let changed = match self.recent.iter().position(|(block, _)| block == hash) {
+			Some(index) =>
+				self.recent.remove(index).map(|(_, changed)| changed).unwrap_or_default(),
+			None => HashSet::new(),
+		};
+		changed.into_iter().filter(|key| self.keys.contains(key)).collect()
+	}
+
+	/// Subscribes to the changes of `keys`, keeping the changes queued by the previous
+	/// subscription.
+	fn watch(&mut self, keys: HashSet<Vec<u8>>) {
+		let stream = match &self.subscribe {
+			Some(subscribe) if !keys.is_empty() => {
+				let filter = keys.iter().cloned().map(StorageKey).collect::<Vec<_>>();
+				subscribe(&filter)
+			},
+			_ => None,
+		};
+		// Subscribe before draining the previous stream, so no changes are missed in between.
+		self.poll_changes();
+		self.stream = stream;
+
+		tracing::debug!(
+			target: LOG_TARGET,
+			"Watching {} storage keys triggering offchain workers",
+			keys.len(),
+		);
+		self.keys = keys;
+	}
+
+	/// Moves the queued storage changes into `recent`.
+	fn poll_changes(&mut self) {
+		let Some(stream) = &mut self.stream else { return };
+
+		while let Some(Some(notification)) = stream.next().now_or_never() {
+			let changed = notification
+				.changes
+				.iter()
+				.filter(|(child, _, _)| child.is_none())
+				.map(|(_, key, _)| key.0.clone())
+				.collect();
+
+			if self.recent.len() == MAX_RECENT_CHANGES {
+				self.recent.pop_front();
+			}
+			self.recent.push_back((notification.block, changed));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_client_api::StorageNotifications;
+	use sp_offchain::{OffchainWorkerSchedule, Trigger};
+	use std::sync::Arc;
+	use substrate_test_runtime_client::runtime::{Block, Hash};
+
+	fn schedule(schedule: OffchainWorkerSchedule) -> PalletOffchainWorkerSchedule {
+		PalletOffchainWorkerSchedule {
+			pallet: b"Pallet".to_vec(),
+			schedule,
+			triggered_by_events: false,
+		}
+	}
+
+	#[test]
+	fn cadence_and_triggers_make_workers_due() {
+		let none = HashSet::new();
+
+		let every_block = schedule(OffchainWorkerSchedule::default());
+		assert!(is_due(&every_block, Wakeup::Imported, 5, &none));
+		assert!(!is_due(&every_block, Wakeup::Finalized, 5, &none));
+
+		let on_finality = schedule(OffchainWorkerSchedule::new(Cadence::OnFinality));
+		assert!(!is_due(&on_finality, Wakeup::Imported, 5, &none));
+		assert!(is_due(&on_finality, Wakeup::Finalized, 5, &none));
+
+		let mut triggered = schedule(
+			OffchainWorkerSchedule::new(Cadence::Never)
+				.with_trigger(Trigger::StorageChanged(b"key".to_vec())),
+		);
+		assert!(!is_due(&triggered, Wakeup::Imported, 5, &none));
+		assert!(is_due(&triggered, Wakeup::Imported, 5, &[b"key".to_vec()].into()));
+		assert!(!is_due(&triggered, Wakeup::Finalized, 5, &[b"key".to_vec()].into()));
+
+		triggered.triggered_by_events = true;
+		assert!(is_due(&triggered, Wakeup::Imported, 5, &none));
+	}
+
+	#[test]
+	fn storage_triggers_report_changes_of_watched_keys() {
+		let notifications = Arc::new(StorageNotifications::<Block>::new(None));
+		let mut triggers = StorageTriggers::<Hash>::new();
+		let subscribe = notifications.clone();
+		triggers.set_subscribe(Box::new(move |keys| Some(subscribe.listen(Some(keys), None))));
+
+		let keys: HashSet<Vec<u8>> = [b"a".to_vec()].into();
+		let (first, second) = (Hash::repeat_byte(1), Hash::repeat_byte(2));
+
+		// Nothing is watched before the first schedules are known.
+		assert!(triggers.changed_keys(&first, keys.clone()).is_empty());
+
+		notifications.trigger(
+			&second,
+			vec![(b"a".to_vec(), Some(vec![1])), (b"b".to_vec(), None)].into_iter(),
+			Vec::<(Vec<u8>, std::vec::IntoIter<(Vec<u8>, Option<Vec<u8>>)>)>::new().into_iter(),
+		);
+
+		assert_eq!(triggers.changed_keys(&second, keys.clone()), keys);
+		// The changes of a block are only reported once.
+		assert!(triggers.changed_keys(&second, keys).is_empty());
+	}
+
+	fn trigger(notifications: &StorageNotifications<Block>, block: &Hash, keys: &[&str]) {
+		notifications.trigger(
+			block,
+			keys.iter().map(|key| (key.as_bytes().to_vec(), Some(vec![1]))),
+			Vec::<(Vec<u8>, std::vec::IntoIter<(Vec<u8>, Option<Vec<u8>>)>)>::new().into_iter(),
+		);
+	}
+
+	#[test]
+	fn storage_triggers_keep_pending_changes_when_keys_change() {
+		let notifications = Arc::new(StorageNotifications::<Block>::new(None));
+		let mut triggers = StorageTriggers::<Hash>::new();
+		let subscribe = notifications.clone();
+		triggers.set_subscribe(Box::new(move |keys| Some(subscribe.listen(Some(keys), None))));
+
+		let (first, second, third) =
+			(Hash::repeat_byte(1), Hash::repeat_byte(2), Hash::repeat_byte(3));
+		let a: HashSet<Vec<u8>> = [b"a".to_vec()].into();
+		let ab: HashSet<Vec<u8>> = [b"a".to_vec(), b"b".to_vec()].into();
+
+		assert!(triggers.changed_keys(&first, a.clone()).is_empty());
+
+		// Both blocks are announced before the import of the first one is processed.
+		trigger(&notifications, &second, &["a"]);
+		trigger(&notifications, &third, &["a", "b"]);
+
+		// Watching more keys doesn't lose the changes of the old subscription, but `b` was only
+		// watched after the changes of `third` were announced.
+		assert_eq!(triggers.changed_keys(&second, ab.clone()), a);
+		assert_eq!(triggers.changed_keys(&third, ab.clone()), a);
+
+		trigger(&notifications, &first, &["a", "b"]);
+		assert_eq!(triggers.changed_keys(&first, ab.clone()), ab);
+	}
+
+	#[test]
+	fn storage_triggers_only_report_currently_watched_keys() {
+		let notifications = Arc::new(StorageNotifications::<Block>::new(None));
+		let mut triggers = StorageTriggers::<Hash>::new();
+		let subscribe = notifications.clone();
+		triggers.set_subscribe(Box::new(move |keys| Some(subscribe.listen(Some(keys), None))));
+
+		let (first, second) = (Hash::repeat_byte(1), Hash::repeat_byte(2));
+		let ab: HashSet<Vec<u8>> = [b"a".to_vec(), b"b".to_vec()].into();
+		let b: HashSet<Vec<u8>> = [b"b".to_vec()].into();
+
+		assert!(triggers.changed_keys(&first, ab).is_empty());
+		trigger(&notifications, &second, &["a", "b"]);
+
+		// `a` no longer triggers any offchain worker of `second`.
+		assert_eq!(triggers.changed_keys(&second, b.clone()), b);
+	}
+}
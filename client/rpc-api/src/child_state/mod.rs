@@ -18,7 +18,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! Substrate child state API
-use crate::state::ReadProof;
+use crate::state::{ReadProof, ReadProofPaged};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use sp_core::storage::{PrefixedStorageKey, StorageData, StorageKey};
 
@@ -95,4 +95,19 @@ pub trait ChildStateApi<Hash> {
 		keys: Vec<StorageKey>,
 		hash: Option<Hash>,
 	) -> RpcResult<ReadProof<Hash>>;
+
+	/// Returns proof of up to `count` child storage entries with the given `prefix`, following
+	/// `start_key` (exclusive).
+	///
+	/// The proof also covers whether further entries exist, so whole maps can be verified by
+	/// continuing from the last returned key.
+	#[method(name = "state_getChildReadProofPaged", blocking)]
+	fn read_child_proof_paged(
+		&self,
+		child_storage_key: PrefixedStorageKey,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
+		hash: Option<Hash>,
+	) -> RpcResult<ReadProofPaged<Hash>>;
 }
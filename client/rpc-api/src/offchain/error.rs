@@ -33,6 +33,25 @@ pub enum Error {
 	/// Unavailable storage kind error.
 	#[error("This storage kind is not available yet.")]
 	UnavailableStorageKind,
+	/// The offchain storage can not be iterated.
+	#[error("Iterating the offchain storage is not supported by the database.")]
+	UnsupportedIteration,
+	/// Provided count exceeds maximum value.
+	#[error("count exceeds maximum value. value: {}, max: {}", .value, .max)]
+	InvalidCount {
+		/// Provided value
+		value: u32,
+		/// Maximum allowed value
+		max: u32,
+	},
+	/// Provided start key exceeds the maximum length.
+	#[error("start key exceeds maximum length. len: {}, max: {}", .len, .max)]
+	StartKeyTooLong {
+		/// Length of the provided key
+		len: u32,
+		/// Maximum allowed length
+		max: u32,
+	},
 	/// Call to an unsafe RPC was denied.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
@@ -50,6 +69,18 @@ impl From<Error> for JsonRpseeError {
 				None::<()>,
 			))
 			.into(),
+			Error::UnsupportedIteration => CallError::Custom(ErrorObject::owned(
+				BASE_ERROR + 2,
+				"Iterating the offchain storage is not supported by the database",
+				None::<()>,
+			))
+			.into(),
+			e @ Error::InvalidCount { .. } =>
+				CallError::Custom(ErrorObject::owned(BASE_ERROR + 3, e.to_string(), None::<()>))
+					.into(),
+			e @ Error::StartKeyTooLong { .. } =>
+				CallError::Custom(ErrorObject::owned(BASE_ERROR + 4, e.to_string(), None::<()>))
+					.into(),
 			Error::UnsafeRpcCalled(e) => e.into(),
 		}
 	}
@@ -34,4 +34,35 @@ pub trait OffchainApi {
 	/// Get offchain local storage under given key and prefix.
 	#[method(name = "offchain_localStorageGet")]
 	fn get_local_storage(&self, kind: StorageKind, key: Bytes) -> RpcResult<Option<Bytes>>;
+
+	/// Returns the keys of offchain local storage with given prefix, with pagination support.
+	/// Up to `count` keys will be returned.
+	/// If `start_key` is passed, return next keys in storage in lexicographic order.
+	/// `start_key` may be at most 128 bytes long, as nodes using the RocksDB database backend
+	/// iterate up to 255 key prefixes per byte of it to find the next keys.
+	/// Not supported by nodes using the parity-db database backend.
+	#[method(name = "offchain_localStorageKeys")]
+	fn local_storage_keys(
+		&self,
+		kind: StorageKind,
+		prefix: Bytes,
+		count: u32,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Vec<Bytes>>;
+
+	/// Returns the keys and values of offchain local storage with given prefix, with pagination
+	/// support.
+	/// Up to `count` entries will be returned.
+	/// If `start_key` is passed, return next entries in storage in lexicographic order.
+	/// `start_key` may be at most 128 bytes long, as nodes using the RocksDB database backend
+	/// iterate up to 255 key prefixes per byte of it to find the next entries.
+	/// Not supported by nodes using the parity-db database backend.
+	#[method(name = "offchain_localStorageIter")]
+	fn local_storage_iter(
+		&self,
+		kind: StorageKind,
+		prefix: Bytes,
+		count: u32,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Vec<(Bytes, Bytes)>>;
 }
@@ -20,7 +20,7 @@
 //! Substrate state API helpers.
 
 use serde::{Deserialize, Serialize};
-use sp_core::Bytes;
+use sp_core::{storage::StorageKey, Bytes};
 
 /// ReadProof struct returned by the RPC
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -31,3 +31,27 @@ pub struct ReadProof<Hash> {
 	/// A proof used to prove that storage entries are included in the storage trie
 	pub proof: Vec<Bytes>,
 }
+
+/// Paged range proof struct returned by the RPC
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadProofPaged<Hash> {
+	/// Block hash used to generate the proof
+	pub at: Hash,
+	/// Keys of the storage entries included in the proof, in lexicographic order
+	pub keys: Vec<StorageKey>,
+	/// Whether no storage entries are left after `keys`
+	pub complete: bool,
+	/// A proof used to prove the storage entries in the range, or their absence
+	pub proof: Vec<Bytes>,
+}
+
+/// Storage proof over a range of blocks returned by the RPC
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadProofRange<Hash> {
+	/// Hashes of the blocks covered by the proof, in ascending order
+	pub blocks: Vec<Hash>,
+	/// SCALE-encoded compact multi-block proof of the storage entries at every block
+	pub proof: Bytes,
+}
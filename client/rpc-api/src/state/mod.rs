@@ -29,7 +29,7 @@ use sp_version::RuntimeVersion;
 pub mod error;
 pub mod helpers;
 
-pub use self::helpers::ReadProof;
+pub use self::helpers::{ReadProof, ReadProofPaged, ReadProofRange};
 
 /// Substrate state API
 #[rpc(client, server)]
@@ -112,6 +112,32 @@ pub trait StateApi<Hash> {
 	#[method(name = "state_getReadProof", blocking)]
 	fn read_proof(&self, keys: Vec<StorageKey>, hash: Option<Hash>) -> RpcResult<ReadProof<Hash>>;
 
+	/// Returns proof of up to `count` storage entries with the given `prefix`, following
+	/// `start_key` (exclusive).
+	///
+	/// The proof also covers whether further entries exist, so whole maps can be verified by
+	/// continuing from the last returned key.
+	#[method(name = "state_getReadProofPaged", blocking)]
+	fn read_proof_paged(
+		&self,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
+		hash: Option<Hash>,
+	) -> RpcResult<ReadProofPaged<Hash>>;
+
+	/// Returns a single compact proof of storage entries at every block from `from` up to `to`.
+	///
+	/// Trie nodes shared between the blocks are only included once. At most 256 blocks and 1000
+	/// keys may be proven by a single call.
+	#[method(name = "state_getReadProofRange", blocking)]
+	fn read_proof_range(
+		&self,
+		keys: Vec<StorageKey>,
+		from: Hash,
+		to: Option<Hash>,
+	) -> RpcResult<ReadProofRange<Hash>>;
+
 	/// New runtime version subscription
 	#[subscription(
 		name = "state_subscribeRuntimeVersion" => "state_runtimeVersion",
@@ -72,6 +72,16 @@ pub struct PeerInfo<Hash, Number> {
 	pub best_number: Number,
 }
 
+/// Manually banned peer information
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BannedPeer {
+	/// Peer ID
+	pub peer_id: String,
+	/// UNIX timestamp in seconds the ban expires at, if it ever does
+	pub expires_at: Option<u64>,
+}
+
 /// The role the node is running as
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum NodeRole {
@@ -24,7 +24,7 @@ use jsonrpsee::{
 	proc_macros::rpc,
 };
 
-pub use self::helpers::{Health, NodeRole, PeerInfo, SyncState, SystemInfo};
+pub use self::helpers::{BannedPeer, Health, NodeRole, PeerInfo, SyncState, SystemInfo};
 
 pub mod error;
 pub mod helpers;
@@ -101,6 +101,22 @@ pub trait SystemApi<Hash, Number> {
 	#[method(name = "system_reservedPeers")]
 	async fn system_reserved_peers(&self) -> RpcResult<Vec<String>>;
 
+	/// Bans a peer, disconnecting from it and refusing any connection with it. The string
+	/// should encode only the PeerId e.g. `QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV`.
+	///
+	/// The ban is lifted after `duration` seconds, or only by `unbanPeer` if `duration` is
+	/// missing. Bans are kept across restarts of the node.
+	#[method(name = "system_banPeer")]
+	async fn system_ban_peer(&self, peer_id: String, duration: Option<u64>) -> RpcResult<()>;
+
+	/// Lifts the ban of a peer. The string should encode only the PeerId.
+	#[method(name = "system_unbanPeer")]
+	async fn system_unban_peer(&self, peer_id: String) -> RpcResult<()>;
+
+	/// Returns the list of manually banned peers
+	#[method(name = "system_bannedPeers")]
+	async fn system_banned_peers(&self) -> RpcResult<Vec<BannedPeer>>;
+
 	/// Returns the roles the node is running as.
 	#[method(name = "system_nodeRoles")]
 	async fn system_node_roles(&self) -> RpcResult<Vec<NodeRole>>;
@@ -20,7 +20,7 @@
 #![allow(non_snake_case)]
 
 //! API trait of the chain head.
-use crate::chain_head::event::{ChainHeadEvent, FollowEvent, NetworkConfig};
+use crate::chain_head::event::{ChainHeadEvent, FollowEvent, NetworkConfig, StorageProofResult};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 #[rpc(client, server)]
@@ -106,6 +106,31 @@ pub trait ChainHeadApi<Hash> {
 		network_config: Option<NetworkConfig>,
 	);
 
+	/// Return a proof of up to `count` storage entries with the given `prefix`, following
+	/// `start_key` (exclusive), at a specific block's state.
+	///
+	/// The proof also covers whether further entries exist, allowing to verify whole maps,
+	/// or their absence, by continuing from the last returned key.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[subscription(
+		name = "chainHead_unstable_storageProof",
+		unsubscribe = "chainHead_unstable_stopStorageProof",
+		item = ChainHeadEvent<StorageProofResult>,
+	)]
+	fn chain_head_unstable_storage_proof(
+		&self,
+		follow_subscription: String,
+		hash: Hash,
+		prefix: String,
+		count: u32,
+		start_key: Option<String>,
+		child_key: Option<String>,
+		network_config: Option<NetworkConfig>,
+	);
+
 	/// Call into the Runtime API at a specified block's state.
 	///
 	/// # Unstable
@@ -24,7 +24,10 @@ use crate::{
 		api::ChainHeadApiServer,
 		chain_head_follow::ChainHeadFollower,
 		error::Error as ChainHeadRpcError,
-		event::{ChainHeadEvent, ChainHeadResult, ErrorEvent, FollowEvent, NetworkConfig},
+		event::{
+			ChainHeadEvent, ChainHeadResult, ErrorEvent, FollowEvent, NetworkConfig,
+			StorageProofResult,
+		},
 		subscription::{SubscriptionManagement, SubscriptionManagementError},
 	},
 	SubscriptionTaskExecutor,
@@ -38,8 +41,8 @@ use jsonrpsee::{
 };
 use log::debug;
 use sc_client_api::{
-	Backend, BlockBackend, BlockchainEvents, CallExecutor, ChildInfo, ExecutorProvider, StorageKey,
-	StorageProvider,
+	Backend, BlockBackend, BlockchainEvents, CallExecutor, ChildInfo, ExecutorProvider,
+	ProofProvider, StorageKey, StorageProvider,
 };
 use sp_api::CallApiAt;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
@@ -49,6 +52,9 @@ use std::{marker::PhantomData, sync::Arc, time::Duration};
 
 pub(crate) const LOG_TARGET: &str = "rpc-spec-v2";
 
+/// The maximum number of storage entries a single storage proof may cover.
+const MAX_STORAGE_PROOF_COUNT: u32 = 1000;
+
 /// An API for chain head RPC calls.
 pub struct ChainHead<BE: Backend<Block>, Block: BlockT, Client> {
 	/// Substrate client.
@@ -148,6 +154,7 @@ where
 		+ BlockchainEvents<Block>
 		+ CallApiAt<Block>
 		+ StorageProvider<Block, BE>
+		+ ProofProvider<Block>
 		+ 'static,
 {
 	fn chain_head_unstable_follow(
@@ -377,6 +384,84 @@ where
 		Ok(())
 	}
 
+	fn chain_head_unstable_storage_proof(
+		&self,
+		mut sink: SubscriptionSink,
+		follow_subscription: String,
+		hash: Block::Hash,
+		prefix: String,
+		count: u32,
+		start_key: Option<String>,
+		child_key: Option<String>,
+		_network_config: Option<NetworkConfig>,
+	) -> SubscriptionResult {
+		if count > MAX_STORAGE_PROOF_COUNT {
+			let _ = sink.reject(ChainHeadRpcError::InvalidParam(count.to_string()));
+			return Ok(())
+		}
+
+		let prefix = parse_hex_param(&mut sink, prefix)?;
+		let start_key = start_key.map(|key| parse_hex_param(&mut sink, key)).transpose()?;
+		let child_key = child_key
+			.map(|child_key| parse_hex_param(&mut sink, child_key))
+			.transpose()?
+			.map(ChildInfo::new_default_from_vec);
+
+		let client = self.client.clone();
+		let subscriptions = self.subscriptions.clone();
+
+		let block_guard = match subscriptions.lock_block(&follow_subscription, hash) {
+			Ok(block) => block,
+			Err(SubscriptionManagementError::SubscriptionAbsent) => {
+				// Invalid invalid subscription ID.
+				let _ = sink.send(&ChainHeadEvent::<StorageProofResult>::Disjoint);
+				return Ok(())
+			},
+			Err(SubscriptionManagementError::BlockHashAbsent) => {
+				// Block is not part of the subscription.
+				let _ = sink.reject(ChainHeadRpcError::InvalidBlock);
+				return Ok(())
+			},
+			Err(error) => {
+				let _ = sink.send(&ChainHeadEvent::<StorageProofResult>::Error(ErrorEvent {
+					error: error.to_string(),
+				}));
+				return Ok(())
+			},
+		};
+
+		let fut = async move {
+			let _block_guard = block_guard;
+			let res = client
+				.read_range_proof(
+					hash,
+					child_key.as_ref(),
+					Some(&prefix),
+					start_key.as_deref(),
+					count,
+					usize::MAX,
+				)
+				.map(|(proof, keys, complete)| {
+					let keys =
+						keys.iter().map(|key| format!("0x{:?}", HexDisplay::from(key))).collect();
+					let proof = proof
+						.into_iter_nodes()
+						.map(|node| format!("0x{:?}", HexDisplay::from(&node)))
+						.collect();
+					ChainHeadEvent::Done(ChainHeadResult {
+						result: StorageProofResult { keys, complete, proof },
+					})
+				})
+				.unwrap_or_else(|error| {
+					ChainHeadEvent::Error(ErrorEvent { error: error.to_string() })
+				});
+			let _ = sink.send(&res);
+		};
+
+		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+
 	fn chain_head_unstable_call(
 		&self,
 		mut sink: SubscriptionSink,
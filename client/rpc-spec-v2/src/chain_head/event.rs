@@ -225,6 +225,18 @@ pub struct ChainHeadResult<T> {
 	pub result: T,
 }
 
+/// The result of the storage proof method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageProofResult {
+	/// The hexadecimal encoded keys of the proven storage entries.
+	pub keys: Vec<String>,
+	/// True if no storage entries are left after `keys`.
+	pub complete: bool,
+	/// The hexadecimal encoded trie nodes of the proof.
+	pub proof: Vec<String>,
+}
+
 /// The event generated by the body / call / storage methods.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -40,5 +40,5 @@ pub use api::ChainHeadApiServer;
 pub use chain_head::ChainHead;
 pub use event::{
 	BestBlockChanged, ChainHeadEvent, ChainHeadResult, ErrorEvent, Finalized, FollowEvent,
-	Initialized, NetworkConfig, NewBlock, RuntimeEvent, RuntimeVersionEvent,
+	Initialized, NetworkConfig, NewBlock, RuntimeEvent, RuntimeVersionEvent, StorageProofResult,
 };
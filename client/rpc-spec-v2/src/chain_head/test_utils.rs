@@ -20,9 +20,10 @@
 use parking_lot::Mutex;
 use sc_client_api::{
 	execution_extensions::ExecutionExtensions, BlockBackend, BlockImportNotification,
-	BlockchainEvents, CallExecutor, ChildInfo, ExecutorProvider, FinalityNotification,
-	FinalityNotifications, FinalizeSummary, ImportNotifications, KeysIter, PairsIter, StorageData,
-	StorageEventStream, StorageKey, StorageProvider,
+	BlockchainEvents, CallExecutor, ChildInfo, CompactProof, ExecutorProvider,
+	FinalityNotification, FinalityNotifications, FinalizeSummary, ImportNotifications,
+	KeyValueStates, KeyValueStorageLevel, KeysIter, PairsIter, ProofProvider, StorageData,
+	StorageEventStream, StorageKey, StorageProof, StorageProvider,
 };
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 use sp_api::{CallApiAt, CallApiAtParams, NumberFor, RuntimeVersion};
@@ -201,6 +202,76 @@ impl<
 	}
 }
 
+impl<Block: BlockT, Client: ProofProvider<Block>> ProofProvider<Block>
+	for ChainHeadMockClient<Client>
+{
+	fn read_proof(
+		&self,
+		hash: Block::Hash,
+		keys: &mut dyn Iterator<Item = &[u8]>,
+	) -> sp_blockchain::Result<StorageProof> {
+		self.client.read_proof(hash, keys)
+	}
+
+	fn read_child_proof(
+		&self,
+		hash: Block::Hash,
+		child_info: &ChildInfo,
+		keys: &mut dyn Iterator<Item = &[u8]>,
+	) -> sp_blockchain::Result<StorageProof> {
+		self.client.read_child_proof(hash, child_info, keys)
+	}
+
+	fn read_range_proof(
+		&self,
+		hash: Block::Hash,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		start_key: Option<&[u8]>,
+		count: u32,
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, Vec<Vec<u8>>, bool)> {
+		self.client
+			.read_range_proof(hash, child_info, prefix, start_key, count, size_limit)
+	}
+
+	fn execution_proof(
+		&self,
+		hash: Block::Hash,
+		method: &str,
+		call_data: &[u8],
+	) -> sp_blockchain::Result<(Vec<u8>, StorageProof)> {
+		self.client.execution_proof(hash, method, call_data)
+	}
+
+	fn read_proof_collection(
+		&self,
+		hash: Block::Hash,
+		start_keys: &[Vec<u8>],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(CompactProof, u32)> {
+		self.client.read_proof_collection(hash, start_keys, size_limit)
+	}
+
+	fn storage_collection(
+		&self,
+		hash: Block::Hash,
+		start_key: &[Vec<u8>],
+		size_limit: usize,
+	) -> sp_blockchain::Result<Vec<(KeyValueStorageLevel, bool)>> {
+		self.client.storage_collection(hash, start_key, size_limit)
+	}
+
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: CompactProof,
+		start_keys: &[Vec<u8>],
+	) -> sp_blockchain::Result<(KeyValueStates, usize)> {
+		self.client.verify_range_proof(root, proof, start_keys)
+	}
+}
+
 impl<Block: BlockT, Client: CallApiAt<Block>> CallApiAt<Block> for ChainHeadMockClient<Client> {
 	type StateBackend = <Client as CallApiAt<Block>>::StateBackend;
 
@@ -12,6 +12,7 @@ use jsonrpsee::{
 use sc_block_builder::BlockBuilderProvider;
 use sc_client_api::ChildInfo;
 use sc_service::client::new_in_mem;
+use serde_json::json;
 use sp_api::BlockT;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::BlockOrigin;
@@ -579,6 +580,69 @@ async fn get_storage() {
 	assert_matches!(event, ChainHeadEvent::<Option<String>>::Done(done) if done.result == expected_value);
 }
 
+#[tokio::test]
+async fn get_storage_proof() {
+	let (client, api, _block_sub, sub_id, block) = setup_api().await;
+	let block_hash = format!("{:?}", block.header.hash());
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+	let key = format!("0x{:?}", HexDisplay::from(&KEY));
+	let child_info = format!("0x{:?}", HexDisplay::from(&CHILD_STORAGE_KEY));
+
+	// Subscription ID is stale the disjoint event is emitted.
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_storageProof",
+			[json!("invalid_sub_id"), json!(block_hash), json!(key), json!(1)],
+		)
+		.await
+		.unwrap();
+	let event: ChainHeadEvent<StorageProofResult> = get_next_event(&mut sub).await;
+	assert_eq!(event, ChainHeadEvent::<StorageProofResult>::Disjoint);
+
+	// Too many entries requested.
+	let err = api
+		.subscribe(
+			"chainHead_unstable_storageProof",
+			[json!(sub_id), json!(block_hash), json!(key), json!(1001)],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2003
+	);
+
+	// Absence of any entry with the prefix is proven.
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_storageProof",
+			[json!(sub_id), json!(block_hash), json!(key), json!(10)],
+		)
+		.await
+		.unwrap();
+	let event: ChainHeadEvent<StorageProofResult> = get_next_event(&mut sub).await;
+	assert_matches!(event, ChainHeadEvent::Done(done) if done.result.keys.is_empty() &&
+		done.result.complete && !done.result.proof.is_empty());
+
+	// Child value set in `setup_api`.
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_storageProof",
+			[
+				json!(sub_id),
+				json!(genesis_hash),
+				json!(""),
+				json!(10),
+				json!(null),
+				json!(child_info),
+			],
+		)
+		.await
+		.unwrap();
+	let event: ChainHeadEvent<StorageProofResult> = get_next_event(&mut sub).await;
+	assert_matches!(event, ChainHeadEvent::Done(done) if done.result.keys == vec![key.clone()] &&
+		done.result.complete);
+}
+
 #[tokio::test]
 async fn get_storage_wrong_key() {
 	let (mut _client, api, mut _block_sub, sub_id, block) = setup_api().await;
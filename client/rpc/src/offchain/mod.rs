@@ -34,6 +34,15 @@ use sp_core::{
 };
 use std::sync::Arc;
 
+/// Maximum number of entries returned by a single local storage iteration.
+const LOCAL_STORAGE_ITER_MAX_COUNT: u32 = 1000;
+
+/// Maximum length of the key a local storage iteration starts after.
+///
+/// The RocksDB backend can't seek to a key, so starting after a key takes up to 255 prefix
+/// iterations per byte of it.
+const LOCAL_STORAGE_ITER_MAX_START_KEY_LEN: u32 = 128;
+
 /// Offchain API
 #[derive(Debug)]
 pub struct Offchain<T: OffchainStorage> {
@@ -47,6 +56,41 @@ impl<T: OffchainStorage> Offchain<T> {
 	pub fn new(storage: T, deny_unsafe: DenyUnsafe) -> Self {
 		Offchain { storage: Arc::new(RwLock::new(storage)), deny_unsafe }
 	}
+
+	/// Retrieve up to `count` entries of the local storage whose keys start with `prefix`.
+	fn iter_local_storage(
+		&self,
+		kind: StorageKind,
+		prefix: &[u8],
+		count: u32,
+		start_key: Option<&[u8]>,
+	) -> RpcResult<Vec<(Vec<u8>, Vec<u8>)>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let storage_prefix = match kind {
+			StorageKind::PERSISTENT => sp_offchain::STORAGE_PREFIX,
+			StorageKind::LOCAL => return Err(JsonRpseeError::from(Error::UnavailableStorageKind)),
+		};
+		if count > LOCAL_STORAGE_ITER_MAX_COUNT {
+			return Err(JsonRpseeError::from(Error::InvalidCount {
+				value: count,
+				max: LOCAL_STORAGE_ITER_MAX_COUNT,
+			}))
+		}
+		if let Some(start_key) = start_key {
+			if start_key.len() > LOCAL_STORAGE_ITER_MAX_START_KEY_LEN as usize {
+				return Err(JsonRpseeError::from(Error::StartKeyTooLong {
+					len: start_key.len().try_into().unwrap_or(u32::MAX),
+					max: LOCAL_STORAGE_ITER_MAX_START_KEY_LEN,
+				}))
+			}
+		}
+
+		self.storage
+			.read()
+			.iter_prefix(storage_prefix, prefix, start_key, count as usize)
+			.ok_or_else(|| JsonRpseeError::from(Error::UnsupportedIteration))
+	}
 }
 
 #[async_trait]
@@ -72,4 +116,26 @@ impl<T: OffchainStorage + 'static> OffchainApiServer for Offchain<T> {
 
 		Ok(self.storage.read().get(prefix, &key).map(Into::into))
 	}
+
+	fn local_storage_keys(
+		&self,
+		kind: StorageKind,
+		prefix: Bytes,
+		count: u32,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Vec<Bytes>> {
+		let entries = self.iter_local_storage(kind, &prefix, count, start_key.as_deref())?;
+		Ok(entries.into_iter().map(|(key, _)| key.into()).collect())
+	}
+
+	fn local_storage_iter(
+		&self,
+		kind: StorageKind,
+		prefix: Bytes,
+		count: u32,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Vec<(Bytes, Bytes)>> {
+		let entries = self.iter_local_storage(kind, &prefix, count, start_key.as_deref())?;
+		Ok(entries.into_iter().map(|(key, value)| (key.into(), value.into())).collect())
+	}
 }
@@ -38,6 +38,49 @@ fn local_storage_should_work() {
 	);
 }
 
+#[test]
+fn local_storage_iteration_should_work() {
+	let storage = InMemOffchainStorage::default();
+	let offchain = Offchain::new(storage, DenyUnsafe::No);
+	for (key, value) in [(&b"index/b"[..], &b"2"[..]), (b"index/a", b"1"), (b"other", b"3")] {
+		offchain
+			.set_local_storage(StorageKind::PERSISTENT, key.to_vec().into(), value.to_vec().into())
+			.unwrap();
+	}
+	let prefix = Bytes(b"index/".to_vec());
+
+	assert_matches!(
+		offchain.local_storage_keys(StorageKind::PERSISTENT, prefix.clone(), 10, None),
+		Ok(ref keys) if *keys == vec![Bytes(b"index/a".to_vec()), Bytes(b"index/b".to_vec())]
+	);
+	assert_matches!(
+		offchain.local_storage_iter(
+			StorageKind::PERSISTENT,
+			prefix.clone(),
+			10,
+			Some(Bytes(b"index/a".to_vec())),
+		),
+		Ok(ref entries) if *entries == vec![(Bytes(b"index/b".to_vec()), Bytes(b"2".to_vec()))]
+	);
+	assert_matches!(
+		offchain.local_storage_keys(StorageKind::PERSISTENT, prefix.clone(), 1001, None),
+		Err(JsonRpseeError::Call(_))
+	);
+	assert_matches!(
+		offchain.local_storage_keys(
+			StorageKind::PERSISTENT,
+			prefix.clone(),
+			10,
+			Some(Bytes(vec![0; 129])),
+		),
+		Err(JsonRpseeError::Call(_))
+	);
+	assert_matches!(
+		offchain.local_storage_keys(StorageKind::LOCAL, prefix, 10, None),
+		Err(JsonRpseeError::Call(_))
+	);
+}
+
 #[test]
 fn offchain_calls_considered_unsafe() {
 	use jsonrpsee::types::error::CallError;
@@ -53,7 +96,13 @@ fn offchain_calls_considered_unsafe() {
 		}
 	);
 	assert_matches!(
-		offchain.get_local_storage(StorageKind::PERSISTENT, key),
+		offchain.get_local_storage(StorageKind::PERSISTENT, key.clone()),
+		Err(JsonRpseeError::Call(CallError::Custom(err))) => {
+			assert_eq!(err.message(), "RPC call is unsafe to be called externally")
+		}
+	);
+	assert_matches!(
+		offchain.local_storage_iter(StorageKind::PERSISTENT, key, 10, None),
 		Err(JsonRpseeError::Call(CallError::Custom(err))) => {
 			assert_eq!(err.message(), "RPC call is unsafe to be called externally")
 		}
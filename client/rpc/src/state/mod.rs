@@ -54,6 +54,12 @@ use sp_blockchain::{HeaderBackend, HeaderMetadata};
 
 const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
 
+/// Maximum number of blocks a `state_getReadProofRange` call may cover.
+const READ_PROOF_RANGE_MAX_BLOCKS: u32 = 256;
+
+/// Maximum number of keys a `state_getReadProofRange` call may prove.
+const READ_PROOF_RANGE_MAX_KEYS: u32 = 1000;
+
 /// State backend API.
 #[async_trait]
 pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
@@ -149,6 +155,23 @@ where
 		keys: Vec<StorageKey>,
 	) -> Result<ReadProof<Block::Hash>, Error>;
 
+	/// Returns proof of a range of storage entries at a specific block's state.
+	fn read_proof_paged(
+		&self,
+		block: Option<Block::Hash>,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
+	) -> Result<ReadProofPaged<Block::Hash>, Error>;
+
+	/// Returns a compact proof of storage entries at every block in a range.
+	fn read_proof_range(
+		&self,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		keys: Vec<StorageKey>,
+	) -> Result<ReadProofRange<Block::Hash>, Error>;
+
 	/// Trace storage changes for block
 	fn trace_block(
 		&self,
@@ -308,6 +331,34 @@ where
 		self.backend.read_proof(block, keys).map_err(Into::into)
 	}
 
+	fn read_proof_paged(
+		&self,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
+		block: Option<Block::Hash>,
+	) -> RpcResult<ReadProofPaged<Block::Hash>> {
+		if count > STORAGE_KEYS_PAGED_MAX_COUNT {
+			return Err(JsonRpseeError::from(Error::InvalidCount {
+				value: count,
+				max: STORAGE_KEYS_PAGED_MAX_COUNT,
+			}))
+		}
+		self.backend
+			.read_proof_paged(block, prefix, count, start_key)
+			.map_err(Into::into)
+	}
+
+	fn read_proof_range(
+		&self,
+		keys: Vec<StorageKey>,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+	) -> RpcResult<ReadProofRange<Block::Hash>> {
+		self.deny_unsafe.check_if_safe()?;
+		self.backend.read_proof_range(from, to, keys).map_err(Into::into)
+	}
+
 	/// Re-execute the given block with the tracing targets given in `targets`
 	/// and capture all state changes.
 	///
@@ -362,6 +413,16 @@ where
 		keys: Vec<StorageKey>,
 	) -> Result<ReadProof<Block::Hash>, Error>;
 
+	/// Returns proof of a range of child storage entries at a specific block's state.
+	fn read_child_proof_paged(
+		&self,
+		block: Option<Block::Hash>,
+		storage_key: PrefixedStorageKey,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
+	) -> Result<ReadProofPaged<Block::Hash>, Error>;
+
 	/// Returns the keys with prefix from a child storage,
 	/// leave prefix empty to get all the keys.
 	fn storage_keys(
@@ -494,6 +555,25 @@ where
 			.read_child_proof(block, child_storage_key, keys)
 			.map_err(Into::into)
 	}
+
+	fn read_child_proof_paged(
+		&self,
+		child_storage_key: PrefixedStorageKey,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
+		block: Option<Block::Hash>,
+	) -> RpcResult<ReadProofPaged<Block::Hash>> {
+		if count > STORAGE_KEYS_PAGED_MAX_COUNT {
+			return Err(JsonRpseeError::from(Error::InvalidCount {
+				value: count,
+				max: STORAGE_KEYS_PAGED_MAX_COUNT,
+			}))
+		}
+		self.backend
+			.read_child_proof_paged(block, child_storage_key, prefix, count, start_key)
+			.map_err(Into::into)
+	}
 }
 
 fn client_err(err: sp_blockchain::Error) -> Error {
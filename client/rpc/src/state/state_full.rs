@@ -24,20 +24,21 @@ use std::{collections::HashMap, marker::PhantomData, sync::Arc, time::Duration};
 use super::{
 	client_err,
 	error::{Error, Result},
-	ChildStateBackend, StateBackend,
+	ChildStateBackend, StateBackend, READ_PROOF_RANGE_MAX_BLOCKS, READ_PROOF_RANGE_MAX_KEYS,
 };
 use crate::{DenyUnsafe, SubscriptionTaskExecutor};
 
+use codec::Encode;
 use futures::{future, stream, FutureExt, StreamExt};
 use jsonrpsee::{
 	core::{async_trait, Error as JsonRpseeError},
 	SubscriptionSink,
 };
 use sc_client_api::{
-	Backend, BlockBackend, BlockchainEvents, CallExecutor, ExecutorProvider, ProofProvider,
-	StorageProvider,
+	Backend, BlockBackend, BlockchainEvents, CallExecutor, CompactMultiProof, ExecutorProvider,
+	ProofProvider, StorageProvider,
 };
-use sc_rpc_api::state::ReadProof;
+use sc_rpc_api::state::{ReadProof, ReadProofPaged, ReadProofRange};
 use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
 use sp_blockchain::{
 	CachedHeaderMetadata, Error as ClientError, HeaderBackend, HeaderMetadata,
@@ -50,7 +51,10 @@ use sp_core::{
 	traits::CallContext,
 	Bytes,
 };
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::{
+	traits::{Block as BlockT, HashFor, One, Saturating},
+	SaturatedConversion,
+};
 use sp_version::RuntimeVersion;
 
 /// The maximum time allowed for an RPC call when running without unsafe RPC enabled.
@@ -372,6 +376,85 @@ where
 			.map_err(client_err)
 	}
 
+	fn read_proof_paged(
+		&self,
+		block: Option<Block::Hash>,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
+	) -> std::result::Result<ReadProofPaged<Block::Hash>, Error> {
+		self.block_or_best(block)
+			.and_then(|block| {
+				self.client
+					.read_range_proof(
+						block,
+						None,
+						prefix.as_ref().map(|prefix| prefix.0.as_slice()),
+						start_key.as_ref().map(|start_key| start_key.0.as_slice()),
+						count,
+						usize::MAX,
+					)
+					.map(|(proof, keys, complete)| ReadProofPaged {
+						at: block,
+						keys: keys.into_iter().map(StorageKey).collect(),
+						complete,
+						proof: proof.into_iter_nodes().map(|node| node.into()).collect(),
+					})
+			})
+			.map_err(client_err)
+	}
+
+	fn read_proof_range(
+		&self,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		keys: Vec<StorageKey>,
+	) -> std::result::Result<ReadProofRange<Block::Hash>, Error> {
+		if keys.len() > READ_PROOF_RANGE_MAX_KEYS as usize {
+			return Err(Error::InvalidCount {
+				value: keys.len().saturated_into(),
+				max: READ_PROOF_RANGE_MAX_KEYS,
+			})
+		}
+
+		// Check the size of the range before walking its headers.
+		let to = self
+			.block_or_best(to)
+			.map_err(|e| invalid_block::<Block>(from, to, e.to_string()))?;
+		let number_of = |hash| {
+			self.client
+				.header_metadata(hash)
+				.map(|meta| meta.number)
+				.map_err(|e| invalid_block::<Block>(from, Some(to), e.to_string()))
+		};
+		let blocks = number_of(to)?.saturating_sub(number_of(from)?).saturating_add(One::one());
+		if blocks > READ_PROOF_RANGE_MAX_BLOCKS.into() {
+			return Err(Error::InvalidCount {
+				value: blocks.saturated_into(),
+				max: READ_PROOF_RANGE_MAX_BLOCKS,
+			})
+		}
+
+		let range = self.query_storage_range(from, Some(to))?;
+
+		let proofs = range
+			.hashes
+			.iter()
+			.map(|block| {
+				let state_root = self.client.header_metadata(*block)?.state_root;
+				let proof =
+					self.client.read_proof(*block, &mut keys.iter().map(|key| key.0.as_ref()))?;
+				Ok((state_root, proof))
+			})
+			.collect::<ClientResult<Vec<_>>>()
+			.map_err(client_err)?;
+
+		let proof = CompactMultiProof::from_storage_proofs::<HashFor<Block>>(proofs)
+			.map_err(|e| Error::Client(Box::new(e)))?;
+
+		Ok(ReadProofRange { blocks: range.hashes, proof: proof.encode().into() })
+	}
+
 	fn subscribe_runtime_version(&self, mut sink: SubscriptionSink) {
 		let client = self.client.clone();
 
@@ -520,6 +603,40 @@ where
 			.map_err(client_err)
 	}
 
+	fn read_child_proof_paged(
+		&self,
+		block: Option<Block::Hash>,
+		storage_key: PrefixedStorageKey,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
+	) -> std::result::Result<ReadProofPaged<Block::Hash>, Error> {
+		self.block_or_best(block)
+			.and_then(|block| {
+				let child_info = match ChildType::from_prefixed_key(&storage_key) {
+					Some((ChildType::ParentKeyId, storage_key)) =>
+						ChildInfo::new_default(storage_key),
+					None => return Err(sp_blockchain::Error::InvalidChildStorageKey),
+				};
+				self.client
+					.read_range_proof(
+						block,
+						Some(&child_info),
+						prefix.as_ref().map(|prefix| prefix.0.as_slice()),
+						start_key.as_ref().map(|start_key| start_key.0.as_slice()),
+						count,
+						usize::MAX,
+					)
+					.map(|(proof, keys, complete)| ReadProofPaged {
+						at: block,
+						keys: keys.into_iter().map(StorageKey).collect(),
+						complete,
+						proof: proof.into_iter_nodes().map(|node| node.into()).collect(),
+					})
+			})
+			.map_err(client_err)
+	}
+
 	fn storage_keys(
 		&self,
 		block: Option<Block::Hash>,
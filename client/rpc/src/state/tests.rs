@@ -21,15 +21,18 @@ use self::error::Error;
 use super::*;
 use crate::testing::{test_executor, timeout_secs};
 use assert_matches::assert_matches;
+use codec::Decode;
 use futures::executor;
 use jsonrpsee::{
 	core::Error as RpcError,
 	types::{error::CallError as RpcCallError, EmptyServerParams as EmptyParams, ErrorObject},
 };
 use sc_block_builder::BlockBuilderProvider;
+use sc_client_api::CompactMultiProof;
 use sc_rpc_api::DenyUnsafe;
 use sp_consensus::BlockOrigin;
 use sp_core::{hash::H256, storage::ChildInfo};
+use sp_runtime::traits::{BlakeTwo256, Header as _};
 use std::sync::Arc;
 use substrate_test_runtime_client::{
 	prelude::*,
@@ -128,6 +131,62 @@ async fn should_return_storage_entries() {
 	);
 }
 
+#[tokio::test]
+async fn should_return_read_proof_paged() {
+	const CHILD_VALUE: &[u8] = b"hello world !";
+
+	let child_info = ChildInfo::new_default(STORAGE_KEY);
+	let client = TestClientBuilder::new()
+		.add_extra_storage(b":map:acc1".to_vec(), vec![1, 2])
+		.add_extra_storage(b":map:acc2".to_vec(), vec![1, 2, 3])
+		.add_extra_child_storage(&child_info, b":map:acc1".to_vec(), CHILD_VALUE.to_vec())
+		.build();
+	let genesis_hash = client.genesis_hash();
+	let (api, child) = new_full(Arc::new(client), test_executor(), DenyUnsafe::No);
+	let prefix = StorageKey(b":map".to_vec());
+
+	let page = api.read_proof_paged(Some(prefix.clone()), 1, None, None).unwrap();
+	assert_eq!(page.keys, vec![StorageKey(b":map:acc1".to_vec())]);
+	assert!(!page.complete);
+	assert!(!page.proof.is_empty());
+
+	// `start_key` is exclusive, so following the last key of each page advances, the pages
+	// don't overlap, and paging ends.
+	let mut start_key = None;
+	let mut keys = Vec::new();
+	let mut pages = 0;
+	loop {
+		let page = api.read_proof_paged(Some(prefix.clone()), 1, start_key, None).unwrap();
+		pages += 1;
+		assert!(pages <= 3, "paging did not end");
+		assert!(page.keys.len() <= 1);
+		keys.extend(page.keys.iter().cloned());
+		if page.complete {
+			break
+		}
+		start_key = page.keys.last().cloned();
+	}
+	assert_eq!(keys, vec![StorageKey(b":map:acc1".to_vec()), StorageKey(b":map:acc2".to_vec())]);
+
+	let page = api
+		.read_proof_paged(Some(prefix.clone()), 10, Some(StorageKey(b":map:acc1".to_vec())), None)
+		.unwrap();
+	assert_eq!(page.keys, vec![StorageKey(b":map:acc2".to_vec())]);
+	assert!(page.complete);
+
+	let page = child
+		.read_child_proof_paged(prefixed_storage_key(), Some(prefix.clone()), 10, None, None)
+		.unwrap();
+	assert_eq!(page.at, genesis_hash);
+	assert_eq!(page.keys, vec![StorageKey(b":map:acc1".to_vec())]);
+	assert!(page.complete);
+
+	assert_matches!(
+		api.read_proof_paged(Some(prefix), STORAGE_KEYS_PAGED_MAX_COUNT + 1, None, None),
+		Err(RpcError::Call(RpcCallError::Custom(err))) if err.code() == 4002
+	);
+}
+
 #[tokio::test]
 async fn should_return_child_storage() {
 	let child_info = ChildInfo::new_default(STORAGE_KEY);
@@ -508,6 +567,71 @@ async fn should_query_storage() {
 	run_tests(Arc::new(TestClientBuilder::new().build())).await;
 }
 
+#[tokio::test]
+async fn should_return_read_proof_range() {
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let (api, _child) = new_full(client.clone(), test_executor(), DenyUnsafe::No);
+
+	let mut hashes = vec![client.genesis_hash()];
+	for index in 0..3u8 {
+		let mut builder = client.new_block(Default::default()).unwrap();
+		builder
+			.push(ExtrinsicBuilder::new_storage_change(vec![1], Some(vec![index])).build())
+			.unwrap();
+		let block = builder.build().unwrap().block;
+		hashes.push(block.header.hash());
+		executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+	}
+
+	let keys = vec![StorageKey(vec![1]), StorageKey(vec![2])];
+	let result = api.read_proof_range(keys.clone(), hashes[1], None).unwrap();
+	assert_eq!(result.blocks, hashes[1..]);
+
+	let roots = result
+		.blocks
+		.iter()
+		.map(|hash| *client.header(*hash).unwrap().unwrap().state_root())
+		.collect::<Vec<_>>();
+	let proof = CompactMultiProof::decode(&mut &result.proof[..]).unwrap();
+	let db = proof.to_memory_db::<BlakeTwo256>(&roots).unwrap();
+
+	// Every block's values are provable from the single proof.
+	for (index, (hash, root)) in result.blocks.iter().zip(&roots).enumerate() {
+		for key in &keys {
+			let proven = sp_trie::read_trie_value::<sp_trie::LayoutV1<BlakeTwo256>, _>(
+				&db, root, &key.0, None, None,
+			)
+			.unwrap();
+			assert_eq!(proven, client.storage(*hash, key).unwrap().map(|data| data.0));
+		}
+		let proven = sp_trie::read_trie_value::<sp_trie::LayoutV1<BlakeTwo256>, _>(
+			&db,
+			root,
+			&[1],
+			None,
+			None,
+		)
+		.unwrap();
+		assert_eq!(proven, Some(vec![index as u8]));
+	}
+
+	// Too many keys are rejected before any proof is generated.
+	let too_many_keys = (0..=READ_PROOF_RANGE_MAX_KEYS)
+		.map(|key| StorageKey(key.to_le_bytes().to_vec()))
+		.collect::<Vec<_>>();
+	assert_matches!(
+		api.read_proof_range(too_many_keys, hashes[1], None),
+		Err(RpcError::Call(RpcCallError::Custom(err))) if err.message().contains("count exceeds maximum value")
+	);
+
+	// Range proofs are unsafe.
+	let (api, _child) = new_full(client.clone(), test_executor(), DenyUnsafe::Yes);
+	assert_matches!(
+		api.read_proof_range(keys, hashes[0], None),
+		Err(RpcError::Call(RpcCallError::Custom(err))) if err.message().contains("RPC call is unsafe to be called externally")
+	);
+}
+
 #[tokio::test]
 async fn should_return_runtime_version() {
 	let client = Arc::new(substrate_test_runtime_client::new());
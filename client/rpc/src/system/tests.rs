@@ -95,6 +95,7 @@ fn api<T: Into<Option<Status>>>(sync: T) -> RpcModule<System<Block>> {
 							connected_peers: Default::default(),
 							not_connected_peers: Default::default(),
 							peerset: serde_json::Value::Null,
+							protocols_bandwidth: Default::default(),
 						})
 						.unwrap(),
 					);
@@ -117,6 +118,20 @@ fn api<T: Into<Option<Status>>>(sync: T) -> RpcModule<System<Block>> {
 					let _ = sender
 						.send(vec!["QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string()]);
 				},
+				Request::NetworkBanPeer(peer, _, sender) |
+				Request::NetworkUnbanPeer(peer, sender) => {
+					let _ = match peer.parse::<PeerId>() {
+						Ok(_) => sender.send(Ok(())),
+						Err(s) =>
+							sender.send(Err(error::Error::MalformattedPeerArg(s.to_string()))),
+					};
+				},
+				Request::NetworkBannedPeers(sender) => {
+					let _ = sender.send(vec![BannedPeer {
+						peer_id: "QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string(),
+						expires_at: Some(1_700_000_000),
+					}]);
+				},
 				Request::NodeRoles(sender) => {
 					let _ = sender.send(vec![NodeRole::Authority]);
 				},
@@ -284,6 +299,7 @@ async fn system_network_state() {
 			connected_peers: Default::default(),
 			not_connected_peers: Default::default(),
 			peerset: serde_json::Value::Null,
+			protocols_bandwidth: Default::default(),
 		}
 	);
 }
@@ -339,6 +355,40 @@ async fn system_network_reserved_peers() {
 	assert_eq!(reserved_peers, vec!["QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string()],);
 }
 
+#[tokio::test]
+async fn system_network_ban_peer() {
+	let _good: () = api(None)
+		.call("system_banPeer", ("QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV", Some(60)))
+		.await
+		.expect("call with good peer id works");
+	let _forever: () = api(None)
+		.call("system_banPeer", ("QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV", None::<u64>))
+		.await
+		.expect("duration is optional");
+	let _unban: () = api(None)
+		.call("system_unbanPeer", ["QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV"])
+		.await
+		.expect("call with good peer id works");
+
+	assert_matches!(
+		api(None).call::<_, ()>("system_banPeer", ("/ip4/198.51.100.19/tcp/30333", None::<u64>)).await,
+		Err(RpcError::Call(CallError::Custom(err))) if err.message().contains("base-58 decode error")
+	);
+}
+
+#[tokio::test]
+async fn system_network_banned_peers() {
+	let banned_peers: Vec<BannedPeer> =
+		api(None).call("system_bannedPeers", EmptyParams::new()).await.unwrap();
+	assert_eq!(
+		banned_peers,
+		vec![BannedPeer {
+			peer_id: "QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string(),
+			expires_at: Some(1_700_000_000),
+		}],
+	);
+}
+
 #[test]
 fn test_add_reset_log_filter() {
 	const EXPECTED_BEFORE_ADD: &'static str = "EXPECTED_BEFORE_ADD";
@@ -46,7 +46,7 @@ use sc_network::{
 	config::{FullNetworkConfiguration, SyncMode},
 	NetworkService, NetworkStateInfo, NetworkStatusProvider,
 };
-use sc_network_bitswap::BitswapRequestHandler;
+use sc_network_bitswap::{BitswapRequestHandler, BitswapStore, ContentProvider};
 use sc_network_common::{role::Roles, sync::warp::WarpSyncParams};
 use sc_network_light::light_client_requests::handler::LightClientRequestHandler;
 use sc_network_sync::{
@@ -73,7 +73,7 @@ use sp_consensus::block_validation::{
 };
 use sp_core::traits::{CodeExecutor, SpawnNamed};
 use sp_keystore::KeystorePtr;
-use sp_runtime::traits::{Block as BlockT, BlockIdTo, NumberFor, Zero};
+use sp_runtime::traits::{Block as BlockT, BlockIdTo, Header as HeaderT, NumberFor, Zero};
 use std::{
 	str::FromStr,
 	sync::Arc,
@@ -223,10 +223,7 @@ where
 				offchain_worker_enabled: config.offchain_worker.enabled,
 				offchain_indexing_api: config.offchain_worker.indexing_enabled,
 				wasm_runtime_overrides: config.wasm_runtime_overrides.clone(),
-				no_genesis: matches!(
-					config.network.sync_mode,
-					SyncMode::LightState { .. } | SyncMode::Warp { .. }
-				),
+				no_genesis: config.no_genesis(),
 				wasm_runtime_substitutes,
 			},
 		)?;
@@ -712,6 +709,36 @@ pub struct BuildNetworkParams<'a, TBl: BlockT, TExPool, TImpQu, TCl> {
 	pub warp_sync_params: Option<WarpSyncParams<TBl>>,
 }
 
+/// Import the snapshot given with `--sync snapshot`, if any.
+///
+/// Syncing continues from the block of the snapshot, so this has to complete before the network
+/// is built with [`build_network`]. The finality of the snapshot is verified with the warp sync
+/// provider.
+pub async fn import_configured_snapshot<TBl, TCl, TImpQu>(
+	config: &Configuration,
+	client: Arc<TCl>,
+	import_queue: &mut TImpQu,
+	warp_sync_params: Option<&WarpSyncParams<TBl>>,
+) -> Result<(), Error>
+where
+	TBl: BlockT,
+	TCl: HeaderBackend<TBl>,
+	TImpQu: ImportQueue<TBl>,
+{
+	let snapshot = match &config.network.snapshot {
+		Some(snapshot) => snapshot,
+		None => return Ok(()),
+	};
+	let warp_sync_provider = match warp_sync_params {
+		Some(WarpSyncParams::WithProvider(provider)) => provider,
+		_ =>
+			return Err("Snapshot sync requires a warp sync provider to verify the snapshot".into()),
+	};
+
+	let input = std::io::BufReader::new(std::fs::File::open(snapshot)?);
+	crate::chain_ops::import_snapshot(client, import_queue, &**warp_sync_provider, input).await
+}
+
 /// Build the network service, the network status sinks and an RPC sender.
 pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 	params: BuildNetworkParams<TBl, TExPool, TImpQu, TCl>,
@@ -761,6 +788,19 @@ where
 			SyncMode::Warp => return Err("Warp sync doesn't work for archive nodes".into()),
 			SyncMode::Full => {},
 		}
+		if config.network.snapshot.is_some() {
+			return Err("Snapshot sync doesn't work for archive nodes".into())
+		}
+	}
+
+	if let Some(snapshot) = &config.network.snapshot {
+		let input = std::io::BufReader::new(std::fs::File::open(snapshot)?);
+		let number = *crate::chain_ops::read_snapshot_header::<TBl>(input)?.number();
+		if client.info().finalized_number < number {
+			return Err("The snapshot must be imported with `import_configured_snapshot` before \
+				building the network"
+				.into())
+		}
 	}
 
 	let protocol_id = config.protocol_id();
@@ -779,6 +819,7 @@ where
 			client.clone(),
 			net_config.network_config.default_peers_set.in_peers as usize +
 				net_config.network_config.default_peers_set.out_peers as usize,
+			net_config.network_config.request_rate_limit,
 		);
 		let config_name = protocol_config.name.clone();
 		spawn_handle.spawn("block-request-handler", Some("networking"), handler.run());
@@ -792,6 +833,7 @@ where
 			config.chain_spec.fork_id(),
 			client.clone(),
 			net_config.network_config.default_peers_set_num_full as usize,
+			net_config.network_config.request_rate_limit,
 		);
 		let config_name = protocol_config.name.clone();
 
@@ -799,10 +841,10 @@ where
 		(protocol_config, config_name)
 	};
 
-	let (warp_sync_protocol_config, warp_request_protocol_name) = match warp_sync_params.as_ref() {
+	let (warp_sync_protocol_configs, warp_request_protocol_name) = match warp_sync_params.as_ref() {
 		Some(WarpSyncParams::WithProvider(warp_with_provider)) => {
 			// Allow both outgoing and incoming requests.
-			let (handler, protocol_config) = WarpSyncRequestHandler::new(
+			let (mut handler, protocol_config) = WarpSyncRequestHandler::new(
 				protocol_id.clone(),
 				client
 					.block_hash(0u32.into())
@@ -813,11 +855,13 @@ where
 				warp_with_provider.clone(),
 			);
 			let config_name = protocol_config.name.clone();
+			let mut protocol_configs = vec![protocol_config];
+			protocol_configs.extend(handler.versioned_protocol_configs());
 
 			spawn_handle.spawn("warp-sync-request-handler", Some("networking"), handler.run());
-			(Some(protocol_config), Some(config_name))
+			(protocol_configs, Some(config_name))
 		},
-		_ => (None, None),
+		_ => (Vec::new(), None),
 	};
 
 	let light_client_request_protocol_config = {
@@ -836,15 +880,20 @@ where
 	net_config.add_request_response_protocol(state_request_protocol_config);
 	net_config.add_request_response_protocol(light_client_request_protocol_config);
 
-	if let Some(config) = warp_sync_protocol_config {
+	for config in warp_sync_protocol_configs {
 		net_config.add_request_response_protocol(config);
 	}
 
-	if config.network.ipfs_server {
-		let (handler, protocol_config) = BitswapRequestHandler::new(client.clone());
+	let bitswap_store = if config.network.ipfs_server {
+		let store = BitswapStore::new();
+		let (handler, protocol_config) =
+			BitswapRequestHandler::new_with_store(client.clone(), store.clone());
 		spawn_handle.spawn("bitswap-request-handler", Some("networking"), handler.run());
 		net_config.add_request_response_protocol(protocol_config);
-	}
+		Some(store)
+	} else {
+		None
+	};
 
 	// create transactions protocol and add it to the list of supported protocols of
 	// `network_params`
@@ -902,6 +951,14 @@ where
 	let network_mut = sc_network::NetworkWorker::new(network_params)?;
 	let network = network_mut.service().clone();
 
+	if let Some(store) = bitswap_store {
+		let mut provider = ContentProvider::new(client.clone(), network.clone(), store);
+		if let Some(interval) = config.network.ipfs_state_interval {
+			provider = provider.with_state_interval(interval.into());
+		}
+		spawn_handle.spawn_blocking("bitswap-content-provider", Some("networking"), provider.run());
+	}
+
 	let (tx_handler, tx_handler_controller) = transactions_handler_proto.build(
 		network.clone(),
 		sync_service.clone(),
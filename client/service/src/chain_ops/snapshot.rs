@@ -0,0 +1,635 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Export and import of state snapshots.
+//!
+//! A snapshot contains the header, the justifications and the state of a finalized block, which is
+//! all a node needs to continue syncing from that block. It's a stream of [`SnapshotChunk`]s, each
+//! one followed by the hash of all chunks up to and including it, and ends with
+//! [`SnapshotChunk::End`]. Reading a corrupted or truncated snapshot fails, and the client checks
+//! the state against the state root of the header when importing it.
+//!
+//! The finality of the block is proven by the warp sync proofs leading to it from the genesis
+//! block. They precede the state and are verified before any state is read, so the state of a
+//! block which isn't final is never loaded. The state is read chunk by chunk, but is imported in
+//! a single operation, so it's held in memory once before the import.
+
+use crate::error::Error;
+use codec::{Decode, Encode, IoReader};
+use futures::future;
+use log::info;
+use sc_client_api::{BlockBackend, HeaderBackend, StorageProvider};
+use sc_consensus::{
+	import_queue::{BlockImportError, BlockImportStatus, ImportQueue, IncomingBlock, Link},
+	ImportedState,
+};
+use sc_network_common::sync::warp::{EncodedProof, VerificationResult, WarpSyncProvider};
+use sp_consensus::BlockOrigin;
+use sp_core::{
+	hashing::blake2_256,
+	storage::{well_known_keys, ChildInfo},
+};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, NumberFor},
+	Justifications,
+};
+use sp_state_machine::{KeyValueStates, KeyValueStorageLevel};
+use std::{
+	collections::BTreeMap,
+	io::{Read, Write},
+	sync::Arc,
+	task::Poll,
+};
+
+/// Magic bytes at the start of every snapshot.
+const MAGIC: [u8; 8] = *b"SNAPSHOT";
+
+/// Version of the snapshot format.
+const VERSION: u32 = 2;
+
+/// Size of the keys and values after which a state chunk is written.
+const STATE_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// A piece of a snapshot.
+#[derive(Encode, Decode)]
+enum SnapshotChunk<B: BlockT> {
+	/// The header of the block, always the first chunk.
+	Header(B::Header),
+	/// The justifications of the block, always the second chunk.
+	Justifications(Option<Justifications>),
+	/// The warp sync proofs of the finality of the block, starting at the genesis block, always
+	/// the third chunk.
+	FinalityProof(Vec<Vec<u8>>),
+	/// Key-value pairs of the state of the block.
+	State(StateChunk),
+	/// The end of the snapshot, with the number of chunks before it.
+	End(u64),
+}
+
+/// Key-value pairs of the main trie or of a child trie.
+#[derive(Encode, Decode)]
+struct StateChunk {
+	/// The prefixed storage key and the root of the child trie, `None` for the main trie.
+	child_trie: Option<(Vec<u8>, Vec<u8>)>,
+	/// The key-value pairs, ordered by key.
+	key_values: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Writes the chunks of a snapshot, each followed by the running hash of the snapshot.
+struct SnapshotWriter<W> {
+	output: W,
+	hash: [u8; 32],
+	chunks: u64,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+	fn new(mut output: W) -> Result<Self, Error> {
+		output.write_all(&MAGIC)?;
+		output.write_all(&VERSION.encode())?;
+		Ok(Self { output, hash: blake2_256(&MAGIC), chunks: 0 })
+	}
+
+	fn write<B: BlockT>(&mut self, chunk: &SnapshotChunk<B>) -> Result<(), Error> {
+		let chunk = chunk.encode();
+		self.hash = blake2_256(&(self.hash, &chunk).encode());
+		self.output.write_all(&chunk.encode())?;
+		self.output.write_all(&self.hash)?;
+		self.chunks += 1;
+		Ok(())
+	}
+
+	/// Write the state of the main trie or of a child trie.
+	fn write_state<B: BlockT>(
+		&mut self,
+		child_trie: Option<(Vec<u8>, Vec<u8>)>,
+		key_values: impl Iterator<Item = Result<(Vec<u8>, Vec<u8>), Error>>,
+	) -> Result<(), Error> {
+		let mut chunk = StateChunk { child_trie, key_values: Vec::new() };
+		let mut chunk_size = 0;
+		for key_value in key_values {
+			let (key, value) = key_value?;
+			chunk_size += key.len() + value.len();
+			chunk.key_values.push((key, value));
+
+			if chunk_size >= STATE_CHUNK_SIZE {
+				let key_values = std::mem::take(&mut chunk.key_values);
+				self.write(&SnapshotChunk::<B>::State(StateChunk {
+					child_trie: chunk.child_trie.clone(),
+					key_values,
+				}))?;
+				chunk_size = 0;
+			}
+		}
+
+		if !chunk.key_values.is_empty() {
+			self.write(&SnapshotChunk::<B>::State(chunk))?;
+		}
+		Ok(())
+	}
+
+	fn finish<B: BlockT>(mut self) -> Result<(), Error> {
+		self.write(&SnapshotChunk::<B>::End(self.chunks))?;
+		self.output.flush()?;
+		Ok(())
+	}
+}
+
+/// Reads the chunks of a snapshot, checking the running hash after each of them.
+struct SnapshotReader<R> {
+	input: IoReader<R>,
+	hash: [u8; 32],
+	chunks: u64,
+}
+
+impl<R: Read> SnapshotReader<R> {
+	fn new(input: R) -> Result<Self, Error> {
+		let mut input = IoReader(input);
+		let magic = <[u8; 8]>::decode(&mut input).map_err(|_| "Snapshot is empty")?;
+		if magic != MAGIC {
+			return Err("Input is not a snapshot".into())
+		}
+		let version = u32::decode(&mut input).map_err(|_| "Snapshot is truncated")?;
+		if version != VERSION {
+			return Err(format!("Unsupported snapshot version {}", version).into())
+		}
+		Ok(Self { input, hash: blake2_256(&MAGIC), chunks: 0 })
+	}
+
+	fn read<B: BlockT>(&mut self) -> Result<SnapshotChunk<B>, Error> {
+		let truncated = |_| Error::from("Snapshot is truncated");
+		let chunk = Vec::<u8>::decode(&mut self.input).map_err(truncated)?;
+		let hash = <[u8; 32]>::decode(&mut self.input).map_err(truncated)?;
+
+		self.hash = blake2_256(&(self.hash, &chunk).encode());
+		if hash != self.hash {
+			return Err(format!("Snapshot is corrupted at chunk #{}", self.chunks).into())
+		}
+		self.chunks += 1;
+
+		SnapshotChunk::decode(&mut &chunk[..])
+			.map_err(|e| format!("Invalid snapshot chunk #{}: {}", self.chunks - 1, e).into())
+	}
+}
+
+/// Generate the warp sync proofs of the finality of the latest block with a finality proof,
+/// starting at the genesis block.
+fn generate_finality_proof<B: BlockT>(
+	warp_sync_provider: &dyn WarpSyncProvider<B>,
+	genesis_hash: B::Hash,
+) -> Result<(B::Header, Vec<Vec<u8>>), Error> {
+	let mut proofs = Vec::new();
+	let mut start = genesis_hash;
+	loop {
+		let proof = warp_sync_provider
+			.generate(start)
+			.map_err(|e| format!("Failed to generate the finality proof: {}", e))?;
+		let (header, is_finished) = warp_sync_provider
+			.proof_target(&proof)
+			.map_err(|e| format!("Failed to generate the finality proof: {}", e))?;
+		proofs.push(proof.0);
+
+		if is_finished {
+			return Ok((header, proofs))
+		}
+		start = header.hash();
+	}
+}
+
+/// Verify that the warp sync proofs prove the finality of `header`, starting at the genesis
+/// authorities.
+fn verify_finality_proof<B: BlockT>(
+	warp_sync_provider: &dyn WarpSyncProvider<B>,
+	proofs: Vec<Vec<u8>>,
+	header: &B::Header,
+) -> Result<(), Error> {
+	let mut set_id = 0;
+	let mut authorities = warp_sync_provider.current_authorities();
+	let count = proofs.len();
+	for (index, proof) in proofs.into_iter().enumerate() {
+		let is_last = index + 1 == count;
+		match warp_sync_provider
+			.verify(&EncodedProof(proof), set_id, authorities)
+			.map_err(|e| format!("Invalid finality proof in snapshot: {}", e))?
+		{
+			VerificationResult::Partial(next_set_id, next_authorities, _) if !is_last => {
+				set_id = next_set_id;
+				authorities = next_authorities;
+			},
+			VerificationResult::Complete(_, _, target) if is_last && target == *header =>
+				return Ok(()),
+			_ => break,
+		}
+	}
+
+	Err("The finality proof in the snapshot doesn't prove the block of the snapshot".into())
+}
+
+/// Export a snapshot of the last block with a finality proof to `output`.
+///
+/// If `hash` is given, it must be the hash of that block.
+pub fn export_snapshot<B, BA, C>(
+	client: Arc<C>,
+	warp_sync_provider: &dyn WarpSyncProvider<B>,
+	hash: Option<B::Hash>,
+	output: impl Write,
+) -> Result<(), Error>
+where
+	B: BlockT,
+	BA: sc_client_api::backend::Backend<B>,
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BA>,
+{
+	let (header, finality_proof) =
+		generate_finality_proof(warp_sync_provider, client.info().genesis_hash)?;
+	let number = *header.number();
+	let proven_hash = header.hash();
+	if hash.map_or(false, |hash| hash != proven_hash) {
+		return Err(format!(
+			"Only block #{} ({}), the last block with a finality proof, can be exported",
+			number, proven_hash,
+		)
+		.into())
+	}
+	let hash = proven_hash;
+
+	info!("Exporting snapshot of block #{} ({})...", number, hash);
+
+	let mut writer = SnapshotWriter::new(output)?;
+	writer.write(&SnapshotChunk::<B>::Header(header))?;
+	writer.write(&SnapshotChunk::<B>::Justifications(client.justifications(hash)?))?;
+	writer.write(&SnapshotChunk::<B>::FinalityProof(finality_proof))?;
+
+	// Child trie roots are recomputed on import, so they aren't part of the main trie's chunks.
+	let mut child_tries = Vec::new();
+	let top = client.storage_pairs(hash, None, None)?.filter_map(|(key, value)| {
+		if well_known_keys::is_child_storage_key(&key.0) {
+			child_tries.push((key.0, value.0));
+			None
+		} else {
+			Some(Ok((key.0, value.0)))
+		}
+	});
+	writer.write_state::<B>(None, top)?;
+
+	for (prefixed_storage_key, root) in child_tries {
+		let child_info = ChildInfo::new_default(
+			&prefixed_storage_key[well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..],
+		);
+		let key_values = client.child_storage_keys(hash, child_info.clone(), None, None)?.map(
+			|key| -> Result<_, Error> {
+				let value = client
+					.child_storage(hash, &child_info, &key)?
+					.ok_or_else(|| Error::from("Child storage changed while exporting"))?;
+				Ok((key.0, value.0))
+			},
+		);
+		writer.write_state::<B>(Some((prefixed_storage_key, root)), key_values)?;
+	}
+
+	writer.finish::<B>()
+}
+
+/// The chunks at the start of a snapshot, preceding the state.
+struct SnapshotHead<B: BlockT> {
+	header: B::Header,
+	justifications: Option<Justifications>,
+	finality_proof: Vec<Vec<u8>>,
+}
+
+fn read_head<B: BlockT, R: Read>(reader: &mut SnapshotReader<R>) -> Result<SnapshotHead<B>, Error> {
+	let header = match reader.read::<B>()? {
+		SnapshotChunk::Header(header) => header,
+		_ => return Err("Snapshot doesn't start with a header".into()),
+	};
+	let justifications = match reader.read::<B>()? {
+		SnapshotChunk::Justifications(justifications) => justifications,
+		_ => return Err("Snapshot is missing the justifications".into()),
+	};
+	let finality_proof = match reader.read::<B>()? {
+		SnapshotChunk::FinalityProof(finality_proof) => finality_proof,
+		_ => return Err("Snapshot is missing the finality proof".into()),
+	};
+	Ok(SnapshotHead { header, justifications, finality_proof })
+}
+
+/// Read the header of the block of the snapshot from `input`.
+pub fn read_snapshot_header<B: BlockT>(input: impl Read) -> Result<B::Header, Error> {
+	let mut reader = SnapshotReader::new(input)?;
+	match reader.read::<B>()? {
+		SnapshotChunk::Header(header) => Ok(header),
+		_ => Err("Snapshot doesn't start with a header".into()),
+	}
+}
+
+/// Read the state chunks following the head of a snapshot.
+fn read_state<B: BlockT, R: Read>(reader: &mut SnapshotReader<R>) -> Result<KeyValueStates, Error> {
+	let mut top = KeyValueStorageLevel {
+		state_root: Vec::new(),
+		parent_storage_keys: Vec::new(),
+		key_values: Vec::new(),
+	};
+	let mut child_tries = BTreeMap::<Vec<u8>, KeyValueStorageLevel>::new();
+	loop {
+		let chunks = reader.chunks;
+		match reader.read::<B>()? {
+			SnapshotChunk::State(StateChunk { child_trie: None, key_values }) =>
+				top.key_values.extend(key_values),
+			SnapshotChunk::State(StateChunk {
+				child_trie: Some((storage_key, root)),
+				key_values,
+			}) => child_tries
+				.entry(storage_key.clone())
+				.or_insert_with(|| KeyValueStorageLevel {
+					state_root: root,
+					parent_storage_keys: vec![storage_key],
+					key_values: Vec::new(),
+				})
+				.key_values
+				.extend(key_values),
+			SnapshotChunk::End(end) if end == chunks => break,
+			_ => return Err(format!("Unexpected snapshot chunk #{}", chunks).into()),
+		}
+	}
+
+	Ok(KeyValueStates(std::iter::once(top).chain(child_tries.into_values()).collect()))
+}
+
+/// Import the snapshot from `input` through the `import_queue`.
+///
+/// The finality of the block of the snapshot is verified with the `warp_sync_provider` before its
+/// state is read. The import is skipped if the client already finalized the block of the snapshot
+/// or a later one.
+pub async fn import_snapshot<B, IQ, C>(
+	client: Arc<C>,
+	import_queue: &mut IQ,
+	warp_sync_provider: &dyn WarpSyncProvider<B>,
+	input: impl Read,
+) -> Result<(), Error>
+where
+	B: BlockT,
+	IQ: ImportQueue<B> + ?Sized,
+	C: HeaderBackend<B>,
+{
+	struct WaitLink<B: BlockT> {
+		hash: B::Hash,
+		result: Option<Result<BlockImportStatus<NumberFor<B>>, BlockImportError>>,
+	}
+
+	impl<B: BlockT> Link<B> for WaitLink<B> {
+		fn blocks_processed(
+			&mut self,
+			_imported: usize,
+			_count: usize,
+			results: Vec<(Result<BlockImportStatus<NumberFor<B>>, BlockImportError>, B::Hash)>,
+		) {
+			for (result, hash) in results {
+				if hash == self.hash {
+					self.result = Some(result);
+				}
+			}
+		}
+	}
+
+	let mut reader = SnapshotReader::new(input)?;
+	let SnapshotHead { header, justifications, finality_proof } = read_head::<B, _>(&mut reader)?;
+	let hash = header.hash();
+	let number = *header.number();
+
+	let finalized_number = client.info().finalized_number;
+	if finalized_number >= number {
+		info!(
+			"Skipping the snapshot of block #{} as block #{} is already finalized",
+			number, finalized_number,
+		);
+		return Ok(())
+	}
+
+	verify_finality_proof(warp_sync_provider, finality_proof, &header)?;
+
+	info!("Importing snapshot of block #{} ({})...", number, hash);
+
+	let state = read_state::<B, _>(&mut reader)?;
+	import_queue.service_ref().import_blocks(
+		BlockOrigin::File,
+		vec![IncomingBlock {
+			hash,
+			header: Some(header),
+			body: None,
+			indexed_body: None,
+			justifications,
+			origin: None,
+			allow_missing_state: true,
+			import_existing: true,
+			state: Some(ImportedState { block: hash, state }),
+			skip_execution: false,
+		}],
+	);
+
+	let mut link = WaitLink::<B> { hash, result: None };
+	let result = future::poll_fn(|cx| {
+		import_queue.poll_actions(cx, &mut link);
+		match link.result.take() {
+			Some(result) => Poll::Ready(result),
+			None => Poll::Pending,
+		}
+	})
+	.await;
+
+	match result {
+		Ok(_) => {
+			info!("🎉 Imported snapshot of block #{} ({})", number, hash);
+			Ok(())
+		},
+		Err(e) => Err(format!("Failed to import the snapshot: {}", e).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_consensus::{BasicQueue, BlockImportParams, ForkChoiceStrategy, Verifier};
+	use sc_network_common::sync::warp::{AuthorityList, SetId};
+	use sp_core::storage::StorageKey;
+	use substrate_test_runtime_client::{
+		prelude::*,
+		runtime::{Block, Header},
+	};
+
+	/// Proves the finality of a single trusted header.
+	struct TestProvider {
+		trusted: Header,
+	}
+
+	impl WarpSyncProvider<Block> for TestProvider {
+		fn generate(
+			&self,
+			_start: <Block as BlockT>::Hash,
+		) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+			Ok(EncodedProof((self.trusted.clone(), true).encode()))
+		}
+
+		fn verify(
+			&self,
+			proof: &EncodedProof,
+			set_id: SetId,
+			authorities: AuthorityList,
+		) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+			let (header, _) = self.proof_target(proof)?;
+			if header != self.trusted {
+				return Err("Untrusted header".into())
+			}
+			Ok(VerificationResult::Complete(set_id, authorities, header))
+		}
+
+		fn current_authorities(&self) -> AuthorityList {
+			Vec::new()
+		}
+
+		fn proof_target(
+			&self,
+			proof: &EncodedProof,
+		) -> Result<(Header, bool), Box<dyn std::error::Error + Send + Sync>> {
+			Ok(<(Header, bool)>::decode(&mut &proof.0[..])?)
+		}
+	}
+
+	/// Imports the blocks as they are, finalizing them.
+	struct FinalizingVerifier;
+
+	#[async_trait::async_trait]
+	impl Verifier<Block> for FinalizingVerifier {
+		async fn verify(
+			&mut self,
+			mut block: BlockImportParams<Block, ()>,
+		) -> Result<BlockImportParams<Block, ()>, String> {
+			block.finalized = true;
+			block.fork_choice = Some(ForkChoiceStrategy::Custom(true));
+			Ok(block)
+		}
+	}
+
+	/// Build a chain of three finalized blocks changing the storage, returning the client, the
+	/// header of the last block and the changed key.
+	fn build_chain() -> (Arc<TestClient>, Header, Vec<u8>) {
+		let mut client = Arc::new(TestClientBuilder::new().build());
+		let key = b"snapshot-key".to_vec();
+		for number in 1u8..=3 {
+			let mut builder = client.new_block(Default::default()).unwrap();
+			builder.push_storage_change(key.clone(), Some(vec![number])).unwrap();
+			let block = builder.build().unwrap().block;
+			futures::executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+		}
+		let hash = client.info().best_hash;
+		client.finalize_block(hash, Some((*b"TEST", vec![1, 2, 3]))).unwrap();
+
+		let header = client.header(hash).unwrap().unwrap();
+		(client, header, key)
+	}
+
+	fn export(client: Arc<TestClient>, provider: &TestProvider) -> Vec<u8> {
+		let mut snapshot = Vec::new();
+		export_snapshot(client, provider, None, &mut snapshot).unwrap();
+		snapshot
+	}
+
+	fn import(provider: &TestProvider, snapshot: &[u8]) -> (Arc<TestClient>, Result<(), Error>) {
+		let client = Arc::new(TestClientBuilder::new().set_no_genesis().build());
+		let mut import_queue = BasicQueue::new(
+			FinalizingVerifier,
+			Box::new(client.clone()),
+			None,
+			&sp_core::testing::TaskExecutor::new(),
+			None,
+		);
+		let result = futures::executor::block_on(import_snapshot(
+			client.clone(),
+			&mut import_queue,
+			provider,
+			snapshot,
+		));
+		(client, result)
+	}
+
+	#[test]
+	fn snapshot_round_trip() {
+		let (source, header, key) = build_chain();
+		let hash = header.hash();
+		let provider = TestProvider { trusted: header.clone() };
+		let snapshot = export(source.clone(), &provider);
+
+		let (client, result) = import(&provider, &snapshot);
+		result.unwrap();
+
+		assert_eq!(client.info().finalized_hash, hash);
+		assert_eq!(client.header(hash).unwrap(), Some(header));
+		assert_eq!(client.justifications(hash).unwrap(), source.justifications(hash).unwrap());
+		assert_eq!(
+			client.storage(hash, &StorageKey(key.clone())).unwrap(),
+			Some(sp_core::storage::StorageData(vec![3])),
+		);
+		assert_eq!(
+			client.storage(hash, &StorageKey(well_known_keys::CODE.to_vec())).unwrap(),
+			source.storage(hash, &StorageKey(well_known_keys::CODE.to_vec())).unwrap(),
+		);
+	}
+
+	#[test]
+	fn exporting_other_blocks_fails() {
+		let (source, header, _) = build_chain();
+		let provider = TestProvider { trusted: header.clone() };
+
+		let result =
+			export_snapshot(source, &provider, Some(*header.parent_hash()), &mut Vec::new());
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn corrupted_snapshot_is_rejected() {
+		let (source, header, _) = build_chain();
+		let provider = TestProvider { trusted: header.clone() };
+		let snapshot = export(source, &provider);
+
+		// Flip a byte of the state, which makes up most of the snapshot.
+		let mut corrupted = snapshot.clone();
+		let middle = corrupted.len() / 2;
+		corrupted[middle] ^= 0xff;
+
+		let (client, result) = import(&provider, &corrupted);
+		assert!(result.unwrap_err().to_string().contains("corrupted"));
+		assert_eq!(client.header(header.hash()).unwrap(), None);
+
+		// A truncated snapshot is rejected as well.
+		let (client, result) = import(&provider, &snapshot[..snapshot.len() - 1]);
+		assert!(result.unwrap_err().to_string().contains("truncated"));
+		assert_eq!(client.header(header.hash()).unwrap(), None);
+	}
+
+	#[test]
+	fn snapshot_without_valid_finality_proof_is_rejected() {
+		let (source, header, _) = build_chain();
+		let snapshot = export(source, &TestProvider { trusted: header.clone() });
+
+		// The importing node trusts another header, e.g. as it follows another chain.
+		let mut other = header.clone();
+		other.state_root = Default::default();
+		let (client, result) = import(&TestProvider { trusted: other }, &snapshot);
+
+		assert!(result.unwrap_err().to_string().contains("Invalid finality proof"));
+		assert_eq!(client.header(header.hash()).unwrap(), None);
+	}
+}
@@ -74,8 +74,8 @@ use sp_runtime::{
 	Digest, Justification, Justifications, StateVersion,
 };
 use sp_state_machine::{
-	prove_child_read, prove_range_read_with_child_with_size, prove_read,
-	read_range_proof_check_with_child_on_proving_backend, Backend as StateBackend,
+	prove_child_read, prove_range_read_with_child_with_size, prove_range_read_with_count,
+	prove_read, read_range_proof_check_with_child_on_proving_backend, Backend as StateBackend,
 	ChildStorageCollection, KeyValueStates, KeyValueStorageLevel, StorageCollection,
 	MAX_NESTED_TRIE_DEPTH,
 };
@@ -1249,6 +1249,21 @@ where
 			.and_then(|state| prove_child_read(state, child_info, keys).map_err(Into::into))
 	}
 
+	fn read_range_proof(
+		&self,
+		hash: Block::Hash,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		start_key: Option<&[u8]>,
+		count: u32,
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, Vec<Vec<u8>>, bool)> {
+		self.state_at(hash).and_then(|state| {
+			prove_range_read_with_count(state, child_info, prefix, count, size_limit, start_key)
+				.map_err(Into::into)
+		})
+	}
+
 	fn execution_proof(
 		&self,
 		hash: Block::Hash,
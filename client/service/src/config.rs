@@ -41,6 +41,7 @@ use std::{
 	io, iter,
 	net::SocketAddr,
 	path::{Path, PathBuf},
+	time::Duration,
 };
 use tempfile::TempDir;
 
@@ -67,6 +68,10 @@ pub struct Configuration {
 	///
 	/// If `None` is given the cache is disabled.
 	pub trie_cache_maximum_size: Option<usize>,
+	/// Maximum size of the trie cache persisted across restarts, in bytes.
+	///
+	/// If `None` is given the trie cache starts cold after every restart.
+	pub trie_cache_persistence_size: Option<usize>,
 	/// State pruning settings.
 	pub state_pruning: Option<PruningMode>,
 	/// Number of blocks to keep in the db.
@@ -173,12 +178,32 @@ impl KeystoreConfig {
 	}
 }
 /// Configuration of the database of the client.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct OffchainWorkerConfig {
 	/// If this is allowed.
 	pub enabled: bool,
 	/// allow writes from the runtime to the offchain worker database.
 	pub indexing_enabled: bool,
+	/// Maximum number of concurrent http requests of a single offchain worker run.
+	pub http_max_concurrent_requests: usize,
+	/// Time after which an http request of an offchain worker is aborted.
+	pub http_request_timeout: Duration,
+	/// Domains offchain workers may send http requests to, including their subdomains.
+	///
+	/// Empty by default, refusing all requests. `*` allows requests to any domain.
+	pub http_allowed_domains: Vec<String>,
+}
+
+impl Default for OffchainWorkerConfig {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			indexing_enabled: false,
+			http_max_concurrent_requests: 32,
+			http_request_timeout: Duration::from_secs(30),
+			http_allowed_domains: Vec::new(),
+		}
+	}
 }
 
 /// Configuration of the Prometheus endpoint.
@@ -232,13 +257,20 @@ impl Configuration {
 	/// Returns true if the genesis state writting will be skipped while initializing the genesis
 	/// block.
 	pub fn no_genesis(&self) -> bool {
-		matches!(self.network.sync_mode, SyncMode::LightState { .. } | SyncMode::Warp { .. })
+		matches!(self.network.sync_mode, SyncMode::LightState { .. } | SyncMode::Warp { .. }) ||
+			self.network.snapshot.is_some()
 	}
 
 	/// Returns the database config for creating the backend.
 	pub fn db_config(&self) -> sc_client_db::DatabaseSettings {
 		sc_client_db::DatabaseSettings {
 			trie_cache_maximum_size: self.trie_cache_maximum_size,
+			trie_cache_persistence: self.trie_cache_persistence_size.map(|maximum_size| {
+				sc_client_db::TrieCachePersistence {
+					path: self.data_path.join("trie_cache"),
+					maximum_size,
+				}
+			}),
 			state_pruning: self.state_pruning.clone(),
 			source: self.database.clone(),
 			blocks_pruning: self.blocks_pruning,
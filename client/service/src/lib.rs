@@ -35,7 +35,11 @@ mod client;
 mod metrics;
 mod task_manager;
 
-use std::{collections::HashMap, net::SocketAddr};
+use std::{
+	collections::HashMap,
+	net::SocketAddr,
+	time::{Duration, UNIX_EPOCH},
+};
 
 use codec::{Decode, Encode};
 use futures::{channel::mpsc, pin_mut, FutureExt, StreamExt};
@@ -56,10 +60,10 @@ use sp_runtime::{
 
 pub use self::{
 	builder::{
-		build_network, new_client, new_db_backend, new_full_client, new_full_parts,
-		new_full_parts_with_genesis_builder, new_native_or_wasm_executor, new_wasm_executor,
-		spawn_tasks, BuildNetworkParams, KeystoreContainer, NetworkStarter, SpawnTasksParams,
-		TFullBackend, TFullCallExecutor, TFullClient,
+		build_network, import_configured_snapshot, new_client, new_db_backend, new_full_client,
+		new_full_parts, new_full_parts_with_genesis_builder, new_native_or_wasm_executor,
+		new_wasm_executor, spawn_tasks, BuildNetworkParams, KeystoreContainer, NetworkStarter,
+		SpawnTasksParams, TFullBackend, TFullCallExecutor, TFullClient,
 	},
 	client::{ClientConfig, LocalCallExecutor},
 	error::Error,
@@ -80,7 +84,7 @@ pub use sc_chain_spec::{
 
 pub use sc_consensus::ImportQueue;
 pub use sc_executor::NativeExecutionDispatch;
-pub use sc_network_common::sync::warp::WarpSyncParams;
+pub use sc_network_common::sync::warp::{WarpSyncParams, WarpSyncProvider};
 #[doc(hidden)]
 pub use sc_network_transactions::config::{TransactionImport, TransactionImportFuture};
 pub use sc_rpc::{
@@ -319,6 +323,45 @@ pub async fn build_system_rpc_future<
 					break
 				}
 			},
+			sc_rpc::system::Request::NetworkBanPeer(peer_id, duration, sender) => {
+				let _ = match peer_id.parse::<PeerId>() {
+					Ok(peer_id) => {
+						network_service.ban_peer(peer_id, duration.map(Duration::from_secs));
+						sender.send(Ok(()))
+					},
+					Err(e) => sender.send(Err(sc_rpc::system::error::Error::MalformattedPeerArg(
+						e.to_string(),
+					))),
+				};
+			},
+			sc_rpc::system::Request::NetworkUnbanPeer(peer_id, sender) => {
+				let _ = match peer_id.parse::<PeerId>() {
+					Ok(peer_id) => {
+						network_service.unban_peer(peer_id);
+						sender.send(Ok(()))
+					},
+					Err(e) => sender.send(Err(sc_rpc::system::error::Error::MalformattedPeerArg(
+						e.to_string(),
+					))),
+				};
+			},
+			sc_rpc::system::Request::NetworkBannedPeers(sender) => {
+				let banned_peers = network_service.banned_peers().await;
+				if let Ok(banned_peers) = banned_peers {
+					let banned_peers = banned_peers
+						.into_iter()
+						.map(|(peer_id, until)| sc_rpc::system::BannedPeer {
+							peer_id: peer_id.to_base58(),
+							expires_at: until.map(|until| {
+								until.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+							}),
+						})
+						.collect();
+					let _ = sender.send(banned_peers);
+				} else {
+					break
+				}
+			},
 			sc_rpc::system::Request::NodeRoles(sender) => {
 				use sc_rpc::system::NodeRole;
 
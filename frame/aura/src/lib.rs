@@ -31,6 +31,8 @@
 //!
 //! - `slot_duration` - Determine the Aura slot-duration based on the Timestamp module
 //!   configuration.
+//! - `slot_schedule` - Determine the Aura slot schedule, which keeps slots increasing when the
+//!   slot-duration changes through a runtime upgrade.
 //!
 //! ## Related Modules
 //!
@@ -45,7 +47,9 @@ use frame_support::{
 	traits::{DisabledValidators, FindAuthor, Get, OnTimestampSet, OneSessionHandler},
 	BoundedSlice, BoundedVec, ConsensusEngineId, Parameter,
 };
-use sp_consensus_aura::{AuthorityIndex, ConsensusLog, Slot, AURA_ENGINE_ID};
+use sp_consensus_aura::{
+	AuthorityIndex, ConsensusLog, Slot, SlotDuration, SlotSchedule, AURA_ENGINE_ID,
+};
 use sp_runtime::{
 	generic::DigestItem,
 	traits::{IsMember, Member, SaturatedConversion, Saturating, Zero},
@@ -104,6 +108,8 @@ pub mod pallet {
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			let schedule_weight = Self::note_slot_schedule();
+
 			if let Some(new_slot) = Self::current_slot_from_digests() {
 				let current_slot = CurrentSlot::<T>::get();
 
@@ -128,9 +134,9 @@ pub mod pallet {
 				// TODO [#3398] Generate offence report for all authorities that skipped their
 				// slots.
 
-				T::DbWeight::get().reads_writes(2, 1)
+				T::DbWeight::get().reads_writes(2, 1).saturating_add(schedule_weight)
 			} else {
-				T::DbWeight::get().reads(1)
+				T::DbWeight::get().reads(1).saturating_add(schedule_weight)
 			}
 		}
 
@@ -153,6 +159,12 @@ pub mod pallet {
 	#[pallet::getter(fn current_slot)]
 	pub(super) type CurrentSlot<T: Config> = StorageValue<_, Slot, ValueQuery>;
 
+	/// The slot schedule of this block.
+	///
+	/// This will be set in `on_initialize`. `None` until the first block is initialized.
+	#[pallet::storage]
+	pub(super) type CurrentSlotSchedule<T: Config> = StorageValue<_, SlotSchedule, OptionQuery>;
+
 	#[pallet::genesis_config]
 	#[derive(frame_support::DefaultNoBound)]
 	pub struct GenesisConfig<T: Config> {
@@ -224,6 +236,43 @@ impl<T: Config> Pallet<T> {
 		<T as pallet_timestamp::Config>::MinimumPeriod::get().saturating_mul(2u32.into())
 	}
 
+	/// Determine the Aura slot schedule of the current block.
+	///
+	/// When [`Self::slot_duration`] changes, e.g. by a runtime upgrade, the new duration becomes
+	/// active at the slot following the last authored one, so slots keep increasing. This
+	/// requires the schedule to have been recorded by a block authored with the old duration.
+	pub fn slot_schedule() -> SlotSchedule {
+		let slot_duration = SlotDuration::from_millis(Self::slot_duration().saturated_into());
+		match CurrentSlotSchedule::<T>::get() {
+			Some(schedule) if schedule.slot_duration == slot_duration => schedule,
+			Some(schedule) =>
+				schedule.change_slot_duration(slot_duration, CurrentSlot::<T>::get() + 1),
+			None => SlotSchedule::from_slot_duration(slot_duration),
+		}
+	}
+
+	/// Record the slot schedule of the current block, activating a changed slot duration.
+	fn note_slot_schedule() -> Weight {
+		let schedule = Self::slot_schedule();
+		let previous = CurrentSlotSchedule::<T>::get();
+		if previous == Some(schedule) {
+			return T::DbWeight::get().reads(2)
+		}
+
+		if let Some(previous) = previous {
+			log::info!(
+				target: LOG_TARGET,
+				"Slot duration changed from {}ms to {}ms at slot {}.",
+				previous.slot_duration.as_millis(),
+				schedule.slot_duration.as_millis(),
+				schedule.activation_slot,
+			);
+		}
+
+		CurrentSlotSchedule::<T>::put(schedule);
+		T::DbWeight::get().reads_writes(2, 1)
+	}
+
 	/// Ensure the correctness of the state of this pallet.
 	///
 	/// This should be valid before or after each state transition of this pallet.
@@ -371,8 +420,8 @@ impl<T: Config> OnTimestampSet<T::Moment> for Pallet<T> {
 		let slot_duration = Self::slot_duration();
 		assert!(!slot_duration.is_zero(), "Aura slot duration cannot be zero.");
 
-		let timestamp_slot = moment / slot_duration;
-		let timestamp_slot = Slot::from(timestamp_slot.saturated_into::<u64>());
+		let timestamp = sp_timestamp::Timestamp::new(moment.saturated_into::<u64>());
+		let timestamp_slot = Self::slot_schedule().slot_at(timestamp);
 
 		assert!(
 			CurrentSlot::<T>::get() == timestamp_slot,
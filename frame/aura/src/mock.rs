@@ -69,13 +69,14 @@ impl frame_system::Config for Test {
 impl pallet_timestamp::Config for Test {
 	type Moment = u64;
 	type OnTimestampSet = Aura;
-	type MinimumPeriod = ConstU64<1>;
+	type MinimumPeriod = MinimumPeriod;
 	type WeightInfo = ();
 }
 
 parameter_types! {
 	static DisabledValidatorTestValue: Vec<AuthorityIndex> = Default::default();
 	pub static AllowMultipleBlocksPerSlot: bool = false;
+	pub static MinimumPeriod: u64 = 1;
 }
 
 pub struct MockDisabledValidators;
@@ -22,8 +22,8 @@
 
 use crate::mock::{build_ext_and_execute_test, Aura, MockDisabledValidators, System};
 use codec::Encode;
-use frame_support::traits::OnInitialize;
-use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use frame_support::traits::{OnInitialize, OnTimestampSet};
+use sp_consensus_aura::{Slot, SlotDuration, SlotSchedule, AURA_ENGINE_ID};
 use sp_runtime::{Digest, DigestItem};
 
 #[test]
@@ -116,3 +116,40 @@ fn pallet_always_rejects_decreasing_slot() {
 		Aura::on_initialize(43);
 	});
 }
+
+#[test]
+fn slot_duration_change_keeps_slots_increasing() {
+	build_ext_and_execute_test(vec![0, 1, 2, 3], || {
+		let initialize = |number: u64, slot: u64| {
+			let pre_digest = Digest {
+				logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, Slot::from(slot).encode())],
+			};
+			System::reset_events();
+			System::initialize(&number, &System::parent_hash(), &pre_digest);
+			Aura::on_initialize(number);
+		};
+
+		// slots of 2ms, counted since the unix epoch.
+		initialize(1, 10);
+		Aura::on_timestamp_set(20);
+		assert_eq!(
+			Aura::slot_schedule(),
+			SlotSchedule::from_slot_duration(SlotDuration::from_millis(2))
+		);
+
+		// the slot duration is doubled, taking effect from the next slot on.
+		crate::mock::MinimumPeriod::set(2);
+		let schedule = SlotSchedule {
+			slot_duration: SlotDuration::from_millis(4),
+			activation_slot: 11.into(),
+			activation_timestamp: 22,
+		};
+		assert_eq!(Aura::slot_schedule(), schedule);
+
+		initialize(2, 11);
+		Aura::on_timestamp_set(22);
+		initialize(3, 12);
+		Aura::on_timestamp_set(26);
+		assert_eq!(Aura::slot_schedule(), schedule);
+	});
+}
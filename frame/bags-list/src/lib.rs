@@ -193,6 +193,13 @@ pub mod pallet {
 	pub(crate) type ListBags<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Twox64Concat, T::Score, list::Bag<T, I>>;
 
+	/// Whether the order of the list is locked, see [`SortedListProvider::lock`].
+	///
+	/// Nodes whose score changed while the list was locked stay in their bag, until they are
+	/// moved through [`Call::rebag`].
+	#[pallet::storage]
+	pub(crate) type Lock<T: Config<I>, I: 'static = ()> = StorageValue<_, (), OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
 	pub enum Event<T: Config<I>, I: 'static = ()> {
@@ -226,7 +233,8 @@ pub mod pallet {
 		/// Will always update the stored score of `dislocated` to the correct score, based on
 		/// `ScoreProvider`.
 		///
-		/// If `dislocated` does not exists, it returns an error.
+		/// If `dislocated` does not exists, or the order of the list is locked, it returns an
+		/// error.
 		#[pallet::call_index(0)]
 		#[pallet::weight(T::WeightInfo::rebag_non_terminal().max(T::WeightInfo::rebag_terminal()))]
 		pub fn rebag(origin: OriginFor<T>, dislocated: AccountIdLookupOf<T>) -> DispatchResult {
@@ -245,7 +253,8 @@ pub mod pallet {
 		///
 		/// Only works if
 		/// - both nodes are within the same bag,
-		/// - and `origin` has a greater `Score` than `lighter`.
+		/// - `origin` has a greater `Score` than `lighter`,
+		/// - and the order of the list isn't locked.
 		#[pallet::call_index(1)]
 		#[pallet::weight(T::WeightInfo::put_in_front_of())]
 		pub fn put_in_front_of(
@@ -254,7 +263,8 @@ pub mod pallet {
 		) -> DispatchResult {
 			let heavier = ensure_signed(origin)?;
 			let lighter = T::Lookup::lookup(lighter)?;
-			List::<T, I>::put_in_front_of(&lighter, &heavier)
+			Pallet::<T, I>::ensure_unlocked()
+				.and_then(|_| List::<T, I>::put_in_front_of(&lighter, &heavier))
 				.map_err::<Error<T, I>, _>(Into::into)
 				.map_err::<DispatchError, _>(Into::into)
 		}
@@ -292,6 +302,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		account: &T::AccountId,
 		new_score: T::Score,
 	) -> Result<Option<(T::Score, T::Score)>, ListError> {
+		Self::ensure_unlocked()?;
 		// If no voter at that node, don't do anything. the caller just wasted the fee to call this.
 		let node = list::Node::<T, I>::get(&account).ok_or(ListError::NodeNotFound)?;
 		let maybe_movement = List::update_position_for(node, new_score);
@@ -302,6 +313,15 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Ok(maybe_movement)
 	}
 
+	/// Returns an error if the order of the list is locked.
+	fn ensure_unlocked() -> Result<(), ListError> {
+		if Lock::<T, I>::exists() {
+			Err(ListError::Locked)
+		} else {
+			Ok(())
+		}
+	}
+
 	/// Equivalent to `ListBags::get`, but public. Useful for tests in outside of this crate.
 	#[cfg(feature = "std")]
 	pub fn list_bags_get(score: T::Score) -> Option<list::Bag<T, I>> {
@@ -370,6 +390,14 @@ impl<T: Config<I>, I: 'static> SortedListProvider<T::AccountId> for Pallet<T, I>
 		List::<T, I>::unsafe_clear()
 	}
 
+	fn lock() {
+		Lock::<T, I>::put(())
+	}
+
+	fn unlock() {
+		Lock::<T, I>::kill()
+	}
+
 	frame_election_provider_support::runtime_benchmarks_enabled! {
 		fn score_update_worst_case(who: &T::AccountId, is_increase: bool) -> Self::Score {
 			use frame_support::traits::Get as _;
@@ -56,6 +56,8 @@ pub enum ListError {
 	NotInSameBag,
 	/// Given node id was not found.
 	NodeNotFound,
+	/// The order of the list is locked.
+	Locked,
 }
 
 #[cfg(test)]
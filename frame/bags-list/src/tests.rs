@@ -661,4 +661,39 @@ mod sorted_list_provider {
 			assert!(non_existent_ids.iter().all(|id| !BagsList::contains(id)));
 		})
 	}
+
+	#[test]
+	fn lock_freezes_the_order() {
+		ExtBuilder::default().build_and_execute(|| {
+			// given
+			assert_eq!(List::<Runtime>::get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4])]);
+			BagsList::lock();
+
+			// then ids can't be moved
+			StakingMock::set_score_of(&1, 2_000);
+			assert_storage_noop!(assert_eq!(
+				BagsList::on_update(&1, 2_000),
+				Err(ListError::Locked)
+			));
+			assert_noop!(
+				BagsList::rebag(RuntimeOrigin::signed(0), 1),
+				crate::pallet::Error::<Runtime>::List(ListError::Locked)
+			);
+			assert_noop!(
+				BagsList::put_in_front_of(RuntimeOrigin::signed(4), 2),
+				crate::pallet::Error::<Runtime>::List(ListError::Locked)
+			);
+
+			// but can still be inserted and removed
+			assert_ok!(BagsList::on_insert(42, 20));
+			assert_ok!(BagsList::on_remove(&42));
+
+			// when
+			BagsList::unlock();
+
+			// then the misplaced id can be moved again
+			assert_ok!(BagsList::rebag(RuntimeOrigin::signed(0), 1));
+			assert_eq!(List::<Runtime>::get_bags(), vec![(1_000, vec![2, 3, 4]), (2_000, vec![1])]);
+		});
+	}
 }
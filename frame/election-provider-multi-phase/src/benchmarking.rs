@@ -20,7 +20,7 @@
 
 use super::*;
 use crate::{unsigned::IndexAssignmentOf, Pallet as MultiPhase};
-use frame_benchmarking::account;
+use frame_benchmarking::{account, BenchmarkError};
 use frame_support::{
 	assert_ok,
 	traits::{Hooks, TryCollect},
@@ -359,6 +359,88 @@ frame_benchmarking::benchmarks! {
 		assert!(<MultiPhase<T>>::signed_submissions().len() as u32 == T::SignedMaxSubmissions::get());
 	}
 
+	register {
+		// number of pages of the ejected submission.
+		let p in 1 .. T::Pages::get();
+		if !<MultiPhase<T>>::is_paged() {
+			return Err(BenchmarkError::Skip)
+		}
+		<MultiPhase<T>>::phase_transition(Phase::Signed);
+
+		// the queue is full, and the weakest submission is ejected along with its `p` pages.
+		let mut scores = PagedSubmissionScoresOf::<T>::default();
+		for i in 0..T::SignedMaxSubmissions::get() {
+			let who: T::AccountId = account("submitters", i, SEED);
+			let claimed_score = ElectionScore {
+				minimal_stake: 10_000_000u128 + (i as u128),
+				..Default::default()
+			};
+			<PagedSignedSubmissionMetadata<T>>::insert(
+				&who,
+				PagedSubmissionMetadata { claimed_score, deposit: Zero::zero() },
+			);
+			scores.try_push((who, claimed_score)).map_err(|_| "too many submissions")?;
+		}
+		let weakest = scores[0].0.clone();
+		for page in 0..p {
+			<PagedSignedSubmissionPages<T>>::insert(&weakest, page, SolutionOf::<T::MinerConfig>::default());
+		}
+		<PagedSignedSubmissionScores<T>>::put(scores);
+
+		let score = ElectionScore { minimal_stake: 20_000_000u128, ..Default::default() };
+		let caller = frame_benchmarking::whitelisted_caller();
+		T::Currency::make_free_balance_be(
+			&caller,
+			T::Currency::minimum_balance() * 1000u32.into() + T::SignedDepositBase::get(),
+		);
+	}: _(RawOrigin::Signed(caller.clone()), score)
+	verify {
+		assert!(<PagedSignedSubmissionMetadata<T>>::contains_key(&caller));
+		assert!(!<PagedSignedSubmissionMetadata<T>>::contains_key(&weakest));
+		assert!(<PagedSignedSubmissionPages<T>>::iter_prefix(&weakest).next().is_none());
+	}
+
+	submit_page {
+		if !<MultiPhase<T>>::is_paged() {
+			return Err(BenchmarkError::Skip)
+		}
+
+		// use the snapshot of the largest solution as the most significant page of a paged one.
+		let witness = SolutionOrSnapshotSize {
+			voters: T::BenchmarkingConfig::VOTERS[1],
+			targets: T::BenchmarkingConfig::TARGETS[1],
+		};
+		let RawSolution { solution, score, .. } = solution_with_size::<T>(
+			witness,
+			T::BenchmarkingConfig::ACTIVE_VOTERS[1],
+			T::BenchmarkingConfig::DESIRED_TARGETS[1],
+		)?;
+		let RoundSnapshot { voters, targets } = <Snapshot<T>>::take().ok_or("snapshot missing")?;
+		let page = <MultiPhase<T>>::msp();
+		<PagedVoterSnapshot<T>>::insert(page, voters);
+		<PagedTargetSnapshot<T>>::put(targets);
+		<MultiPhase<T>>::phase_transition(Phase::Signed);
+
+		// the page replaces an empty one, so the old page is read and more deposit is reserved.
+		let caller: T::AccountId = frame_benchmarking::whitelisted_caller();
+		let page_deposit = T::SignedDepositByte::get() * (solution.encoded_size() as u32).into();
+		T::Currency::make_free_balance_be(
+			&caller,
+			T::Currency::minimum_balance() * 1000u32.into() +
+				T::SignedDepositBase::get() +
+				page_deposit,
+		);
+		assert_ok!(<MultiPhase<T>>::register(RawOrigin::Signed(caller.clone()).into(), score));
+		assert_ok!(<MultiPhase<T>>::submit_page(
+			RawOrigin::Signed(caller.clone()).into(),
+			page,
+			Some(Box::new(Default::default())),
+		));
+	}: _(RawOrigin::Signed(caller.clone()), page, Some(Box::new(solution.clone())))
+	verify {
+		assert_eq!(<PagedSignedSubmissionPages<T>>::get(&caller, page), Some(solution));
+	}
+
 	submit_unsigned {
 		// number of votes in snapshot.
 		let v in (T::BenchmarkingConfig::VOTERS[0]) .. T::BenchmarkingConfig::VOTERS[1];
@@ -533,3 +615,18 @@ frame_benchmarking::benchmarks! {
 		crate::mock::Runtime,
 	);
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{ExtBuilder, Runtime};
+
+	// the test suite skips the paged benchmarks, as its elections are not paged.
+	#[test]
+	fn paged_benchmarks_work() {
+		ExtBuilder::default().pages(2).build_and_execute(|| {
+			assert_ok!(MultiPhase::<Runtime>::test_benchmark_register());
+			assert_ok!(MultiPhase::<Runtime>::test_benchmark_submit_page());
+		});
+	}
+}
@@ -152,6 +152,14 @@
 //!
 //! See the `staking-miner` documentation in the Polkadot repository for more information.
 //!
+//! ### Paged Elections
+//!
+//! If [`Config::Pages`] is more than one, the snapshot is created over multiple blocks in
+//! [`Phase::Snapshot`], signed solutions are registered and submitted one page at a time, and are
+//! verified one page per block in [`Phase::SignedValidation`] instead of the unsigned phase. The
+//! result is returned to the caller in pages through [`ElectionProvider::elect_paged`]. See the
+//! [`paged`] module for more information.
+//!
 //! ## Feasible Solution (correct solution)
 //!
 //! All submissions must undergo a feasibility check. Signed solutions are checked one by one at the
@@ -232,8 +240,8 @@
 
 use codec::{Decode, Encode};
 use frame_election_provider_support::{
-	BoundedSupportsOf, ElectionDataProvider, ElectionProvider, ElectionProviderBase,
-	InstantElectionProvider, NposSolution,
+	merge_paged_supports, BoundedSupportsOf, ElectionDataProvider, ElectionProvider,
+	ElectionProviderBase, InstantElectionProvider, NposSolution, PageIndex,
 };
 use frame_support::{
 	dispatch::DispatchClass,
@@ -271,12 +279,14 @@ pub mod helpers;
 const LOG_TARGET: &str = "runtime::election-provider";
 
 pub mod migrations;
+pub mod paged;
 pub mod signed;
 pub mod unsigned;
 pub mod weights;
 use unsigned::VoterOf;
 pub use weights::WeightInfo;
 
+pub use paged::{PagedSubmissionMetadata, PagedSubmissionScoresOf};
 pub use signed::{
 	BalanceOf, NegativeImbalanceOf, PositiveImbalanceOf, SignedSubmission, SignedSubmissionOf,
 	SignedSubmissions, SubmissionIndicesOf,
@@ -336,6 +346,12 @@ pub enum Phase<Bn> {
 	/// After that, the only way to leave this phase is through a successful
 	/// `T::ElectionProvider::elect`.
 	Emergency,
+	/// The snapshot of a paged election is being created, one page per block. The inner value is
+	/// the next page to be created.
+	Snapshot(PageIndex),
+	/// The signed phase of a paged election is closed, and the signed submissions are being
+	/// verified, one page per block.
+	SignedValidation,
 }
 
 impl<Bn> Default for Phase<Bn> {
@@ -374,6 +390,16 @@ impl<Bn: PartialEq + Eq> Phase<Bn> {
 	pub fn is_off(&self) -> bool {
 		matches!(self, Phase::Off)
 	}
+
+	/// Whether the snapshot of a paged election is being created or not.
+	pub fn is_snapshot(&self) -> bool {
+		matches!(self, Phase::Snapshot(_))
+	}
+
+	/// Whether the signed submissions of a paged election are being verified or not.
+	pub fn is_signed_validation(&self) -> bool {
+		matches!(self, Phase::SignedValidation)
+	}
 }
 
 /// The type of `Computation` that provided this election data.
@@ -660,12 +686,22 @@ pub mod pallet {
 		#[pallet::constant]
 		type SignedDepositWeight: Get<BalanceOf<Self>>;
 
-		/// The maximum number of electing voters to put in the snapshot. At the moment, snapshots
-		/// are only over a single block, but once multi-block elections are introduced they will
-		/// take place over multiple blocks.
+		/// The maximum number of electing voters to put in each page of the snapshot.
 		#[pallet::constant]
 		type MaxElectingVoters: Get<SolutionVoterIndexOf<Self::MinerConfig>>;
 
+		/// The number of pages of the snapshot, of the signed solutions and of the result of the
+		/// election.
+		///
+		/// If this is more than one, the elections are paged, see the [`paged`] module. The
+		/// unsigned phase must then be at least this long, since signed solutions are verified
+		/// in it, one page per block.
+		///
+		/// Paged elections only accept signed solutions: no unsigned solution is mined or
+		/// accepted, and [`Config::Fallback`] is used if no signed solution is verified in time.
+		#[pallet::constant]
+		type Pages: Get<PageIndex>;
+
 		/// The maximum number of electable targets to put in the snapshot.
 		#[pallet::constant]
 		type MaxElectableTargets: Get<SolutionTargetIndexOf<Self::MinerConfig>>;
@@ -764,6 +800,10 @@ pub mod pallet {
 				next_election,
 				Self::snapshot_metadata()
 			);
+			if Self::is_paged() {
+				return Self::on_initialize_paged(remaining)
+			}
+
 			match current_phase {
 				Phase::Off if remaining <= signed_deadline && remaining > unsigned_deadline => {
 					// NOTE: if signed-phase length is zero, second part of the if-condition fails.
@@ -884,6 +924,11 @@ pub mod pallet {
 			// `SignedMaxSubmissions` is a red flag that the developer does not understand how to
 			// configure this pallet.
 			assert!(T::SignedMaxSubmissions::get() >= T::SignedMaxRefunds::get());
+
+			// Paged signed solutions are verified one page per block, in the unsigned phase.
+			if Self::is_paged() {
+				assert!(T::UnsignedPhase::get() >= T::Pages::get().into());
+			}
 		}
 
 		#[cfg(feature = "try-runtime")]
@@ -1025,6 +1070,9 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
+			// paged solutions are submitted through `register` and `submit_page`.
+			ensure!(!Self::is_paged(), Error::<T>::CallNotAllowed);
+
 			// ensure solution is timely.
 			ensure!(Self::current_phase().is_signed(), Error::<T>::PreDispatchEarlySubmission);
 
@@ -1128,6 +1176,41 @@ pub mod pallet {
 			<QueuedSolution<T>>::put(solution);
 			Ok(())
 		}
+
+		/// Register a paged solution for the signed phase, claiming the score of the supports of
+		/// all of its pages merged together.
+		///
+		/// The dispatch origin fo this call must be __signed__, and this is only available in
+		/// paged elections.
+		///
+		/// The pages of the solution are then submitted with [`Call::submit_page`]. A base deposit
+		/// is reserved upon registration. Based on the outcome, the solution might be rewarded,
+		/// slashed, or get the deposit back.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::register(T::Pages::get()))]
+		pub fn register(origin: OriginFor<T>, claimed_score: ElectionScore) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_register(who, claimed_score)
+		}
+
+		/// Submit, replace, or remove if `maybe_solution` is `None`, a single page of a paged
+		/// solution registered with [`Call::register`].
+		///
+		/// The dispatch origin fo this call must be __signed__, and this is only available in
+		/// paged elections.
+		///
+		/// A deposit is reserved for each byte of the page. Pages which are never submitted are
+		/// treated as empty ones.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::submit_page())]
+		pub fn submit_page(
+			origin: OriginFor<T>,
+			page: PageIndex,
+			maybe_solution: Option<Box<SolutionOf<T::MinerConfig>>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_submit_page(who, page, maybe_solution.map(|solution| *solution))
+		}
 	}
 
 	#[pallet::event]
@@ -1161,6 +1244,8 @@ pub mod pallet {
 			to: Phase<BlockNumberFor<T>>,
 			round: u32,
 		},
+		/// A page of a registered paged solution was stored, replaced or removed.
+		SolutionPageStored { origin: T::AccountId, page: PageIndex },
 	}
 
 	/// Error of the pallet that can be returned in response to dispatches.
@@ -1194,6 +1279,12 @@ pub mod pallet {
 		BoundNotMet,
 		/// Submitted solution has too many winners
 		TooManyWinners,
+		/// The origin has already registered a paged solution.
+		SignedAlreadyRegistered,
+		/// The origin has not registered a paged solution.
+		SignedNotRegistered,
+		/// The page index is out of bounds.
+		SignedInvalidPage,
 	}
 
 	#[pallet::validate_unsigned]
@@ -1340,6 +1431,72 @@ pub mod pallet {
 	#[pallet::getter(fn minimum_untrusted_score)]
 	pub type MinimumUntrustedScore<T: Config> = StorageValue<_, ElectionScore>;
 
+	// The following storage items are only used in paged elections, see the `paged` module.
+
+	/// The pages of the voters of the snapshot of a paged election.
+	///
+	/// Created one page per block in [`Phase::Snapshot`] and cleared upon calling `elect`.
+	#[pallet::storage]
+	pub type PagedVoterSnapshot<T: Config> =
+		StorageMap<_, Twox64Concat, PageIndex, Vec<VoterOf<T>>, OptionQuery>;
+
+	/// The targets of the snapshot of a paged election.
+	///
+	/// Created along with the most significant page of [`PagedVoterSnapshot`].
+	#[pallet::storage]
+	pub type PagedTargetSnapshot<T: Config> = StorageValue<_, Vec<T::AccountId>>;
+
+	/// The registered paged signed submissions, along with their claimed score, sorted from the
+	/// worst to the best one.
+	#[pallet::storage]
+	pub type PagedSignedSubmissionScores<T: Config> =
+		StorageValue<_, PagedSubmissionScoresOf<T>, ValueQuery>;
+
+	/// The metadata of the registered paged signed submission of each account.
+	#[pallet::storage]
+	pub type PagedSignedSubmissionMetadata<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		PagedSubmissionMetadata<BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	/// The submitted pages of the registered paged signed submission of each account.
+	#[pallet::storage]
+	pub type PagedSignedSubmissionPages<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		PageIndex,
+		SolutionOf<T::MinerConfig>,
+		OptionQuery,
+	>;
+
+	/// The next page of the best paged signed submission to be verified.
+	///
+	/// Absent if the verification of the best submission has not started yet.
+	#[pallet::storage]
+	pub type PagedVerificationPage<T: Config> = StorageValue<_, PageIndex>;
+
+	/// The pages of the result of a paged election.
+	///
+	/// While a paged signed submission is being verified, this contains the pages verified so
+	/// far.
+	#[pallet::storage]
+	pub type PagedQueuedSolution<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		PageIndex,
+		BoundedSupports<T::AccountId, T::MaxWinners>,
+		OptionQuery,
+	>;
+
+	/// The score of the verified paged signed submission in [`PagedQueuedSolution`].
+	#[pallet::storage]
+	pub type PagedQueuedScore<T: Config> = StorageValue<_, ElectionScore>;
+
 	/// The current storage version.
 	///
 	/// v1: https://github.com/paritytech/substrate/pull/12237/
@@ -1440,12 +1597,18 @@ impl<T: Config> Pallet<T> {
 			return Err(ElectionError::DataProvider("Snapshot too big for submission."))
 		}
 
+		let desired_targets = Self::capped_desired_targets(targets.len() as u32)?;
+
+		Ok((targets, voters, desired_targets))
+	}
+
+	/// The number of targets to elect, capped to the given number of electable targets.
+	fn capped_desired_targets(max_desired_targets: u32) -> Result<u32, ElectionError<T>> {
 		let mut desired_targets = <Pallet<T> as ElectionProviderBase>::desired_targets_checked()
 			.map_err(|e| ElectionError::DataProvider(e))?;
 
 		// If `desired_targets` > `targets.len()`, cap `desired_targets` to that level and emit a
 		// warning
-		let max_desired_targets: u32 = targets.len() as u32;
 		if desired_targets > max_desired_targets {
 			log!(
 				warn,
@@ -1456,7 +1619,7 @@ impl<T: Config> Pallet<T> {
 			desired_targets = max_desired_targets;
 		}
 
-		Ok((targets, voters, desired_targets))
+		Ok(desired_targets)
 	}
 
 	/// Creates the snapshot. Writes new data to:
@@ -1491,11 +1654,14 @@ impl<T: Config> Pallet<T> {
 		);
 	}
 
-	/// Kill everything created by [`Pallet::create_snapshot`].
+	/// Kill everything created by [`Pallet::create_snapshot`] and
+	/// [`Pallet::create_snapshot_page`].
 	pub fn kill_snapshot() {
 		<Snapshot<T>>::kill();
 		<SnapshotMetadata<T>>::kill();
 		<DesiredTargets<T>>::kill();
+		<PagedTargetSnapshot<T>>::kill();
+		let _ = <PagedVoterSnapshot<T>>::clear(u32::MAX, None);
 	}
 
 	/// Checks the feasibility of a solution.
@@ -1594,13 +1760,14 @@ impl<T: Config> Pallet<T> {
 	}
 
 	// [`Snapshot`] state check. Invariants:
-	// - [`DesiredTargets`] exists if and only if [`Snapshot`] is present.
-	// - [`SnapshotMetadata`] exist if and only if [`Snapshot`] is present.
+	// - [`DesiredTargets`] exists if and only if [`Snapshot`] or [`PagedTargetSnapshot`] is
+	//   present.
+	// - [`SnapshotMetadata`] exist if and only if [`Snapshot`] or [`PagedTargetSnapshot`] is
+	//   present.
 	fn try_state_snapshot() -> Result<(), TryRuntimeError> {
-		if (<Snapshot<T>>::exists() &&
-			<SnapshotMetadata<T>>::exists() &&
-			<DesiredTargets<T>>::exists()) ||
-			(!<Snapshot<T>>::exists() &&
+		let snapshot_exists = <Snapshot<T>>::exists() || <PagedTargetSnapshot<T>>::exists();
+		if (snapshot_exists && <SnapshotMetadata<T>>::exists() && <DesiredTargets<T>>::exists()) ||
+			(!snapshot_exists &&
 				!<SnapshotMetadata<T>>::exists() &&
 				!<DesiredTargets<T>>::exists())
 		{
@@ -1659,12 +1826,12 @@ impl<T: Config> Pallet<T> {
 	}
 
 	// [`Phase::Off`] state check. Invariants:
-	// - If phase is `Phase::Off`, [`Snapshot`] must be none.
+	// - If phase is `Phase::Off`, [`Snapshot`] and [`PagedTargetSnapshot`] must be none.
 	fn try_state_phase_off() -> Result<(), TryRuntimeError> {
 		match Self::current_phase().is_off() {
 			false => Ok(()),
 			true =>
-				if <Snapshot<T>>::get().is_some() {
+				if <Snapshot<T>>::get().is_some() || <PagedTargetSnapshot<T>>::exists() {
 					Err("Snapshot must be none when in Phase::Off".into())
 				} else {
 					Ok(())
@@ -1690,6 +1857,13 @@ impl<T: Config> ElectionProvider for Pallet<T> {
 	}
 
 	fn elect() -> Result<BoundedSupportsOf<Self>, Self::Error> {
+		if Self::is_paged() {
+			let pages =
+				(0..Self::pages()).rev().map(Self::elect_paged).collect::<Result<Vec<_>, _>>()?;
+			return merge_paged_supports(pages)
+				.ok_or(ElectionError::Feasibility(FeasibilityError::BoundedConversionFailed))
+		}
+
 		match Self::do_elect() {
 			Ok(supports) => {
 				// All went okay, record the weight, put sign to be Off, clean snapshot, etc.
@@ -1704,6 +1878,33 @@ impl<T: Config> ElectionProvider for Pallet<T> {
 			},
 		}
 	}
+
+	fn pages() -> PageIndex {
+		T::Pages::get().max(1)
+	}
+
+	fn elect_paged(page: PageIndex) -> Result<BoundedSupportsOf<Self>, Self::Error> {
+		if !Self::is_paged() {
+			return if page == 0 { Self::elect() } else { Ok(Default::default()) }
+		}
+
+		match Self::do_elect_paged(page) {
+			Ok(supports) => {
+				// All went okay, record the weight, and once the last page has been handed out, put
+				// sign to be Off, clean snapshot, etc.
+				Self::weigh_supports(&supports);
+				if page == 0 {
+					Self::rotate_round();
+				}
+				Ok(supports)
+			},
+			Err(why) => {
+				log!(error, "Entering emergency mode: {:?}", why);
+				Self::phase_transition(Phase::Emergency);
+				Err(why)
+			},
+		}
+	}
 }
 
 /// convert a DispatchError to a custom InvalidTransaction with the inner code being the error
@@ -298,6 +298,7 @@ parameter_types! {
 	pub static MockWeightInfo: MockedWeightInfo = MockedWeightInfo::Real;
 	pub static MaxElectingVoters: VoterIndex = u32::max_value();
 	pub static MaxElectableTargets: TargetIndex = TargetIndex::max_value();
+	pub static Pages: u32 = 1;
 
 	#[derive(Debug)]
 	pub static MaxWinners: u32 = 200;
@@ -406,6 +407,7 @@ impl crate::Config for Runtime {
 		frame_election_provider_support::onchain::OnChainExecution<OnChainSeqPhragmen>;
 	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
 	type MaxElectingVoters = MaxElectingVoters;
+	type Pages = Pages;
 	type MaxElectableTargets = MaxElectableTargets;
 	type MaxWinners = MaxWinners;
 	type MinerConfig = Self;
@@ -462,6 +464,26 @@ impl ElectionDataProvider for StakingMock {
 		Ok(voters)
 	}
 
+	fn electing_voters_paged(
+		maybe_max_len: Option<usize>,
+		page: PageIndex,
+		pages: PageIndex,
+	) -> data_provider::Result<Vec<VoterOf<Runtime>>> {
+		// the voters are split in `pages` chunks, the first of which is the most significant page.
+		let voters = Voters::get();
+		let chunk_size = ((voters.len() + pages as usize - 1) / pages as usize).max(1);
+		let mut page_voters = voters
+			.chunks(chunk_size)
+			.nth((pages - 1 - page) as usize)
+			.map(|chunk| chunk.to_vec())
+			.unwrap_or_default();
+		if let Some(max_len) = maybe_max_len {
+			page_voters.truncate(max_len)
+		}
+
+		Ok(page_voters)
+	}
+
 	fn desired_targets() -> data_provider::Result<u32> {
 		Ok(DesiredTargets::get())
 	}
@@ -568,6 +590,10 @@ impl ExtBuilder {
 		<SignedMaxWeight>::set(weight);
 		self
 	}
+	pub fn pages(self, pages: u32) -> Self {
+		<Pages>::set(pages);
+		self
+	}
 	pub fn build(self) -> sp_io::TestExternalities {
 		sp_tracing::try_init_simple();
 		let mut storage =
@@ -0,0 +1,764 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The paged election implementation.
+//!
+//! When [`Config::Pages`] is more than one, the snapshot, the signed submissions and the result of
+//! the election are all split into pages, such that no single block has to process all of the
+//! voters at once:
+//!
+//! 1. The snapshot is created one page per block in [`Phase::Snapshot`], starting from the most
+//!    significant page. The targets are only fetched along with the most significant page.
+//! 2. In the signed phase, miners [`Pallet::register`] the score they claim for their solution and
+//!    then [`Pallet::submit_page`] each of its pages. Each page of the solution refers to the
+//!    voters of the same page of the snapshot.
+//! 3. In [`Phase::SignedValidation`], the best registered submission is verified one page per
+//!    block. Once all of its pages are verified, the score of their merged supports is checked
+//!    against the claimed score. A valid submission is rewarded and queued, and all of the others
+//!    are discarded. An invalid one is slashed, and the next best one is verified.
+//! 4. The queued result is handed out one page at a time through
+//!    [`ElectionProvider::elect_paged`](frame_election_provider_support::ElectionProvider).
+//!
+//! The unsigned phase is not available in paged elections: [`Phase::Unsigned`] is never entered,
+//! so the offchain worker does not mine any solution and [`Pallet::submit_unsigned`] is rejected.
+//! If no signed submission is verified in time, [`Config::Fallback`] is used.
+//!
+//! While the snapshot and the verification are spread over many blocks, all of the pages of the
+//! result may still be fetched in a single block. This is what `pallet-staking` does when
+//! planning a new era, so the weight of
+//! [`ElectionProvider::elect_paged`](frame_election_provider_support::ElectionProvider) for all of
+//! the pages must fit in that block.
+
+use crate::{
+	unsigned::MinerConfig, BalanceOf, Config, ElectionCompute, ElectionError, Error, Event,
+	FeasibilityError, Miner, PagedQueuedScore, PagedQueuedSolution, PagedSignedSubmissionMetadata,
+	PagedSignedSubmissionPages, PagedSignedSubmissionScores, PagedTargetSnapshot,
+	PagedVerificationPage, PagedVoterSnapshot, Pallet, Phase, QueuedSolution, ReadySolution,
+	SnapshotMetadata, SolutionOf, SolutionOrSnapshotSize, WeightInfo,
+};
+use codec::{Decode, Encode};
+use frame_election_provider_support::{
+	merge_paged_supports, BoundedSupportsOf, ElectionDataProvider, InstantElectionProvider,
+	NposSolution, PageIndex,
+};
+use frame_support::{
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Get, ReservableCurrency},
+	weights::Weight,
+	BoundedVec,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_npos_elections::{ElectionScore, EvaluateSupport};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	Perbill, RuntimeDebug, SaturatedConversion,
+};
+
+/// The metadata of a registered paged signed submission.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct PagedSubmissionMetadata<Balance> {
+	/// The score claimed for the merged supports of all of the pages.
+	pub claimed_score: ElectionScore,
+	/// The deposit reserved for the registration and all of the pages submitted so far.
+	pub deposit: Balance,
+}
+
+/// The registered paged signed submissions along with their claimed score, sorted from the worst
+/// to the best one.
+pub type PagedSubmissionScoresOf<T> = BoundedVec<
+	(<T as frame_system::Config>::AccountId, ElectionScore),
+	<T as Config>::SignedMaxSubmissions,
+>;
+
+impl<T: Config> Pallet<T> {
+	/// Whether the elections are paged, i.e. [`Config::Pages`] is more than one.
+	pub fn is_paged() -> bool {
+		T::Pages::get() > 1
+	}
+
+	/// The most significant page, which is always the first one to be processed.
+	pub fn msp() -> PageIndex {
+		T::Pages::get().saturating_sub(1)
+	}
+
+	/// The paged counterpart of the phase transitions of `on_initialize`.
+	pub(crate) fn on_initialize_paged(remaining: BlockNumberFor<T>) -> Weight {
+		let pages: BlockNumberFor<T> = T::Pages::get().into();
+		let signed_deadline = T::SignedPhase::get() + T::UnsignedPhase::get();
+		let unsigned_deadline = T::UnsignedPhase::get();
+
+		match Self::current_phase() {
+			// NOTE: the snapshot is started `pages` blocks early, such that the signed phase still
+			// lasts for `T::SignedPhase` blocks.
+			Phase::Off if remaining <= signed_deadline + pages && remaining > unsigned_deadline =>
+				Self::create_snapshot_page_and_transition(Self::msp(), remaining),
+			Phase::Snapshot(page) => Self::create_snapshot_page_and_transition(page, remaining),
+			Phase::Signed if remaining <= unsigned_deadline && remaining > Zero::zero() => {
+				Self::phase_transition(Phase::SignedValidation);
+				T::WeightInfo::on_initialize_open_unsigned()
+			},
+			Phase::SignedValidation => Self::verify_next_page(),
+			_ => T::WeightInfo::on_initialize_nothing(),
+		}
+	}
+
+	/// Create the given page of the snapshot and move on to the next one, or to the signed phase
+	/// once the least significant page has been created.
+	fn create_snapshot_page_and_transition(
+		page: PageIndex,
+		remaining: BlockNumberFor<T>,
+	) -> Weight {
+		match Self::create_snapshot_page(page) {
+			Ok(()) if page > 0 => Self::phase_transition(Phase::Snapshot(page - 1)),
+			Ok(()) if remaining > T::UnsignedPhase::get() => Self::phase_transition(Phase::Signed),
+			Ok(()) => Self::phase_transition(Phase::SignedValidation),
+			Err(why) => {
+				// Not much we can do about this at this point, start over in the next block.
+				log!(warn, "failed to create snapshot page {} due to {:?}", page, why);
+				Self::kill_snapshot();
+				if !Self::current_phase().is_off() {
+					Self::phase_transition(Phase::Off);
+				}
+				return T::WeightInfo::on_initialize_nothing()
+			},
+		}
+		T::WeightInfo::on_initialize_open_signed()
+	}
+
+	/// Creates a single page of the snapshot. Writes new data to:
+	///
+	/// 1. [`PagedVoterSnapshot`], for the given page.
+	/// 2. [`SnapshotMetadata`], accumulating the voters of all of the pages created so far.
+	/// 3. [`PagedTargetSnapshot`] and [`crate::DesiredTargets`], for the most significant page.
+	///
+	/// The pages must be created in order, starting from the most significant one.
+	///
+	/// This is a *self-weighing* function, it will register its own extra weight as
+	/// [`frame_support::dispatch::DispatchClass::Mandatory`] with the system pallet.
+	pub fn create_snapshot_page(page: PageIndex) -> Result<(), ElectionError<T>> {
+		if page == Self::msp() {
+			let target_limit = T::MaxElectableTargets::get().saturated_into::<usize>();
+			let targets = T::DataProvider::electable_targets(Some(target_limit))
+				.map_err(ElectionError::DataProvider)?;
+			if targets.len() > target_limit {
+				return Err(ElectionError::DataProvider("Snapshot too big for submission."))
+			}
+			let desired_targets = Self::capped_desired_targets(targets.len() as u32)?;
+
+			<SnapshotMetadata<T>>::put(SolutionOrSnapshotSize {
+				voters: 0,
+				targets: targets.len() as u32,
+			});
+			<crate::DesiredTargets<T>>::put(desired_targets);
+			<PagedTargetSnapshot<T>>::put(targets);
+		}
+
+		let mut metadata = Self::snapshot_metadata()
+			.ok_or(ElectionError::Feasibility(FeasibilityError::SnapshotUnavailable))?;
+
+		let voter_limit = T::MaxElectingVoters::get().saturated_into::<usize>();
+		let voters =
+			T::DataProvider::electing_voters_paged(Some(voter_limit), page, T::Pages::get())
+				.map_err(ElectionError::DataProvider)?;
+		if voters.len() > voter_limit {
+			return Err(ElectionError::DataProvider("Snapshot too big for submission."))
+		}
+
+		log!(info, "creating snapshot page {} with {} voters", page, voters.len());
+		Self::register_weight(T::WeightInfo::create_snapshot_internal(
+			voters.len() as u32,
+			metadata.targets,
+		));
+
+		metadata.voters = metadata.voters.saturating_add(voters.len() as u32);
+		<SnapshotMetadata<T>>::put(metadata);
+		<PagedVoterSnapshot<T>>::insert(page, voters);
+		Ok(())
+	}
+
+	/// Register a paged signed submission of `who`, claiming the given score.
+	///
+	/// If the queue of submissions is full, the weakest one is ejected to make room for this one,
+	/// if this one is sufficiently better.
+	pub(crate) fn do_register(who: T::AccountId, claimed_score: ElectionScore) -> DispatchResult {
+		ensure!(Self::is_paged(), Error::<T>::CallNotAllowed);
+		ensure!(Self::current_phase().is_signed(), Error::<T>::PreDispatchEarlySubmission);
+		ensure!(
+			!<PagedSignedSubmissionMetadata<T>>::contains_key(&who),
+			Error::<T>::SignedAlreadyRegistered
+		);
+
+		let mut scores = <PagedSignedSubmissionScores<T>>::get();
+		let maybe_ejected = if scores.len() >= T::SignedMaxSubmissions::get() as usize {
+			// the weakest submission is always the first one.
+			let weakest = scores.first().map(|(_, score)| *score).unwrap_or_default();
+			ensure!(
+				claimed_score.strict_threshold_better(weakest, T::BetterSignedThreshold::get()),
+				Error::<T>::SignedQueueFull
+			);
+			Some(scores.remove(0).0)
+		} else {
+			None
+		};
+
+		// submissions that were registered earlier win ties.
+		let index = scores.partition_point(|(_, score)| *score < claimed_score);
+		scores
+			.try_insert(index, (who.clone(), claimed_score))
+			.map_err(|_| Error::<T>::SignedQueueFull)?;
+
+		let deposit = T::SignedDepositBase::get();
+		T::Currency::reserve(&who, deposit).map_err(|_| Error::<T>::SignedCannotPayDeposit)?;
+
+		// Thereafter, the function cannot fail.
+		if let Some(ejected) = maybe_ejected.as_ref() {
+			Self::discard_paged_submission(ejected);
+		}
+
+		<PagedSignedSubmissionScores<T>>::put(scores);
+		<PagedSignedSubmissionMetadata<T>>::insert(
+			&who,
+			PagedSubmissionMetadata { claimed_score, deposit },
+		);
+		Self::deposit_event(Event::SolutionStored {
+			compute: ElectionCompute::Signed,
+			origin: Some(who),
+			prev_ejected: maybe_ejected.is_some(),
+		});
+		Ok(())
+	}
+
+	/// Store, replace or, if `maybe_solution` is `None`, remove a page of the registered paged
+	/// signed submission of `who`.
+	///
+	/// The deposit of the submission is adjusted to the size of the new page.
+	pub(crate) fn do_submit_page(
+		who: T::AccountId,
+		page: PageIndex,
+		maybe_solution: Option<SolutionOf<T::MinerConfig>>,
+	) -> DispatchResult {
+		ensure!(Self::is_paged(), Error::<T>::CallNotAllowed);
+		ensure!(Self::current_phase().is_signed(), Error::<T>::PreDispatchEarlySubmission);
+		ensure!(page < T::Pages::get(), Error::<T>::SignedInvalidPage);
+		let mut metadata =
+			<PagedSignedSubmissionMetadata<T>>::get(&who).ok_or(Error::<T>::SignedNotRegistered)?;
+
+		if let Some(solution) = maybe_solution.as_ref() {
+			let size = Self::snapshot_metadata().ok_or(Error::<T>::MissingSnapshotMetadata)?;
+			let page_voters = <PagedVoterSnapshot<T>>::decode_len(page).unwrap_or_default();
+			ensure!(
+				T::MinerConfig::solution_weight(
+					page_voters as u32,
+					size.targets,
+					solution.voter_count() as u32,
+					solution.unique_targets().len() as u32,
+				)
+				.all_lt(T::SignedMaxWeight::get()),
+				Error::<T>::SignedTooMuchWeight,
+			);
+		}
+
+		let old_deposit = Self::page_deposit(
+			<PagedSignedSubmissionPages<T>>::get(&who, page)
+				.map_or(0, |solution| solution.encoded_size()),
+		);
+		let new_deposit = Self::page_deposit(
+			maybe_solution.as_ref().map_or(0, |solution| solution.encoded_size()),
+		);
+		if new_deposit > old_deposit {
+			let extra = new_deposit - old_deposit;
+			T::Currency::reserve(&who, extra).map_err(|_| Error::<T>::SignedCannotPayDeposit)?;
+			metadata.deposit = metadata.deposit.saturating_add(extra);
+		} else {
+			let refund = old_deposit - new_deposit;
+			let _remaining = T::Currency::unreserve(&who, refund);
+			debug_assert!(_remaining.is_zero());
+			metadata.deposit = metadata.deposit.saturating_sub(refund);
+		}
+
+		match maybe_solution {
+			Some(solution) => <PagedSignedSubmissionPages<T>>::insert(&who, page, solution),
+			None => <PagedSignedSubmissionPages<T>>::remove(&who, page),
+		}
+		<PagedSignedSubmissionMetadata<T>>::insert(&who, metadata);
+		Self::deposit_event(Event::SolutionPageStored { origin: who, page });
+		Ok(())
+	}
+
+	/// The deposit for storing a page of a paged signed submission which is `encoded_len` bytes
+	/// long.
+	fn page_deposit(encoded_len: usize) -> BalanceOf<T> {
+		let encoded_len: u32 = encoded_len.saturated_into();
+		T::SignedDepositByte::get().saturating_mul(encoded_len.into())
+	}
+
+	/// Verify the next page of the best registered paged signed submission, returning the weight
+	/// consumed.
+	///
+	/// Nothing happens once a submission has been accepted, or if there are no submissions left.
+	pub(crate) fn verify_next_page() -> Weight {
+		let pages = T::Pages::get() as u64;
+		let mut weight =
+			T::WeightInfo::on_initialize_nothing().saturating_add(T::DbWeight::get().reads(1));
+		if <PagedQueuedScore<T>>::exists() {
+			return weight
+		}
+
+		let scores = <PagedSignedSubmissionScores<T>>::get();
+		weight = weight.saturating_add(T::DbWeight::get().reads(1));
+		let (who, claimed_score) = match scores.last().cloned() {
+			Some(best) => best,
+			None => return weight,
+		};
+
+		// a page which was never submitted is treated as an empty one.
+		let page = <PagedVerificationPage<T>>::get().unwrap_or_else(Self::msp);
+		let solution = <PagedSignedSubmissionPages<T>>::take(&who, page).unwrap_or_default();
+		let feasibility_weight = T::WeightInfo::feasibility_check(
+			<PagedVoterSnapshot<T>>::decode_len(page).unwrap_or_default() as u32,
+			<PagedTargetSnapshot<T>>::decode_len().unwrap_or_default() as u32,
+			solution.voter_count() as u32,
+			Self::desired_targets().unwrap_or_default(),
+		);
+		weight = weight
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2))
+			.saturating_add(feasibility_weight);
+		if page == 0 {
+			// the supports of all of the pages are merged to check the score.
+			weight = weight.saturating_add(T::DbWeight::get().reads(pages));
+		}
+
+		let outcome = Self::feasibility_check_page(page, solution).and_then(|supports| {
+			<PagedQueuedSolution<T>>::insert(page, supports);
+			if page == 0 {
+				Self::feasibility_check_paged_score(claimed_score).map(Some)
+			} else {
+				Ok(None)
+			}
+		});
+
+		match outcome {
+			Ok(None) => <PagedVerificationPage<T>>::put(page - 1),
+			Ok(Some(score)) => {
+				log!(info, "paged signed submission of {:?} accepted with score {:?}", who, score);
+				Self::accept_paged_submission(&who, score);
+				// all of the other submissions are discarded.
+				let submissions = scores.len() as u64;
+				weight = weight
+					.saturating_add(T::WeightInfo::finalize_signed_phase_accept_solution())
+					.saturating_add(
+						T::DbWeight::get()
+							.reads_writes(submissions, submissions.saturating_mul(pages + 1)),
+					);
+			},
+			Err(why) => {
+				log!(
+					warn,
+					"paged signed submission of {:?} rejected at page {}: {:?}",
+					who,
+					page,
+					why
+				);
+				Self::reject_paged_submission(&who);
+				weight = weight
+					.saturating_add(T::WeightInfo::finalize_signed_phase_reject_solution())
+					.saturating_add(T::DbWeight::get().reads_writes(2, 2 * pages + 3));
+			},
+		}
+		weight
+	}
+
+	/// Checks the feasibility of a single page of a paged solution against the same page of the
+	/// snapshot, returning its supports.
+	///
+	/// A single page may back at most [`crate::DesiredTargets`] winners. The exact number of
+	/// winners and the score are only checked once all of the pages are known, see
+	/// [`Self::feasibility_check_paged_score`].
+	///
+	/// The weight of the check is accounted for by the caller.
+	pub fn feasibility_check_page(
+		page: PageIndex,
+		solution: SolutionOf<T::MinerConfig>,
+	) -> Result<BoundedSupportsOf<Self>, FeasibilityError> {
+		let desired_targets =
+			Self::desired_targets().ok_or(FeasibilityError::SnapshotUnavailable)?;
+		let targets =
+			<PagedTargetSnapshot<T>>::get().ok_or(FeasibilityError::SnapshotUnavailable)?;
+		let voters =
+			<PagedVoterSnapshot<T>>::get(page).ok_or(FeasibilityError::SnapshotUnavailable)?;
+
+		ensure!(
+			solution.unique_targets().len() as u32 <= desired_targets,
+			FeasibilityError::WrongWinnerCount
+		);
+		ensure!(desired_targets <= T::MaxWinners::get(), FeasibilityError::TooManyDesiredTargets);
+
+		Miner::<T::MinerConfig>::solution_supports(solution, &voters, &targets)?
+			.try_into()
+			.map_err(|_| FeasibilityError::BoundedConversionFailed)
+	}
+
+	/// Checks the merged supports of all of the pages in [`PagedQueuedSolution`] against the
+	/// claimed score of a paged solution.
+	pub fn feasibility_check_paged_score(
+		claimed_score: ElectionScore,
+	) -> Result<ElectionScore, FeasibilityError> {
+		let desired_targets =
+			Self::desired_targets().ok_or(FeasibilityError::SnapshotUnavailable)?;
+		let supports = merge_paged_supports(<PagedQueuedSolution<T>>::iter_values())
+			.ok_or(FeasibilityError::WrongWinnerCount)?;
+		ensure!(supports.len() as u32 == desired_targets, FeasibilityError::WrongWinnerCount);
+
+		ensure!(
+			Self::minimum_untrusted_score().map_or(true, |min_score| {
+				claimed_score.strict_threshold_better(min_score, Perbill::zero())
+			}),
+			FeasibilityError::UntrustedScoreTooLow
+		);
+		ensure!(supports.evaluate() == claimed_score, FeasibilityError::InvalidScore);
+		Ok(claimed_score)
+	}
+
+	/// Queue the verified paged submission of `who`, reward them and discard all of the other
+	/// submissions.
+	fn accept_paged_submission(who: &T::AccountId, score: ElectionScore) {
+		<PagedQueuedScore<T>>::put(score);
+		<PagedVerificationPage<T>>::kill();
+
+		for (other, _) in <PagedSignedSubmissionScores<T>>::take() {
+			if &other == who {
+				if let Some(metadata) = <PagedSignedSubmissionMetadata<T>>::take(who) {
+					Self::reward_signed_submitter(who, metadata.deposit, Zero::zero());
+				}
+				let _ = <PagedSignedSubmissionPages<T>>::clear_prefix(who, u32::MAX, None);
+			} else {
+				Self::discard_paged_submission(&other);
+			}
+		}
+	}
+
+	/// Slash the invalid paged submission of `who` and start over with the next best one.
+	fn reject_paged_submission(who: &T::AccountId) {
+		<PagedSignedSubmissionScores<T>>::mutate(|scores| scores.retain(|(other, _)| other != who));
+		if let Some(metadata) = <PagedSignedSubmissionMetadata<T>>::take(who) {
+			Self::finalize_signed_phase_reject_solution(who, metadata.deposit);
+		}
+		let _ = <PagedSignedSubmissionPages<T>>::clear_prefix(who, u32::MAX, None);
+
+		let _ = <PagedQueuedSolution<T>>::clear(u32::MAX, None);
+		<PagedVerificationPage<T>>::kill();
+	}
+
+	/// Remove the paged submission of `who` without checking it, returning their deposit.
+	///
+	/// The submission is not removed from [`PagedSignedSubmissionScores`].
+	fn discard_paged_submission(who: &T::AccountId) {
+		if let Some(metadata) = <PagedSignedSubmissionMetadata<T>>::take(who) {
+			let _remaining = T::Currency::unreserve(who, metadata.deposit);
+			debug_assert!(_remaining.is_zero());
+		}
+		let _ = <PagedSignedSubmissionPages<T>>::clear_prefix(who, u32::MAX, None);
+	}
+
+	/// Returns the given page of the result of a paged election.
+	///
+	/// The result is decided along with the most significant page, and every page is only handed
+	/// out once.
+	pub(crate) fn do_elect_paged(
+		page: PageIndex,
+	) -> Result<BoundedSupportsOf<Self>, ElectionError<T>> {
+		if page == Self::msp() {
+			Self::finalize_paged_election()
+				.map(|(compute, score)| {
+					Self::deposit_event(Event::ElectionFinalized { compute, score });
+					if Self::round() != 1 {
+						log!(info, "Finalized paged election round with compute {:?}.", compute);
+					}
+				})
+				.map_err(|err| {
+					Self::deposit_event(Event::ElectionFailed);
+					if Self::round() != 1 {
+						log!(warn, "Failed to finalize paged election round. reason {:?}", err);
+					}
+					err
+				})?;
+		}
+		Ok(<PagedQueuedSolution<T>>::take(page).unwrap_or_default())
+	}
+
+	/// Decide the result of a paged election, storing all of its pages in [`PagedQueuedSolution`].
+	///
+	/// A solution set in the emergency phase takes precedence over a verified paged submission,
+	/// which takes precedence over [`Config::Fallback`]. Solutions which are not paged are handed
+	/// out in full along with the most significant page. Any paged submission which has not been
+	/// verified by now is discarded, and gets its deposit back.
+	fn finalize_paged_election() -> Result<(ElectionCompute, ElectionScore), ElectionError<T>> {
+		for (who, _) in <PagedSignedSubmissionScores<T>>::take() {
+			Self::discard_paged_submission(&who);
+		}
+		<PagedVerificationPage<T>>::kill();
+
+		if let Some(ReadySolution { supports, score, compute }) = <QueuedSolution<T>>::take() {
+			<PagedQueuedScore<T>>::kill();
+			let _ = <PagedQueuedSolution<T>>::clear(u32::MAX, None);
+			<PagedQueuedSolution<T>>::insert(Self::msp(), supports);
+			Ok((compute, score))
+		} else if let Some(score) = <PagedQueuedScore<T>>::take() {
+			Ok((ElectionCompute::Signed, score))
+		} else {
+			let _ = <PagedQueuedSolution<T>>::clear(u32::MAX, None);
+			let supports =
+				T::Fallback::instant_elect(None, None).map_err(ElectionError::Fallback)?;
+			<PagedQueuedSolution<T>>::insert(Self::msp(), supports);
+			Ok((ElectionCompute::Fallback, Default::default()))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{helpers, mock::*, Snapshot};
+	use frame_election_provider_support::ElectionProvider;
+	use frame_support::{assert_noop, assert_ok};
+	use sp_npos_elections::Assignment;
+	use sp_runtime::{PerThing, PerU16};
+
+	/// Build a page of a solution, out of `(voter, targets)` votes which each put all of their
+	/// stake behind a single target.
+	fn page_solution(page: PageIndex, votes: Vec<(AccountId, AccountId)>) -> SolutionOf<Runtime> {
+		let voters = PagedVoterSnapshot::<Runtime>::get(page).unwrap();
+		let targets = PagedTargetSnapshot::<Runtime>::get().unwrap();
+		let assignments = votes
+			.into_iter()
+			.map(|(who, target)| Assignment { who, distribution: vec![(target, PerU16::one())] })
+			.collect::<Vec<_>>();
+
+		SolutionOf::<Runtime>::from_assignment(
+			&assignments,
+			helpers::voter_index_fn_linear::<Runtime>(&voters),
+			helpers::target_index_fn_linear::<Runtime>(&targets),
+		)
+		.unwrap()
+	}
+
+	/// A solution electing 30 and 40, backed by 50 each.
+	fn paged_solution() -> (ElectionScore, SolutionOf<Runtime>, SolutionOf<Runtime>) {
+		let score = ElectionScore { minimal_stake: 50, sum_stake: 100, sum_stake_squared: 5000 };
+		let msp = page_solution(1, vec![(2, 30), (3, 40), (4, 30)]);
+		let lsp = page_solution(0, vec![(30, 30), (40, 40)]);
+		(score, msp, lsp)
+	}
+
+	fn submit_paged_solution(who: AccountId) -> ElectionScore {
+		let (score, msp, lsp) = paged_solution();
+		assert_ok!(MultiPhase::register(RuntimeOrigin::signed(who), score));
+		assert_ok!(MultiPhase::submit_page(RuntimeOrigin::signed(who), 1, Some(Box::new(msp))));
+		assert_ok!(MultiPhase::submit_page(RuntimeOrigin::signed(who), 0, Some(Box::new(lsp))));
+		score
+	}
+
+	fn totals(supports: BoundedSupportsOf<MultiPhase>) -> Vec<(AccountId, u128)> {
+		supports.into_iter().map(|(target, support)| (target, support.total)).collect()
+	}
+
+	#[test]
+	fn snapshot_is_created_over_pages() {
+		ExtBuilder::default().pages(2).build_and_execute(|| {
+			roll_to(12);
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+
+			roll_to(13);
+			assert_eq!(MultiPhase::current_phase(), Phase::Snapshot(0));
+			assert_eq!(
+				PagedVoterSnapshot::<Runtime>::get(1)
+					.unwrap()
+					.into_iter()
+					.map(|(who, _, _)| who)
+					.collect::<Vec<_>>(),
+				vec![1, 2, 3, 4]
+			);
+			assert!(PagedVoterSnapshot::<Runtime>::get(0).is_none());
+			assert_eq!(PagedTargetSnapshot::<Runtime>::get().unwrap(), vec![10, 20, 30, 40]);
+			assert_eq!(
+				MultiPhase::snapshot_metadata().unwrap(),
+				SolutionOrSnapshotSize { voters: 4, targets: 4 }
+			);
+
+			roll_to(14);
+			assert_eq!(MultiPhase::current_phase(), Phase::Signed);
+			assert_eq!(
+				PagedVoterSnapshot::<Runtime>::get(0)
+					.unwrap()
+					.into_iter()
+					.map(|(who, _, _)| who)
+					.collect::<Vec<_>>(),
+				vec![10, 20, 30, 40]
+			);
+			assert_eq!(
+				MultiPhase::snapshot_metadata().unwrap(),
+				SolutionOrSnapshotSize { voters: 8, targets: 4 }
+			);
+			assert_eq!(MultiPhase::desired_targets(), Some(2));
+			assert!(<Snapshot<Runtime>>::get().is_none());
+
+			roll_to(25);
+			assert_eq!(MultiPhase::current_phase(), Phase::SignedValidation);
+
+			// without any submission, the fallback is returned along with the first page.
+			assert_eq!(MultiPhase::elect_paged(1).unwrap().len(), 2);
+			assert!(MultiPhase::elect_paged(0).unwrap().is_empty());
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+			assert!(PagedTargetSnapshot::<Runtime>::get().is_none());
+			assert!(PagedVoterSnapshot::<Runtime>::get(1).is_none());
+			assert!(multi_phase_events().contains(&Event::ElectionFinalized {
+				compute: ElectionCompute::Fallback,
+				score: Default::default(),
+			}));
+		})
+	}
+
+	#[test]
+	fn paged_calls_are_checked() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to_signed();
+			assert_noop!(
+				MultiPhase::register(RuntimeOrigin::signed(99), Default::default()),
+				Error::<Runtime>::CallNotAllowed,
+			);
+		});
+
+		ExtBuilder::default().pages(2).build_and_execute(|| {
+			assert_noop!(
+				MultiPhase::register(RuntimeOrigin::signed(99), Default::default()),
+				Error::<Runtime>::PreDispatchEarlySubmission,
+			);
+
+			roll_to_signed();
+			assert_noop!(
+				MultiPhase::submit(RuntimeOrigin::signed(99), Box::new(Default::default())),
+				Error::<Runtime>::CallNotAllowed,
+			);
+			assert_noop!(
+				MultiPhase::submit_page(RuntimeOrigin::signed(99), 0, None),
+				Error::<Runtime>::SignedNotRegistered,
+			);
+
+			assert_ok!(MultiPhase::register(RuntimeOrigin::signed(99), Default::default()));
+			assert_eq!(balances(&99), (95, 5));
+			assert_noop!(
+				MultiPhase::register(RuntimeOrigin::signed(99), Default::default()),
+				Error::<Runtime>::SignedAlreadyRegistered,
+			);
+			assert_noop!(
+				MultiPhase::submit_page(RuntimeOrigin::signed(99), 2, None),
+				Error::<Runtime>::SignedInvalidPage,
+			);
+			assert_ok!(MultiPhase::submit_page(RuntimeOrigin::signed(99), 1, None));
+			assert_eq!(
+				multi_phase_events().last(),
+				Some(&Event::SolutionPageStored { origin: 99, page: 1 })
+			);
+		})
+	}
+
+	#[test]
+	fn paged_submission_is_verified_and_elected() {
+		ExtBuilder::default().pages(2).build_and_execute(|| {
+			roll_to_signed();
+			let score = submit_paged_solution(99);
+			assert_eq!(balances(&99), (95, 5));
+
+			roll_to(25);
+			assert_eq!(MultiPhase::current_phase(), Phase::SignedValidation);
+
+			roll_to(26);
+			assert_eq!(
+				totals(PagedQueuedSolution::<Runtime>::get(1).unwrap()),
+				vec![(30, 20), (40, 10)]
+			);
+			assert_eq!(PagedVerificationPage::<Runtime>::get(), Some(0));
+			assert!(PagedQueuedScore::<Runtime>::get().is_none());
+
+			roll_to(27);
+			assert_eq!(PagedQueuedScore::<Runtime>::get(), Some(score));
+			assert!(PagedSignedSubmissionScores::<Runtime>::get().is_empty());
+			assert_eq!(balances(&99), (100 + 7, 0));
+
+			assert_eq!(MultiPhase::pages(), 2);
+			assert_eq!(totals(MultiPhase::elect_paged(1).unwrap()), vec![(30, 20), (40, 10)]);
+			assert_eq!(totals(MultiPhase::elect_paged(0).unwrap()), vec![(30, 30), (40, 40)]);
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+			assert_eq!(MultiPhase::round(), 2);
+			assert!(multi_phase_events()
+				.contains(&Event::ElectionFinalized { compute: ElectionCompute::Signed, score }));
+		})
+	}
+
+	#[test]
+	fn verifying_a_page_is_weighed() {
+		ExtBuilder::default().pages(2).build_and_execute(|| {
+			roll_to_signed();
+			submit_paged_solution(99);
+			roll_to(25);
+			assert_eq!(MultiPhase::current_phase(), Phase::SignedValidation);
+
+			let nothing = <() as WeightInfo>::on_initialize_nothing();
+			let feasibility = <() as WeightInfo>::feasibility_check(3, 4, 3, 2);
+			assert!(MultiPhase::verify_next_page().all_gte(nothing.saturating_add(feasibility)));
+
+			// accepting the submission is weighed as well.
+			let accept = <() as WeightInfo>::finalize_signed_phase_accept_solution();
+			assert!(MultiPhase::verify_next_page().all_gte(accept));
+			assert!(PagedQueuedScore::<Runtime>::get().is_some());
+
+			// once accepted, nothing is verified anymore.
+			assert!(MultiPhase::verify_next_page().all_lt(feasibility));
+		})
+	}
+
+	#[test]
+	fn invalid_paged_submission_is_slashed() {
+		ExtBuilder::default().pages(2).build_and_execute(|| {
+			roll_to_signed();
+			let score = submit_paged_solution(99);
+
+			// a better claimed score, but the least significant page is missing.
+			let (_, msp, _) = paged_solution();
+			let fake_score = ElectionScore { minimal_stake: 60, ..score };
+			assert_ok!(MultiPhase::register(RuntimeOrigin::signed(100), fake_score));
+			assert_ok!(MultiPhase::submit_page(RuntimeOrigin::signed(100), 1, Some(Box::new(msp))));
+
+			// the fake submission is verified first, and slashed.
+			roll_to(27);
+			assert_eq!(balances(&100), (95, 0));
+			assert!(multi_phase_events().contains(&Event::Slashed { account: 100, value: 5 }));
+			assert!(PagedQueuedScore::<Runtime>::get().is_none());
+			assert!(PagedQueuedSolution::<Runtime>::iter().next().is_none());
+
+			// then the honest one is verified and accepted.
+			roll_to(29);
+			assert_eq!(PagedQueuedScore::<Runtime>::get(), Some(score));
+			assert_eq!(balances(&99), (100 + 7, 0));
+
+			let supports = MultiPhase::elect().unwrap();
+			assert_eq!(totals(supports), vec![(30, 50), (40, 50)]);
+		})
+	}
+}
@@ -470,6 +470,18 @@ impl<T: Config> Pallet<T> {
 		// write this ready solution.
 		<QueuedSolution<T>>::put(ready_solution);
 
+		Self::reward_signed_submitter(who, deposit, call_fee);
+	}
+
+	/// Reward the submitter of an accepted signed solution, returning their deposit and refunding
+	/// the given call fee.
+	///
+	/// Infallible
+	pub(crate) fn reward_signed_submitter(
+		who: &T::AccountId,
+		deposit: BalanceOf<T>,
+		call_fee: BalanceOf<T>,
+	) {
 		let reward = T::SignedRewardBase::get();
 		// emit reward event
 		Self::deposit_event(crate::Event::Rewarded { account: who.clone(), value: reward });
@@ -34,7 +34,7 @@ use frame_system::{offchain::SubmitTransaction, pallet_prelude::BlockNumberFor};
 use scale_info::TypeInfo;
 use sp_npos_elections::{
 	assignment_ratio_to_staked_normalized, assignment_staked_to_ratio_normalized, ElectionResult,
-	ElectionScore, EvaluateSupport,
+	ElectionScore, EvaluateSupport, Supports,
 };
 use sp_runtime::{
 	offchain::storage::{MutateStorageError, StorageValueRef},
@@ -729,10 +729,33 @@ impl<T: MinerConfig> Miner<T> {
 			FeasibilityError::UntrustedScoreTooLow
 		);
 
+		let supports = Self::solution_supports(solution, &snapshot_voters, &snapshot_targets)?;
+
+		// Finally, check that the claimed score was indeed correct.
+		let known_score = supports.evaluate();
+		ensure!(known_score == score, FeasibilityError::InvalidScore);
+
+		// Size of winners in miner solution is equal to `desired_targets` <= `MaxWinners`.
+		let supports = supports
+			.try_into()
+			.defensive_map_err(|_| FeasibilityError::BoundedConversionFailed)?;
+
+		Ok(ReadySolution { supports, compute, score })
+	}
+
+	/// Build the supports of `solution`, checking that all of its voters and votes are valid
+	/// based on the given snapshot.
+	///
+	/// Neither the number of winners nor the score of the solution are checked.
+	pub fn solution_supports(
+		solution: SolutionOf<T>,
+		snapshot_voters: &Vec<MinerVoterOf<T>>,
+		snapshot_targets: &Vec<T::AccountId>,
+	) -> Result<Supports<T::AccountId>, FeasibilityError> {
 		// ----- Start building. First, we need some closures.
-		let cache = helpers::generate_voter_cache::<T>(&snapshot_voters);
-		let voter_at = helpers::voter_at_fn::<T>(&snapshot_voters);
-		let target_at = helpers::target_at_fn::<T>(&snapshot_targets);
+		let cache = helpers::generate_voter_cache::<T>(snapshot_voters);
+		let voter_at = helpers::voter_at_fn::<T>(snapshot_voters);
+		let target_at = helpers::target_at_fn::<T>(snapshot_targets);
 		let voter_index = helpers::voter_index_fn_usize::<T>(&cache);
 
 		// Then convert solution -> assignment. This will fail if any of the indices are gibberish,
@@ -764,23 +787,12 @@ impl<T: MinerConfig> Miner<T> {
 		})?;
 
 		// ----- Start building support. First, we need one more closure.
-		let stake_of = helpers::stake_of_fn::<T>(&snapshot_voters, &cache);
+		let stake_of = helpers::stake_of_fn::<T>(snapshot_voters, &cache);
 
 		// This might fail if the normalization fails. Very unlikely. See `integrity_test`.
 		let staked_assignments = assignment_ratio_to_staked_normalized(assignments, stake_of)
 			.map_err::<FeasibilityError, _>(Into::into)?;
-		let supports = sp_npos_elections::to_supports(&staked_assignments);
-
-		// Finally, check that the claimed score was indeed correct.
-		let known_score = supports.evaluate();
-		ensure!(known_score == score, FeasibilityError::InvalidScore);
-
-		// Size of winners in miner solution is equal to `desired_targets` <= `MaxWinners`.
-		let supports = supports
-			.try_into()
-			.defensive_map_err(|_| FeasibilityError::BoundedConversionFailed)?;
-
-		Ok(ReadySolution { supports, compute, score })
+		Ok(sp_npos_elections::to_supports(&staked_assignments))
 	}
 }
 
@@ -63,6 +63,8 @@ pub trait WeightInfo {
 	fn submit() -> Weight;
 	fn submit_unsigned(v: u32, t: u32, a: u32, d: u32, ) -> Weight;
 	fn feasibility_check(v: u32, t: u32, a: u32, d: u32, ) -> Weight;
+	fn register(p: u32, ) -> Weight;
+	fn submit_page() -> Weight;
 }
 
 /// Weights for pallet_election_provider_multi_phase using the Substrate node and recommended hardware.
@@ -273,6 +275,51 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(Weight::from_parts(0, 32).saturating_mul(t.into()))
 			.saturating_add(Weight::from_parts(0, 553).saturating_mul(v.into()))
 	}
+	/// Storage: ElectionProviderMultiPhase CurrentPhase (r:1 w:0)
+	/// Proof Skipped: ElectionProviderMultiPhase CurrentPhase (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionMetadata (r:2 w:2)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionScores (r:1 w:1)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionScores (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionPages (r:0 w:1)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionPages (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `p` is `[1, 32]`.
+	fn register(p: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1167 + p * (3 ±0)`
+		//  Estimated: `6196 + p * (3 ±0)`
+		// Minimum execution time: 63_942_000 picoseconds.
+		Weight::from_parts(65_708_000, 6196)
+			// Standard Error: 4_210
+			.saturating_add(Weight::from_parts(1_452_118, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 3).saturating_mul(p.into()))
+	}
+	/// Storage: ElectionProviderMultiPhase CurrentPhase (r:1 w:0)
+	/// Proof Skipped: ElectionProviderMultiPhase CurrentPhase (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionMetadata (r:1 w:1)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase SnapshotMetadata (r:1 w:0)
+	/// Proof Skipped: ElectionProviderMultiPhase SnapshotMetadata (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedVoterSnapshot (r:1 w:0)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedVoterSnapshot (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionPages (r:1 w:1)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionPages (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn submit_page() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `34871`
+		//  Estimated: `38336`
+		// Minimum execution time: 412_530_000 picoseconds.
+		Weight::from_parts(421_904_000, 38336)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -482,4 +529,49 @@ impl WeightInfo for () {
 			.saturating_add(Weight::from_parts(0, 32).saturating_mul(t.into()))
 			.saturating_add(Weight::from_parts(0, 553).saturating_mul(v.into()))
 	}
+	/// Storage: ElectionProviderMultiPhase CurrentPhase (r:1 w:0)
+	/// Proof Skipped: ElectionProviderMultiPhase CurrentPhase (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionMetadata (r:2 w:2)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionScores (r:1 w:1)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionScores (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionPages (r:0 w:1)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionPages (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `p` is `[1, 32]`.
+	fn register(p: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1167 + p * (3 ±0)`
+		//  Estimated: `6196 + p * (3 ±0)`
+		// Minimum execution time: 63_942_000 picoseconds.
+		Weight::from_parts(65_708_000, 6196)
+			// Standard Error: 4_210
+			.saturating_add(Weight::from_parts(1_452_118, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 3).saturating_mul(p.into()))
+	}
+	/// Storage: ElectionProviderMultiPhase CurrentPhase (r:1 w:0)
+	/// Proof Skipped: ElectionProviderMultiPhase CurrentPhase (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionMetadata (r:1 w:1)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionMetadata (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase SnapshotMetadata (r:1 w:0)
+	/// Proof Skipped: ElectionProviderMultiPhase SnapshotMetadata (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedVoterSnapshot (r:1 w:0)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedVoterSnapshot (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ElectionProviderMultiPhase PagedSignedSubmissionPages (r:1 w:1)
+	/// Proof Skipped: ElectionProviderMultiPhase PagedSignedSubmissionPages (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn submit_page() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `34871`
+		//  Estimated: `38336`
+		// Minimum execution time: 412_530_000 picoseconds.
+		Weight::from_parts(421_904_000, 38336)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
 }
@@ -267,6 +267,12 @@ pub type IndexAssignmentOf<C> = IndexAssignment<
 	<C as NposSolution>::Accuracy,
 >;
 
+/// The index of a page of a paged election.
+///
+/// Pages are always processed from the most significant one, `pages - 1`, down to the least
+/// significant one, `0`.
+pub type PageIndex = u32;
+
 /// Types that are used by the data provider trait.
 pub mod data_provider {
 	/// Alias for the result type of the election data provider.
@@ -307,6 +313,32 @@ pub trait ElectionDataProvider {
 	/// appropriate weight at the end of execution with the system pallet directly.
 	fn electing_voters(maybe_max_len: Option<usize>) -> data_provider::Result<Vec<VoterOf<Self>>>;
 
+	/// A single page of the voters that participate in the election.
+	///
+	/// The pages of a snapshot are requested in order, from `pages - 1` down to `0`, and each page
+	/// continues where the previous one left off, such that no voter is returned twice within the
+	/// same snapshot. Requesting page `pages - 1` always starts a new snapshot.
+	///
+	/// If `maybe_max_len` is `Some(v)` then the resulting vector MUST NOT be longer than `v` items
+	/// long.
+	///
+	/// The default implementation returns [`Self::electing_voters`] as the most significant page
+	/// and nothing in the rest of them.
+	///
+	/// This should be implemented as a self-weighing function. The implementor should register its
+	/// appropriate weight at the end of execution with the system pallet directly.
+	fn electing_voters_paged(
+		maybe_max_len: Option<usize>,
+		page: PageIndex,
+		pages: PageIndex,
+	) -> data_provider::Result<Vec<VoterOf<Self>>> {
+		if page == pages.saturating_sub(1) {
+			Self::electing_voters(maybe_max_len)
+		} else {
+			Ok(Vec::new())
+		}
+	}
+
 	/// The number of targets to elect.
 	///
 	/// This should be implemented as a self-weighing function. The implementor should register its
@@ -416,6 +448,26 @@ pub trait ElectionProvider: ElectionProviderBase {
 	/// implementor should register its appropriate weight at the end of execution with the
 	/// system pallet directly.
 	fn elect() -> Result<BoundedSupportsOf<Self>, Self::Error>;
+
+	/// The number of pages in which [`Self::elect_paged`] returns the result of the election.
+	fn pages() -> PageIndex {
+		1
+	}
+
+	/// Returns a single page of the result of the election.
+	///
+	/// The pages must be requested in order, from `Self::pages() - 1` down to `0`, and the
+	/// election is concluded once the last page, `0`, has been returned. The same target may be
+	/// backed in more than one page, see [`merge_paged_supports`].
+	///
+	/// The default implementation returns the result of [`Self::elect`] as its only page.
+	fn elect_paged(page: PageIndex) -> Result<BoundedSupportsOf<Self>, Self::Error> {
+		if page == 0 {
+			Self::elect()
+		} else {
+			Ok(Default::default())
+		}
+	}
 }
 
 /// A (almost) marker trait that signifies an election provider as working synchronously. i.e. being
@@ -567,6 +619,15 @@ pub trait SortedListProvider<AccountId> {
 	/// unbounded amount of storage accesses.
 	fn unsafe_clear();
 
+	/// Freeze the order of the list, e.g. while it is iterated across several blocks.
+	///
+	/// While locked, ids can still be inserted and removed, but the list refuses to move ids
+	/// around, including through [`Self::on_update`]. Does nothing by default.
+	fn lock() {}
+
+	/// Allow the order of the list to change again after [`Self::lock`].
+	fn unlock() {}
+
 	/// Check internal state of the list. Only meant for debugging.
 	#[cfg(feature = "try-runtime")]
 	fn try_state() -> Result<(), TryRuntimeError>;
@@ -678,6 +739,28 @@ pub type BoundedSupportsOf<E> = BoundedSupports<
 	<E as ElectionProviderBase>::MaxWinners,
 >;
 
+/// Merge the pages returned by [`ElectionProvider::elect_paged`] into a single set of supports,
+/// summing up the backing of the targets which are backed in more than one page.
+///
+/// Returns `None` if the merged supports do not fit in `Bound`.
+pub fn merge_paged_supports<AccountId: PartialEq, Bound: Get<u32>>(
+	pages: impl IntoIterator<Item = BoundedSupports<AccountId, Bound>>,
+) -> Option<BoundedSupports<AccountId, Bound>> {
+	let mut merged = BoundedSupports::<AccountId, Bound>::default();
+	for page in pages {
+		for (target, support) in page.into_inner() {
+			match merged.iter_mut().find(|(existing, _)| *existing == target) {
+				Some((_, existing)) => {
+					existing.total = existing.total.saturating_add(support.total);
+					existing.voters.extend(support.voters);
+				},
+				None => merged.try_push((target, support)).ok()?,
+			}
+		}
+	}
+	Some(merged)
+}
+
 sp_core::generate_feature_enabled_macro!(
 	runtime_benchmarks_enabled,
 	feature = "runtime-benchmarks",
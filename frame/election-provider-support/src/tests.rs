@@ -453,3 +453,36 @@ fn index_assignments_generate_same_solution_as_plain_assignments() {
 
 	assert_eq!(solution, index_compact);
 }
+
+#[test]
+fn merge_paged_supports_sums_up_backings() {
+	use crate::{merge_paged_supports, BoundedSupports, Support};
+
+	let page = |supports: Vec<(u32, Support<u32>)>| -> BoundedSupports<u32, ConstU32<2>> {
+		supports.try_into().unwrap()
+	};
+
+	let merged = merge_paged_supports(vec![
+		page(vec![(10, Support { total: 15, voters: vec![(1, 10), (10, 5)] })]),
+		page(vec![
+			(20, Support { total: 5, voters: vec![(2, 5)] }),
+			(10, Support { total: 3, voters: vec![(3, 3)] }),
+		]),
+	])
+	.unwrap();
+
+	assert_eq!(
+		merged.into_inner(),
+		vec![
+			(10, Support { total: 18, voters: vec![(1, 10), (10, 5), (3, 3)] }),
+			(20, Support { total: 5, voters: vec![(2, 5)] }),
+		]
+	);
+
+	// three distinct targets do not fit in the bound.
+	assert!(merge_paged_supports(vec![
+		page(vec![(10, Default::default()), (20, Default::default())]),
+		page(vec![(30, Default::default())]),
+	])
+	.is_none());
+}
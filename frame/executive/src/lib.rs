@@ -130,6 +130,7 @@ use frame_support::{
 use frame_system::pallet_prelude::BlockNumberFor;
 use sp_runtime::{
 	generic::Digest,
+	offchain::schedule::{PalletOffchainWorkerSchedule, Trigger},
 	traits::{
 		self, Applyable, CheckEqual, Checkable, Dispatchable, Header, NumberFor, One,
 		ValidateUnsigned, Zero,
@@ -672,6 +673,82 @@ where
 
 	/// Start an offchain worker and generate extrinsics.
 	pub fn offchain_worker(header: &frame_system::pallet_prelude::HeaderFor<System>) {
+		Self::initialize_offchain_worker(header);
+
+		<AllPalletsWithSystem as OffchainWorker<BlockNumberFor<System>>>::offchain_worker(
+			*header.number(),
+		)
+	}
+
+	/// Start the offchain workers of the pallets called `pallets` only, in the given order.
+	pub fn offchain_worker_of(
+		header: &frame_system::pallet_prelude::HeaderFor<System>,
+		pallets: &[Vec<u8>],
+	) {
+		Self::initialize_offchain_worker(header);
+
+		for pallet in pallets {
+			let found = sp_std::str::from_utf8(pallet).map_or(false, |pallet| {
+				<AllPalletsWithSystem as OffchainWorker<BlockNumberFor<System>>>::offchain_worker_of(
+					pallet,
+					*header.number(),
+				)
+			});
+			if !found {
+				frame_support::log::warn!(
+					target: LOG_TARGET,
+					"No offchain worker of pallet {:?} to run",
+					sp_std::str::from_utf8(pallet),
+				);
+			}
+		}
+	}
+
+	/// Returns the offchain worker schedules of all pallets.
+	///
+	/// Event triggers are matched against the events of the current block.
+	pub fn offchain_worker_schedules() -> Vec<PalletOffchainWorkerSchedule> {
+		let schedules =
+			<AllPalletsWithSystem as OffchainWorker<BlockNumberFor<System>>>::offchain_worker_schedules();
+
+		let has_event_triggers = schedules.iter().any(|(_, schedule)| {
+			schedule.triggers.iter().any(|trigger| matches!(trigger, Trigger::Event { .. }))
+		});
+		// The pallet and variant indices of an event are the first two bytes of its encoding.
+		let events = if has_event_triggers {
+			<frame_system::Pallet<System>>::read_events_no_consensus()
+				.map(|record| {
+					record
+						.event
+						.using_encoded(|event| (event.first().copied(), event.get(1).copied()))
+				})
+				.collect::<Vec<_>>()
+		} else {
+			Vec::new()
+		};
+
+		schedules
+			.into_iter()
+			.map(|(pallet, schedule)| {
+				let triggered_by_events = schedule.triggers.iter().any(|trigger| match trigger {
+					Trigger::Event { pallet_index, event_index } =>
+						events.iter().any(|(event_pallet, event_variant)| {
+							*event_pallet == Some(*pallet_index) &&
+								event_index.map_or(true, |index| *event_variant == Some(index))
+						}),
+					Trigger::StorageChanged(_) => false,
+				});
+
+				PalletOffchainWorkerSchedule {
+					pallet: pallet.as_bytes().to_vec(),
+					schedule,
+					triggered_by_events,
+				}
+			})
+			.collect()
+	}
+
+	fn initialize_offchain_worker(header: &frame_system::pallet_prelude::HeaderFor<System>) {
 		sp_io::init_tracing();
 		// We need to keep events available for offchain workers,
 		// hence we initialize the block manually.
@@ -684,10 +761,6 @@ where
 		// the hash for the header before. However, here we are aware of the hash and we can add it
 		// as well.
 		frame_system::BlockHash::<System>::insert(header.number(), header.hash());
-
-		<AllPalletsWithSystem as OffchainWorker<BlockNumberFor<System>>>::offchain_worker(
-			*header.number(),
-		)
 	}
 }
 
@@ -721,6 +794,7 @@ mod tests {
 	mod custom {
 		use frame_support::pallet_prelude::*;
 		use frame_system::pallet_prelude::*;
+		use sp_runtime::offchain::schedule::{Cadence, OffchainWorkerSchedule, Trigger};
 
 		#[pallet::pallet]
 		pub struct Pallet<T>(_);
@@ -754,6 +828,12 @@ mod tests {
 			fn offchain_worker(n: BlockNumberFor<T>) {
 				assert_eq!(BlockNumberFor::<T>::from(1u32), n);
 			}
+
+			fn offchain_worker_schedule() -> OffchainWorkerSchedule {
+				// Also run whenever `Balances` emits an event.
+				OffchainWorkerSchedule::new(Cadence::EveryNBlocks(2))
+					.with_trigger(Trigger::Event { pallet_index: 1, event_index: None })
+			}
 		}
 
 		#[pallet::call]
@@ -1500,6 +1580,52 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn offchain_worker_schedules_are_reported() {
+		use sp_runtime::offchain::schedule::{Cadence, OffchainWorkerSchedule, Trigger};
+
+		new_test_ext(1).execute_with(|| {
+			// Only pallets implementing an offchain worker are scheduled.
+			let schedules = Executive::offchain_worker_schedules();
+			assert_eq!(
+				schedules.iter().map(|s| &s.pallet[..]).collect::<Vec<_>>(),
+				vec![&b"Custom"[..]],
+			);
+			assert_eq!(
+				schedules[0].schedule,
+				OffchainWorkerSchedule::new(Cadence::EveryNBlocks(2))
+					.with_trigger(Trigger::Event { pallet_index: 1, event_index: None }),
+			);
+			assert!(!schedules[0].triggered_by_events);
+
+			// Events are only deposited from block 1 on.
+			System::set_block_number(1);
+			System::deposit_event(pallet_balances::Event::<Runtime>::Deposit { who: 1, amount: 1 });
+
+			let schedules = Executive::offchain_worker_schedules();
+			assert!(schedules[0].triggered_by_events);
+		});
+	}
+
+	#[test]
+	fn offchain_worker_of_runs_given_pallets() {
+		new_test_ext(1).execute_with(|| {
+			let header = Header::new(
+				1,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			);
+
+			// The offchain worker of `Custom` asserts it runs with the header's number, and
+			// unknown pallets are ignored.
+			Executive::offchain_worker_of(&header, &[b"Unknown".to_vec(), b"Custom".to_vec()]);
+
+			assert_eq!(header.hash(), System::block_hash(1));
+		});
+	}
+
 	#[test]
 	fn calculating_storage_root_twice_works() {
 		let call = RuntimeCall::Custom(custom::Call::calculate_storage_root {});
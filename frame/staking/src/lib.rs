@@ -881,6 +881,25 @@ impl Default for Forcing {
 	}
 }
 
+/// Progress of a paged snapshot of the voters, as taken by
+/// [`frame_election_provider_support::ElectionDataProvider::electing_voters_paged`].
+///
+/// The order of the `VoterList` is locked while the snapshot is [`SnapshotStatus::Ongoing`] or
+/// [`SnapshotStatus::Resuming`].
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen, Default)]
+pub enum SnapshotStatus<AccountId> {
+	/// The snapshot is ongoing, the next page starts right after the given voter.
+	Ongoing(AccountId),
+	/// The snapshot is ongoing, but the voter it stopped at left the `VoterList` in between
+	/// pages. The next page starts at, and includes, the given voter.
+	Resuming(AccountId),
+	/// All of the voters have been returned, the remaining pages are empty.
+	Consumed,
+	/// No snapshot is being taken.
+	#[default]
+	Waiting,
+}
+
 /// A `Convert` implementation that finds the stash of the given controller account,
 /// if any.
 pub struct StashOf<T>(sp_std::marker::PhantomData<T>);
@@ -19,8 +19,8 @@
 //! Implementations for the Staking FRAME Pallet.
 
 use frame_election_provider_support::{
-	data_provider, BoundedSupportsOf, ElectionDataProvider, ElectionProvider, ScoreProvider,
-	SortedListProvider, VoteWeight, VoterOf,
+	data_provider, BoundedSupportsOf, ElectionDataProvider, ElectionProvider, PageIndex,
+	ScoreProvider, SortedListProvider, VoteWeight, VoterOf,
 };
 use frame_support::{
 	defensive,
@@ -48,7 +48,7 @@ use sp_std::prelude::*;
 use crate::{
 	log, slashing, weights::WeightInfo, ActiveEraInfo, BalanceOf, EraPayout, Exposure, ExposureOf,
 	Forcing, IndividualExposure, MaxWinnersOf, Nominations, PositiveImbalanceOf, RewardDestination,
-	SessionInterface, StakingLedger, ValidatorPrefs,
+	SessionInterface, SnapshotStatus, StakingLedger, ValidatorPrefs,
 };
 
 use super::{pallet::*, STAKING_ID};
@@ -281,6 +281,19 @@ impl<T: Config> Pallet<T> {
 		<Ledger<T>>::insert(controller, ledger);
 	}
 
+	/// Update the score of `stash` in the `VoterList`, if it exists in it.
+	///
+	/// While a paged voter snapshot is being taken the order of the `VoterList` is locked, and the
+	/// update is skipped. The voter can be moved to its right position with `rebag` afterwards.
+	///
+	/// The ledger of `stash` must be updated prior to calling this.
+	pub(crate) fn update_voter_score(stash: &T::AccountId) {
+		if Self::is_voter_snapshot_ongoing() || !T::VoterList::contains(stash) {
+			return
+		}
+		let _ = T::VoterList::on_update(stash, Self::weight_of(stash)).defensive();
+	}
+
 	/// Chill a stash account.
 	pub(crate) fn chill_stash(stash: &T::AccountId) {
 		let chilled_as_validator = Self::do_remove_validator(stash);
@@ -521,11 +534,25 @@ impl<T: Config> Pallet<T> {
 				// both bounds checked in integrity test to be equal
 				.defensive_unwrap_or_default()
 		} else {
-			let result = <T::ElectionProvider>::elect().map_err(|e| {
-				log!(warn, "election provider failed due to {:?}", e);
+			// fetch all pages of the result, from the most significant one down to the last.
+			//
+			// NOTE: all of the pages are fetched in this very block, the election provider only
+			// spreads the snapshot and the computation of the result over many blocks.
+			let pages = <T::ElectionProvider as ElectionProvider>::pages();
+			let mut result = Vec::with_capacity(pages as usize);
+			for page in (0..pages).rev() {
+				let supports = <T::ElectionProvider>::elect_paged(page).map_err(|e| {
+					log!(warn, "election provider failed due to {:?}", e);
+					Self::deposit_event(Event::StakingElectionFailed);
+				});
+				result.push(supports.ok()?);
+			}
+
+			frame_election_provider_support::merge_paged_supports(result).or_else(|| {
+				defensive!("the pages of the election result exceed `MaxWinners`");
 				Self::deposit_event(Event::StakingElectionFailed);
-			});
-			result.ok()?
+				None
+			})?
 		};
 
 		let exposures = Self::collect_exposures(election_result);
@@ -763,6 +790,118 @@ impl<T: Config> Pallet<T> {
 	///
 	/// This function is self-weighing as [`DispatchClass::Mandatory`].
 	pub fn get_npos_voters(maybe_max_len: Option<usize>) -> Vec<VoterOf<Self>> {
+		let (all_voters, min_active_stake) =
+			Self::collect_npos_voters(maybe_max_len, T::VoterList::iter());
+
+		MinimumActiveStake::<T>::put(min_active_stake);
+
+		all_voters
+	}
+
+	/// Get a single page of the voters that are eligible for the npos election.
+	///
+	/// Requesting the most significant page, `pages - 1`, starts iterating the voter list from
+	/// the beginning, and each following page continues right after the last voter seen by the
+	/// previous one, as recorded in [`VoterSnapshotStatus`]. A voter leaving the list in between
+	/// pages while being that cursor moves it forward to its successor.
+	///
+	/// The order of the `VoterList` is locked from the first page until the last one, or until
+	/// all of the voters have been returned, so that no voter is returned twice or skipped.
+	///
+	/// `MinimumActiveStake` is set to the minimum active nominator stake of all the pages.
+	///
+	/// This function is self-weighing as [`DispatchClass::Mandatory`].
+	pub fn get_npos_voters_paged(
+		maybe_max_len: Option<usize>,
+		page: PageIndex,
+		pages: PageIndex,
+	) -> Vec<VoterOf<Self>> {
+		let is_first_page = page == pages.saturating_sub(1);
+
+		let mut sorted_voters = match VoterSnapshotStatus::<T>::get() {
+			_ if is_first_page => T::VoterList::iter(),
+			SnapshotStatus::Ongoing(last) => T::VoterList::iter_from(&last).unwrap_or_else(|_| {
+				defensive!("last voter of the previous page is not in the `VoterList`");
+				Box::new(sp_std::iter::empty())
+			}),
+			SnapshotStatus::Resuming(next) => match T::VoterList::iter_from(&next) {
+				Ok(rest) => Box::new(sp_std::iter::once(next).chain(rest)),
+				Err(_) => {
+					defensive!("next voter of the snapshot is not in the `VoterList`");
+					Box::new(sp_std::iter::empty())
+				},
+			},
+			SnapshotStatus::Consumed | SnapshotStatus::Waiting => Box::new(sp_std::iter::empty()),
+		};
+
+		let mut last_seen = None;
+		let (voters, min_active_stake) = Self::collect_npos_voters(
+			maybe_max_len,
+			sorted_voters.by_ref().inspect(|voter| last_seen = Some(voter.clone())),
+		);
+
+		if is_first_page {
+			MinimumActiveStake::<T>::put(min_active_stake);
+		} else if !voters.is_empty() {
+			MinimumActiveStake::<T>::mutate(|minimum| *minimum = min_active_stake.min(*minimum));
+		}
+
+		let status = match last_seen {
+			_ if page == 0 => SnapshotStatus::Waiting,
+			Some(last) if sorted_voters.next().is_some() => SnapshotStatus::Ongoing(last),
+			_ => SnapshotStatus::Consumed,
+		};
+		Self::set_voter_snapshot_status(status);
+
+		voters
+	}
+
+	/// Move the cursor of an ongoing paged voter snapshot off `who`, which is about to leave the
+	/// `VoterList`, so that the next page starts at the voter following `who` instead.
+	///
+	/// Must be called before `who` is removed from the `VoterList`.
+	fn advance_voter_snapshot_cursor(who: &T::AccountId) {
+		let is_cursor = match VoterSnapshotStatus::<T>::get() {
+			SnapshotStatus::Ongoing(cursor) | SnapshotStatus::Resuming(cursor) => cursor == *who,
+			SnapshotStatus::Consumed | SnapshotStatus::Waiting => false,
+		};
+		if !is_cursor {
+			return
+		}
+
+		let status = match T::VoterList::iter_from(who).ok().and_then(|mut rest| rest.next()) {
+			Some(next) => SnapshotStatus::Resuming(next),
+			None => SnapshotStatus::Consumed,
+		};
+		Self::set_voter_snapshot_status(status);
+	}
+
+	/// Whether a paged voter snapshot is in between pages.
+	fn is_voter_snapshot_ongoing() -> bool {
+		match VoterSnapshotStatus::<T>::get() {
+			SnapshotStatus::Ongoing(_) | SnapshotStatus::Resuming(_) => true,
+			SnapshotStatus::Consumed | SnapshotStatus::Waiting => false,
+		}
+	}
+
+	/// Set the status of the paged voter snapshot, locking the order of the `VoterList` while it
+	/// is ongoing and unlocking it otherwise.
+	fn set_voter_snapshot_status(status: SnapshotStatus<T::AccountId>) {
+		match status {
+			SnapshotStatus::Ongoing(_) | SnapshotStatus::Resuming(_) => T::VoterList::lock(),
+			SnapshotStatus::Consumed | SnapshotStatus::Waiting => T::VoterList::unlock(),
+		}
+		VoterSnapshotStatus::<T>::put(status);
+	}
+
+	/// Collect the voters that are eligible for the npos election out of `sorted_voters`.
+	///
+	/// `maybe_max_len` can imposes a cap on the number of voters returned. Returns the voters
+	/// along with the minimum active nominator stake among them.
+	fn collect_npos_voters(
+		maybe_max_len: Option<usize>,
+		mut sorted_voters: impl Iterator<Item = T::AccountId>,
+	) -> (Vec<VoterOf<Self>>, BalanceOf<T>) {
 		let max_allowed_len = {
 			let all_voter_count = T::VoterList::count() as usize;
 			maybe_max_len.unwrap_or(all_voter_count).min(all_voter_count)
@@ -778,7 +917,6 @@ impl<T: Config> Pallet<T> {
 		let mut nominators_taken = 0u32;
 		let mut min_active_stake = u64::MAX;
 
-		let mut sorted_voters = T::VoterList::iter();
 		while all_voters.len() < max_allowed_len &&
 			voters_seen < (NPOS_MAX_ITERATIONS_COEFFICIENT * max_allowed_len as u32)
 		{
@@ -833,8 +971,6 @@ impl<T: Config> Pallet<T> {
 		let min_active_stake: T::CurrencyBalance =
 			if all_voters.len() == 0 { 0u64.into() } else { min_active_stake.into() };
 
-		MinimumActiveStake::<T>::put(min_active_stake);
-
 		log!(
 			info,
 			"generated {} npos voters, {} from validators and {} nominators",
@@ -843,7 +979,7 @@ impl<T: Config> Pallet<T> {
 			nominators_taken
 		);
 
-		all_voters
+		(all_voters, min_active_stake)
 	}
 
 	/// Get the targets for an upcoming npos election.
@@ -910,6 +1046,7 @@ impl<T: Config> Pallet<T> {
 	pub fn do_remove_nominator(who: &T::AccountId) -> bool {
 		let outcome = if Nominators::<T>::contains_key(who) {
 			Nominators::<T>::remove(who);
+			Self::advance_voter_snapshot_cursor(who);
 			let _ = T::VoterList::on_remove(who).defensive();
 			true
 		} else {
@@ -955,6 +1092,7 @@ impl<T: Config> Pallet<T> {
 	pub fn do_remove_validator(who: &T::AccountId) -> bool {
 		let outcome = if Validators::<T>::contains_key(who) {
 			Validators::<T>::remove(who);
+			Self::advance_voter_snapshot_cursor(who);
 			let _ = T::VoterList::on_remove(who).defensive();
 			true
 		} else {
@@ -1012,6 +1150,17 @@ impl<T: Config> ElectionDataProvider for Pallet<T> {
 		Ok(voters)
 	}
 
+	fn electing_voters_paged(
+		maybe_max_len: Option<usize>,
+		page: PageIndex,
+		pages: PageIndex,
+	) -> data_provider::Result<Vec<VoterOf<Self>>> {
+		let voters = Self::get_npos_voters_paged(maybe_max_len, page, pages);
+		debug_assert!(maybe_max_len.map_or(true, |max| voters.len() <= max));
+
+		Ok(voters)
+	}
+
 	fn electable_targets(maybe_max_len: Option<usize>) -> data_provider::Result<Vec<T::AccountId>> {
 		let target_count = T::TargetList::count();
 
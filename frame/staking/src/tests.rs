@@ -4568,6 +4568,115 @@ mod election_data_provider {
 			});
 	}
 
+	#[test]
+	fn paged_voters_continue_where_previous_page_left_off() {
+		ExtBuilder::default()
+			.set_status(41, StakerStatus::Validator)
+			.build_and_execute(|| {
+				let all_voters = Staking::electing_voters(None).unwrap();
+				assert_eq!(all_voters.len(), 5);
+
+				// three pages of at most two voters each.
+				let mut paged = Staking::electing_voters_paged(Some(2), 2, 3).unwrap();
+				assert_eq!(paged.len(), 2);
+				assert!(matches!(VoterSnapshotStatus::<Test>::get(), SnapshotStatus::Ongoing(_)));
+
+				paged.extend(Staking::electing_voters_paged(Some(2), 1, 3).unwrap());
+				assert_eq!(paged.len(), 4);
+
+				paged.extend(Staking::electing_voters_paged(Some(2), 0, 3).unwrap());
+				assert_eq!(paged, all_voters);
+				assert_eq!(VoterSnapshotStatus::<Test>::get(), SnapshotStatus::Waiting);
+
+				// once all voters are returned, the remaining pages are empty.
+				assert_eq!(Staking::electing_voters_paged(Some(5), 2, 3).unwrap().len(), 5);
+				assert_eq!(VoterSnapshotStatus::<Test>::get(), SnapshotStatus::Consumed);
+				assert!(Staking::electing_voters_paged(Some(5), 1, 3).unwrap().is_empty());
+				assert!(Staking::electing_voters_paged(Some(5), 0, 3).unwrap().is_empty());
+				assert_eq!(VoterSnapshotStatus::<Test>::get(), SnapshotStatus::Waiting);
+			});
+	}
+
+	#[test]
+	fn paged_voters_survive_the_cursor_voter_leaving() {
+		ExtBuilder::default()
+			.set_status(41, StakerStatus::Validator)
+			.build_and_execute(|| {
+				let all_voters = Staking::electing_voters(None).unwrap();
+				assert_eq!(all_voters.len(), 5);
+
+				let mut paged = Staking::electing_voters_paged(Some(2), 2, 3).unwrap();
+				let cursor = match VoterSnapshotStatus::<Test>::get() {
+					SnapshotStatus::Ongoing(cursor) => cursor,
+					status => panic!("unexpected snapshot status {:?}", status),
+				};
+				assert_eq!(cursor, paged[1].0);
+				let next = all_voters[2].0;
+
+				// the last voter of the page leaves, the next page starts at its successor.
+				Staking::chill_stash(&cursor);
+				assert_eq!(VoterSnapshotStatus::<Test>::get(), SnapshotStatus::Resuming(next));
+
+				// and so does the successor, before being returned.
+				let dropped = next;
+				Staking::chill_stash(&dropped);
+				let next = all_voters[3].0;
+				assert_eq!(VoterSnapshotStatus::<Test>::get(), SnapshotStatus::Resuming(next));
+
+				paged.extend(Staking::electing_voters_paged(Some(2), 1, 3).unwrap());
+				paged.extend(Staking::electing_voters_paged(Some(2), 0, 3).unwrap());
+				assert_eq!(VoterSnapshotStatus::<Test>::get(), SnapshotStatus::Waiting);
+
+				// only the voter that left before being returned is missing.
+				assert_eq!(paged.len(), 4);
+				assert_eq!(
+					paged,
+					all_voters
+						.into_iter()
+						.filter(|(who, _, _)| *who != dropped)
+						.collect::<Vec<_>>()
+				);
+			});
+	}
+
+	#[test]
+	fn paged_voters_lock_the_voter_list_order() {
+		ExtBuilder::default()
+			.set_status(41, StakerStatus::Validator)
+			.build_and_execute(|| {
+				let all_voters = Staking::electing_voters(None).unwrap();
+				assert_eq!(all_voters.len(), 5);
+				// all of the voters are in the same bag, 101 being the last one.
+				assert_eq!(all_voters[4].0, 101);
+
+				let mut paged = Staking::electing_voters_paged(Some(2), 2, 3).unwrap();
+
+				// 101 bonds enough to belong to a bag in front of the voters already returned.
+				assert_ok!(Staking::bond_extra(RuntimeOrigin::signed(101), 1_000));
+				assert_eq!(<Test as Config>::VoterList::iter().last(), Some(101));
+				assert_noop!(
+					VoterBagsList::rebag(RuntimeOrigin::signed(0), 101),
+					pallet_bags_list::Error::<Test, pallet_bags_list::Instance1>::List(
+						pallet_bags_list::ListError::Locked
+					)
+				);
+
+				paged.extend(Staking::electing_voters_paged(Some(2), 1, 3).unwrap());
+				paged.extend(Staking::electing_voters_paged(Some(2), 0, 3).unwrap());
+				assert_eq!(VoterSnapshotStatus::<Test>::get(), SnapshotStatus::Waiting);
+
+				// every voter is returned exactly once.
+				assert_eq!(
+					paged.into_iter().map(|(who, _, _)| who).collect::<Vec<_>>(),
+					all_voters.into_iter().map(|(who, _, _)| who).collect::<Vec<_>>()
+				);
+
+				// once the snapshot is done, 101 can be moved to its bag.
+				assert_ok!(VoterBagsList::rebag(RuntimeOrigin::signed(0), 101));
+				assert_eq!(<Test as Config>::VoterList::iter().next(), Some(101));
+			});
+	}
+
 	// Tests the criteria that in `ElectionDataProvider::voters` function, we try to get at most
 	// `maybe_max_len` voters, and if some of them end up being skipped, we iterate at most `2 *
 	// maybe_max_len`.
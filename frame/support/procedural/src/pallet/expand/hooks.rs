@@ -20,14 +20,15 @@ use crate::pallet::Def;
 
 /// * implement the individual traits using the Hooks trait
 pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
-	let (where_clause, span, has_runtime_upgrade) = match def.hooks.as_ref() {
+	let (where_clause, span, has_runtime_upgrade, has_offchain_worker) = match def.hooks.as_ref() {
 		Some(hooks) => {
 			let where_clause = hooks.where_clause.clone();
 			let span = hooks.attr_span;
 			let has_runtime_upgrade = hooks.has_runtime_upgrade;
-			(where_clause, span, has_runtime_upgrade)
+			let has_offchain_worker = hooks.has_offchain_worker;
+			(where_clause, span, has_runtime_upgrade, has_offchain_worker)
 		},
-		None => (def.config.where_clause.clone(), def.pallet_struct.attr_span, false),
+		None => (def.config.where_clause.clone(), def.pallet_struct.attr_span, false, false),
 	};
 
 	let frame_support = &def.frame_support;
@@ -59,6 +60,28 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 		}
 	};
 
+	let offchain_worker_schedules = if has_offchain_worker {
+		quote::quote! {
+			let pallet_name = <
+				<T as #frame_system::Config>::PalletInfo
+				as
+				#frame_support::traits::PalletInfo
+			>::name::<Self>().unwrap_or("<unknown pallet name>");
+
+			#frame_support::sp_std::vec![(
+				pallet_name,
+				<
+					Self as #frame_support::traits::Hooks<
+						#frame_system::pallet_prelude::BlockNumberFor::<T>
+					>
+				>::offchain_worker_schedule(),
+			)]
+		}
+	} else {
+		// Nothing to schedule, so the node doesn't call into the runtime for this pallet.
+		quote::quote! { #frame_support::sp_std::vec::Vec::new() }
+	};
+
 	let log_try_state = quote::quote! {
 		let pallet_name = <
 			<T as #frame_system::Config>::PalletInfo
@@ -243,6 +266,35 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 					>
 				>::offchain_worker(n)
 			}
+
+			fn offchain_worker_schedules() -> #frame_support::sp_std::vec::Vec<(
+				&'static str,
+				#frame_support::sp_runtime::offchain::schedule::OffchainWorkerSchedule,
+			)> {
+				#offchain_worker_schedules
+			}
+
+			fn offchain_worker_of(
+				pallet: &str,
+				n: #frame_system::pallet_prelude::BlockNumberFor::<T>,
+			) -> bool {
+				let pallet_name = <
+					<T as #frame_system::Config>::PalletInfo
+					as
+					#frame_support::traits::PalletInfo
+				>::name::<Self>();
+
+				if pallet_name != Some(pallet) {
+					return false
+				}
+
+				<
+					Self as #frame_support::traits::Hooks<
+						#frame_system::pallet_prelude::BlockNumberFor::<T>
+					>
+				>::offchain_worker(n);
+				true
+			}
 		}
 
 		// Integrity tests are only required for when `std` is enabled.
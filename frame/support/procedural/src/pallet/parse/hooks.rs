@@ -31,6 +31,8 @@ pub struct HooksDef {
 	pub attr_span: proc_macro2::Span,
 	/// Boolean flag, set to true if the `on_runtime_upgrade` method of hooks was implemented.
 	pub has_runtime_upgrade: bool,
+	/// Boolean flag, set to true if the `offchain_worker` method of hooks was implemented.
+	pub has_offchain_worker: bool,
 }
 
 impl HooksDef {
@@ -71,16 +73,21 @@ impl HooksDef {
 			return Err(syn::Error::new(item_trait.span(), msg))
 		}
 
-		let has_runtime_upgrade = item.items.iter().any(|i| match i {
-			syn::ImplItem::Fn(method) => method.sig.ident == "on_runtime_upgrade",
-			_ => false,
-		});
+		let has_method = |name: &str| {
+			item.items.iter().any(|i| match i {
+				syn::ImplItem::Fn(method) => method.sig.ident == name,
+				_ => false,
+			})
+		};
+		let has_runtime_upgrade = has_method("on_runtime_upgrade");
+		let has_offchain_worker = has_method("offchain_worker");
 
 		Ok(Self {
 			attr_span,
 			index,
 			instances,
 			has_runtime_upgrade,
+			has_offchain_worker,
 			where_clause: item.generics.where_clause.clone(),
 		})
 	}
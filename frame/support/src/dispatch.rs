@@ -2078,6 +2078,30 @@ macro_rules! decl_module {
 			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
 		{
 			fn offchain_worker(_block_number_not_used: <$trait_instance as $system::Config>::BlockNumber) { $( $impl )* }
+
+			fn offchain_worker_schedules() -> $crate::sp_std::vec::Vec<(
+				&'static str,
+				$crate::sp_runtime::offchain::schedule::OffchainWorkerSchedule,
+			)> {
+				let pallet_name = <<$trait_instance as $system::Config>::PalletInfo
+					as $crate::traits::PalletInfo>::name::<Self>().unwrap_or("<unknown pallet name>");
+				$crate::sp_std::vec![(pallet_name, Default::default())]
+			}
+
+			fn offchain_worker_of(
+				pallet: &str,
+				n: <$trait_instance as $system::Config>::BlockNumber,
+			) -> bool {
+				let pallet_name = <<$trait_instance as $system::Config>::PalletInfo
+					as $crate::traits::PalletInfo>::name::<Self>();
+				if pallet_name != Some(pallet) {
+					return false
+				}
+				<Self as $crate::traits::OffchainWorker<
+					<$trait_instance as $system::Config>::BlockNumber
+				>>::offchain_worker(n);
+				true
+			}
 		}
 	};
 
@@ -2092,6 +2116,30 @@ macro_rules! decl_module {
 			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
 		{
 			fn offchain_worker($param: $param_ty) { $( $impl )* }
+
+			fn offchain_worker_schedules() -> $crate::sp_std::vec::Vec<(
+				&'static str,
+				$crate::sp_runtime::offchain::schedule::OffchainWorkerSchedule,
+			)> {
+				let pallet_name = <<$trait_instance as $system::Config>::PalletInfo
+					as $crate::traits::PalletInfo>::name::<Self>().unwrap_or("<unknown pallet name>");
+				$crate::sp_std::vec![(pallet_name, Default::default())]
+			}
+
+			fn offchain_worker_of(
+				pallet: &str,
+				n: <$trait_instance as $system::Config>::BlockNumber,
+			) -> bool {
+				let pallet_name = <<$trait_instance as $system::Config>::PalletInfo
+					as $crate::traits::PalletInfo>::name::<Self>();
+				if pallet_name != Some(pallet) {
+					return false
+				}
+				<Self as $crate::traits::OffchainWorker<
+					<$trait_instance as $system::Config>::BlockNumber
+				>>::offchain_worker(n);
+				true
+			}
 		}
 	};
 
@@ -20,7 +20,7 @@
 
 use crate::weights::Weight;
 use impl_trait_for_tuples::impl_for_tuples;
-use sp_runtime::traits::AtLeast32BitUnsigned;
+use sp_runtime::{offchain::schedule::OffchainWorkerSchedule, traits::AtLeast32BitUnsigned};
 use sp_std::prelude::*;
 
 #[cfg(feature = "try-runtime")]
@@ -354,6 +354,15 @@ pub trait Hooks<BlockNumber> {
 	/// Any state alterations are lost and are not persisted.
 	fn offchain_worker(_n: BlockNumber) {}
 
+	/// When and for how long [`Hooks::offchain_worker`] runs.
+	///
+	/// By default it runs on every new best block. Only pallets implementing
+	/// [`Hooks::offchain_worker`] are scheduled, and nodes which don't support offchain worker
+	/// scheduling ignore the schedule.
+	fn offchain_worker_schedule() -> OffchainWorkerSchedule {
+		OffchainWorkerSchedule::default()
+	}
+
 	/// Run integrity test.
 	///
 	/// The test is not executed in a externalities provided environment.
@@ -29,7 +29,9 @@ pub use sp_runtime::traits::{
 	ConstBool, ConstI128, ConstI16, ConstI32, ConstI64, ConstI8, ConstU128, ConstU16, ConstU32,
 	ConstU64, ConstU8, Get, GetDefault, TryCollect, TypedGet,
 };
-use sp_runtime::{traits::Block as BlockT, DispatchError};
+use sp_runtime::{
+	offchain::schedule::OffchainWorkerSchedule, traits::Block as BlockT, DispatchError,
+};
 use sp_std::{cmp::Ordering, prelude::*};
 
 #[doc(hidden)]
@@ -846,9 +848,6 @@ impl<Origin: PartialEq> PrivilegeCmp<Origin> for EqualPrivilegeOnly {
 /// but cannot preform any alterations. More specifically alterations are
 /// not forbidden, but they are not persisted in any way after the worker
 /// has finished.
-#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
-#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
-#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
 pub trait OffchainWorker<BlockNumber> {
 	/// This function is being called after every block import (when fully synced).
 	///
@@ -857,6 +856,38 @@ pub trait OffchainWorker<BlockNumber> {
 	/// with results to trigger any on-chain changes.
 	/// Any state alterations are lost and are not persisted.
 	fn offchain_worker(_n: BlockNumber) {}
+
+	/// The schedules of the offchain workers, along with the names of their pallets.
+	fn offchain_worker_schedules() -> Vec<(&'static str, OffchainWorkerSchedule)> {
+		Vec::new()
+	}
+
+	/// Run only the offchain worker of the pallet called `pallet`.
+	///
+	/// Returns `false` if no such pallet is part of `Self`.
+	fn offchain_worker_of(_pallet: &str, _n: BlockNumber) -> bool {
+		false
+	}
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+impl<BlockNumber: Clone> OffchainWorker<BlockNumber> for Tuple {
+	fn offchain_worker(n: BlockNumber) {
+		for_tuples!( #( Tuple::offchain_worker(n.clone()); )* );
+	}
+
+	fn offchain_worker_schedules() -> Vec<(&'static str, OffchainWorkerSchedule)> {
+		let mut schedules = Vec::new();
+		for_tuples!( #( schedules.extend(Tuple::offchain_worker_schedules()); )* );
+		schedules
+	}
+
+	fn offchain_worker_of(pallet: &str, n: BlockNumber) -> bool {
+		for_tuples!( #( if Tuple::offchain_worker_of(pallet, n.clone()) { return true } )* );
+		false
+	}
 }
 
 /// Some amount of backing from a group. The precise definition of what it means to "back" something
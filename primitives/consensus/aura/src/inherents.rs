@@ -67,6 +67,17 @@ impl InherentDataProvider {
 
 		Self { slot }
 	}
+
+	/// Creates the inherent data provider by calculating the slot from the given
+	/// `timestamp` and `schedule`.
+	pub fn from_timestamp_and_slot_schedule(
+		timestamp: sp_timestamp::Timestamp,
+		schedule: sp_consensus_slots::SlotSchedule,
+	) -> Self {
+		let slot = schedule.slot_at(timestamp);
+
+		Self { slot }
+	}
 }
 
 #[cfg(feature = "std")]
@@ -63,7 +63,7 @@ pub mod ed25519 {
 	pub type AuthorityId = app_ed25519::Public;
 }
 
-pub use sp_consensus_slots::{Slot, SlotDuration};
+pub use sp_consensus_slots::{Slot, SlotDuration, SlotSchedule};
 
 /// The `ConsensusEngineId` of AuRa.
 pub const AURA_ENGINE_ID: ConsensusEngineId = [b'a', b'u', b'r', b'a'];
@@ -86,11 +86,15 @@ sp_api::decl_runtime_apis! {
 	/// API necessary for block authorship with aura.
 	pub trait AuraApi<AuthorityId: Codec> {
 		/// Returns the slot duration for Aura.
-		///
-		/// Currently, only the value provided by this type at genesis will be used.
 		fn slot_duration() -> SlotDuration;
 
 		/// Return the current set of authorities.
 		fn authorities() -> Vec<AuthorityId>;
+
+		/// Returns the slot schedule for blocks built on top of this block.
+		///
+		/// Runtimes without this function count slots of `slot_duration` since the unix epoch.
+		#[api_version(2)]
+		fn slot_schedule() -> SlotSchedule;
 	}
 }
@@ -0,0 +1,448 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compact GRANDPA justifications, e.g. for transfer to light clients.
+
+use crate::{AuthorityId, AuthorityList, AuthoritySignature, GrandpaJustification, SetId};
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Header as HeaderT, RuntimeDebug};
+use sp_std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	vec::Vec,
+};
+
+/// A signed precommit of a [`CompactGrandpaJustification`].
+#[derive(Clone, Encode, Decode, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CompactSignedPrecommit<Header: HeaderT> {
+	/// The block voted on, omitted if it's the commit target.
+	pub target: Option<(Header::Hash, Header::Number)>,
+	/// The signature on the precommit.
+	pub signature: AuthoritySignature,
+	/// The authority which signed the precommit.
+	pub id: AuthorityId,
+}
+
+/// A [`GrandpaJustification`] compacted for transfer to light clients.
+///
+/// Only the precommits needed to reach the supermajority threshold are kept, preferring the ones
+/// for the commit target itself whose targets are then omitted, and the votes ancestries only
+/// contain the headers routing the remaining precommits to the commit target.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CompactGrandpaJustification<Header: HeaderT> {
+	/// The round the commit was made in.
+	pub round: u64,
+	/// The hash of the finalized block.
+	pub target_hash: Header::Hash,
+	/// The number of the finalized block.
+	pub target_number: Header::Number,
+	/// The precommits finalizing the target.
+	pub precommits: Vec<CompactSignedPrecommit<Header>>,
+	/// The headers routing the precommits which aren't for the commit target to it.
+	pub votes_ancestries: Vec<Header>,
+}
+
+/// Errors verifying a [`CompactGrandpaJustification`].
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum JustificationError {
+	/// A precommit is signed by an authority which isn't part of the set.
+	UnknownAuthority,
+	/// An authority has multiple precommits in the justification.
+	DuplicateAuthority,
+	/// A precommit has an invalid signature.
+	InvalidSignature,
+	/// A precommit target can't be routed to the commit target using the votes ancestries.
+	InvalidAncestry,
+	/// The votes ancestries contain headers which aren't needed.
+	UnusedAncestry,
+	/// The precommits don't reach the supermajority threshold.
+	InsufficientWeight,
+}
+
+impl<Header: HeaderT> CompactGrandpaJustification<Header> {
+	/// Compact the given justification, which is assumed to be valid.
+	///
+	/// Duplicate precommits and the ones which aren't for a descendant of the commit target are
+	/// dropped. If the `authorities` which made the justification are given, the precommits in
+	/// excess of the supermajority threshold are dropped as well.
+	pub fn new(
+		justification: GrandpaJustification<Header>,
+		authorities: Option<&AuthorityList>,
+	) -> Self {
+		let GrandpaJustification { round, commit, votes_ancestries } = justification;
+		let target = (commit.target_hash, commit.target_number);
+
+		let weights = authorities.map(weights);
+		let threshold = weights.as_ref().map(threshold);
+
+		// precommits for the commit target don't need any ancestry, so use them first
+		let mut precommits = commit.precommits;
+		precommits.sort_by_key(|signed| signed.precommit.target_hash != target.0);
+
+		let mut voters = BTreeSet::new();
+		let mut visited = BTreeSet::new();
+		let mut weight = 0u64;
+		let mut compact_precommits = Vec::new();
+		let ancestries = ancestries(&votes_ancestries);
+		for signed in precommits {
+			if threshold.map_or(false, |threshold| weight >= threshold) {
+				break
+			}
+
+			if voters.contains(&signed.id) {
+				continue
+			}
+
+			let voter_weight = match &weights {
+				Some(weights) => match weights.get(&signed.id) {
+					Some(weight) => *weight,
+					None => continue,
+				},
+				None => 0,
+			};
+
+			let precommit_target = (signed.precommit.target_hash, signed.precommit.target_number);
+			if precommit_target != target &&
+				!route::<Header>(&ancestries, target, precommit_target.0, &mut visited)
+			{
+				continue
+			}
+
+			voters.insert(signed.id.clone());
+			weight = weight.saturating_add(voter_weight);
+			compact_precommits.push(CompactSignedPrecommit {
+				target: (precommit_target != target).then_some(precommit_target),
+				signature: signed.signature,
+				id: signed.id,
+			});
+		}
+		drop(ancestries);
+
+		let votes_ancestries = votes_ancestries
+			.into_iter()
+			.filter(|header| visited.contains(&header.hash()))
+			.collect();
+
+		CompactGrandpaJustification {
+			round,
+			target_hash: target.0,
+			target_number: target.1,
+			precommits: compact_precommits,
+			votes_ancestries,
+		}
+	}
+
+	/// Verify the justification finalizes its target, given the set it was made by.
+	pub fn verify(
+		&self,
+		set_id: SetId,
+		authorities: &AuthorityList,
+	) -> Result<(), JustificationError> {
+		let weights = weights(authorities);
+		let ancestries = ancestries(&self.votes_ancestries);
+		let target = (self.target_hash, self.target_number);
+
+		let mut voters = BTreeSet::new();
+		let mut visited = BTreeSet::new();
+		let mut weight = 0u64;
+		let mut buf = Vec::new();
+		for signed in &self.precommits {
+			let voter_weight =
+				*weights.get(&signed.id).ok_or(JustificationError::UnknownAuthority)?;
+
+			if !voters.insert(&signed.id) {
+				return Err(JustificationError::DuplicateAuthority)
+			}
+
+			let (target_hash, target_number) = signed.target.unwrap_or(target);
+			if !crate::check_message_signature_with_buffer(
+				&grandpa::Message::Precommit(grandpa::Precommit { target_hash, target_number }),
+				&signed.id,
+				&signed.signature,
+				self.round,
+				set_id,
+				&mut buf,
+			) {
+				return Err(JustificationError::InvalidSignature)
+			}
+
+			if !route::<Header>(&ancestries, target, target_hash, &mut visited) {
+				return Err(JustificationError::InvalidAncestry)
+			}
+
+			weight = weight.saturating_add(voter_weight);
+		}
+
+		// every header must be used, and only once
+		if visited.len() != self.votes_ancestries.len() {
+			return Err(JustificationError::UnusedAncestry)
+		}
+
+		if weight < threshold(&weights) {
+			return Err(JustificationError::InsufficientWeight)
+		}
+
+		Ok(())
+	}
+}
+
+fn weights(authorities: &AuthorityList) -> BTreeMap<AuthorityId, u64> {
+	authorities.iter().cloned().collect()
+}
+
+// the weight required for a supermajority, as computed by `finality_grandpa`
+fn threshold(weights: &BTreeMap<AuthorityId, u64>) -> u64 {
+	let total = weights.values().fold(0u64, |total, weight| total.saturating_add(*weight));
+	total - total.saturating_sub(1) / 3
+}
+
+fn ancestries<Header: HeaderT>(headers: &[Header]) -> BTreeMap<Header::Hash, &Header> {
+	headers.iter().map(|header| (header.hash(), header)).collect()
+}
+
+// walk back from `block` to the commit `target` through the `ancestries`, marking the headers
+// used as visited. returns false if there's no such route.
+fn route<Header: HeaderT>(
+	ancestries: &BTreeMap<Header::Hash, &Header>,
+	target: (Header::Hash, Header::Number),
+	block: Header::Hash,
+	visited: &mut BTreeSet<Header::Hash>,
+) -> bool {
+	let mut route = Vec::new();
+	let mut current_hash = block;
+	while current_hash != target.0 {
+		match ancestries.get(&current_hash) {
+			Some(header) if *header.number() > target.1 => {
+				route.push(current_hash);
+				current_hash = *header.parent_hash();
+			},
+			_ => return false,
+		}
+	}
+
+	visited.extend(route);
+	true
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{AuthorityPair, Commit};
+	use sp_application_crypto::Pair;
+	use sp_core::H256;
+	use sp_runtime::testing::Header;
+
+	const ROUND: u64 = 1;
+	const SET_ID: SetId = 0;
+
+	fn header(number: u64, parent_hash: H256, fork: u8) -> Header {
+		Header::new(
+			number,
+			Default::default(),
+			H256::repeat_byte(fork),
+			parent_hash,
+			Default::default(),
+		)
+	}
+
+	fn precommit(
+		pair: &AuthorityPair,
+		target: &Header,
+	) -> grandpa::SignedPrecommit<H256, u64, AuthoritySignature, AuthorityId> {
+		let precommit =
+			grandpa::Precommit { target_hash: target.hash(), target_number: *target.number() };
+		let payload = crate::localized_payload(
+			ROUND,
+			SET_ID,
+			&grandpa::Message::Precommit(precommit.clone()),
+		);
+		grandpa::SignedPrecommit { precommit, signature: pair.sign(&payload), id: pair.public() }
+	}
+
+	struct Fixture {
+		pairs: Vec<AuthorityPair>,
+		authorities: AuthorityList,
+		target: Header,
+		// the descendants of the target on the best chain
+		best: Vec<Header>,
+		// a sibling of the target
+		sibling: Header,
+	}
+
+	impl Fixture {
+		fn new() -> Self {
+			let pairs = (1..=4).map(|i| AuthorityPair::from_seed(&[i; 32])).collect::<Vec<_>>();
+			let authorities = pairs.iter().map(|pair| (pair.public(), 1)).collect();
+			let genesis = header(0, Default::default(), 0);
+			let target = header(1, genesis.hash(), 0);
+			let block2 = header(2, target.hash(), 0);
+			let block3 = header(3, block2.hash(), 0);
+			let sibling = header(1, genesis.hash(), 1);
+			Fixture { pairs, authorities, target, best: vec![block2, block3], sibling }
+		}
+
+		// a justification with two precommits on the target, one on its grandchild and one on
+		// its sibling
+		fn justification(&self) -> GrandpaJustification<Header> {
+			let precommits = vec![
+				precommit(&self.pairs[0], &self.best[1]),
+				precommit(&self.pairs[1], &self.target),
+				precommit(&self.pairs[2], &self.sibling),
+				precommit(&self.pairs[3], &self.target),
+			];
+			GrandpaJustification {
+				round: ROUND,
+				commit: Commit::<Header> {
+					target_hash: self.target.hash(),
+					target_number: *self.target.number(),
+					precommits,
+				},
+				votes_ancestries: vec![
+					self.best[1].clone(),
+					self.sibling.clone(),
+					self.best[0].clone(),
+				],
+			}
+		}
+	}
+
+	#[test]
+	fn compact_justification_keeps_the_precommits_needed() {
+		let fixture = Fixture::new();
+
+		// without the authorities, only the precommit on the sibling is dropped, with its header
+		let compact = CompactGrandpaJustification::new(fixture.justification(), None);
+		assert_eq!(
+			compact.precommits.iter().map(|signed| signed.target).collect::<Vec<_>>(),
+			vec![None, None, Some((fixture.best[1].hash(), 3))],
+		);
+		assert_eq!(
+			compact.votes_ancestries,
+			vec![fixture.best[1].clone(), fixture.best[0].clone()]
+		);
+		assert_eq!(compact.verify(SET_ID, &fixture.authorities), Ok(()));
+
+		// with the authorities, the precommits on the target reach the threshold of 3 with the
+		// one on its grandchild
+		let compact =
+			CompactGrandpaJustification::new(fixture.justification(), Some(&fixture.authorities));
+		assert_eq!(compact.precommits.len(), 3);
+		assert_eq!(compact.verify(SET_ID, &fixture.authorities), Ok(()));
+
+		// the precommits on the target are enough when they reach the threshold on their own
+		let authorities = fixture
+			.authorities
+			.iter()
+			.cloned()
+			.map(|(id, _)| {
+				let weight = if id == fixture.pairs[1].public() || id == fixture.pairs[3].public() {
+					3
+				} else {
+					1
+				};
+				(id, weight)
+			})
+			.collect::<AuthorityList>();
+		let compact = CompactGrandpaJustification::new(fixture.justification(), Some(&authorities));
+		assert_eq!(
+			compact.precommits.iter().map(|signed| signed.target).collect::<Vec<_>>(),
+			vec![None, None],
+		);
+		assert!(compact.votes_ancestries.is_empty());
+		assert_eq!(compact.verify(SET_ID, &authorities), Ok(()));
+	}
+
+	#[test]
+	fn compact_justification_verification_fails_on_invalid_justifications() {
+		let fixture = Fixture::new();
+		let compact =
+			CompactGrandpaJustification::new(fixture.justification(), Some(&fixture.authorities));
+		let verify = |justification: &CompactGrandpaJustification<Header>| {
+			justification.verify(SET_ID, &fixture.authorities)
+		};
+
+		let mut invalid = compact.clone();
+		invalid.votes_ancestries.retain(|header| *header.number() != 2);
+		assert_eq!(verify(&invalid), Err(JustificationError::InvalidAncestry));
+
+		let mut invalid = compact.clone();
+		invalid.votes_ancestries.push(fixture.sibling.clone());
+		assert_eq!(verify(&invalid), Err(JustificationError::UnusedAncestry));
+
+		let mut invalid = compact.clone();
+		invalid.votes_ancestries.push(fixture.best[0].clone());
+		assert_eq!(verify(&invalid), Err(JustificationError::UnusedAncestry));
+
+		let mut invalid = compact.clone();
+		invalid.precommits.pop();
+		invalid.votes_ancestries.clear();
+		assert_eq!(verify(&invalid), Err(JustificationError::InsufficientWeight));
+
+		let mut invalid = compact.clone();
+		invalid.precommits[1] = invalid.precommits[0].clone();
+		assert_eq!(verify(&invalid), Err(JustificationError::DuplicateAuthority));
+
+		let mut invalid = compact.clone();
+		invalid.precommits[0].id = AuthorityPair::from_seed(&[5; 32]).public();
+		assert_eq!(verify(&invalid), Err(JustificationError::UnknownAuthority));
+
+		let mut invalid = compact.clone();
+		invalid.round += 1;
+		assert_eq!(verify(&invalid), Err(JustificationError::InvalidSignature));
+
+		assert_eq!(
+			compact.verify(SET_ID + 1, &fixture.authorities),
+			Err(JustificationError::InvalidSignature)
+		);
+	}
+
+	#[test]
+	fn route_walks_the_ancestries_back_to_the_target() {
+		let fixture = Fixture::new();
+		let target = (fixture.target.hash(), *fixture.target.number());
+
+		// the ancestry of the target's grandchild is pruned down to its parent
+		let pruned = vec![fixture.best[1].clone()];
+		let mut visited = BTreeSet::new();
+		assert!(!route::<Header>(
+			&ancestries(&pruned),
+			target,
+			fixture.best[1].hash(),
+			&mut visited
+		));
+		assert!(visited.is_empty());
+
+		let headers =
+			vec![fixture.best[1].clone(), fixture.best[0].clone(), fixture.sibling.clone()];
+		let all = ancestries(&headers);
+		assert!(route::<Header>(&all, target, fixture.best[1].hash(), &mut visited));
+		assert_eq!(visited, fixture.best.iter().map(|header| header.hash()).collect());
+
+		// the target routes to itself without using any header
+		let mut visited = BTreeSet::new();
+		assert!(route::<Header>(&all, target, target.0, &mut visited));
+		assert!(visited.is_empty());
+
+		// blocks which aren't descendants of the target don't route to it
+		assert!(!route::<Header>(&all, target, fixture.sibling.hash(), &mut visited));
+		assert!(!route::<Header>(&all, target, H256::repeat_byte(2), &mut visited));
+		assert!(visited.is_empty());
+	}
+}
@@ -36,6 +36,10 @@ use sp_runtime::{
 };
 use sp_std::{borrow::Cow, vec::Vec};
 
+mod justification;
+
+pub use justification::{CompactGrandpaJustification, CompactSignedPrecommit, JustificationError};
+
 /// The log target to be used by client code.
 pub const CLIENT_LOG_TARGET: &str = "grandpa";
 /// The log target to be used by runtime code.
@@ -102,7 +102,20 @@ impl From<Slot> for u64 {
 }
 
 /// A slot duration defined in milliseconds.
-#[derive(Clone, Copy, Debug, Encode, Decode, Hash, PartialOrd, Ord, PartialEq, Eq, TypeInfo)]
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Encode,
+	Decode,
+	Hash,
+	PartialOrd,
+	Ord,
+	PartialEq,
+	Eq,
+	TypeInfo,
+	MaxEncodedLen,
+)]
 pub struct SlotDuration(u64);
 
 impl SlotDuration {
@@ -127,6 +140,56 @@ impl SlotDuration {
 	}
 }
 
+/// Maps timestamps to slots for a slot duration which became active at a given slot.
+///
+/// Anchoring the slot duration at its activation keeps slots increasing when the slot duration
+/// changes, instead of renumbering all slots since the unix epoch.
+#[derive(Clone, Copy, Debug, Encode, Decode, MaxEncodedLen, PartialEq, Eq, TypeInfo)]
+pub struct SlotSchedule {
+	/// The slot duration.
+	pub slot_duration: SlotDuration,
+	/// The first slot using `slot_duration`.
+	pub activation_slot: Slot,
+	/// The timestamp in milliseconds at which `activation_slot` starts.
+	pub activation_timestamp: u64,
+}
+
+impl SlotSchedule {
+	/// The schedule of a chain which always used `slot_duration`, counting slots since the unix
+	/// epoch.
+	pub const fn from_slot_duration(slot_duration: SlotDuration) -> Self {
+		Self { slot_duration, activation_slot: Slot(0), activation_timestamp: 0 }
+	}
+
+	/// Returns the slot containing `timestamp`.
+	///
+	/// Timestamps before the activation map to the activation slot.
+	pub fn slot_at(&self, timestamp: Timestamp) -> Slot {
+		let elapsed = timestamp.as_millis().saturating_sub(self.activation_timestamp);
+		self.activation_slot.saturating_add(elapsed / self.slot_duration.as_millis())
+	}
+
+	/// Returns the timestamp at which `slot` starts.
+	///
+	/// Slots before the activation map to the activation timestamp.
+	pub fn slot_start(&self, slot: Slot) -> Timestamp {
+		let elapsed = slot
+			.0
+			.saturating_sub(self.activation_slot.0)
+			.saturating_mul(self.slot_duration.0);
+		Timestamp::new(self.activation_timestamp.saturating_add(elapsed))
+	}
+
+	/// Returns the schedule continuing `self` with `slot_duration` from `activation_slot` on.
+	pub fn change_slot_duration(&self, slot_duration: SlotDuration, activation_slot: Slot) -> Self {
+		Self {
+			slot_duration,
+			activation_slot,
+			activation_timestamp: self.slot_start(activation_slot).as_millis(),
+		}
+	}
+}
+
 /// Represents an equivocation proof. An equivocation happens when a validator
 /// produces more than one block on the same slot. The proof of equivocation
 /// are the given distinct headers that were signed by the validator and which
@@ -142,3 +205,86 @@ pub struct EquivocationProof<Header, Id> {
 	/// The second header involved in the equivocation.
 	pub second_header: Header,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SLOT_DURATION: SlotDuration = SlotDuration::from_millis(6000);
+
+	#[test]
+	fn schedule_from_slot_duration_counts_slots_since_unix_epoch() {
+		let schedule = SlotSchedule::from_slot_duration(SLOT_DURATION);
+
+		assert_eq!(schedule.slot_at(0.into()), Slot::from(0));
+		assert_eq!(schedule.slot_at(5999.into()), Slot::from(0));
+		assert_eq!(schedule.slot_at(6000.into()), Slot::from(1));
+		assert_eq!(schedule.slot_at(60_001.into()), Slot::from(10));
+
+		assert_eq!(schedule.slot_start(10.into()), Timestamp::new(60_000));
+	}
+
+	#[test]
+	fn slots_are_counted_from_the_activation() {
+		let schedule = SlotSchedule {
+			slot_duration: SLOT_DURATION,
+			activation_slot: 100.into(),
+			activation_timestamp: 1_000_000,
+		};
+
+		// the first and last millisecond of the activation slot.
+		assert_eq!(schedule.slot_at(1_000_000.into()), Slot::from(100));
+		assert_eq!(schedule.slot_at(1_005_999.into()), Slot::from(100));
+		assert_eq!(schedule.slot_at(1_006_000.into()), Slot::from(101));
+
+		assert_eq!(schedule.slot_start(100.into()), Timestamp::new(1_000_000));
+		assert_eq!(schedule.slot_start(101.into()), Timestamp::new(1_006_000));
+
+		// everything before the activation is clamped to it.
+		assert_eq!(schedule.slot_at(0.into()), Slot::from(100));
+		assert_eq!(schedule.slot_start(0.into()), Timestamp::new(1_000_000));
+
+		for slot in 100..110 {
+			assert_eq!(schedule.slot_at(schedule.slot_start(slot.into())), Slot::from(slot));
+		}
+	}
+
+	#[test]
+	fn slot_schedule_saturates() {
+		let schedule = SlotSchedule {
+			slot_duration: SlotDuration::from_millis(1),
+			activation_slot: (u64::MAX - 1).into(),
+			activation_timestamp: u64::MAX - 1,
+		};
+
+		assert_eq!(schedule.slot_at(u64::MAX.into()), Slot::from(u64::MAX));
+		assert_eq!(schedule.slot_start(u64::MAX.into()), Timestamp::new(u64::MAX));
+
+		let schedule = SlotSchedule::from_slot_duration(SLOT_DURATION);
+		assert_eq!(schedule.slot_start(u64::MAX.into()), Timestamp::new(u64::MAX));
+	}
+
+	#[test]
+	fn changing_the_slot_duration_keeps_slots_increasing() {
+		let old = SlotSchedule::from_slot_duration(SLOT_DURATION);
+		let new = old.change_slot_duration(SlotDuration::from_millis(2000), 10.into());
+
+		assert_eq!(new.activation_slot, Slot::from(10));
+		assert_eq!(new.activation_timestamp, 60_000);
+		assert_eq!(new.slot_start(10.into()), old.slot_start(10.into()));
+
+		// slots before the activation keep their old duration.
+		assert_eq!(old.slot_at(59_999.into()), Slot::from(9));
+		// and the following ones use the new one, without renumbering.
+		assert_eq!(new.slot_at(60_000.into()), Slot::from(10));
+		assert_eq!(new.slot_at(61_999.into()), Slot::from(10));
+		assert_eq!(new.slot_at(62_000.into()), Slot::from(11));
+		assert_eq!(new.slot_start(13.into()), Timestamp::new(66_000));
+
+		// changing the duration again continues from the second schedule.
+		let newer = new.change_slot_duration(SLOT_DURATION, 13.into());
+		assert_eq!(newer.activation_timestamp, 66_000);
+		assert_eq!(newer.slot_at(71_999.into()), Slot::from(13));
+		assert_eq!(newer.slot_at(72_000.into()), Slot::from(14));
+	}
+}
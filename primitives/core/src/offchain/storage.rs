@@ -90,6 +90,32 @@ impl OffchainStorage for InMemOffchainStorage {
 			_ => false,
 		}
 	}
+
+	fn iter_prefix(
+		&self,
+		prefix: &[u8],
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		count: usize,
+	) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+		let mut entries = self
+			.storage
+			.iter()
+			.filter_map(|(key, value)| Some((key.strip_prefix(prefix)?, value)))
+			.filter(|(key, _)| {
+				key.starts_with(key_prefix) && start_key.map_or(true, |start| *key > start)
+			})
+			.collect::<Vec<_>>();
+		entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+		Some(
+			entries
+				.into_iter()
+				.take(count)
+				.map(|(key, value)| (key.to_vec(), value.clone()))
+				.collect(),
+		)
+	}
 }
 
 fn unavailable_yet<R: Default>(name: &str) -> R {
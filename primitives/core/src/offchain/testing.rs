@@ -31,6 +31,8 @@ use crate::{
 };
 use std::{
 	collections::{BTreeMap, VecDeque},
+	io::{self, Read, Write},
+	net::{SocketAddr, TcpStream},
 	sync::Arc,
 };
 
@@ -116,6 +118,16 @@ impl OffchainStorage for TestPersistentOffchainDB {
 	) -> bool {
 		self.persistent.write().compare_and_set(prefix, key, old_value, new_value)
 	}
+
+	fn iter_prefix(
+		&self,
+		prefix: &[u8],
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		count: usize,
+	) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+		self.persistent.read().iter_prefix(prefix, key_prefix, start_key, count)
+	}
 }
 
 /// Internal state of the externalities.
@@ -135,6 +147,10 @@ pub struct OffchainState {
 	pub seed: [u8; 32],
 	/// A timestamp simulating the current time.
 	pub timestamp: Timestamp,
+	/// Local HTTP server the requests are sent to, if any.
+	local_server: Option<SocketAddr>,
+	/// Status of the requests sent to the local HTTP server.
+	local_statuses: BTreeMap<RequestId, RequestStatus>,
 }
 
 impl OffchainState {
@@ -179,6 +195,116 @@ impl OffchainState {
 		}
 		self.expected_requests.push_front(expected);
 	}
+
+	/// Send HTTP requests to the server listening on `addr` instead of answering them with the
+	/// expected requests.
+	///
+	/// This allows running tests against a local mock server. Requests are sent over plain
+	/// HTTP/1.1 once their body is complete, whatever the host of their URI is.
+	pub fn forward_to_local_server(&mut self, addr: SocketAddr) {
+		self.local_server = Some(addr);
+	}
+
+	/// Sends the request to the local server if there is one and it wasn't sent yet.
+	fn send_to_local_server(&mut self, id: RequestId) {
+		let addr = match self.local_server {
+			Some(addr) if !self.local_statuses.contains_key(&id) => addr,
+			_ => return,
+		};
+		let req = match self.requests.get_mut(&id) {
+			Some(req) => req,
+			None => return,
+		};
+
+		let status = match local_server_exchange(addr, req) {
+			Ok((status, headers, body)) => {
+				req.sent = true;
+				req.response = Some(body);
+				req.response_headers = headers;
+				RequestStatus::Finished(status)
+			},
+			Err(_) => RequestStatus::IoError,
+		};
+		self.local_statuses.insert(id, status);
+	}
+}
+
+/// Sends `request` to the HTTP server listening on `addr`, returning the status code, headers and
+/// body of the response.
+fn local_server_exchange(
+	addr: SocketAddr,
+	request: &PendingRequest,
+) -> io::Result<(u16, Vec<(String, String)>, Vec<u8>)> {
+	let invalid = |error: &str| io::Error::new(io::ErrorKind::InvalidData, error.to_owned());
+
+	// `scheme://authority/path`
+	let mut uri = request.uri.splitn(4, '/');
+	let authority = uri.nth(2).unwrap_or_default();
+	let path = format!("/{}", uri.next().unwrap_or_default());
+
+	let mut head = format!(
+		"{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+		request.method,
+		path,
+		authority,
+		request.body.len(),
+	);
+	for (name, value) in &request.headers {
+		head.push_str(&format!("{}: {}\r\n", name, value));
+	}
+	head.push_str("\r\n");
+
+	let mut stream = TcpStream::connect(addr)?;
+	stream.write_all(head.as_bytes())?;
+	stream.write_all(&request.body)?;
+	let mut response = Vec::new();
+	stream.read_to_end(&mut response)?;
+
+	let head_len = response
+		.windows(4)
+		.position(|window| window == b"\r\n\r\n")
+		.ok_or_else(|| invalid("Incomplete response head"))?;
+	let body = response.split_off(head_len + 4);
+	let head = String::from_utf8(response).map_err(|_| invalid("Invalid response head"))?;
+
+	let mut lines = head.trim_end().split("\r\n");
+	let status = lines
+		.next()
+		.and_then(|line| line.split(' ').nth(1))
+		.and_then(|status| status.parse().ok())
+		.ok_or_else(|| invalid("Invalid status line"))?;
+	let headers = lines
+		.filter_map(|line| line.split_once(':'))
+		.map(|(name, value)| (name.trim().to_owned(), value.trim().to_owned()))
+		.collect::<Vec<_>>();
+
+	let chunked = headers.iter().any(|(name, value)| {
+		name.eq_ignore_ascii_case("transfer-encoding") && value.eq_ignore_ascii_case("chunked")
+	});
+	let body = if chunked {
+		decode_chunked(&body).ok_or_else(|| invalid("Invalid chunked body"))?
+	} else {
+		body
+	};
+
+	Ok((status, headers, body))
+}
+
+/// Decodes a body sent with the `chunked` transfer encoding.
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+	let mut decoded = Vec::new();
+	loop {
+		let line_len = body.windows(2).position(|window| window == b"\r\n")?;
+		let size = std::str::from_utf8(&body[..line_len]).ok()?.split(';').next()?;
+		let size = usize::from_str_radix(size.trim(), 16).ok()?;
+		body = &body[line_len + 2..];
+
+		if size == 0 {
+			return Some(decoded)
+		}
+		decoded.extend_from_slice(body.get(..size)?);
+		body = body.get(size + 2..)?;
+	}
 }
 
 impl Drop for OffchainState {
@@ -233,6 +359,142 @@ impl offchain::Externalities for TestOffchainExt {
 		self.0.read().seed
 	}
 
+	fn http_request_start(
+		&mut self,
+		method: &str,
+		uri: &str,
+		meta: &[u8],
+	) -> Result<RequestId, ()> {
+		let mut state = self.0.write();
+		let id = RequestId(state.requests.len() as u16);
+		state.requests.insert(
+			id,
+			PendingRequest {
+				method: method.into(),
+				uri: uri.into(),
+				meta: meta.into(),
+				..Default::default()
+			},
+		);
+		Ok(id)
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		request_id: RequestId,
+		name: &str,
+		value: &str,
+	) -> Result<(), ()> {
+		let mut state = self.0.write();
+		if let Some(req) = state.requests.get_mut(&request_id) {
+			req.headers.push((name.into(), value.into()));
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		request_id: RequestId,
+		chunk: &[u8],
+		_deadline: Option<Timestamp>,
+	) -> Result<(), HttpError> {
+		let mut state = self.0.write();
+
+		let sent = {
+			let req = state.requests.get_mut(&request_id).ok_or(HttpError::IoError)?;
+			req.body.extend(chunk);
+			if chunk.is_empty() {
+				req.sent = true;
+			}
+			req.sent
+		};
+
+		if sent {
+			if state.local_server.is_some() {
+				state.send_to_local_server(request_id);
+			} else {
+				state.fulfill_expected(request_id.0);
+			}
+		}
+
+		Ok(())
+	}
+
+	fn http_response_wait(
+		&mut self,
+		ids: &[RequestId],
+		_deadline: Option<Timestamp>,
+	) -> Vec<RequestStatus> {
+		let mut state = self.0.write();
+
+		ids.iter()
+			.map(|id| {
+				// Waiting for a response sends the request, even if its body isn't complete.
+				state.send_to_local_server(*id);
+
+				match (state.requests.get(id), state.local_statuses.get(id)) {
+					(None, _) => RequestStatus::Invalid,
+					(Some(_), Some(status)) => *status,
+					(Some(req), None) if req.response.is_none() => {
+						panic!("No `response` provided for request with id: {:?}", id)
+					},
+					_ => RequestStatus::Finished(200),
+				}
+			})
+			.collect()
+	}
+
+	fn http_response_headers(&mut self, request_id: RequestId) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let state = self.0.read();
+		if let Some(req) = state.requests.get(&request_id) {
+			req.response_headers
+				.clone()
+				.into_iter()
+				.map(|(k, v)| (k.into_bytes(), v.into_bytes()))
+				.collect()
+		} else {
+			Default::default()
+		}
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		request_id: RequestId,
+		buffer: &mut [u8],
+		_deadline: Option<Timestamp>,
+	) -> Result<usize, HttpError> {
+		let mut state = self.0.write();
+		state.send_to_local_server(request_id);
+		if state.local_statuses.get(&request_id) == Some(&RequestStatus::IoError) {
+			state.requests.remove(&request_id);
+			state.local_statuses.remove(&request_id);
+			return Err(HttpError::IoError)
+		}
+
+		if let Some(req) = state.requests.get_mut(&request_id) {
+			let response = req
+				.response
+				.as_mut()
+				.unwrap_or_else(|| panic!("No response provided for request: {:?}", request_id));
+
+			if req.read >= response.len() {
+				// Remove the pending request as per spec.
+				state.requests.remove(&request_id);
+				state.local_statuses.remove(&request_id);
+				Ok(0)
+			} else {
+				let read = std::cmp::min(buffer.len(), response[req.read..].len());
+				buffer[0..read].copy_from_slice(&response[req.read..req.read + read]);
+				req.read += read;
+				Ok(read)
+			}
+		} else {
+			Err(HttpError::IoError)
+		}
+	}
+
 	fn set_authorized_nodes(&mut self, _nodes: Vec<OpaquePeerId>, _authorized_only: bool) {
 		unimplemented!()
 	}
@@ -314,3 +576,80 @@ impl TransactionPool for TestTransactionPoolExt {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::offchain::Externalities;
+	use std::net::TcpListener;
+
+	// Serves a single request, returning it once `response` was sent.
+	fn serve_once(response: &'static [u8]) -> (SocketAddr, std::thread::JoinHandle<Vec<u8>>) {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let addr = listener.local_addr().unwrap();
+		let server = std::thread::spawn(move || {
+			let (mut stream, _) = listener.accept().unwrap();
+			let mut request = Vec::new();
+			let mut buf = [0; 1024];
+			// The test requests have a body of 3 bytes.
+			while !request
+				.windows(4)
+				.position(|w| w == b"\r\n\r\n")
+				.map_or(false, |head_len| request.len() >= head_len + 4 + 3)
+			{
+				let read = stream.read(&mut buf).unwrap();
+				request.extend_from_slice(&buf[..read]);
+			}
+			stream.write_all(response).unwrap();
+			request
+		});
+		(addr, server)
+	}
+
+	#[test]
+	fn requests_are_sent_to_local_server() {
+		let (addr, server) = serve_once(
+			b"HTTP/1.1 201 Created\r\nX-Test: yes\r\nTransfer-Encoding: chunked\r\n\r\n\
+			  5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n",
+		);
+		let (mut offchain, state) = TestOffchainExt::new();
+		state.write().forward_to_local_server(addr);
+
+		let id = offchain
+			.http_request_start("POST", "http://example.com/path?query", &[])
+			.unwrap();
+		offchain.http_request_add_header(id, "X-Auth", "test").unwrap();
+		offchain.http_request_write_body(id, &[1, 2, 3], None).unwrap();
+		offchain.http_request_write_body(id, &[], None).unwrap();
+
+		let request = String::from_utf8_lossy(&server.join().unwrap()).into_owned();
+		assert!(request.starts_with("POST /path?query HTTP/1.1\r\n"));
+		assert!(request.contains("\r\nHost: example.com\r\n"));
+		assert!(request.contains("\r\nX-Auth: test\r\n"));
+		assert!(request.ends_with("\r\n\r\n\u{1}\u{2}\u{3}"));
+
+		assert_eq!(offchain.http_response_wait(&[id], None), vec![RequestStatus::Finished(201)]);
+		assert!(offchain
+			.http_response_headers(id)
+			.contains(&(b"X-Test".to_vec(), b"yes".to_vec())));
+
+		let mut buf = [0; 64];
+		let read = offchain.http_response_read_body(id, &mut buf, None).unwrap();
+		assert_eq!(&buf[..read], b"hello world");
+		assert_eq!(offchain.http_response_read_body(id, &mut buf, None), Ok(0));
+		assert_eq!(offchain.http_response_wait(&[id], None), vec![RequestStatus::Invalid]);
+	}
+
+	#[test]
+	fn unreachable_local_server_fails_requests() {
+		let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+		let (mut offchain, state) = TestOffchainExt::new();
+		state.write().forward_to_local_server(addr);
+
+		let id = offchain.http_request_start("GET", "http://localhost/", &[]).unwrap();
+		assert_eq!(offchain.http_response_wait(&[id], None), vec![RequestStatus::IoError]);
+
+		let mut buf = [0; 64];
+		assert_eq!(offchain.http_response_read_body(id, &mut buf, None), Err(HttpError::IoError));
+	}
+}
@@ -42,6 +42,27 @@ where
 }
 
 impl<D: KeyValueDB> DbAdapter<D> {
+	// Calls `f` with the entries whose key starts with `prefix`, except `skip`. Returns `false` if
+	// `f` stopped the iteration.
+	fn visit_prefix(
+		&self,
+		col: ColumnId,
+		prefix: &[u8],
+		skip: Option<&[u8]>,
+		f: &mut dyn FnMut(&[u8], &[u8]) -> bool,
+	) -> error::Result<bool> {
+		for entry in self.0.iter_with_prefix(col, prefix) {
+			let (key, value) = entry.map_err(|e| error::DatabaseError(Box::new(e)))?;
+			if skip == Some(&*key) {
+				continue
+			}
+			if !f(&key, &value) {
+				return Ok(false)
+			}
+		}
+		Ok(true)
+	}
+
 	// Returns counter key and counter value if it exists.
 	fn read_counter(&self, col: ColumnId, key: &[u8]) -> error::Result<(Vec<u8>, Option<u32>)> {
 		// Add a key suffix for the counter
@@ -116,4 +137,37 @@ impl<D: KeyValueDB, H: Clone + AsRef<[u8]>> Database<H> for DbAdapter<D> {
 	fn contains(&self, col: ColumnId, key: &[u8]) -> bool {
 		handle_err(self.0.has_key(col, key))
 	}
+
+	fn iter_prefix(
+		&self,
+		col: ColumnId,
+		prefix: &[u8],
+		start_key: Option<&[u8]>,
+		f: &mut dyn FnMut(&[u8], &[u8]) -> bool,
+	) -> error::Result<()> {
+		let start = match start_key {
+			Some(start) if start.starts_with(prefix) => start,
+			// Every key under `prefix` sorts before `start`.
+			Some(start) if start > prefix => return Ok(()),
+			_ => return self.visit_prefix(col, prefix, None, f).map(|_| ()),
+		};
+
+		// `KeyValueDB` can only seek to a prefix, so the keys after `start` are visited as the keys
+		// extending `start`, followed by the keys extending each greater sibling of its prefixes,
+		// from the longest prefix to `prefix` itself. This creates up to 255 iterators per byte of
+		// `start` after `prefix`, so callers should bound the length of `start`.
+		if !self.visit_prefix(col, start, Some(start), f)? {
+			return Ok(())
+		}
+		for len in (prefix.len()..start.len()).rev() {
+			let mut sibling = start[..=len].to_vec();
+			while sibling[len] < u8::MAX {
+				sibling[len] += 1;
+				if !self.visit_prefix(col, &sibling, None, f)? {
+					return Ok(())
+				}
+			}
+		}
+		Ok(())
+	}
 }
@@ -116,6 +116,26 @@ pub trait Database<H: Clone + AsRef<[u8]>>: Send + Sync {
 	///
 	/// Not all database implementations use a prefix for keys, so this function may be a noop.
 	fn sanitize_key(&self, _key: &mut Vec<u8>) {}
+
+	/// Call `f` with every key and value in `col` whose key starts with `prefix`, in ascending key
+	/// order, until `f` returns `false`.
+	///
+	/// If `start_key` is given, iteration starts at the first key strictly greater than it.
+	///
+	/// Returns an error if the database doesn't support iterating over `col`, which is the
+	/// default.
+	fn iter_prefix(
+		&self,
+		_col: ColumnId,
+		_prefix: &[u8],
+		_start_key: Option<&[u8]>,
+		_f: &mut dyn FnMut(&[u8], &[u8]) -> bool,
+	) -> error::Result<()> {
+		Err(error::DatabaseError(Box::new(std::io::Error::new(
+			std::io::ErrorKind::Unsupported,
+			"Iteration is not supported by this database",
+		))))
+	}
 }
 
 impl<H> std::fmt::Debug for dyn Database<H> {
@@ -74,6 +74,32 @@ where
 		let s = self.0.read();
 		s.get(&col).and_then(|c| c.get(key).map(|(_, v)| v.clone()))
 	}
+
+	fn iter_prefix(
+		&self,
+		col: ColumnId,
+		prefix: &[u8],
+		start_key: Option<&[u8]>,
+		f: &mut dyn FnMut(&[u8], &[u8]) -> bool,
+	) -> error::Result<()> {
+		let s = self.0.read();
+		let mut entries = s
+			.get(&col)
+			.into_iter()
+			.flatten()
+			.filter(|(key, _)| {
+				key.starts_with(prefix) && start_key.map_or(true, |start| key.as_slice() > start)
+			})
+			.collect::<Vec<_>>();
+		entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+		for (key, (_, value)) in entries {
+			if !f(key, value) {
+				break
+			}
+		}
+		Ok(())
+	}
 }
 
 impl MemDb {
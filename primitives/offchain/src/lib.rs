@@ -23,6 +23,11 @@
 
 /// Re-export of parent module scope storage prefix.
 pub use sp_core::offchain::STORAGE_PREFIX;
+pub use sp_runtime::offchain::schedule::{
+	Cadence, OffchainWorkerSchedule, PalletOffchainWorkerSchedule, Trigger,
+};
+
+use sp_runtime::sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
 	/// The offchain worker api.
@@ -34,5 +39,13 @@ sp_api::decl_runtime_apis! {
 
 		/// Starts the off-chain task for given block header.
 		fn offchain_worker(header: &Block::Header);
+
+		/// Returns the offchain worker schedules of all pallets at this block.
+		#[api_version(3)]
+		fn offchain_worker_schedules() -> Vec<PalletOffchainWorkerSchedule>;
+
+		/// Starts the off-chain tasks of the pallets called `pallets` for given block header.
+		#[api_version(3)]
+		fn offchain_worker_of(header: &Block::Header, pallets: Vec<Vec<u8>>);
 	}
 }
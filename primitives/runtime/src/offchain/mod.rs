@@ -18,6 +18,7 @@
 
 //! A collection of higher lever helpers for offchain calls.
 
+pub mod schedule;
 pub mod storage;
 pub mod storage_lock;
 
@@ -0,0 +1,159 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scheduling of the offchain workers of individual pallets.
+//!
+//! Instead of running every offchain worker on every block, a pallet declares an
+//! [`OffchainWorkerSchedule`]: a [`Cadence`] at which its worker runs, [`Trigger`]s which
+//! additionally wake it up, and a timeout. The node runs only the workers which are due.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_core::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// How often an offchain worker runs.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum Cadence {
+	/// Run on every new best block.
+	EveryBlock,
+	/// Run on new best blocks whose number is a multiple of the given period.
+	///
+	/// A period of zero never runs the offchain worker.
+	EveryNBlocks(u32),
+	/// Run on every finalized block which is announced by a finality notification.
+	OnFinality,
+	/// Only run when one of the [`Trigger`]s of the schedule fires.
+	Never,
+}
+
+/// Condition which runs an offchain worker on a new best block regardless of its [`Cadence`].
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum Trigger {
+	/// The block changed the value stored under the given storage key.
+	StorageChanged(Vec<u8>),
+	/// The block emitted an event of the pallet with the given index.
+	///
+	/// If `event_index` is `Some`, only the event with the given variant index fires the trigger.
+	Event {
+		/// Index of the pallet emitting the event in the runtime.
+		pallet_index: u8,
+		/// Variant index of the event within the events of the pallet.
+		event_index: Option<u8>,
+	},
+}
+
+/// When and for how long the offchain worker of a pallet runs.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct OffchainWorkerSchedule {
+	/// How often the offchain worker runs.
+	pub cadence: Cadence,
+	/// Conditions which additionally run the offchain worker.
+	pub triggers: Vec<Trigger>,
+	/// Time in milliseconds after which the node stops waiting for the offchain worker.
+	///
+	/// The timeout is advisory, as the node can't interrupt a running offchain worker. Once it is
+	/// reached, the HTTP requests of the offchain worker fail, so a worker waiting for responses
+	/// returns early, but a worker busy computing keeps running. Either way, the worker isn't run
+	/// again before it returned. The offchain workers due at the same block run together, so they
+	/// share the longest of their timeouts, and have none if any of them has none.
+	pub timeout_ms: Option<u64>,
+}
+
+impl Default for OffchainWorkerSchedule {
+	fn default() -> Self {
+		Self::new(Cadence::EveryBlock)
+	}
+}
+
+impl OffchainWorkerSchedule {
+	/// Create a schedule running at `cadence`, without triggers and timeout.
+	pub fn new(cadence: Cadence) -> Self {
+		Self { cadence, triggers: Vec::new(), timeout_ms: None }
+	}
+
+	/// Additionally run the offchain worker when `trigger` fires.
+	pub fn with_trigger(mut self, trigger: Trigger) -> Self {
+		self.triggers.push(trigger);
+		self
+	}
+
+	/// Stop waiting for the offchain worker after `timeout_ms` milliseconds.
+	pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+		self.timeout_ms = Some(timeout_ms);
+		self
+	}
+
+	/// Returns the storage keys whose changes trigger the offchain worker.
+	pub fn storage_triggers(&self) -> impl Iterator<Item = &[u8]> {
+		self.triggers.iter().filter_map(|trigger| match trigger {
+			Trigger::StorageChanged(key) => Some(&key[..]),
+			Trigger::Event { .. } => None,
+		})
+	}
+
+	/// Returns if the offchain worker is due on a new best block with the given number.
+	///
+	/// Only the [`Cadence`] is taken into account.
+	pub fn is_due_at(&self, number: u64) -> bool {
+		match self.cadence {
+			Cadence::EveryBlock => true,
+			Cadence::EveryNBlocks(period) => period != 0 && number % u64::from(period) == 0,
+			Cadence::OnFinality | Cadence::Never => false,
+		}
+	}
+}
+
+/// The schedule of the offchain worker of a single pallet at a given block.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct PalletOffchainWorkerSchedule {
+	/// Name of the pallet in the runtime.
+	pub pallet: Vec<u8>,
+	/// The schedule declared by the pallet.
+	pub schedule: OffchainWorkerSchedule,
+	/// If one of the event [`Trigger`]s of the schedule fired in the block.
+	pub triggered_by_events: bool,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cadence_is_respected() {
+		assert!(OffchainWorkerSchedule::default().is_due_at(7));
+
+		let every_third = OffchainWorkerSchedule::new(Cadence::EveryNBlocks(3));
+		assert!(every_third.is_due_at(0));
+		assert!(!every_third.is_due_at(4));
+		assert!(every_third.is_due_at(6));
+
+		assert!(!OffchainWorkerSchedule::new(Cadence::EveryNBlocks(0)).is_due_at(0));
+		assert!(!OffchainWorkerSchedule::new(Cadence::OnFinality).is_due_at(3));
+		assert!(!OffchainWorkerSchedule::new(Cadence::Never).is_due_at(3));
+	}
+
+	#[test]
+	fn storage_triggers_are_listed() {
+		let schedule = OffchainWorkerSchedule::new(Cadence::Never)
+			.with_trigger(Trigger::StorageChanged(b"key".to_vec()))
+			.with_trigger(Trigger::Event { pallet_index: 1, event_index: None });
+
+		assert_eq!(schedule.storage_triggers().collect::<Vec<_>>(), vec![&b"key"[..]]);
+	}
+}
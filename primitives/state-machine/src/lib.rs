@@ -151,7 +151,8 @@ mod std_reexport {
 	};
 	pub use sp_trie::{
 		trie_types::{TrieDBMutV0, TrieDBMutV1},
-		CompactProof, DBValue, LayoutV0, LayoutV1, MemoryDB, StorageProof, TrieMut,
+		CompactMultiProof, CompactProof, DBValue, LayoutV0, LayoutV1, MemoryDB, StorageProof,
+		TrieMut,
 	};
 }
 
@@ -774,6 +775,87 @@ mod execution {
 		Ok((proof, count))
 	}
 
+	/// Generate range storage read proof of at most `count` key value pairs.
+	///
+	/// The range starts right after `start_at`, which is excluded, so paging can continue from
+	/// the last returned key. Fewer pairs are read once the proof reaches `size_limit`, which it
+	/// may exceed by the nodes of the last pair, and at least one pair is always read. Returns the
+	/// proof, the keys it covers and whether the end of the range was reached. The proof is
+	/// checked with `read_range_proof_check_with_count`, using the number of returned keys as
+	/// `count` unless the end of the range was reached.
+	pub fn prove_range_read_with_count<B, H>(
+		backend: B,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		count: u32,
+		size_limit: usize,
+		start_at: Option<&[u8]>,
+	) -> Result<(StorageProof, Vec<Vec<u8>>, bool), Box<dyn Error>>
+	where
+		B: AsTrieBackend<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let trie_backend = backend.as_trie_backend();
+		prove_range_read_with_count_on_trie_backend(
+			trie_backend,
+			child_info,
+			prefix,
+			count,
+			size_limit,
+			start_at,
+		)
+	}
+
+	/// Generate range storage read proof of at most `count` key value pairs on an existing trie
+	/// backend.
+	pub fn prove_range_read_with_count_on_trie_backend<S, H>(
+		trie_backend: &TrieBackend<S, H>,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		count: u32,
+		size_limit: usize,
+		start_at: Option<&[u8]>,
+	) -> Result<(StorageProof, Vec<Vec<u8>>, bool), Box<dyn Error>>
+	where
+		S: trie_backend_essence::TrieBackendStorage<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let recorder = sp_trie::recorder::Recorder::default();
+		let proving_backend =
+			TrieBackendBuilder::wrap(trie_backend).with_recorder(recorder.clone()).build();
+		let mut keys = Vec::new();
+		let complete = {
+			// NOTE: The values need to be fetched as well for the proof to be checkable.
+			let mut iter = proving_backend
+				.pairs(IterArgs {
+					child_info: child_info.cloned(),
+					prefix,
+					start_at,
+					start_at_exclusive: true,
+					..IterArgs::default()
+				})
+				.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+
+			// This needs to stop at the same point as `read_range_proof_check_with_count`.
+			while let Some(item) = iter.next() {
+				let (key, _) = item.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+				keys.push(key);
+				if keys.len() as u32 >= count || recorder.estimate_encoded_size() >= size_limit {
+					break
+				}
+			}
+
+			iter.was_complete()
+		};
+
+		let proof = proving_backend
+			.extract_proof()
+			.expect("A recorder was set and thus, a storage proof can be extracted; qed");
+		Ok((proof, keys, complete))
+	}
+
 	/// Generate child storage read proof.
 	pub fn prove_child_read<B, H, I>(
 		backend: B,
@@ -904,6 +986,32 @@ mod execution {
 		)
 	}
 
+	/// Check storage range proof, generated by `prove_range_read_with_count` call.
+	///
+	/// `start_at` is excluded from the range, as it is when generating the proof.
+	pub fn read_range_proof_check_with_count<H>(
+		root: H::Out,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		count: u32,
+		start_at: Option<&[u8]>,
+	) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+	where
+		H: Hasher + 'static,
+		H::Out: Ord + Codec,
+	{
+		let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+		read_range_proof_check_on_proving_backend_inner(
+			&proving_backend,
+			child_info,
+			prefix,
+			Some(count),
+			start_at,
+			true,
+		)
+	}
+
 	/// Check child storage read proof, generated by `prove_child_read` call.
 	pub fn read_child_proof_check<H, I>(
 		root: H::Out,
@@ -930,6 +1038,42 @@ mod execution {
 		Ok(result)
 	}
 
+	/// Check storage read proofs over several state roots, generated by `prove_read` calls and
+	/// combined with [`CompactMultiProof::from_storage_proofs`].
+	///
+	/// Returns the read values at every root, in the order of `roots`.
+	pub fn read_multi_proof_check<H, I>(
+		roots: &[H::Out],
+		proof: CompactMultiProof,
+		keys: I,
+	) -> Result<Vec<HashMap<Vec<u8>, Option<Vec<u8>>>>, Box<dyn Error>>
+	where
+		H: Hasher + 'static,
+		H::Out: Ord + Codec,
+		I: IntoIterator,
+		I::Item: AsRef<[u8]>,
+	{
+		let db = proof
+			.to_memory_db::<H>(roots)
+			.map_err(|_| Box::new(ExecutionError::InvalidProof) as Box<dyn Error>)?;
+		let keys = keys.into_iter().collect::<Vec<_>>();
+
+		roots
+			.iter()
+			.map(|root| {
+				let proving_backend = TrieBackendBuilder::new(&db, *root).build();
+				let mut result = HashMap::new();
+				for key in &keys {
+					let value = proving_backend
+						.storage(key.as_ref())
+						.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+					result.insert(key.as_ref().to_vec(), value);
+				}
+				Ok(result)
+			})
+			.collect()
+	}
+
 	/// Check storage read proof on pre-created proving backend.
 	pub fn read_proof_check_on_proving_backend<H>(
 		proving_backend: &TrieBackend<MemoryDB<H>, H>,
@@ -968,6 +1112,28 @@ mod execution {
 		count: Option<u32>,
 		start_at: Option<&[u8]>,
 	) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+	where
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		read_range_proof_check_on_proving_backend_inner(
+			proving_backend,
+			child_info,
+			prefix,
+			count,
+			start_at,
+			false,
+		)
+	}
+
+	fn read_range_proof_check_on_proving_backend_inner<H>(
+		proving_backend: &TrieBackend<MemoryDB<H>, H>,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		count: Option<u32>,
+		start_at: Option<&[u8]>,
+		start_at_exclusive: bool,
+	) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
 	where
 		H: Hasher,
 		H::Out: Ord + Codec,
@@ -978,6 +1144,7 @@ mod execution {
 				child_info: child_info.cloned(),
 				prefix,
 				start_at,
+				start_at_exclusive,
 				stop_on_incomplete_database: true,
 				..IterArgs::default()
 			})
@@ -1701,6 +1868,175 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn prove_range_read_with_count_works() {
+		let child_info = ChildInfo::new_default(b"sub1");
+		let mut remote_backend =
+			trie_backend::tests::test_trie(StateVersion::default(), None, None);
+		remote_backend.insert(
+			vec![(
+				Some(child_info.clone()),
+				(0..10u8).map(|i| (vec![b'p', i], Some(vec![i; 40]))).collect(),
+			)],
+			StateVersion::default(),
+		);
+		let remote_root = *remote_backend.root();
+
+		for child_info in [None, Some(&child_info)] {
+			let mut start_at = None::<Vec<u8>>;
+			let mut proven = Vec::new();
+			loop {
+				let (proof, keys, complete) = prove_range_read_with_count(
+					remote_backend.clone(),
+					child_info,
+					Some(b"p"),
+					3,
+					usize::MAX,
+					start_at.as_deref(),
+				)
+				.unwrap();
+				let (values, checked_complete) = read_range_proof_check_with_count::<BlakeTwo256>(
+					remote_root,
+					proof,
+					child_info,
+					Some(b"p"),
+					3,
+					start_at.as_deref(),
+				)
+				.unwrap();
+				assert_eq!(values.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>(), keys);
+				assert_eq!(complete, checked_complete);
+				assert!(keys.len() <= 3);
+
+				// `start_at` is exclusive, pages never overlap.
+				proven.extend(values);
+				if complete {
+					break
+				}
+				start_at = keys.last().cloned();
+			}
+
+			if child_info.is_some() {
+				let expected = (0..10u8).map(|i| (vec![b'p', i], vec![i; 40])).collect::<Vec<_>>();
+				assert_eq!(proven, expected);
+			} else {
+				assert!(proven.is_empty());
+			}
+		}
+	}
+
+	#[test]
+	fn prove_range_read_with_count_stops_at_size_limit() {
+		let child_info = ChildInfo::new_default(b"sub1");
+		let mut remote_backend =
+			trie_backend::tests::test_trie(StateVersion::default(), None, None);
+		remote_backend.insert(
+			vec![(
+				Some(child_info.clone()),
+				(0..10u8).map(|i| (vec![b'p', i], Some(vec![i; 400]))).collect(),
+			)],
+			StateVersion::default(),
+		);
+		let remote_root = *remote_backend.root();
+
+		let prove = |size_limit| {
+			prove_range_read_with_count(
+				remote_backend.clone(),
+				Some(&child_info),
+				Some(b"p"),
+				10,
+				size_limit,
+				None,
+			)
+			.unwrap()
+		};
+
+		// At least one pair is always read.
+		let (_, keys, complete) = prove(0);
+		assert_eq!(keys.len(), 1);
+		assert!(!complete);
+
+		let (proof, keys, complete) = prove(2000);
+		assert!(keys.len() > 1 && keys.len() < 10);
+		assert!(!complete);
+		// The proof only exceeds the limit by the last pair.
+		assert!(proof.encoded_size() < 2000 + 1000);
+
+		// The proof is checked with the number of returned keys.
+		let (values, checked_complete) = read_range_proof_check_with_count::<BlakeTwo256>(
+			remote_root,
+			proof,
+			Some(&child_info),
+			Some(b"p"),
+			keys.len() as u32,
+			None,
+		)
+		.unwrap();
+		assert_eq!(values.into_iter().map(|(k, _)| k).collect::<Vec<_>>(), keys);
+		assert!(!checked_complete);
+	}
+
+	#[test]
+	fn multi_proof_check_works() {
+		let state_version = StateVersion::V1;
+		let keys: &[&[u8]] = &[b"value2", b"value3", b"missing"];
+
+		let mut backend = trie_backend::tests::test_trie(state_version, None, None);
+		let child_info = ChildInfo::new_default(b"sub1");
+		let mut roots = Vec::new();
+		let mut proofs = Vec::new();
+		let mut expected = Vec::<HashMap<_, _>>::new();
+		for i in 0..5u8 {
+			let top = vec![(b"value2".to_vec(), Some(vec![i])), (vec![i; 64], Some(vec![i; 64]))];
+			let child = vec![(b"value3".to_vec(), Some(vec![i; 40]))];
+			backend.insert(vec![(None, top), (Some(child_info.clone()), child)], state_version);
+
+			let root = *backend.root();
+			roots.push(root);
+			proofs.push(StorageProof::merge([
+				prove_read(backend.clone(), keys).unwrap(),
+				prove_child_read(backend.clone(), &child_info, &[b"value3"]).unwrap(),
+			]));
+			expected.push(
+				keys.iter().map(|key| (key.to_vec(), backend.storage(key).unwrap())).collect(),
+			);
+		}
+
+		let single_proofs_size = proofs
+			.iter()
+			.zip(&roots)
+			.map(|(proof, root)| {
+				proof.clone().into_compact_proof::<BlakeTwo256>(*root).unwrap().encoded_size()
+			})
+			.sum::<usize>();
+		let multi_proof = CompactMultiProof::from_storage_proofs::<BlakeTwo256>(
+			roots.iter().copied().zip(proofs),
+		)
+		.unwrap();
+		assert!(multi_proof.encoded_size() < single_proofs_size);
+
+		let results =
+			read_multi_proof_check::<BlakeTwo256, _>(&roots, multi_proof.clone(), keys).unwrap();
+		assert_eq!(results, expected);
+
+		// The child tries are included as well.
+		let db = multi_proof.to_memory_db::<BlakeTwo256>(&roots).unwrap();
+		for (i, root) in roots.iter().enumerate() {
+			let backend = TrieBackendBuilder::new(&db, *root).build();
+			assert_eq!(
+				backend.child_storage(&child_info, b"value3").unwrap(),
+				Some(vec![i as u8; 40]),
+			);
+		}
+
+		// The roots need to be checked in the order they were encoded in.
+		let mut reversed_roots = roots.clone();
+		reversed_roots.reverse();
+		assert!(
+			read_multi_proof_check::<BlakeTwo256, _>(&reversed_roots, multi_proof, keys).is_err()
+		);
+	}
+
 	#[test]
 	fn prove_read_with_size_limit_works() {
 		let state_version = StateVersion::V0;
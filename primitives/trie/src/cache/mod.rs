@@ -53,7 +53,7 @@ use trie_db::{node::NodeOwned, CachedValue};
 
 mod shared_cache;
 
-pub use shared_cache::SharedTrieCache;
+pub use shared_cache::{SharedTrieCache, SharedTrieCacheSnapshot};
 
 use self::shared_cache::ValueCacheKeyHash;
 
@@ -951,6 +951,117 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn snapshot_and_restore_work() {
+		use codec::{Decode, Encode};
+
+		let (db, root) = create_trie();
+
+		let shared_cache = Cache::new(CACHE_SIZE);
+		{
+			let local_cache = shared_cache.local_cache();
+
+			let mut cache = local_cache.as_trie_db_cache(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root).with_cache(&mut cache).build();
+
+			for (k, _) in TEST_DATA {
+				trie.get(k).unwrap().unwrap();
+			}
+		}
+
+		let snapshot = shared_cache.snapshot(root, usize::MAX).encode();
+		let snapshot = SharedTrieCacheSnapshot::decode(&mut &snapshot[..]).unwrap();
+		assert_eq!(*snapshot.storage_root(), root);
+
+		let restored_cache = Cache::new(CACHE_SIZE);
+		let (nodes, values) = restored_cache.restore(snapshot, root);
+		assert_eq!(nodes, shared_cache.read_lock_inner().node_cache().lru.len());
+		assert!(values > 0);
+		assert_eq!(
+			shared_cache
+				.read_lock_inner()
+				.node_cache()
+				.lru
+				.iter()
+				.map(|d| *d.0)
+				.collect::<Vec<_>>(),
+			restored_cache
+				.read_lock_inner()
+				.node_cache()
+				.lru
+				.iter()
+				.map(|d| *d.0)
+				.collect::<Vec<_>>(),
+		);
+
+		// Everything should be served from the restored cache, without touching the db.
+		{
+			let empty_db = MemoryDB::default();
+			let local_cache = restored_cache.local_cache();
+
+			let mut cache = local_cache.as_trie_db_cache(root);
+			let trie =
+				TrieDBBuilder::<Layout>::new(&empty_db, &root).with_cache(&mut cache).build();
+
+			for (k, v) in TEST_DATA {
+				assert_eq!(v.to_vec(), trie.get(k).unwrap().unwrap());
+			}
+		}
+
+		// Values taken at another storage root are stale and need to be discarded.
+		let restored_cache = Cache::new(CACHE_SIZE);
+		let (nodes, values) =
+			restored_cache.restore(shared_cache.snapshot(root, usize::MAX), Default::default());
+		assert!(nodes > 0);
+		assert_eq!(values, 0);
+		assert!(restored_cache.read_lock_inner().value_cache().lru.is_empty());
+
+		// The size limit is respected.
+		let snapshot = shared_cache.snapshot(root, 0);
+		assert!(snapshot.is_empty());
+	}
+
+	#[test]
+	fn snapshot_skips_nodes_over_the_size_limit() {
+		const BIG_KEY: &[u8] = b"big";
+
+		let (mut db, mut root) = create_trie();
+		{
+			let mut trie = TrieDBMutBuilder::<Layout>::from_existing(&mut db, &mut root).build();
+			trie.insert(BIG_KEY, &[9; 2048]).expect("Inserts data");
+		}
+
+		let shared_cache = Cache::new(CACHE_SIZE);
+		for keys in [TEST_DATA.iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![BIG_KEY]] {
+			let local_cache = shared_cache.local_cache();
+
+			let mut cache = local_cache.as_trie_db_cache(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root).with_cache(&mut cache).build();
+
+			for k in keys {
+				trie.get(k).unwrap().unwrap();
+			}
+		}
+
+		// The big value was read last, so its node is the first one to be snapshotted.
+		let snapshot = shared_cache.snapshot(root, 1024);
+		assert!(!snapshot.is_empty());
+
+		let restored_cache = Cache::new(CACHE_SIZE);
+		let (nodes, _) = restored_cache.restore(snapshot, root);
+		assert!(nodes > 0);
+
+		let empty_db = MemoryDB::default();
+		let local_cache = restored_cache.local_cache();
+		let mut cache = local_cache.as_trie_db_cache(root);
+		let trie = TrieDBBuilder::<Layout>::new(&empty_db, &root).with_cache(&mut cache).build();
+
+		for (k, v) in TEST_DATA {
+			assert_eq!(v.to_vec(), trie.get(k).unwrap().unwrap());
+		}
+		assert!(trie.get(BIG_KEY).is_err());
+	}
+
 	#[test]
 	fn cache_respects_bounds() {
 		let (mut db, root) = create_trie();
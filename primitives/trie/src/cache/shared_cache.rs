@@ -19,6 +19,7 @@
 ///! Provides the [`SharedNodeCache`], the [`SharedValueCache`] and the [`SharedTrieCache`]
 ///! that combines both caches and is exported to the outside.
 use super::{CacheSize, NodeCached};
+use codec::{Decode, Encode};
 use hash_db::Hasher;
 use hashbrown::{hash_set::Entry as SetEntry, HashSet};
 use nohash_hasher::BuildNoHashHasher;
@@ -28,7 +29,7 @@ use std::{
 	hash::{BuildHasher, Hasher as _},
 	sync::Arc,
 };
-use trie_db::{node::NodeOwned, CachedValue};
+use trie_db::{node::NodeOwned, CachedValue, NodeCodec as _};
 
 lazy_static::lazy_static! {
 	static ref RANDOM_STATE: ahash::RandomState = ahash::RandomState::default();
@@ -564,6 +565,44 @@ impl<H: Eq + std::hash::Hash + Clone + Copy + AsRef<[u8]>> SharedValueCache<H> {
 	}
 }
 
+/// A node stored inside of a [`SharedTrieCacheSnapshot`].
+#[derive(Encode, Decode)]
+enum SnapshotNode {
+	/// An encoded trie node.
+	Trie(Vec<u8>),
+	/// A raw value stored in its own node.
+	Value(Vec<u8>),
+}
+
+/// A snapshot of the most recently used entries of a [`SharedTrieCache`].
+///
+/// Created by [`SharedTrieCache::snapshot`] and loaded back with [`SharedTrieCache::restore`].
+/// Nodes are stored in their encoded form and are re-hashed when restored, so only the values are
+/// tied to the `storage_root` the snapshot was taken at.
+#[derive(Encode, Decode)]
+pub struct SharedTrieCacheSnapshot<H> {
+	/// The storage root the cached values belong to.
+	storage_root: H,
+	/// The cached nodes, ordered from the most recently used to the least recently used.
+	nodes: Vec<SnapshotNode>,
+	/// The cached values under `storage_root`, in the same order as `nodes`.
+	///
+	/// `None` means the key doesn't exist in the trie, `Some(_)` is the hash of the value node.
+	values: Vec<(Vec<u8>, Option<H>)>,
+}
+
+impl<H> SharedTrieCacheSnapshot<H> {
+	/// The storage root this snapshot was taken at.
+	pub fn storage_root(&self) -> &H {
+		&self.storage_root
+	}
+
+	/// Returns `true` if this snapshot holds neither nodes nor values.
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty() && self.values.is_empty()
+	}
+}
+
 /// The inner of [`SharedTrieCache`].
 pub(super) struct SharedTrieCacheInner<H: Hasher> {
 	node_cache: SharedNodeCache<H::Out>,
@@ -705,6 +744,155 @@ impl<H: Hasher> SharedTrieCache<H> {
 			.cloned()
 	}
 
+	/// Take a snapshot of the most recently used nodes and values of this cache.
+	///
+	/// Only values which belong to `storage_root` are included. The snapshot is capped at roughly
+	/// `maximum_size` bytes of node and value data, filled up starting with the hottest entries.
+	///
+	/// Values that are stored inline in their parent node are skipped, as the value cache may only
+	/// reference the hash of a value when the value lives in its own node.
+	pub fn snapshot(
+		&self,
+		storage_root: H::Out,
+		maximum_size: usize,
+	) -> SharedTrieCacheSnapshot<H::Out> {
+		let inner = self.inner.read();
+
+		let mut remaining = maximum_size;
+		let mut nodes = Vec::new();
+		for (_, node) in inner.node_cache.lru.iter() {
+			let node = match node {
+				NodeOwned::Value(data, _) => SnapshotNode::Value(data.to_vec()),
+				node => SnapshotNode::Trie(node.to_encoded::<crate::NodeCodec<H>>()),
+			};
+
+			let size = match &node {
+				SnapshotNode::Trie(data) | SnapshotNode::Value(data) => data.len(),
+			};
+			// Skip nodes which don't fit, such that a single large node doesn't leave out all
+			// of the smaller ones after it.
+			if size > remaining {
+				continue
+			}
+			remaining -= size;
+			nodes.push(node);
+		}
+
+		let mut values = Vec::new();
+		for (key, value) in inner.value_cache.lru.iter() {
+			if key.storage_root != storage_root {
+				continue
+			}
+
+			let value = match value {
+				CachedValue::NonExisting => None,
+				CachedValue::ExistingHash(hash) => Some(*hash),
+				CachedValue::Existing { hash, .. } => {
+					// Only keep the value if it is stored in its own node.
+					match inner.node_cache.lru.peek(hash) {
+						Some(NodeOwned::Value(..)) => Some(*hash),
+						_ => continue,
+					}
+				},
+			};
+
+			let size = key.storage_key.len() + std::mem::size_of::<H::Out>();
+			if size > remaining {
+				continue
+			}
+			remaining -= size;
+			values.push((key.storage_key.to_vec(), value));
+		}
+
+		tracing::debug!(
+			target: super::LOG_TARGET,
+			"Took a snapshot of the shared trie cache: {} nodes, {} values, {} bytes",
+			nodes.len(),
+			values.len(),
+			maximum_size - remaining,
+		);
+
+		SharedTrieCacheSnapshot { storage_root, nodes, values }
+	}
+
+	/// Load a `snapshot` taken by [`Self::snapshot`] into this cache.
+	///
+	/// Nodes are always restored, as they are addressed by their own hash. The values are only
+	/// restored if the snapshot was taken at `storage_root`, otherwise they are stale and are
+	/// discarded. Nodes which fail to decode are skipped.
+	///
+	/// Returns the number of nodes and values which were inserted.
+	pub fn restore(
+		&self,
+		snapshot: SharedTrieCacheSnapshot<H::Out>,
+		storage_root: H::Out,
+	) -> (usize, usize) {
+		let mut inner = match self.write_lock_inner() {
+			Some(inner) => inner,
+			None => {
+				tracing::warn!(
+					target: super::LOG_TARGET,
+					"Timeout while trying to acquire a write lock to restore the shared trie cache"
+				);
+				return (0, 0)
+			},
+		};
+
+		// The snapshot is ordered from the hottest to the coldest entry, so insert it in reverse
+		// to end up with the same order in the lru. Evict as much as necessary to stay in bounds.
+		let node_cache = &mut inner.node_cache.lru;
+		node_cache.limiter_mut().items_evicted = 0;
+		node_cache.limiter_mut().max_items_evicted = usize::MAX;
+
+		let mut restored_nodes = 0;
+		for node in snapshot.nodes.into_iter().rev() {
+			let (hash, node) = match node {
+				SnapshotNode::Value(data) => {
+					let hash = H::hash(&data);
+					(hash, NodeOwned::Value(data.into(), hash))
+				},
+				SnapshotNode::Trie(data) => {
+					let node = match crate::NodeCodec::<H>::decode(&data)
+						.ok()
+						.and_then(|node| node.to_owned_node::<crate::LayoutV1<H>>().ok())
+					{
+						Some(node) => node,
+						None => continue,
+					};
+					(H::hash(&data), node)
+				},
+			};
+
+			if node_cache.insert(hash, node) {
+				restored_nodes += 1;
+			}
+		}
+
+		let mut restored_values = 0;
+		if snapshot.storage_root == storage_root {
+			let value_cache = &mut inner.value_cache.lru;
+			value_cache.limiter_mut().items_evicted = 0;
+			value_cache.limiter_mut().max_items_evicted = usize::MAX;
+
+			for (key, value) in snapshot.values.into_iter().rev() {
+				let key = ValueCacheRef::new(&key, storage_root).into();
+				let value = value.map_or(CachedValue::NonExisting, CachedValue::ExistingHash);
+				if value_cache.insert(key, value) {
+					restored_values += 1;
+				}
+			}
+		}
+
+		tracing::debug!(
+			target: super::LOG_TARGET,
+			"Restored the shared trie cache from a snapshot: {} nodes, {} values",
+			restored_nodes,
+			restored_values,
+		);
+
+		(restored_nodes, restored_values)
+	}
+
 	/// Returns the used memory size of this cache in bytes.
 	pub fn used_memory_size(&self) -> usize {
 		let inner = self.inner.read();
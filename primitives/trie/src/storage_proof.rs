@@ -20,10 +20,14 @@ use codec::{Decode, Encode};
 use hash_db::{HashDB, Hasher};
 use scale_info::TypeInfo;
 use sp_std::{
-	collections::btree_set::BTreeSet,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
 	iter::{DoubleEndedIterator, IntoIterator},
 	vec::Vec,
 };
+use trie_db::{
+	node::{Node, NodeHandle, Value},
+	NodeCodec as _,
+};
 // Note that `LayoutV1` usage here (proof compaction) is compatible
 // with `LayoutV0`.
 use crate::LayoutV1 as Layout;
@@ -201,3 +205,145 @@ impl CompactProof {
 		Ok((db, root))
 	}
 }
+
+/// Storage proofs of several state roots in compact form.
+///
+/// Meant for proving the same keys over a range of consecutive blocks, where most of the trie
+/// nodes are identical from one state root to the next. A trie node is only included in the
+/// proof of the first root that uses it, the proofs of the following roots reference it by hash.
+/// Thus the proofs have to be decoded in order, into the same database.
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
+pub struct CompactMultiProof {
+	/// The compact proof of every root, in the order of the roots.
+	pub proofs: Vec<CompactProof>,
+}
+
+impl CompactMultiProof {
+	/// Encode the storage proofs of several state roots with default trie layout.
+	///
+	/// The proofs are expected in the order the roots will be checked in by the verifier.
+	pub fn from_storage_proofs<H: Hasher>(
+		proofs: impl IntoIterator<Item = (H::Out, StorageProof)>,
+	) -> Result<Self, crate::CompactProofError<H::Out, crate::Error<H::Out>>> {
+		let mut known_nodes = BTreeSet::new();
+		let mut compact_proofs = Vec::new();
+
+		for (root, proof) in proofs {
+			let nodes = proof.into_nodes();
+			let needed = nodes_to_encode::<H>(&nodes, &known_nodes);
+
+			let db = StorageProof::new(needed.iter().cloned()).into_memory_db::<H>();
+			let compact_proof = if db.contains(&root, crate::EMPTY_PREFIX) {
+				crate::encode_compact::<Layout<H>, crate::MemoryDB<H>>(&db, &root)?
+			} else {
+				// Every node of this root is part of a previous proof.
+				CompactProof { encoded_nodes: Vec::new() }
+			};
+
+			compact_proofs.push(compact_proof);
+			known_nodes.extend(nodes);
+		}
+
+		Ok(Self { proofs: compact_proofs })
+	}
+
+	/// Decode into a [`MemoryDB`](crate::MemoryDB) holding the nodes of all `expected_roots`.
+	///
+	/// `expected_roots` are the roots of the proofs, in the order they were encoded in.
+	pub fn to_memory_db<H: Hasher>(
+		&self,
+		expected_roots: &[H::Out],
+	) -> Result<crate::MemoryDB<H>, crate::CompactProofError<H::Out, crate::Error<H::Out>>> {
+		if self.proofs.len() != expected_roots.len() {
+			return Err(crate::CompactProofError::IncompleteProof)
+		}
+
+		let mut db = crate::MemoryDB::<H>::new(&[]);
+		for (proof, root) in self.proofs.iter().zip(expected_roots) {
+			if proof.encoded_nodes.is_empty() {
+				if !db.contains(root, crate::EMPTY_PREFIX) {
+					return Err(crate::CompactProofError::IncompleteProof)
+				}
+				continue
+			}
+
+			crate::decode_compact::<Layout<H>, _, _>(
+				&mut db,
+				proof.iter_compact_encoded_nodes(),
+				Some(root),
+			)?;
+		}
+
+		Ok(db)
+	}
+}
+
+/// Returns the `nodes` that need to be encoded, given the already encoded `known_nodes`.
+///
+/// A known node can only be skipped if none of its descendants in `nodes` is new, as the compact
+/// encoding walks the trie from the root and can't reach nodes below a skipped one. Child trie
+/// roots are found by looking for inline values matching the hash of a node.
+fn nodes_to_encode<H: Hasher>(
+	nodes: &BTreeSet<Vec<u8>>,
+	known_nodes: &BTreeSet<Vec<u8>>,
+) -> BTreeSet<Vec<u8>> {
+	let hashes = nodes.iter().map(|node| (H::hash(node), node)).collect::<BTreeMap<_, _>>();
+
+	let mut parents = BTreeMap::<H::Out, Vec<H::Out>>::new();
+	for (hash, node) in &hashes {
+		for child in node_children::<H>(node) {
+			if hashes.contains_key(&child) {
+				parents.entry(child).or_default().push(*hash);
+			}
+		}
+	}
+
+	let mut needed = BTreeSet::new();
+	let mut to_visit = hashes
+		.iter()
+		.filter(|(_, node)| !known_nodes.contains(*node))
+		.map(|(hash, _)| *hash)
+		.collect::<Vec<_>>();
+	while let Some(hash) = to_visit.pop() {
+		if needed.insert(hash) {
+			to_visit.extend(parents.get(&hash).into_iter().flatten().copied());
+		}
+	}
+
+	hashes
+		.into_iter()
+		.filter(|(hash, _)| needed.contains(hash))
+		.map(|(_, node)| node.clone())
+		.collect()
+}
+
+/// Returns the hashes referenced by the encoded `node`, including inline values of hash length.
+fn node_children<H: Hasher>(node: &[u8]) -> Vec<H::Out> {
+	let Ok(node) = crate::NodeCodec::<H>::decode(node) else { return Vec::new() };
+
+	let (children, value) = match node {
+		Node::Empty => (Vec::new(), None),
+		Node::Leaf(_, value) => (Vec::new(), Some(value)),
+		Node::Extension(_, child) => ([child].into_iter().collect(), None),
+		Node::Branch(children, value) | Node::NibbledBranch(_, children, value) =>
+			(children.into_iter().flatten().collect(), value),
+	};
+
+	children
+		.into_iter()
+		.filter_map(|child| match child {
+			NodeHandle::Hash(hash) => Some(hash),
+			NodeHandle::Inline(_) => None,
+		})
+		.chain(value.map(|value| match value {
+			Value::Inline(data) | Value::Node(data) => data,
+		}))
+		.filter_map(|data| {
+			let mut hash = H::Out::default();
+			(hash.as_ref().len() == data.len()).then(|| {
+				hash.as_mut().copy_from_slice(data);
+				hash
+			})
+		})
+		.collect()
+}
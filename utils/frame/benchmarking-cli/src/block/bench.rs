@@ -18,7 +18,7 @@
 
 //! Contains the core benchmarking logic.
 
-use codec::DecodeAll;
+use codec::{DecodeAll, Encode};
 use frame_support::weights::constants::WEIGHT_REF_TIME_PER_NANOS;
 use frame_system::ConsumedWeight;
 use sc_block_builder::{BlockBuilderApi, BlockBuilderProvider};
@@ -26,15 +26,20 @@ use sc_cli::{Error, Result};
 use sc_client_api::{
 	Backend as ClientBackend, BlockBackend, HeaderBackend, StorageProvider, UsageProvider,
 };
-use sp_api::{ApiExt, Core, HeaderT, ProvideRuntimeApi};
+use sc_executor::{WasmExecutor, DEFAULT_PROFILING_INTERVAL};
+use sp_api::{ApiExt, CallApiAt, Core, HeaderT, ProvideRuntimeApi};
 use sp_blockchain::Error::RuntimeApiError;
+use sp_core::traits::CallContext;
+use sp_externalities::Extensions;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT, DigestItem, OpaqueExtrinsic};
+use sp_state_machine::{backend::BackendRuntimeCode, StateMachine};
 use sp_storage::StorageKey;
+use sp_wasm_interface::HostFunctions;
 
 use clap::Args;
 use log::{info, warn};
 use serde::Serialize;
-use std::{fmt::Debug, marker::PhantomData, sync::Arc, time::Instant};
+use std::{fmt::Debug, marker::PhantomData, path::PathBuf, sync::Arc, time::Instant};
 use thousands::Separable;
 
 use crate::shared::{StatSelect, Stats};
@@ -56,28 +61,42 @@ pub struct BenchmarkParams {
 	/// Number of times that the benchmark should be repeated for each block.
 	#[arg(long, default_value_t = 10)]
 	pub repeat: u32,
+
+	/// Additionally profile the execution of the blocks and write the profile to the given path.
+	///
+	/// The blocks are executed once more with the profiler enabled, so the profile does not
+	/// affect the measured times. It is written in the folded stacks format, which can be turned
+	/// into a flamegraph using e.g. `inferno-flamegraph`. Time spent in host functions is
+	/// attributed to a separate `<host>` frame.
+	#[arg(long, value_name = "PATH")]
+	pub profile: Option<PathBuf>,
 }
 
+/// The host functions the profiling executor provides to the runtime.
+type HostFunctionsOf<ExtraHostFunctions> = (sp_io::SubstrateHostFunctions, ExtraHostFunctions);
+
 /// Convenience closure for the [`Benchmark::run()`] function.
-pub struct Benchmark<Block, BA, C> {
+pub struct Benchmark<Block, BA, C, ExtraHostFunctions> {
 	client: Arc<C>,
 	params: BenchmarkParams,
-	_p: PhantomData<(Block, BA, C)>,
+	_p: PhantomData<(Block, BA, C, ExtraHostFunctions)>,
 }
 
 /// Helper for nano seconds.
 type NanoSeconds = u64;
 
-impl<Block, BA, C> Benchmark<Block, BA, C>
+impl<Block, BA, C, ExtraHostFunctions> Benchmark<Block, BA, C, ExtraHostFunctions>
 where
 	Block: BlockT<Extrinsic = OpaqueExtrinsic>,
+	ExtraHostFunctions: HostFunctions,
 	BA: ClientBackend<Block>,
 	C: BlockBuilderProvider<BA, Block, C>
 		+ ProvideRuntimeApi<Block>
 		+ StorageProvider<Block, BA>
 		+ UsageProvider<Block>
 		+ BlockBackend<Block>
-		+ HeaderBackend<Block>,
+		+ HeaderBackend<Block>
+		+ CallApiAt<Block>,
 	C::Api: ApiExt<Block, StateBackend = BA::State> + BlockBuilderApi<Block>,
 {
 	/// Returns a new [`Self`] from the arguments.
@@ -91,6 +110,12 @@ where
 			return Err("Cannot benchmark the genesis block".into())
 		}
 
+		let profiler = self.params.profile.as_ref().map(|_| {
+			WasmExecutor::<HostFunctionsOf<ExtraHostFunctions>>::builder()
+				.with_profiling(DEFAULT_PROFILING_INTERVAL)
+				.build()
+		});
+
 		for i in self.params.from..=self.params.to {
 			let block_num = BlockId::Number(i.into());
 			let hash = self.client.expect_block_hash_from_id(&block_num)?;
@@ -101,11 +126,60 @@ where
 			let took = self.measure_block(&block, *block.header().parent_hash())?;
 
 			self.log_weight(i, block.extrinsics().len(), consumed, took);
+
+			if let Some(profiler) = &profiler {
+				self.profile_block(&block, *block.header().parent_hash(), profiler)?;
+			}
+		}
+
+		if let (Some(path), Some(profile)) =
+			(&self.params.profile, profiler.and_then(|profiler| profiler.take_profile()))
+		{
+			let file = std::fs::File::create(path)?;
+			profile.write_folded(std::io::BufWriter::new(file))?;
+			info!(
+				"Wrote profile of {:?} to {}, {:?} of which were spent in host functions",
+				profile.total(),
+				path.display(),
+				profile.host_time(),
+			);
 		}
 
 		Ok(())
 	}
 
+	/// Execute the block once with the profiling `executor`.
+	fn profile_block(
+		&self,
+		block: &Block,
+		parent_hash: Block::Hash,
+		executor: &WasmExecutor<HostFunctionsOf<ExtraHostFunctions>>,
+	) -> Result<()> {
+		let state = self
+			.client
+			.state_at(parent_hash)
+			.map_err(|e| Error::Client(RuntimeApiError(e)))?;
+		let mut extensions = Extensions::default();
+		self.client
+			.initialize_extensions(parent_hash, &mut extensions)
+			.map_err(|e| Error::Client(RuntimeApiError(e)))?;
+
+		StateMachine::new(
+			&state,
+			&mut Default::default(),
+			executor,
+			"Core_execute_block",
+			&block.encode(),
+			&mut extensions,
+			&BackendRuntimeCode::new(&state).runtime_code()?,
+			CallContext::Onchain,
+		)
+		.execute()
+		.map_err(|e| format!("Error profiling block: {}", e))?;
+
+		Ok(())
+	}
+
 	/// Return the average *execution* aka. *import* time of the block.
 	fn measure_block(&self, block: &Block, parent_hash: Block::Hash) -> Result<NanoSeconds> {
 		let mut record = Vec::<NanoSeconds>::default();
@@ -22,7 +22,7 @@
 use sc_block_builder::{BlockBuilderApi, BlockBuilderProvider};
 use sc_cli::{CliConfiguration, ImportParams, Result, SharedParams};
 use sc_client_api::{Backend as ClientBackend, BlockBackend, StorageProvider, UsageProvider};
-use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_api::{ApiExt, CallApiAt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{traits::Block as BlockT, OpaqueExtrinsic};
 
@@ -81,20 +81,25 @@ impl BlockCmd {
 	/// Benchmark the execution time of historic blocks and compare it to their consumed weight.
 	///
 	/// Output will be printed to console.
-	pub fn run<Block, BA, C>(&self, client: Arc<C>) -> Result<()>
+	///
+	/// `ExtraHostFunctions` are the host functions the node provides to its runtime on top of
+	/// the default Substrate ones. They are needed when profiling the blocks.
+	pub fn run<Block, BA, C, ExtraHostFunctions>(&self, client: Arc<C>) -> Result<()>
 	where
 		Block: BlockT<Extrinsic = OpaqueExtrinsic>,
+		ExtraHostFunctions: sp_wasm_interface::HostFunctions,
 		BA: ClientBackend<Block>,
 		C: BlockBuilderProvider<BA, Block, C>
 			+ BlockBackend<Block>
 			+ ProvideRuntimeApi<Block>
 			+ StorageProvider<Block, BA>
 			+ UsageProvider<Block>
-			+ HeaderBackend<Block>,
+			+ HeaderBackend<Block>
+			+ CallApiAt<Block>,
 		C::Api: ApiExt<Block, StateBackend = BA::State> + BlockBuilderApi<Block>,
 	{
 		// Put everything in the benchmark type to have the generic types handy.
-		Benchmark::new(client, self.params.clone()).run()
+		Benchmark::<Block, BA, C, ExtraHostFunctions>::new(client, self.params.clone()).run()
 	}
 }
 
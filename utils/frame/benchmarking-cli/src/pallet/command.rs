@@ -210,15 +210,18 @@ impl PalletCmd {
 		let method =
 			execution_method_from_cli(self.wasm_method, self.wasmtime_instantiation_strategy);
 
-		let executor = WasmExecutor::<(
+		let mut executor = WasmExecutor::<(
 			sp_io::SubstrateHostFunctions,
 			frame_benchmarking::benchmarking::HostFunctions,
 			ExtraHostFunctions,
 		)>::builder()
 		.with_execution_method(method)
 		.with_max_runtime_instances(2)
-		.with_runtime_cache_size(2)
-		.build();
+		.with_runtime_cache_size(2);
+		if let Some(fuel_limit) = self.wasm_fuel_limit {
+			executor = executor.with_fuel_limit(fuel_limit);
+		}
+		let executor = executor.build();
 
 		let extensions = || -> Extensions {
 			let mut extensions = Extensions::default();
@@ -433,6 +436,8 @@ impl PalletCmd {
 
 					batches_db.extend(batch);
 				}
+				// Only the fuel consumed by the timed runs below is reported.
+				let _ = executor.take_consumed_fuel();
 				// Finally run a bunch of loops to get extrinsic timing information.
 				for r in 0..self.external_repeat {
 					let state = &state_without_tracking;
@@ -465,6 +470,18 @@ impl PalletCmd {
 
 					batches.extend(batch);
 
+					if let Some(fuel) = executor.take_consumed_fuel() {
+						log::info!(
+							target: LOG_TARGET,
+							"Benchmark {}::{} {:?} consumed {} fuel for {} repeats",
+							String::from_utf8_lossy(&pallet),
+							String::from_utf8_lossy(&extrinsic),
+							selected_components,
+							fuel,
+							self.repeat,
+						);
+					}
+
 					// Show progress information
 					if let Ok(elapsed) = timer.elapsed() {
 						if elapsed >= time::Duration::from_secs(5) {
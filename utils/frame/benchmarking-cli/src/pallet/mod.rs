@@ -151,6 +151,14 @@ pub struct PalletCmd {
 	)]
 	pub wasmtime_instantiation_strategy: WasmtimeInstantiationStrategy,
 
+	/// Enable deterministic fuel metering, giving every runtime call the specified amount of fuel.
+	///
+	/// The fuel consumed by every timed benchmark run is logged, which allows to compare the cost
+	/// of the benchmarked code with its weight without any timing noise. Only has an effect when
+	/// `wasm-execution` is set to `compiled`.
+	#[arg(long, value_name = "FUEL")]
+	pub wasm_fuel_limit: Option<u64>,
+
 	/// Limit the memory the database cache can use.
 	#[arg(long = "db-cache", value_name = "MiB", default_value_t = 1024)]
 	pub database_cache_size: u32,
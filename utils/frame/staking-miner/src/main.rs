@@ -0,0 +1,186 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A standalone miner of election solutions for `pallet-election-provider-multi-phase`.
+//!
+//! The snapshot of the ongoing election is fetched from a node with `remote-externalities`, and a
+//! solution is mined out of it with either `seq-phragmen` or `PhragMMS`, followed by a number of
+//! balancing iterations. Unlike the offchain worker of the pallet, this does not require being a
+//! validator, nor is it limited by the time an offchain worker is granted.
+//!
+//! Two commands are available:
+//!
+//! - `dry-run` mines a solution, checks its feasibility and scores it against the solutions which
+//!   are already queued, without submitting anything.
+//! - `mine` does the same, and then submits the solution as a signed solution if it improves on the
+//!   queued ones, and if the signed phase is open.
+//!
+//! This binary is built against `kitchensink-runtime`, and the node it connects to must run the
+//! same runtime version.
+
+mod mine;
+mod submit;
+
+use clap::{Args, Parser, Subcommand};
+use mine::{MinedSolution, Solver};
+use node_primitives::Hash;
+use pallet_election_provider_multi_phase::{unsigned::MinerError, FeasibilityError};
+use sp_core::{sr25519, Pair};
+use substrate_rpc_client::ws_client;
+
+/// A common log target to use.
+pub(crate) const LOG_TARGET: &str = "staking-miner";
+
+#[derive(Debug, Parser)]
+#[command(author, version, about)]
+struct Opt {
+	/// The `ws` URI of the node to connect to.
+	#[arg(long, default_value = "ws://localhost:9944", global = true)]
+	uri: String,
+
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Mine a solution and submit it as a signed solution, if it improves on the queued ones.
+	Mine {
+		#[command(flatten)]
+		miner: MinerOpts,
+
+		/// The secret URI of the account which submits the solution, and pays its deposit.
+		#[arg(long)]
+		suri: String,
+	},
+	/// Mine a solution and score it against the queued ones, without submitting it.
+	DryRun {
+		#[command(flatten)]
+		miner: MinerOpts,
+	},
+}
+
+#[derive(Debug, Clone, Args)]
+struct MinerOpts {
+	/// The NPoS solver to mine with.
+	#[arg(long, value_enum, default_value_t = Solver::SeqPhragmen)]
+	solver: Solver,
+
+	/// The number of balancing iterations to run on the result of the solver.
+	#[arg(long, default_value_t = 10)]
+	balancing_iterations: usize,
+
+	/// The block at which to fetch the snapshot. Defaults to the latest finalized block.
+	#[arg(long)]
+	at: Option<Hash>,
+}
+
+/// Errors of the miner.
+#[derive(Debug)]
+pub(crate) enum Error {
+	/// An RPC call to the node failed.
+	Rpc(String),
+	/// The secret URI of the signer could not be parsed.
+	InvalidSuri(String),
+	/// The state of the node could not be fetched.
+	RemoteExternalities(&'static str),
+	/// There is no ongoing election, or it has no snapshot.
+	SnapshotUnavailable,
+	/// Paged elections are not supported.
+	PagedElection,
+	/// The election is not in the signed phase.
+	NotSignedPhase,
+	/// The mined solution does not improve on the queued ones.
+	NotAnImprovement,
+	/// The mined solution is too heavy to be submitted as a signed solution.
+	TooMuchWeight,
+	/// Mining failed.
+	Miner(MinerError),
+	/// The mined solution is not feasible.
+	Feasibility(FeasibilityError),
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+	sp_tracing::try_init_simple();
+	let Opt { uri, command } = Opt::parse();
+
+	match command {
+		Command::DryRun { miner } => {
+			let (mined, _) = mine_remote(&uri, miner).await?;
+			report(&mined);
+			Ok(())
+		},
+		Command::Mine { miner, suri } => {
+			let signer = sr25519::Pair::from_string(&suri, None)
+				.map_err(|e| Error::InvalidSuri(format!("{:?}", e)))?;
+			let (mined, at) = mine_remote(&uri, miner).await?;
+			report(&mined);
+
+			if !mined.phase.is_signed() {
+				return Err(Error::NotSignedPhase)
+			}
+			if !mined.improves_on_queued() {
+				return Err(Error::NotAnImprovement)
+			}
+			if !mined.fits_signed_weight() {
+				return Err(Error::TooMuchWeight)
+			}
+
+			let rpc = ws_client(&uri).await.map_err(Error::Rpc)?;
+			let hash = submit::submit_solution(&rpc, &signer, mined.raw_solution, at).await?;
+			println!("submitted in extrinsic {:?}", hash);
+			Ok(())
+		},
+	}
+}
+
+/// Fetch the state of the election from the node and mine a solution out of it.
+///
+/// Returns the solution along with the hash of the block it was mined at.
+async fn mine_remote(uri: &str, opts: MinerOpts) -> Result<(MinedSolution, Hash), Error> {
+	let MinerOpts { solver, balancing_iterations, at } = opts;
+	let mut ext = remote_externalities::Builder::<node_primitives::Block>::new()
+		.mode(remote_externalities::Mode::Online(remote_externalities::OnlineConfig {
+			transport: uri.to_owned().into(),
+			at,
+			// the fee multiplier is needed to estimate the call fee, part of the deposit.
+			pallets: vec!["ElectionProviderMultiPhase".to_owned(), "TransactionPayment".to_owned()],
+			child_trie: false,
+			..Default::default()
+		}))
+		.build()
+		.await
+		.map_err(Error::RemoteExternalities)?;
+
+	let at = ext.block_hash;
+	log::info!(target: LOG_TARGET, "mining at block {:?}", at);
+	mine::mine_solution(&mut ext, solver, balancing_iterations).map(|mined| (mined, at))
+}
+
+/// Print a mined solution along with how it scores against the queued ones.
+fn report(mined: &MinedSolution) {
+	println!("phase: {:?}, round: {}", mined.phase, mined.raw_solution.round);
+	println!("snapshot: {} voters, {} targets", mined.size.voters, mined.size.targets);
+	println!("mined score: {:?}", mined.raw_solution.score);
+	println!("queued score: {:?}", mined.queued_score);
+	println!("best signed score: {:?}", mined.best_signed_score);
+	println!("improves on queued: {}", mined.improves_on_queued());
+	println!("weight: {:?}, fits signed weight: {}", mined.weight, mined.fits_signed_weight());
+	println!("deposit: {}", mined.deposit);
+}
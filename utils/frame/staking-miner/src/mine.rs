@@ -0,0 +1,313 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mining of a solution out of the snapshot of a remote node.
+
+use crate::{Error, LOG_TARGET};
+use frame_election_provider_support::{PhragMMS, SequentialPhragmen};
+use frame_support::{
+	dispatch::DispatchClass, sp_io::TestExternalities, traits::Get, weights::Weight,
+};
+use kitchensink_runtime::{ElectionProviderMultiPhase as MultiPhase, Runtime, RuntimeBlockLength};
+use node_primitives::{AccountId, Balance, BlockNumber};
+use pallet_election_provider_multi_phase::{
+	Config, ElectionCompute, Miner, MinerConfig, Phase, RawSolution, SignedSubmissionIndices,
+	SolutionAccuracyOf, SolutionOf, SolutionOrSnapshotSize,
+};
+use sp_npos_elections::{BalancingConfig, ElectionScore};
+use sp_runtime::Perbill;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The NPoS solvers which can be used to mine a solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum Solver {
+	/// Sequential phragmen.
+	SeqPhragmen,
+	/// PhragMMS.
+	#[value(name = "phragmms")]
+	PhragMMS,
+}
+
+/// The number of balancing iterations of the solver, as configured on the command line.
+///
+/// This is a process-wide static rather than a thread-local one, as the runtime may execute on any
+/// thread of the async executor.
+static BALANCING_ITERATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The balancing configuration of the solver, as read from [`BALANCING_ITERATIONS`].
+pub(crate) struct Balancing;
+
+impl Get<Option<BalancingConfig>> for Balancing {
+	fn get() -> Option<BalancingConfig> {
+		match BALANCING_ITERATIONS.load(Ordering::Relaxed) {
+			0 => None,
+			iterations => Some(BalancingConfig { iterations, tolerance: 0 }),
+		}
+	}
+}
+
+/// The room left in a signed extrinsic for everything but the solution: the call index, the
+/// signature, the signed extensions and the round.
+const SIGNED_EXTRINSIC_OVERHEAD: u32 = 1024;
+
+/// The maximum length of a signed solution, such that its extrinsic fits in the normal class of a
+/// block.
+pub(crate) struct SignedMaxLength;
+
+impl Get<u32> for SignedMaxLength {
+	fn get() -> u32 {
+		RuntimeBlockLength::get()
+			.max
+			.get(DispatchClass::Normal)
+			.saturating_sub(SIGNED_EXTRINSIC_OVERHEAD)
+	}
+}
+
+/// The miner configuration of signed solutions.
+///
+/// The [`MinerConfig`] of the runtime bounds the solutions mined by the offchain worker, which are
+/// submitted as unsigned ones. Signed solutions are instead bounded by the signed weight limit of
+/// the runtime, and by the length of a signed extrinsic.
+pub(crate) struct SignedMinerConfig;
+
+impl MinerConfig for SignedMinerConfig {
+	type AccountId = AccountId;
+	type Solution = <Runtime as MinerConfig>::Solution;
+	type MaxVotesPerVoter = <Runtime as MinerConfig>::MaxVotesPerVoter;
+	type MaxLength = SignedMaxLength;
+	type MaxWeight = <Runtime as Config>::SignedMaxWeight;
+	type MaxWinners = <Runtime as MinerConfig>::MaxWinners;
+
+	// `submit` weighs signed solutions with the weight function of the runtime's miner config.
+	fn solution_weight(v: u32, t: u32, a: u32, d: u32) -> Weight {
+		<Runtime as MinerConfig>::solution_weight(v, t, a, d)
+	}
+}
+
+/// A mined solution, along with everything needed to judge whether it is worth submitting.
+pub(crate) struct MinedSolution {
+	/// The solution itself.
+	pub(crate) raw_solution: RawSolution<SolutionOf<Runtime>>,
+	/// The size of the snapshot it was mined from.
+	pub(crate) size: SolutionOrSnapshotSize,
+	/// The deposit a signed submission of this solution would reserve, as of the block it was
+	/// mined at.
+	pub(crate) deposit: Balance,
+	/// The weight of the feasibility check of this solution.
+	pub(crate) weight: Weight,
+	/// The phase of the election at the time of mining.
+	pub(crate) phase: Phase<BlockNumber>,
+	/// The score of the queued solution, if any.
+	pub(crate) queued_score: Option<ElectionScore>,
+	/// The score of the best signed submission, if any.
+	pub(crate) best_signed_score: Option<ElectionScore>,
+}
+
+impl MinedSolution {
+	/// Whether this solution is better, by at least the signed threshold of the runtime, than both
+	/// the queued solution and the best signed submission.
+	pub(crate) fn improves_on_queued(&self) -> bool {
+		let threshold: Perbill = <Runtime as Config>::BetterSignedThreshold::get();
+		let score = self.raw_solution.score;
+		[self.queued_score, self.best_signed_score]
+			.into_iter()
+			.flatten()
+			.all(|other| score.strict_threshold_better(other, threshold))
+	}
+
+	/// Whether this solution can be submitted without exceeding the maximum weight of a signed
+	/// solution.
+	pub(crate) fn fits_signed_weight(&self) -> bool {
+		self.weight.all_lt(<Runtime as Config>::SignedMaxWeight::get())
+	}
+}
+
+/// Mine a solution with `solver` out of the snapshot stored in `ext`.
+///
+/// The solution is trimmed to the limits of [`SignedMinerConfig`], and checked for feasibility
+/// against the same snapshot before being returned.
+pub(crate) fn mine_solution(
+	ext: &mut TestExternalities,
+	solver: Solver,
+	balancing_iterations: usize,
+) -> Result<MinedSolution, Error> {
+	BALANCING_ITERATIONS.store(balancing_iterations, Ordering::Relaxed);
+
+	ext.execute_with(|| {
+		if MultiPhase::is_paged() {
+			return Err(Error::PagedElection)
+		}
+
+		let phase = MultiPhase::current_phase();
+		let round = MultiPhase::round();
+		let snapshot = MultiPhase::snapshot().ok_or(Error::SnapshotUnavailable)?;
+		let desired_targets = MultiPhase::desired_targets().ok_or(Error::SnapshotUnavailable)?;
+		log::info!(
+			target: LOG_TARGET,
+			"mining round {} with {:?}: {} voters, {} targets, {} desired targets",
+			round,
+			solver,
+			snapshot.voters.len(),
+			snapshot.targets.len(),
+			desired_targets,
+		);
+
+		let (solution, score, size) = match solver {
+			Solver::SeqPhragmen => Miner::<SignedMinerConfig>::mine_solution_with_snapshot::<
+				SequentialPhragmen<AccountId, SolutionAccuracyOf<Runtime>, Balancing>,
+			>(snapshot.voters, snapshot.targets, desired_targets),
+			Solver::PhragMMS => Miner::<SignedMinerConfig>::mine_solution_with_snapshot::<
+				PhragMMS<AccountId, SolutionAccuracyOf<Runtime>, Balancing>,
+			>(snapshot.voters, snapshot.targets, desired_targets),
+		}
+		.map_err(Error::Miner)?;
+
+		let raw_solution = RawSolution { solution, score, round };
+		MultiPhase::feasibility_check(raw_solution.clone(), ElectionCompute::Signed)
+			.map_err(Error::Feasibility)?;
+
+		let deposit = MultiPhase::deposit_for(&raw_solution, size);
+		let weight = MultiPhase::solution_weight_of(&raw_solution, size);
+		let queued_score = MultiPhase::queued_solution().map(|queued| queued.score);
+		let best_signed_score =
+			<SignedSubmissionIndices<Runtime>>::get().last().map(|(score, _, _)| *score);
+
+		Ok(MinedSolution {
+			raw_solution,
+			size,
+			deposit,
+			weight,
+			phase,
+			queued_score,
+			best_signed_score,
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_election_provider_multi_phase::{
+		unsigned::VoterOf, CurrentPhase, DesiredTargets, RoundSnapshot, Snapshot, SnapshotMetadata,
+	};
+
+	fn account(i: u8) -> AccountId {
+		AccountId::from([i; 32])
+	}
+
+	/// Externalities holding the snapshot of an election of 2 out of 4 targets, in the signed
+	/// phase.
+	fn seeded_ext() -> TestExternalities {
+		let targets = (1..=4).map(account).collect::<Vec<_>>();
+		let voters = (0..8u8)
+			.map(|i| {
+				let votes = vec![account(1 + i % 4), account(1 + (i + 1) % 4)];
+				(account(10 + i), 100 * (i as u64 + 1), votes.try_into().unwrap())
+			})
+			.collect::<Vec<VoterOf<Runtime>>>();
+
+		let mut ext = TestExternalities::new_empty();
+		ext.execute_with(|| {
+			<SnapshotMetadata<Runtime>>::put(SolutionOrSnapshotSize {
+				voters: voters.len() as u32,
+				targets: targets.len() as u32,
+			});
+			<Snapshot<Runtime>>::put(RoundSnapshot { voters, targets });
+			<DesiredTargets<Runtime>>::put(2);
+			<CurrentPhase<Runtime>>::put(Phase::Signed);
+		});
+		ext
+	}
+
+	fn mined(score: ElectionScore, weight: Weight) -> MinedSolution {
+		MinedSolution {
+			raw_solution: RawSolution { score, ..Default::default() },
+			size: Default::default(),
+			deposit: 0,
+			weight,
+			phase: Phase::Signed,
+			queued_score: None,
+			best_signed_score: None,
+		}
+	}
+
+	fn score(minimal_stake: u128) -> ElectionScore {
+		ElectionScore { minimal_stake, ..Default::default() }
+	}
+
+	#[test]
+	fn mined_solutions_are_feasible() {
+		for solver in [Solver::SeqPhragmen, Solver::PhragMMS] {
+			let mut ext = seeded_ext();
+			let mined = mine_solution(&mut ext, solver, 10).unwrap();
+
+			assert_eq!(mined.size, SolutionOrSnapshotSize { voters: 8, targets: 4 });
+			assert_eq!(mined.raw_solution.round, 1);
+			assert!(mined.phase.is_signed());
+			assert!(mined.improves_on_queued());
+			assert!(mined.fits_signed_weight());
+			ext.execute_with(|| {
+				let ready = MultiPhase::feasibility_check(
+					mined.raw_solution.clone(),
+					ElectionCompute::Signed,
+				)
+				.unwrap();
+				assert_eq!(ready.supports.len(), 2);
+				assert_eq!(ready.score, mined.raw_solution.score);
+			});
+		}
+	}
+
+	#[test]
+	fn mining_requires_a_snapshot() {
+		let mut ext = TestExternalities::new_empty();
+		assert!(matches!(
+			mine_solution(&mut ext, Solver::SeqPhragmen, 0),
+			Err(Error::SnapshotUnavailable)
+		));
+	}
+
+	#[test]
+	fn improves_on_queued_compares_to_both_scores() {
+		let mut mined = mined(score(10), Weight::zero());
+		assert!(mined.improves_on_queued());
+
+		mined.queued_score = Some(score(5));
+		assert!(mined.improves_on_queued());
+
+		// an equal score is no improvement.
+		mined.best_signed_score = Some(score(10));
+		assert!(!mined.improves_on_queued());
+
+		mined.best_signed_score = Some(score(9));
+		assert!(mined.improves_on_queued());
+
+		mined.queued_score = Some(score(11));
+		assert!(!mined.improves_on_queued());
+	}
+
+	#[test]
+	fn fits_signed_weight_is_bounded_by_the_signed_max_weight() {
+		let max = <Runtime as Config>::SignedMaxWeight::get();
+		assert_eq!(<SignedMinerConfig as MinerConfig>::MaxWeight::get(), max);
+
+		assert!(mined(score(10), Weight::zero()).fits_signed_weight());
+		assert!(mined(score(10), max.saturating_sub(Weight::from_parts(1, 1))).fits_signed_weight());
+		assert!(!mined(score(10), max).fits_signed_weight());
+	}
+}
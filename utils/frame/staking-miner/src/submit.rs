@@ -0,0 +1,209 @@
+// This file is part of a fork of Substrate which has had various changes.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// Copyright (C) 2022-2023 Luke Parker
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signing and submission of a mined solution.
+
+use crate::{Error, LOG_TARGET};
+use codec::Encode;
+use frame_support::traits::Get;
+use kitchensink_runtime::{
+	BlockHashCount, Runtime, RuntimeCall, SignedExtra, SignedPayload, UncheckedExtrinsic, VERSION,
+};
+use node_primitives::{AccountId, Hash, Header, Nonce};
+use pallet_election_provider_multi_phase::{RawSolution, SolutionOf};
+use sp_core::{sr25519, Bytes, Pair};
+use sp_rpc::{list::ListOrValue, number::NumberOrHex};
+use sp_runtime::{generic::Era, traits::Header as _};
+use substrate_rpc_client::{rpc_params, AuthorApi, ChainApi, ClientT, StateApi, WsClient};
+
+fn rpc_err(e: substrate_rpc_client::Error) -> Error {
+	Error::Rpc(e.to_string())
+}
+
+/// Sign a `submit` call of `raw_solution` with `signer`, and submit it to the node.
+///
+/// The transaction is mortal, starting at the block `at` the solution was mined at. Returns the
+/// hash of the submitted extrinsic.
+pub(crate) async fn submit_solution(
+	rpc: &WsClient,
+	signer: &sr25519::Pair,
+	raw_solution: RawSolution<SolutionOf<Runtime>>,
+	at: Hash,
+) -> Result<Hash, Error> {
+	let account = AccountId::from(signer.public());
+	let nonce: Nonce = rpc
+		.request("system_accountNextIndex", rpc_params![account.to_string()])
+		.await
+		.map_err(rpc_err)?;
+
+	let genesis_hash = match ChainApi::<(), Hash, Header, ()>::block_hash(
+		rpc,
+		Some(ListOrValue::Value(NumberOrHex::Number(0))),
+	)
+	.await
+	.map_err(rpc_err)?
+	{
+		ListOrValue::Value(Some(hash)) => hash,
+		_ => return Err(Error::Rpc("genesis hash not found".into())),
+	};
+	let header = ChainApi::<(), Hash, Header, ()>::header(rpc, Some(at))
+		.await
+		.map_err(rpc_err)?
+		.ok_or_else(|| Error::Rpc(format!("header of {:?} not found", at)))?;
+
+	let version = StateApi::<Hash>::runtime_version(rpc, Some(at)).await.map_err(rpc_err)?;
+	if version.spec_version != VERSION.spec_version ||
+		version.transaction_version != VERSION.transaction_version
+	{
+		log::warn!(
+			target: LOG_TARGET,
+			"remote runtime version {}/{} differs from the local one {}/{}, the submission is likely \
+			to be rejected",
+			version.spec_version,
+			version.transaction_version,
+			VERSION.spec_version,
+			VERSION.transaction_version,
+		);
+	}
+
+	let extrinsic = build_extrinsic(
+		signer,
+		raw_solution,
+		nonce,
+		genesis_hash,
+		&header,
+		version.spec_version,
+		version.transaction_version,
+	);
+
+	log::info!(target: LOG_TARGET, "submitting solution of {} with nonce {}", signer.public(), nonce);
+	AuthorApi::<Hash, Hash>::submit_extrinsic(rpc, Bytes(extrinsic.encode()))
+		.await
+		.map_err(rpc_err)
+}
+
+/// Build a `submit` extrinsic of `raw_solution`, signed by `signer`.
+///
+/// The transaction is mortal, starting at the block of `header`.
+pub(crate) fn build_extrinsic(
+	signer: &sr25519::Pair,
+	raw_solution: RawSolution<SolutionOf<Runtime>>,
+	nonce: Nonce,
+	genesis_hash: Hash,
+	header: &Header,
+	spec_version: u32,
+	transaction_version: u32,
+) -> UncheckedExtrinsic {
+	let account = AccountId::from(signer.public());
+	let at = header.hash();
+	let period =
+		BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+	let extra: SignedExtra = (
+		frame_system::CheckNonZeroSender::<Runtime>::new(),
+		frame_system::CheckSpecVersion::<Runtime>::new(),
+		frame_system::CheckTxVersion::<Runtime>::new(),
+		frame_system::CheckGenesis::<Runtime>::new(),
+		frame_system::CheckEra::<Runtime>::from(Era::mortal(period, header.number.into())),
+		frame_system::CheckNonce::<Runtime>::from(nonce),
+		frame_system::CheckWeight::<Runtime>::new(),
+		pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::<Runtime>::from(0, None),
+	);
+
+	let call = RuntimeCall::ElectionProviderMultiPhase(
+		pallet_election_provider_multi_phase::Call::submit { raw_solution: Box::new(raw_solution) },
+	);
+	let raw_payload = SignedPayload::from_raw(
+		call.clone(),
+		extra.clone(),
+		((), spec_version, transaction_version, genesis_hash, at, (), (), ()),
+	);
+	let signature = raw_payload.using_encoded(|payload| signer.sign(payload));
+	UncheckedExtrinsic::new_signed(
+		call,
+		account.into(),
+		kitchensink_runtime::Signature::Sr25519(signature),
+		extra,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Decode;
+	use sp_runtime::traits::Verify;
+
+	#[test]
+	fn submit_extrinsic_is_signed_by_the_signer() {
+		let signer = sr25519::Pair::from_string("//Alice", None).unwrap();
+		let account = AccountId::from(signer.public());
+		let header = Header::new(
+			7,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		let genesis_hash = Hash::repeat_byte(1);
+		let raw_solution = RawSolution::<SolutionOf<Runtime>> { round: 3, ..Default::default() };
+
+		let extrinsic = build_extrinsic(
+			&signer,
+			raw_solution.clone(),
+			5,
+			genesis_hash,
+			&header,
+			VERSION.spec_version,
+			VERSION.transaction_version,
+		);
+		let decoded = UncheckedExtrinsic::decode(&mut &extrinsic.encode()[..]).unwrap();
+		assert_eq!(
+			decoded.function,
+			RuntimeCall::ElectionProviderMultiPhase(
+				pallet_election_provider_multi_phase::Call::submit {
+					raw_solution: Box::new(raw_solution)
+				}
+			)
+		);
+
+		let (address, signature, extra) = decoded.signature.unwrap();
+		assert_eq!(address, account);
+		let payload_for = |genesis_hash| {
+			SignedPayload::from_raw(
+				decoded.function.clone(),
+				extra.clone(),
+				(
+					(),
+					VERSION.spec_version,
+					VERSION.transaction_version,
+					genesis_hash,
+					header.hash(),
+					(),
+					(),
+					(),
+				),
+			)
+		};
+		assert!(
+			payload_for(genesis_hash).using_encoded(|payload| signature.verify(payload, &account))
+		);
+
+		// the signature is bound to the chain.
+		assert!(!payload_for(Hash::repeat_byte(2))
+			.using_encoded(|payload| signature.verify(payload, &account)));
+	}
+}
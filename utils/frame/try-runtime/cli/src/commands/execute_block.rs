@@ -17,17 +17,17 @@
 // limitations under the License.
 
 use crate::{
-	build_executor, full_extensions, rpc_err_handler, state_machine_call_with_proof, LiveState,
-	SharedParams, State, LOG_TARGET,
+	executor_builder, full_extensions, rpc_err_handler, state_machine_call_with_proof,
+	write_profile, LiveState, SharedParams, State, LOG_TARGET,
 };
 use parity_scale_codec::Encode;
-use sc_executor::sp_wasm_interface::HostFunctions;
+use sc_executor::{sp_wasm_interface::HostFunctions, DEFAULT_PROFILING_INTERVAL};
 use sp_rpc::{list::ListOrValue, number::NumberOrHex};
 use sp_runtime::{
 	generic::SignedBlock,
 	traits::{Block as BlockT, Header as HeaderT, NumberFor},
 };
-use std::{fmt::Debug, str::FromStr};
+use std::{fmt::Debug, path::PathBuf, str::FromStr};
 use substrate_rpc_client::{ws_client, ChainApi};
 
 /// Configurations of the [`crate::Command::ExecuteBlock`].
@@ -61,6 +61,14 @@ pub struct ExecuteBlockCmd {
 	)]
 	pub block_ws_uri: Option<String>,
 
+	/// Profile the execution of the block and write the profile to the given path.
+	///
+	/// The profile is written in the folded stacks format, which can be turned into a flamegraph
+	/// using e.g. `inferno-flamegraph`. Time spent in host functions is attributed to a separate
+	/// `<host>` frame. Only has an effect when `wasm-execution` is set to `compiled`.
+	#[arg(long, value_name = "PATH")]
+	pub profile: Option<PathBuf>,
+
 	/// The state type to use.
 	#[command(subcommand)]
 	pub state: State,
@@ -97,7 +105,11 @@ where
 	<NumberFor<Block> as TryInto<u64>>::Error: Debug,
 	HostFns: HostFunctions,
 {
-	let executor = build_executor::<HostFns>(&shared);
+	let mut executor = executor_builder::<HostFns>(&shared);
+	if command.profile.is_some() {
+		executor = executor.with_profiling(DEFAULT_PROFILING_INTERVAL);
+	}
+	let executor = executor.build();
 	let ext = command.state.into_ext::<Block, HostFns>(&shared, &executor, None, true).await?;
 
 	// get the block number associated with this block.
@@ -127,6 +139,9 @@ where
 	let signature_check = false;
 	let payload = (block.clone(), state_root_check, signature_check, command.try_state).encode();
 
+	// Only the execution of the block itself is profiled.
+	let _ = executor.take_profile();
+
 	let _ = state_machine_call_with_proof::<Block, HostFns>(
 		&ext,
 		&executor,
@@ -136,6 +151,10 @@ where
 		shared.export_proof,
 	)?;
 
+	if let (Some(path), Some(profile)) = (command.profile, executor.take_profile()) {
+		write_profile(&profile, &path)?;
+	}
+
 	Ok(())
 }
 
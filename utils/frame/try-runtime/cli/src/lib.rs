@@ -365,7 +365,8 @@ use sc_cli::{
 	DEFAULT_WASM_EXECUTION_METHOD,
 };
 use sc_executor::{
-	sp_wasm_interface::HostFunctions, HeapAllocStrategy, WasmExecutor, DEFAULT_HEAP_ALLOC_STRATEGY,
+	sp_wasm_interface::HostFunctions, HeapAllocStrategy, Profile, WasmExecutor,
+	WasmExecutorBuilder, DEFAULT_HEAP_ALLOC_STRATEGY,
 };
 use sp_api::HashT;
 use sp_core::{
@@ -387,7 +388,11 @@ use sp_runtime::{
 };
 use sp_state_machine::{CompactProof, OverlayedChanges, StateMachine, TrieBackendBuilder};
 use sp_version::StateVersion;
-use std::{fmt::Debug, path::PathBuf, str::FromStr};
+use std::{
+	fmt::Debug,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
 
 pub mod block_building_info;
 pub mod commands;
@@ -527,6 +532,14 @@ pub struct SharedParams {
 	)]
 	pub wasmtime_instantiation_strategy: WasmtimeInstantiationStrategy,
 
+	/// Enable deterministic fuel metering, giving every runtime call the specified amount of fuel.
+	///
+	/// The fuel consumed by each call is logged, which allows comparing the real cost of the
+	/// runtime with the declared weights without timing noise. Only has an effect when
+	/// `wasm-execution` is set to `compiled`.
+	#[arg(long, value_name = "FUEL")]
+	pub wasm_fuel_limit: Option<u64>,
+
 	/// The number of 64KB pages to allocate for Wasm execution. Defaults to
 	/// [`sc_service::Configuration.default_heap_pages`].
 	#[arg(long)]
@@ -820,18 +833,50 @@ pub(crate) fn full_extensions<H: HostFunctions>(wasm_executor: WasmExecutor<H>)
 
 /// Build wasm executor by default config.
 pub(crate) fn build_executor<H: HostFunctions>(shared: &SharedParams) -> WasmExecutor<H> {
+	executor_builder(shared).build()
+}
+
+/// Prepare a wasm executor builder configured by `shared`.
+pub(crate) fn executor_builder<H: HostFunctions>(shared: &SharedParams) -> WasmExecutorBuilder<H> {
 	let heap_pages = shared
 		.heap_pages
 		.map_or(DEFAULT_HEAP_ALLOC_STRATEGY, |p| HeapAllocStrategy::Static { extra_pages: p as _ });
 
-	WasmExecutor::builder()
+	let builder = WasmExecutor::builder()
 		.with_execution_method(execution_method_from_cli(
 			shared.wasm_method,
 			shared.wasmtime_instantiation_strategy,
 		))
 		.with_onchain_heap_alloc_strategy(heap_pages)
-		.with_offchain_heap_alloc_strategy(heap_pages)
-		.build()
+		.with_offchain_heap_alloc_strategy(heap_pages);
+
+	match shared.wasm_fuel_limit {
+		Some(fuel_limit) => builder.with_fuel_limit(fuel_limit),
+		None => builder,
+	}
+}
+
+/// Write the given `profile` to `path` in the folded stacks format.
+pub(crate) fn write_profile(profile: &Profile, path: &Path) -> sc_cli::Result<()> {
+	let file = std::fs::File::create(path)?;
+	profile.write_folded(std::io::BufWriter::new(file))?;
+
+	log::info!(
+		target: LOG_TARGET,
+		"Wrote profile of {:?} to {}, {:?} of which were spent in host functions",
+		profile.total(),
+		path.display(),
+		profile.host_time(),
+	);
+
+	Ok(())
+}
+
+/// Log the fuel consumed by the calls to `method`, if fuel metering is enabled.
+fn log_consumed_fuel<HostFns: HostFunctions>(executor: &WasmExecutor<HostFns>, method: &str) {
+	if let Some(fuel) = executor.take_consumed_fuel() {
+		log::info!(target: LOG_TARGET, "{} consumed {} fuel", method, fuel);
+	}
 }
 
 /// Ensure that the given `ext` is compiled with `try-runtime`
@@ -875,6 +920,7 @@ pub(crate) fn state_machine_call<Block: BlockT, HostFns: HostFunctions>(
 	.execute()
 	.map_err(|e| format!("failed to execute '{}': {}", method, e))
 	.map_err::<sc_cli::Error, _>(Into::into)?;
+	log_consumed_fuel(executor, method);
 
 	Ok((changes, encoded_results))
 }
@@ -914,6 +960,7 @@ pub(crate) fn state_machine_call_with_proof<Block: BlockT, HostFns: HostFunction
 	.execute()
 	.map_err(|e| format!("failed to execute {}: {}", method, e))
 	.map_err::<sc_cli::Error, _>(Into::into)?;
+	log_consumed_fuel(executor, method);
 
 	let proof = proving_backend
 		.extract_proof()